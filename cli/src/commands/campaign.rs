@@ -0,0 +1,170 @@
+use anyhow::Result;
+use clap::{Subcommand, ValueEnum};
+use serde_json::{json, Value};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+
+use donaproto::states::HardCapMode;
+use donaproto_client::{
+    pda::{find_creator_data, find_holding_wallet_owner},
+    CreateDonationBuilder, InitializeCreatorBuilder,
+};
+
+use super::{associated_token_account, decode::decode_onchain};
+use crate::config::{new_account_keypair, with_accounts, Config};
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum HardCap {
+    None,
+    Reject,
+    Trim,
+}
+
+impl From<HardCap> for HardCapMode {
+    fn from(hard_cap: HardCap) -> Self {
+        match hard_cap {
+            HardCap::None => HardCapMode::None,
+            HardCap::Reject => HardCapMode::Reject,
+            HardCap::Trim => HardCapMode::Trim,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum CampaignCommand {
+    /// Create a donation campaign owned by the signer
+    Create {
+        /// Amount collecting, in `mint` base units
+        #[arg(long)]
+        amount: u64,
+        #[arg(long)]
+        ending_timestamp: u64,
+        #[arg(long)]
+        ipfs_hash: String,
+        /// Donation mint, the protocol donation mint when omitted
+        #[arg(long)]
+        mint: Option<Pubkey>,
+        /// Recipient token wallet, the signer associated token account when omitted
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+        #[arg(long, value_enum, default_value_t = HardCap::None)]
+        hard_cap: HardCap,
+        /// Let donors refund while the campaign runs
+        #[arg(long)]
+        refundable: bool,
+        /// Keypair of the new campaign account, a random one when omitted
+        #[arg(long)]
+        donation_keypair: Option<String>,
+    },
+    /// Print campaign state with progress and consistency checks
+    Show { donation_data: Pubkey },
+    /// Withdraw collected funds to the signer
+    Withdraw {
+        donation_data: Pubkey,
+        /// Recipient token wallet owned by the signer, its associated token account when omitted
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+    },
+}
+
+pub fn process(
+    config: &Config,
+    donation_protocol: Pubkey,
+    command: CampaignCommand,
+) -> Result<Value> {
+    match command {
+        CampaignCommand::Create {
+            amount,
+            ending_timestamp,
+            ipfs_hash,
+            mint,
+            recipient,
+            hard_cap,
+            refundable,
+            donation_keypair,
+        } => {
+            let payer = config.payer_key();
+            let protocol = config.client.protocol_keys(&donation_protocol)?;
+            let donation_mint = mint.unwrap_or(protocol.donation_mint);
+            let donation_data = new_account_keypair(donation_keypair.as_ref())?;
+
+            let mut instructions = Vec::new();
+            let creator_data = find_creator_data(&donation_protocol, &payer).0;
+            if !config.account_exists(&creator_data)? {
+                instructions
+                    .push(InitializeCreatorBuilder::new(donation_protocol, payer).instruction());
+            }
+            let (holding_wallet_owner, _) = find_holding_wallet_owner(&donation_data.pubkey());
+            let (holding_wallet, create_holding_wallet) =
+                associated_token_account(&payer, &holding_wallet_owner, &donation_mint);
+            instructions.push(create_holding_wallet);
+            let recipient = match recipient {
+                Some(recipient) => recipient,
+                None => {
+                    let (recipient, create_recipient) =
+                        associated_token_account(&payer, &payer, &donation_mint);
+                    instructions.push(create_recipient);
+                    recipient
+                }
+            };
+            let mut builder = CreateDonationBuilder::new(
+                donation_protocol,
+                donation_data.pubkey(),
+                donation_mint,
+                recipient,
+                payer,
+                amount,
+                ipfs_hash,
+                ending_timestamp,
+            )
+            .holding_wallet(holding_wallet)
+            .hard_cap_mode(hard_cap.into());
+            if refundable {
+                builder = builder.refundable();
+            }
+            instructions.push(
+                config
+                    .client
+                    .create_donation(builder, &donation_protocol, &donation_mint)?
+                    .instruction(),
+            );
+
+            let output = config.process(&instructions, &[&donation_data])?;
+            Ok(with_accounts(
+                output,
+                json!({
+                    "donation_data": donation_data.pubkey().to_string(),
+                    "holding_wallet": holding_wallet.to_string(),
+                    "creator_data": creator_data.to_string(),
+                }),
+            ))
+        }
+        CampaignCommand::Show { donation_data } => {
+            Ok(decode_onchain(config, &donation_data)?.to_json())
+        }
+        CampaignCommand::Withdraw {
+            donation_data,
+            recipient,
+        } => {
+            let payer = config.payer_key();
+            let campaign = config.client.campaign_keys(&donation_data)?;
+            let mut instructions = Vec::new();
+            let recipient = match recipient {
+                Some(recipient) => recipient,
+                None => {
+                    let (recipient, create_recipient) =
+                        associated_token_account(&payer, &payer, &campaign.donation_mint);
+                    instructions.push(create_recipient);
+                    recipient
+                }
+            };
+            instructions.push(
+                config
+                    .client
+                    .withdraw_funds(&donation_data, recipient, payer)?
+                    .instruction(),
+            );
+
+            config.process(&instructions, &[])
+        }
+    }
+}
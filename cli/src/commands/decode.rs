@@ -0,0 +1,77 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use clap::Args;
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+
+use donaproto_client::{DecodeContext, DecodedAccount, DonaprotoAccount};
+
+use crate::config::Config;
+
+#[derive(Args)]
+pub struct DecodeArgs {
+    pub address: Pubkey,
+    /// Decode a raw account dump, e.g. from `solana account --output-file`, instead of fetching
+    #[arg(long)]
+    pub file: Option<String>,
+    /// Print a human readable table instead of JSON
+    #[arg(long)]
+    pub text: bool,
+}
+
+pub fn process(config: &Config, args: DecodeArgs) -> Result<Value> {
+    let decoded = match &args.file {
+        Some(file) => {
+            let context = DecodeContext {
+                now: unix_now(),
+                holding_balance: None,
+            };
+            DecodedAccount::decode(args.address, &std::fs::read(file)?, &context)?
+        }
+        None => decode_onchain(config, &args.address)?,
+    };
+
+    if args.text {
+        return Ok(Value::String(decoded.to_string()));
+    }
+    Ok(decoded.to_json())
+}
+
+/// Fetches the account along with the state its consistency checks compare against
+pub fn decode_onchain(config: &Config, address: &Pubkey) -> Result<DecodedAccount> {
+    let data = config.rpc().get_account_data(address)?;
+    let account = DonaprotoAccount::decode(address, &data)?;
+    let holding_balance = match &account {
+        DonaprotoAccount::Donation(donation_data) => Some(
+            config
+                .rpc()
+                .get_token_account_balance(&donation_data.holding_wallet)?
+                .amount
+                .parse()?,
+        ),
+        _ => None,
+    };
+    let slot = config.rpc().get_slot()?;
+    let now = config
+        .rpc()
+        .get_block_time(slot)
+        .map(|block_time| block_time as u64)
+        .unwrap_or_else(|_| unix_now());
+
+    Ok(DecodedAccount::new(
+        *address,
+        account,
+        &DecodeContext {
+            now,
+            holding_balance,
+        },
+    ))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
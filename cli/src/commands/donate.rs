@@ -0,0 +1,35 @@
+use anyhow::Result;
+use clap::Args;
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+
+use super::associated_token_account;
+use crate::config::Config;
+
+#[derive(Args)]
+pub struct DonateArgs {
+    pub donation_data: Pubkey,
+    /// Amount in the campaign mint base units
+    pub amount: u64,
+    /// Count the donation towards a funding round
+    #[arg(long)]
+    pub funding_round: Option<Pubkey>,
+}
+
+pub fn process(config: &Config, args: DonateArgs) -> Result<Value> {
+    let payer = config.payer_key();
+    let mut builder = config
+        .client
+        .donate(&args.donation_data, payer, args.amount)?;
+    if let Some(funding_round) = args.funding_round {
+        builder = builder.funding_round(funding_round);
+    }
+
+    // rewards are paid into the donor associated token account
+    let campaign = config.client.campaign_keys(&args.donation_data)?;
+    let protocol = config.client.protocol_keys(&campaign.donation_protocol)?;
+    let (_, create_reward_wallet) =
+        associated_token_account(&payer, &payer, &protocol.treasury_mint);
+
+    config.process(&[create_reward_wallet, builder.instruction()], &[])
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::{
+    get_associated_token_address,
+    spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+};
+use solana_sdk::instruction::Instruction;
+
+pub mod campaign;
+pub mod decode;
+pub mod donate;
+pub mod pool;
+pub mod protocol;
+
+/// Associated token account of `owner` along with the instruction creating it when missing
+pub fn associated_token_account(
+    payer: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> (Pubkey, Instruction) {
+    (
+        get_associated_token_address(owner, mint),
+        create_associated_token_account_idempotent(payer, owner, mint, &anchor_spl::token::ID),
+    )
+}
@@ -0,0 +1,81 @@
+use anyhow::Result;
+use clap::Subcommand;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+use donaproto_client::{
+    pda::{find_authorized_clmm, find_authorized_clmm_pool},
+    AuthorizeClmmBuilder,
+};
+
+use crate::config::{with_accounts, Config};
+
+#[derive(Subcommand)]
+pub enum PoolCommand {
+    /// Allow pools of a CLMM program to price v2 donation mints
+    AuthorizeClmm {
+        #[arg(long)]
+        clmm_program: Pubkey,
+    },
+    /// Allow a single pool of an authorized CLMM program, mints are read from the pool state
+    Authorize {
+        #[arg(long)]
+        pool_state: Pubkey,
+    },
+    /// List authorized pools pricing a mint, the deepest one is used by default
+    List {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+}
+
+pub fn process(config: &Config, donation_protocol: Pubkey, command: PoolCommand) -> Result<Value> {
+    match command {
+        PoolCommand::AuthorizeClmm { clmm_program } => {
+            let instruction =
+                AuthorizeClmmBuilder::new(donation_protocol, clmm_program, config.payer_key())
+                    .instruction();
+            let output = config.process(&[instruction], &[])?;
+            Ok(with_accounts(
+                output,
+                json!({
+                    "authorized_clmm": find_authorized_clmm(&donation_protocol, &clmm_program).0.to_string(),
+                }),
+            ))
+        }
+        PoolCommand::Authorize { pool_state } => {
+            let instruction = config
+                .client
+                .authorize_clmm_pool(&donation_protocol, &pool_state, config.payer_key())?
+                .instruction();
+            let output = config.process(&[instruction], &[])?;
+            Ok(with_accounts(
+                output,
+                json!({
+                    "authorized_clmm_pool": find_authorized_clmm_pool(&donation_protocol, &pool_state).0.to_string(),
+                }),
+            ))
+        }
+        PoolCommand::List { mint } => {
+            let protocol = config.client.protocol_keys(&donation_protocol)?;
+            let pools: Vec<Value> = config
+                .client
+                .authorized_pools(&donation_protocol, &mint)?
+                .iter()
+                .map(|(key, authorized_pool)| {
+                    json!({
+                        "authorized_clmm_pool": key.to_string(),
+                        "pool_state": authorized_pool.pool_state.to_string(),
+                        "program_id": authorized_pool.program_id.to_string(),
+                    })
+                })
+                .collect();
+            let selected = config
+                .client
+                .find_pool(&protocol, &mint)
+                .ok()
+                .map(|pool| pool.pool_state.to_string());
+            Ok(json!({ "pools": pools, "selected_pool_state": selected }))
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use anyhow::Result;
+use clap::Subcommand;
+use serde_json::{json, Value};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+
+use donaproto_client::{
+    pda::find_treasury_owner, InitializeDonationProtocolBuilder, MigrateAccountBuilder,
+};
+
+use super::{associated_token_account, decode::decode_onchain};
+use crate::config::{new_account_keypair, with_accounts, Config};
+
+#[derive(Subcommand)]
+pub enum ProtocolCommand {
+    /// Create a donation protocol, the signer becomes its authority
+    Init {
+        #[arg(long)]
+        treasury_mint: Pubkey,
+        #[arg(long)]
+        donation_mint: Pubkey,
+        #[arg(long, default_value_t = 0)]
+        min_amount_to_earn: u64,
+        #[arg(long, default_value_t = 0)]
+        min_amount_to_collect: u64,
+        /// Keypair of the new protocol account, a random one when omitted
+        #[arg(long)]
+        protocol_keypair: Option<String>,
+    },
+    /// Print protocol settings
+    Show { donation_protocol: Pubkey },
+    /// Upgrade protocol, campaign, creator or contributor accounts to the current layout version,
+    /// the signer pays for the extra rent
+    Migrate {
+        #[arg(required = true)]
+        accounts: Vec<Pubkey>,
+    },
+}
+
+pub fn process(config: &Config, command: ProtocolCommand) -> Result<Value> {
+    match command {
+        ProtocolCommand::Init {
+            treasury_mint,
+            donation_mint,
+            min_amount_to_earn,
+            min_amount_to_collect,
+            protocol_keypair,
+        } => {
+            let donation_protocol = new_account_keypair(protocol_keypair.as_ref())?;
+            let (treasury_owner, _) = find_treasury_owner(&donation_protocol.pubkey());
+            let (treasury, create_treasury) =
+                associated_token_account(&config.payer_key(), &treasury_owner, &treasury_mint);
+            let initialize = InitializeDonationProtocolBuilder::new(
+                donation_protocol.pubkey(),
+                treasury,
+                treasury_mint,
+                donation_mint,
+                config.payer_key(),
+            )
+            .min_amount_to_earn(min_amount_to_earn)
+            .min_amount_to_collect(min_amount_to_collect)
+            .instruction();
+
+            let output = config.process(&[create_treasury, initialize], &[&donation_protocol])?;
+            Ok(with_accounts(
+                output,
+                json!({
+                    "donation_protocol": donation_protocol.pubkey().to_string(),
+                    "treasury": treasury.to_string(),
+                    "treasury_owner": treasury_owner.to_string(),
+                }),
+            ))
+        }
+        ProtocolCommand::Show { donation_protocol } => {
+            Ok(decode_onchain(config, &donation_protocol)?.to_json())
+        }
+        ProtocolCommand::Migrate { accounts } => {
+            let instructions: Vec<_> = accounts
+                .iter()
+                .map(|account| {
+                    MigrateAccountBuilder::new(*account, config.payer_key()).instruction()
+                })
+                .collect();
+
+            config.process(&instructions, &[])
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signer},
+    transaction::Transaction,
+};
+
+use donaproto_client::DonaprotoRpc;
+
+pub struct Config {
+    pub client: DonaprotoRpc,
+    pub payer: Keypair,
+    pub dry_run: bool,
+}
+
+impl Config {
+    pub fn new(url: &str, keypair_path: &str, dry_run: bool) -> Result<Self> {
+        let rpc = RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed());
+        Ok(Self {
+            client: DonaprotoRpc::new(rpc),
+            payer: read_keypair(keypair_path)?,
+            dry_run,
+        })
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        self.client.rpc()
+    }
+
+    pub fn payer_key(&self) -> Pubkey {
+        self.payer.pubkey()
+    }
+
+    pub fn account_exists(&self, key: &Pubkey) -> Result<bool> {
+        Ok(self
+            .rpc()
+            .get_account_with_commitment(key, self.rpc().commitment())?
+            .value
+            .is_some())
+    }
+
+    /// Signs with the payer and `signers`, then sends or only simulates with `--dry-run`
+    pub fn process(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<Value> {
+        let mut all_signers: Vec<&Keypair> = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+        let blockhash = self.rpc().get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+
+        if self.dry_run {
+            let simulation = self.rpc().simulate_transaction(&transaction)?.value;
+            return Ok(json!({
+                "dry_run": true,
+                "err": simulation.err.map(|err| err.to_string()),
+                "logs": simulation.logs.unwrap_or_default(),
+                "units_consumed": simulation.units_consumed,
+            }));
+        }
+
+        let signature = self.rpc().send_and_confirm_transaction(&transaction)?;
+        Ok(json!({ "signature": signature.to_string() }))
+    }
+}
+
+pub fn read_keypair(path: &str) -> Result<Keypair> {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => format!("{}/{}", std::env::var("HOME")?, rest),
+        None => path.to_string(),
+    };
+    read_keypair_file(&path).map_err(|err| anyhow!("Failed to read keypair {}: {}", path, err))
+}
+
+/// New account keypair, read from `path` when given so the address can be chosen up front
+pub fn new_account_keypair(path: Option<&String>) -> Result<Keypair> {
+    match path {
+        Some(path) => read_keypair(path),
+        None => Ok(Keypair::new()),
+    }
+}
+
+/// Merges the keys created by a command into its transaction output
+pub fn with_accounts(mut output: Value, accounts: Value) -> Value {
+    output["accounts"] = accounts;
+    output
+}
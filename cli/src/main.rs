@@ -0,0 +1,68 @@
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+
+mod commands;
+mod config;
+
+use commands::{
+    campaign::CampaignCommand, decode::DecodeArgs, donate::DonateArgs, pool::PoolCommand,
+    protocol::ProtocolCommand,
+};
+use config::Config;
+
+#[derive(Parser)]
+#[command(name = "donaproto", about = "Manage donaproto protocols and campaigns")]
+struct Cli {
+    #[arg(long, global = true, default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Signer and fee payer
+    #[arg(long, global = true, default_value = "~/.config/solana/id.json")]
+    keypair: String,
+    /// Simulate transactions instead of sending them
+    #[arg(long, global = true)]
+    dry_run: bool,
+    /// Donation protocol account, required by pool and campaign commands
+    #[arg(long, global = true, env = "DONAPROTO_PROTOCOL")]
+    protocol: Option<Pubkey>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(subcommand)]
+    Protocol(ProtocolCommand),
+    #[command(subcommand)]
+    Pool(PoolCommand),
+    #[command(subcommand)]
+    Campaign(CampaignCommand),
+    /// Donate to a campaign, switching to donate_v2 for non protocol mints
+    Donate(DonateArgs),
+    /// Decode any donaproto account, detecting its type by discriminator
+    Decode(DecodeArgs),
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let config = Config::new(&cli.url, &cli.keypair, cli.dry_run)?;
+    let protocol = || {
+        cli.protocol
+            .ok_or_else(|| anyhow!("--protocol is required"))
+    };
+
+    let output = match cli.command {
+        Command::Protocol(command) => commands::protocol::process(&config, command)?,
+        Command::Pool(command) => commands::pool::process(&config, protocol()?, command)?,
+        Command::Campaign(command) => commands::campaign::process(&config, protocol()?, command)?,
+        Command::Donate(args) => commands::donate::process(&config, args)?,
+        Command::Decode(args) => commands::decode::process(&config, args)?,
+    };
+    match output {
+        Value::String(text) => print!("{}", text),
+        output => println!("{}", serde_json::to_string_pretty(&output)?),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,556 @@
+//! Decodes raw donaproto account data, detecting the account type by its discriminator
+
+use std::fmt;
+
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, Discriminator};
+use donaproto::states::{
+    AuthorizedClmm, AuthorizedClmmPool, ContributorData, CreatorData, DonationData,
+    DonationProtocolData, DonationReceipt, DonationTiers, EmissionCarryOver, FundingRound,
+    HardCapMode, LegacyDonationData, MatchPledge, MintFee, PayoutSplit, ReceiptMetadata,
+    RewardVesting, Subscription, Versioned, BPS_DENOMINATOR,
+};
+use serde_json::{json, Map, Value};
+
+use crate::error::{ClientError, Result};
+
+pub enum DonaprotoAccount {
+    DonationProtocol(DonationProtocolData),
+    Creator(CreatorData),
+    Contributor(ContributorData),
+    Donation(DonationData),
+    AuthorizedClmm(AuthorizedClmm),
+    AuthorizedClmmPool(AuthorizedClmmPool),
+    Subscription(Subscription),
+    PayoutSplit(PayoutSplit),
+    MintFee(MintFee),
+    RewardVesting(RewardVesting),
+    DonationReceipt(DonationReceipt),
+    FundingRound(FundingRound),
+    MatchPledge(MatchPledge),
+    DonationTiers(DonationTiers),
+    ReceiptMetadata(ReceiptMetadata),
+}
+
+fn deserialize<T: AccountDeserialize>(address: &Pubkey, mut data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut data).map_err(|err| ClientError::InvalidAccount(*address, err))
+}
+
+/// Borsh layouts awaiting `migrate_account` are converted,
+/// keeping their version so the report asks for the migration
+fn deserialize_donation_data(address: &Pubkey, data: &[u8]) -> Result<DonationData> {
+    if data.len() == DonationData::LEN {
+        return DonationData::try_from_account_data(data)
+            .map_err(|err| ClientError::InvalidAccount(*address, err));
+    }
+    let legacy = LegacyDonationData::try_from_account_data(data)
+        .map_err(|err| ClientError::InvalidAccount(*address, err))?;
+    let mut donation_data = legacy
+        .to_donation_data()
+        .map_err(|err| ClientError::InvalidAccount(*address, err))?;
+    donation_data.version = legacy.version;
+
+    Ok(donation_data)
+}
+
+impl DonaprotoAccount {
+    pub fn decode(address: &Pubkey, data: &[u8]) -> Result<Self> {
+        let discriminator: [u8; 8] = match data.get(..8) {
+            Some(discriminator) => discriminator.try_into().unwrap(),
+            None => return Err(ClientError::UnknownAccount(*address)),
+        };
+        let account = match discriminator {
+            d if d == DonationProtocolData::DISCRIMINATOR => {
+                Self::DonationProtocol(deserialize(address, data)?)
+            }
+            d if d == CreatorData::DISCRIMINATOR => Self::Creator(deserialize(address, data)?),
+            d if d == ContributorData::DISCRIMINATOR => {
+                Self::Contributor(deserialize(address, data)?)
+            }
+            d if d == DonationData::DISCRIMINATOR => {
+                Self::Donation(deserialize_donation_data(address, data)?)
+            }
+            d if d == AuthorizedClmm::DISCRIMINATOR => {
+                Self::AuthorizedClmm(deserialize(address, data)?)
+            }
+            d if d == AuthorizedClmmPool::DISCRIMINATOR => {
+                Self::AuthorizedClmmPool(deserialize(address, data)?)
+            }
+            d if d == Subscription::DISCRIMINATOR => {
+                Self::Subscription(deserialize(address, data)?)
+            }
+            d if d == PayoutSplit::DISCRIMINATOR => Self::PayoutSplit(deserialize(address, data)?),
+            d if d == MintFee::DISCRIMINATOR => Self::MintFee(deserialize(address, data)?),
+            d if d == RewardVesting::DISCRIMINATOR => {
+                Self::RewardVesting(deserialize(address, data)?)
+            }
+            d if d == DonationReceipt::DISCRIMINATOR => {
+                Self::DonationReceipt(deserialize(address, data)?)
+            }
+            d if d == FundingRound::DISCRIMINATOR => {
+                Self::FundingRound(deserialize(address, data)?)
+            }
+            d if d == MatchPledge::DISCRIMINATOR => Self::MatchPledge(deserialize(address, data)?),
+            d if d == DonationTiers::DISCRIMINATOR => {
+                Self::DonationTiers(deserialize(address, data)?)
+            }
+            d if d == ReceiptMetadata::DISCRIMINATOR => {
+                Self::ReceiptMetadata(deserialize(address, data)?)
+            }
+            _ => return Err(ClientError::UnknownAccount(*address)),
+        };
+
+        Ok(account)
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::DonationProtocol(_) => "DonationProtocolData",
+            Self::Creator(_) => "CreatorData",
+            Self::Contributor(_) => "ContributorData",
+            Self::Donation(_) => "DonationData",
+            Self::AuthorizedClmm(_) => "AuthorizedClmm",
+            Self::AuthorizedClmmPool(_) => "AuthorizedClmmPool",
+            Self::Subscription(_) => "Subscription",
+            Self::PayoutSplit(_) => "PayoutSplit",
+            Self::MintFee(_) => "MintFee",
+            Self::RewardVesting(_) => "RewardVesting",
+            Self::DonationReceipt(_) => "DonationReceipt",
+            Self::FundingRound(_) => "FundingRound",
+            Self::MatchPledge(_) => "MatchPledge",
+            Self::DonationTiers(_) => "DonationTiers",
+            Self::ReceiptMetadata(_) => "ReceiptMetadata",
+        }
+    }
+}
+
+/// State outside of the decoded account used by computed fields and consistency checks
+#[derive(Clone, Copy, Default)]
+pub struct DecodeContext {
+    pub now: u64,
+    /// Token balance of `DonationData::holding_wallet`
+    pub holding_balance: Option<u64>,
+}
+
+pub struct DecodedAccount {
+    pub address: Pubkey,
+    pub account: DonaprotoAccount,
+    pub fields: Map<String, Value>,
+    pub computed: Map<String, Value>,
+    pub warnings: Vec<String>,
+}
+
+impl DecodedAccount {
+    pub fn new(address: Pubkey, account: DonaprotoAccount, context: &DecodeContext) -> Self {
+        let mut decoded = Self {
+            address,
+            fields: Map::new(),
+            computed: Map::new(),
+            warnings: Vec::new(),
+            account,
+        };
+        decoded.describe(context);
+        decoded
+    }
+
+    pub fn decode(address: Pubkey, data: &[u8], context: &DecodeContext) -> Result<Self> {
+        let account = DonaprotoAccount::decode(&address, data)?;
+        Ok(Self::new(address, account, context))
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "address": self.address.to_string(),
+            "type": self.account.type_name(),
+            "fields": self.fields,
+            "computed": self.computed,
+            "warnings": self.warnings,
+        })
+    }
+
+    fn describe(&mut self, context: &DecodeContext) {
+        let fields = match &self.account {
+            DonaprotoAccount::DonationProtocol(data) => describe_protocol(data),
+            DonaprotoAccount::Creator(data) => json!({
+                "total_amount_received": data.total_amount_received,
+                "total_amount_collecting": data.total_amount_collecting,
+                "donations_created_count": data.donations_created_count,
+                "donations_closed_count": data.donations_closed_count,
+                "donation_protocol": data.donation_protocol.to_string(),
+                "version": data.version,
+            }),
+            DonaprotoAccount::Contributor(data) => json!({
+                "total_amount_donated": data.total_amount_donated,
+                "total_amount_earned": data.total_amount_earned,
+                "donations_count": data.donations_count,
+                "donation_protocol": data.donation_protocol.to_string(),
+                "bump": data.bump,
+                "total_amount_vested": data.total_amount_vested,
+                "total_amount_claimed": data.total_amount_claimed,
+                "version": data.version,
+            }),
+            DonaprotoAccount::Donation(data) => describe_donation(data),
+            DonaprotoAccount::AuthorizedClmm(data) => json!({
+                "program_id": data.program_id.to_string(),
+                "donation_protocol": data.donation_protocol.to_string(),
+            }),
+            DonaprotoAccount::AuthorizedClmmPool(data) => json!({
+                "pool_state": data.pool_state.to_string(),
+                "program_id": data.program_id.to_string(),
+                "mint": data.mint.to_string(),
+                "donation_protocol": data.donation_protocol.to_string(),
+            }),
+            DonaprotoAccount::Subscription(data) => json!({
+                "donor": data.donor.to_string(),
+                "donor_token_wallet": data.donor_token_wallet.to_string(),
+                "donation_data": data.donation_data.to_string(),
+                "donation_protocol": data.donation_protocol.to_string(),
+                "amount": data.amount,
+                "period": data.period,
+                "next_due": data.next_due,
+                "ending_timestamp": data.ending_timestamp,
+                "installments_paid": data.installments_paid,
+                "bump": data.bump,
+            }),
+            DonaprotoAccount::PayoutSplit(data) => json!({
+                "donation_data": data.donation_data.to_string(),
+                "recipients": data.recipients.iter().map(|recipient| json!({
+                    "token_wallet": recipient.token_wallet.to_string(),
+                    "share_bps": recipient.share_bps,
+                    "total_amount_received": recipient.total_amount_received,
+                })).collect::<Vec<Value>>(),
+                "bump": data.bump,
+            }),
+            DonaprotoAccount::MintFee(data) => json!({
+                "donation_protocol": data.donation_protocol.to_string(),
+                "mint": data.mint.to_string(),
+                "fee_bps": data.fee_bps,
+            }),
+            DonaprotoAccount::RewardVesting(data) => json!({
+                "contributor": data.contributor.to_string(),
+                "donation_protocol": data.donation_protocol.to_string(),
+                "total_amount_vested": data.total_amount_vested,
+                "total_amount_claimed": data.total_amount_claimed,
+                "start_timestamp": data.start_timestamp,
+                "bump": data.bump,
+                "vesting_cliff": data.vesting_cliff,
+                "vesting_duration": data.vesting_duration,
+                "claimed_at_start": data.claimed_at_start,
+            }),
+            DonaprotoAccount::DonationReceipt(data) => json!({
+                "donation_data": data.donation_data.to_string(),
+                "donor": data.donor.to_string(),
+                "amount_donated": data.amount_donated,
+                "default_amount_donated": data.default_amount_donated,
+                "reward_amount": data.reward_amount,
+                "bump": data.bump,
+                "funding_round": data.funding_round.to_string(),
+                "round_amount_donated": data.round_amount_donated,
+                "matched_amount": data.matched_amount,
+                "perk_tier": data.perk_tier,
+                "amount_receipted": data.amount_receipted,
+                "amount_refundable": data.amount_refundable,
+            }),
+            DonaprotoAccount::FundingRound(data) => json!({
+                "donation_protocol": data.donation_protocol.to_string(),
+                "authority": data.authority.to_string(),
+                "matching_mint": data.matching_mint.to_string(),
+                "matching_vault": data.matching_vault.to_string(),
+                "start_timestamp": data.start_timestamp,
+                "end_timestamp": data.end_timestamp,
+                "is_finalized": data.is_finalized,
+                "matching_pool_amount": data.matching_pool_amount,
+                "campaigns": data.campaigns.iter().map(|campaign| json!({
+                    "donation_data": campaign.donation_data.to_string(),
+                    "sqrt_sum": campaign.sqrt_sum,
+                    "total_amount_contributed": campaign.total_amount_contributed,
+                    "match_amount": campaign.match_amount,
+                    "is_distributed": campaign.is_distributed,
+                })).collect::<Vec<Value>>(),
+                "matching_vault_owner_bump": data.matching_vault_owner_bump,
+            }),
+            DonaprotoAccount::MatchPledge(data) => json!({
+                "donation_data": data.donation_data.to_string(),
+                "sponsor": data.sponsor.to_string(),
+                "escrow": data.escrow.to_string(),
+                "ratio_bps": data.ratio_bps,
+                "cap": data.cap,
+                "total_amount_matched": data.total_amount_matched,
+                "deadline": data.deadline,
+                "bump": data.bump,
+            }),
+            DonaprotoAccount::DonationTiers(data) => json!({
+                "donation_data": data.donation_data.to_string(),
+                "tiers": data.tiers.iter().map(|tier| json!({
+                    "threshold": tier.threshold,
+                    "supply": tier.supply,
+                    "claimed": tier.claimed,
+                    "ipfs_hash": tier.ipfs_hash,
+                })).collect::<Vec<Value>>(),
+                "bump": data.bump,
+            }),
+            DonaprotoAccount::ReceiptMetadata(data) => json!({
+                "receipt_mint": data.receipt_mint.to_string(),
+                "donation_data": data.donation_data.to_string(),
+                "donor": data.donor.to_string(),
+                "donation_mint": data.donation_mint.to_string(),
+                "amount": data.amount,
+                "timestamp": data.timestamp,
+                "bump": data.bump,
+            }),
+        };
+        if let Value::Object(fields) = fields {
+            self.fields = fields;
+        }
+
+        let report = check(&self.account, context);
+        self.computed = report.computed;
+        self.warnings = report.warnings;
+    }
+}
+
+#[derive(Default)]
+struct Report {
+    computed: Map<String, Value>,
+    warnings: Vec<String>,
+}
+
+impl Report {
+    fn computed(&mut self, name: &str, value: impl Into<Value>) {
+        self.computed.insert(name.to_string(), value.into());
+    }
+
+    fn warn(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+
+    fn check_version(&mut self, version: u8, current_version: u8) {
+        if version < current_version {
+            self.warn(format!(
+                "layout version {} is older than {}, run migrate_account",
+                version, current_version
+            ));
+        }
+    }
+}
+
+/// Computed fields and inconsistencies, only for accounts where they help debugging
+fn check(account: &DonaprotoAccount, context: &DecodeContext) -> Report {
+    let mut report = Report::default();
+    let now = context.now;
+    match account {
+        DonaprotoAccount::Donation(data) => {
+            let progress_percent = if data.amount_collecting == 0 {
+                100.0
+            } else {
+                data.total_amount_received as f64 * 100.0 / data.amount_collecting as f64
+            };
+            let is_ended = now >= data.ending_timestamp;
+            let is_withdrawable = !data.is_closed()
+                && (is_ended || data.total_amount_received >= data.amount_collecting);
+
+            report.computed(
+                "progress_percent",
+                (progress_percent * 100.0).round() / 100.0,
+            );
+            report.computed("amount_remaining", data.headroom());
+            report.computed("time_remaining", data.ending_timestamp.saturating_sub(now));
+            report.computed("is_ended", is_ended);
+            report.computed("is_withdrawable", is_withdrawable);
+            if let Some(holding_balance) = context.holding_balance {
+                report.computed("holding_balance", holding_balance);
+                if !data.is_closed() && holding_balance != data.total_amount_received {
+                    report.warn(format!(
+                        "holding balance {} differs from total_amount_received {}",
+                        holding_balance, data.total_amount_received
+                    ));
+                }
+                if data.is_closed() && holding_balance > 0 {
+                    report.warn(format!(
+                        "closed donation still holds {} tokens",
+                        holding_balance
+                    ));
+                }
+            }
+            if data.is_hard_capped() && data.total_amount_received > data.amount_collecting {
+                report.warn("hard capped donation received more than amount_collecting".into());
+            }
+            if data.total_amount_matched > data.total_amount_received {
+                report.warn("total_amount_matched exceeds total_amount_received".into());
+            }
+            if data.self_funded_amount > data.total_amount_received {
+                report.warn("self_funded_amount exceeds total_amount_received".into());
+            }
+            report.check_version(data.version, DonationData::VERSION);
+        }
+        DonaprotoAccount::DonationProtocol(data) => {
+            let is_over_budget = data.is_emission_scheduled()
+                && data.current_epoch_consumed > data.current_epoch_budget;
+            let is_cliff_after_duration =
+                data.is_reward_vesting() && data.vesting_cliff > data.vesting_duration;
+            report.computed("is_emission_scheduled", data.is_emission_scheduled());
+            report.computed("is_reward_vesting", data.is_reward_vesting());
+            if is_over_budget {
+                report.warn("current epoch consumed more than its budget".into());
+            }
+            if is_cliff_after_duration {
+                report.warn("vesting_cliff exceeds vesting_duration".into());
+            }
+            report.check_version(data.version, DonationProtocolData::VERSION);
+        }
+        DonaprotoAccount::Creator(data) => {
+            if data.donations_closed_count > data.donations_created_count {
+                report.warn("more donations closed than created".into());
+            }
+            report.check_version(data.version, CreatorData::VERSION);
+        }
+        DonaprotoAccount::Contributor(data) => {
+            if data.total_amount_claimed > data.total_amount_vested {
+                report.warn("total_amount_claimed exceeds total_amount_vested".into());
+            }
+            report.check_version(data.version, ContributorData::VERSION);
+        }
+        DonaprotoAccount::Subscription(data) => {
+            let is_due = data.next_due <= now && data.next_due <= data.ending_timestamp;
+            report.computed("is_due", is_due);
+            report.computed("is_ended", data.next_due > data.ending_timestamp);
+        }
+        DonaprotoAccount::PayoutSplit(data) => {
+            let total_bps: u32 = data
+                .recipients
+                .iter()
+                .map(|recipient| recipient.share_bps as u32)
+                .sum();
+            if total_bps != BPS_DENOMINATOR as u32 {
+                report.warn(format!("shares add up to {} bps", total_bps));
+            }
+        }
+        DonaprotoAccount::RewardVesting(data) => {
+            let unclaimed = data
+                .total_amount_vested
+                .saturating_sub(data.total_amount_claimed);
+            report.computed("unclaimed_amount", unclaimed);
+            if data.total_amount_claimed > data.total_amount_vested {
+                report.warn("total_amount_claimed exceeds total_amount_vested".into());
+            }
+        }
+        DonaprotoAccount::DonationReceipt(data) => {
+            if data.amount_receipted > data.amount_donated {
+                report.warn("amount_receipted exceeds amount_donated".into());
+            }
+            if data.amount_refundable > data.amount_donated {
+                report.warn("amount_refundable exceeds amount_donated".into());
+            }
+        }
+        DonaprotoAccount::FundingRound(data) => {
+            let total_match: u64 = data
+                .campaigns
+                .iter()
+                .map(|campaign| campaign.match_amount)
+                .sum();
+            report.computed("is_active", data.is_active(now));
+            report.computed("time_remaining", data.end_timestamp.saturating_sub(now));
+            if total_match > data.matching_pool_amount {
+                report.warn("campaign matches exceed the matching pool".into());
+            }
+        }
+        DonaprotoAccount::MatchPledge(data) => {
+            let remaining = data.cap.saturating_sub(data.total_amount_matched);
+            report.computed("amount_remaining", data.headroom());
+            report.computed("is_expired", now >= data.deadline);
+            if data.total_amount_matched > data.cap {
+                report.warn("total_amount_matched exceeds cap".into());
+            }
+        }
+        DonaprotoAccount::DonationTiers(data) => {
+            let over_claimed = data.tiers.iter().any(|tier| tier.claimed > tier.supply);
+            if over_claimed {
+                report.warn("a tier is claimed past its supply".into());
+            }
+        }
+        DonaprotoAccount::AuthorizedClmm(_)
+        | DonaprotoAccount::AuthorizedClmmPool(_)
+        | DonaprotoAccount::MintFee(_)
+        | DonaprotoAccount::ReceiptMetadata(_) => {}
+    }
+    report
+}
+
+impl fmt::Display for DecodedAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {}", self.account.type_name(), self.address)?;
+        for (name, value) in self.fields.iter().chain(self.computed.iter()) {
+            writeln!(f, "  {:<28}{}", name, display_value(value))?;
+        }
+        for warning in &self.warnings {
+            writeln!(f, "  WARNING: {}", warning)?;
+        }
+        Ok(())
+    }
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn describe_protocol(data: &DonationProtocolData) -> Value {
+    json!({
+        "treasury_mint": data.treasury_mint.to_string(),
+        "treasury": data.treasury.to_string(),
+        "donation_mint": data.donation_mint.to_string(),
+        "authority": data.authority.to_string(),
+        "min_amount_to_earn": data.min_amount_to_earn,
+        "min_amount_to_collect": data.min_amount_to_collect,
+        "treasury_owner_bump": data.treasury_owner_bump,
+        "fee_bps": data.fee_bps,
+        "epoch_duration": data.epoch_duration,
+        "epoch_decay_bps": data.epoch_decay_bps,
+        "emission_carry_over": match data.emission_carry_over {
+            EmissionCarryOver::Forfeit => "forfeit",
+            EmissionCarryOver::CarryOver => "carry_over",
+        },
+        "epoch_start_timestamp": data.epoch_start_timestamp,
+        "current_epoch": data.current_epoch,
+        "current_epoch_base_budget": data.current_epoch_base_budget,
+        "current_epoch_budget": data.current_epoch_budget,
+        "current_epoch_consumed": data.current_epoch_consumed,
+        "vesting_duration": data.vesting_duration,
+        "vesting_cliff": data.vesting_cliff,
+        "total_reward_vesting": data.total_reward_vesting,
+        "max_self_funded_bps": data.max_self_funded_bps,
+        "version": data.version,
+    })
+}
+
+fn describe_donation(data: &DonationData) -> Value {
+    json!({
+        "amount_collecting": data.amount_collecting,
+        "total_amount_received": data.total_amount_received,
+        "ending_timestamp": data.ending_timestamp,
+        "is_closed": data.is_closed(),
+        "recipient": data.recipient.to_string(),
+        "donation_protocol": data.donation_protocol.to_string(),
+        "holding_wallet": data.holding_wallet.to_string(),
+        "creator_data": data.creator_data.to_string(),
+        "donation_mint": data.donation_mint.to_string(),
+        "holding_bump": data.holding_bump,
+        "ipfs_hash": data.ipfs_hash(),
+        "payout_split": data.payout_split.to_string(),
+        "fee_bps": data.fee_bps,
+        "fee_amount": data.fee_amount,
+        "creator_wallet": data.creator_wallet.to_string(),
+        "self_funded_amount": data.self_funded_amount,
+        "total_amount_matched": data.total_amount_matched,
+        "match_pledge": data.match_pledge.to_string(),
+        "donation_tiers": data.donation_tiers.to_string(),
+        "hard_cap_mode": match data.hard_cap_mode() {
+            HardCapMode::None => "none",
+            HardCapMode::Reject => "reject",
+            HardCapMode::Trim => "trim",
+        },
+        "is_refundable": data.is_refundable(),
+        "version": data.version,
+    })
+}
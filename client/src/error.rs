@@ -0,0 +1,20 @@
+use anchor_lang::prelude::Pubkey;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error(transparent)]
+    Rpc(#[from] solana_client::client_error::ClientError),
+    #[error("Account {0} could not be deserialized: {1}")]
+    InvalidAccount(Pubkey, anchor_lang::error::Error),
+    #[error("Account {0} is not a donaproto account")]
+    UnknownAccount(Pubkey),
+    #[error("No authorized CLMM pool prices {0} against the protocol mint")]
+    NoAuthorizedPool(Pubkey),
+    #[error("Pool state {0} does not pair the protocol mint")]
+    InvalidPoolState(Pubkey),
+    #[error("Event could not be decoded: {0}")]
+    InvalidEvent(String),
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
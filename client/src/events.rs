@@ -0,0 +1,96 @@
+//! Decodes the events donaproto emits with `emit!` from the log messages of a transaction
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use donaproto::events::{
+    DonationCreated, DonationReceived, DonationRefunded, FundsWithdrawn, MatchDistributed,
+    ProtocolInitialized, RewardsClaimed, TreasuryFunded, TreasuryRotated, TreasuryWithdrawn,
+};
+
+use crate::error::{ClientError, Result};
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+pub enum DonaprotoEvent {
+    ProtocolInitialized(ProtocolInitialized),
+    DonationCreated(DonationCreated),
+    DonationReceived(DonationReceived),
+    DonationRefunded(DonationRefunded),
+    RewardsClaimed(RewardsClaimed),
+    FundsWithdrawn(FundsWithdrawn),
+    MatchDistributed(MatchDistributed),
+    TreasuryFunded(TreasuryFunded),
+    TreasuryWithdrawn(TreasuryWithdrawn),
+    TreasuryRotated(TreasuryRotated),
+}
+
+fn deserialize<T: AnchorDeserialize>(mut data: &[u8]) -> Result<T> {
+    T::deserialize(&mut data).map_err(|err| ClientError::InvalidEvent(err.to_string()))
+}
+
+impl DonaprotoEvent {
+    /// None for events unknown to this client version
+    pub fn decode(data: &[u8]) -> Result<Option<Self>> {
+        let (discriminator, data) = match data.get(..8) {
+            Some(discriminator) => (discriminator, &data[8..]),
+            None => {
+                return Err(ClientError::InvalidEvent(
+                    "missing discriminator".to_string(),
+                ))
+            }
+        };
+        let event = match discriminator {
+            d if d == ProtocolInitialized::DISCRIMINATOR => {
+                Self::ProtocolInitialized(deserialize(data)?)
+            }
+            d if d == DonationCreated::DISCRIMINATOR => Self::DonationCreated(deserialize(data)?),
+            d if d == DonationReceived::DISCRIMINATOR => Self::DonationReceived(deserialize(data)?),
+            d if d == DonationRefunded::DISCRIMINATOR => Self::DonationRefunded(deserialize(data)?),
+            d if d == RewardsClaimed::DISCRIMINATOR => Self::RewardsClaimed(deserialize(data)?),
+            d if d == FundsWithdrawn::DISCRIMINATOR => Self::FundsWithdrawn(deserialize(data)?),
+            d if d == MatchDistributed::DISCRIMINATOR => Self::MatchDistributed(deserialize(data)?),
+            d if d == TreasuryFunded::DISCRIMINATOR => Self::TreasuryFunded(deserialize(data)?),
+            d if d == TreasuryWithdrawn::DISCRIMINATOR => {
+                Self::TreasuryWithdrawn(deserialize(data)?)
+            }
+            d if d == TreasuryRotated::DISCRIMINATOR => Self::TreasuryRotated(deserialize(data)?),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(event))
+    }
+}
+
+/// Events in emission order. `Program data:` lines are only taken while donaproto is on top
+/// of the invoke stack, so data logged by CPI callees or other programs is never mistaken
+/// for a donaproto event.
+pub fn parse_logs(logs: &[String]) -> Result<Vec<DonaprotoEvent>> {
+    let program_id = crate::PROGRAM_ID.to_string();
+    let mut invoke_stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for log in logs {
+        if let Some(data) = log.strip_prefix(PROGRAM_DATA) {
+            if invoke_stack.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            let data = STANDARD
+                .decode(data)
+                .map_err(|err| ClientError::InvalidEvent(err.to_string()))?;
+            if let Some(event) = DonaprotoEvent::decode(&data)? {
+                events.push(event);
+            }
+        } else if let Some(message) = log.strip_prefix("Program ") {
+            let mut words = message.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(program), Some("invoke")) => invoke_stack.push(program),
+                (Some(_), Some("success")) | (Some(_), Some("failed:")) => {
+                    invoke_stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(events)
+}
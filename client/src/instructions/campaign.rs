@@ -0,0 +1,337 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, system_program, sysvar},
+};
+use donaproto::{
+    accounts, instruction,
+    states::{HardCapMode, PerkTierArgs},
+};
+
+use super::{build_instruction, readonly_accounts, writable_accounts};
+use crate::{
+    keys::{CampaignKeys, PoolKeys},
+    pda::{
+        find_creator_data, find_donation_tiers, find_fee_vault, find_holding_wallet,
+        find_holding_wallet_owner, find_mint_fee, find_payout_split,
+    },
+};
+
+pub struct InitializeCreatorBuilder {
+    donation_protocol: Pubkey,
+    creator_wallet: Pubkey,
+    payer: Pubkey,
+}
+
+impl InitializeCreatorBuilder {
+    pub fn new(donation_protocol: Pubkey, creator_wallet: Pubkey) -> Self {
+        Self {
+            donation_protocol,
+            creator_wallet,
+            payer: creator_wallet,
+        }
+    }
+
+    pub fn payer(mut self, payer: Pubkey) -> Self {
+        self.payer = payer;
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::InitializeCreator {
+                creator_data: find_creator_data(&self.donation_protocol, &self.creator_wallet).0,
+                donation_protocol: self.donation_protocol,
+                creator_wallet_address: self.creator_wallet,
+                payer: self.payer,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            instruction::InitializeCreator {},
+            &[],
+        )
+    }
+}
+
+pub struct CreateDonationBuilder {
+    donation_protocol: Pubkey,
+    donation_data: Pubkey,
+    donation_mint: Pubkey,
+    holding_wallet: Pubkey,
+    recipient: Pubkey,
+    creator_wallet: Pubkey,
+    amount: u64,
+    ipfs_hash: String,
+    ending_timestamp: u64,
+    hard_cap_mode: HardCapMode,
+    tiers: Vec<PerkTierArgs>,
+    refundable: bool,
+    pool: Option<PoolKeys>,
+    default_donation_mint: Pubkey,
+}
+
+impl CreateDonationBuilder {
+    /// `donation_data` is a new keypair co-signing the transaction,
+    /// the holding wallet defaults to the associated token account of the holding pda
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        donation_protocol: Pubkey,
+        donation_data: Pubkey,
+        donation_mint: Pubkey,
+        recipient: Pubkey,
+        creator_wallet: Pubkey,
+        amount: u64,
+        ipfs_hash: impl Into<String>,
+        ending_timestamp: u64,
+    ) -> Self {
+        Self {
+            donation_protocol,
+            donation_data,
+            donation_mint,
+            holding_wallet: find_holding_wallet(&donation_data, &donation_mint),
+            recipient,
+            creator_wallet,
+            amount,
+            ipfs_hash: ipfs_hash.into(),
+            ending_timestamp,
+            hard_cap_mode: HardCapMode::None,
+            tiers: Vec::new(),
+            refundable: false,
+            pool: None,
+            default_donation_mint: Pubkey::default(),
+        }
+    }
+
+    pub fn holding_wallet(mut self, holding_wallet: Pubkey) -> Self {
+        self.holding_wallet = holding_wallet;
+        self
+    }
+
+    pub fn hard_cap_mode(mut self, hard_cap_mode: HardCapMode) -> Self {
+        self.hard_cap_mode = hard_cap_mode;
+        self
+    }
+
+    /// Lets donors refund while the donation runs
+    pub fn refundable(mut self) -> Self {
+        self.refundable = true;
+        self
+    }
+
+    /// Perk tiers are added in ascending `threshold` order,
+    /// donors reach them through their cumulative contribution
+    pub fn tier(mut self, threshold: u64, supply: u32, ipfs_hash: impl Into<String>) -> Self {
+        self.tiers.push(PerkTierArgs {
+            threshold,
+            supply,
+            ipfs_hash: ipfs_hash.into(),
+        });
+        self
+    }
+
+    /// Switches to `create_donation_v2` for a donation mint other than the protocol one
+    pub fn v2(mut self, default_donation_mint: Pubkey, pool: PoolKeys) -> Self {
+        self.default_donation_mint = default_donation_mint;
+        self.pool = Some(pool);
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let (holding_wallet_owner, holding_bump) = find_holding_wallet_owner(&self.donation_data);
+        let creator_data = find_creator_data(&self.donation_protocol, &self.creator_wallet).0;
+        let mint_fee = find_mint_fee(&self.donation_protocol, &self.donation_mint).0;
+        let donation_tiers =
+            (!self.tiers.is_empty()).then(|| find_donation_tiers(&self.donation_data).0);
+        match self.pool {
+            None => build_instruction(
+                accounts::CreateDonation {
+                    donation_data: self.donation_data,
+                    donation_tiers,
+                    donation_protocol: self.donation_protocol,
+                    holding_wallet: self.holding_wallet,
+                    holding_wallet_owner,
+                    recipient: self.recipient,
+                    creator_data,
+                    donation_mint: self.donation_mint,
+                    mint_fee,
+                    creator_wallet_address: self.creator_wallet,
+                    system_program: system_program::ID,
+                    rent: sysvar::rent::ID,
+                },
+                instruction::CreateDonation {
+                    amount: self.amount,
+                    ipfs_hash: self.ipfs_hash.clone(),
+                    ending_timestamp: self.ending_timestamp,
+                    holding_bump,
+                    hard_cap_mode: self.hard_cap_mode,
+                    tiers: self.tiers.clone(),
+                    refundable: self.refundable,
+                },
+                &[],
+            ),
+            Some(pool) => build_instruction(
+                accounts::CreateDonationV2 {
+                    donation_data: self.donation_data,
+                    donation_tiers,
+                    donation_protocol: self.donation_protocol,
+                    holding_wallet: self.holding_wallet,
+                    holding_wallet_owner,
+                    recipient: self.recipient,
+                    creator_data,
+                    donation_mint: self.donation_mint,
+                    default_donation_mint: self.default_donation_mint,
+                    authorized_clmm_pool: pool.authorized_clmm_pool,
+                    pool_state: pool.pool_state,
+                    mint_fee,
+                    creator_wallet_address: self.creator_wallet,
+                    system_program: system_program::ID,
+                    rent: sysvar::rent::ID,
+                },
+                instruction::CreateDonationV2 {
+                    amount: self.amount,
+                    ipfs_hash: self.ipfs_hash.clone(),
+                    ending_timestamp: self.ending_timestamp,
+                    holding_bump,
+                    hard_cap_mode: self.hard_cap_mode,
+                    tiers: self.tiers.clone(),
+                    refundable: self.refundable,
+                },
+                &[],
+            ),
+        }
+    }
+}
+
+pub struct WithdrawFundsBuilder {
+    campaign: CampaignKeys,
+    recipient_token_wallet: Pubkey,
+    payer: Pubkey,
+    fee_vault: Option<Pubkey>,
+    payout_recipients: Vec<Pubkey>,
+    pool: Option<PoolKeys>,
+    default_donation_mint: Pubkey,
+}
+
+impl WithdrawFundsBuilder {
+    /// `payer` must own `recipient_token_wallet`, the campaign recipient unless it has a payout split
+    pub fn new(campaign: &CampaignKeys, recipient_token_wallet: Pubkey, payer: Pubkey) -> Self {
+        Self {
+            campaign: *campaign,
+            recipient_token_wallet,
+            payer,
+            fee_vault: None,
+            payout_recipients: Vec::new(),
+            pool: None,
+            default_donation_mint: Pubkey::default(),
+        }
+    }
+
+    /// Required when the campaign charges a protocol fee
+    pub fn with_fee_vault(mut self) -> Self {
+        self.fee_vault = Some(
+            find_fee_vault(
+                &self.campaign.donation_protocol,
+                &self.campaign.donation_mint,
+            )
+            .0,
+        );
+        self
+    }
+
+    /// Recipient token wallets of the campaign payout split, in split order
+    pub fn payout_recipients(mut self, payout_recipients: Vec<Pubkey>) -> Self {
+        self.payout_recipients = payout_recipients;
+        self
+    }
+
+    /// Switches to `withdraw_funds_v2` for a donation mint other than the protocol one
+    pub fn v2(mut self, default_donation_mint: Pubkey, pool: PoolKeys) -> Self {
+        self.default_donation_mint = default_donation_mint;
+        self.pool = Some(pool);
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let remaining_accounts = writable_accounts(&self.payout_recipients);
+        match self.pool {
+            None => build_instruction(
+                accounts::WithdrawFunds {
+                    donation_data: self.campaign.donation_data,
+                    creator_data: self.campaign.creator_data,
+                    donation_protocol: self.campaign.donation_protocol,
+                    holding_wallet: self.campaign.holding_wallet,
+                    holding_wallet_owner: self.campaign.holding_wallet_owner,
+                    recipient_token_wallet: self.recipient_token_wallet,
+                    donation_mint: self.campaign.donation_mint,
+                    fee_vault: self.fee_vault,
+                    payout_split: self.campaign.payout_split,
+                    payer: self.payer,
+                    token_program: anchor_spl::token::ID,
+                },
+                instruction::WithdrawFunds {},
+                &remaining_accounts,
+            ),
+            Some(pool) => build_instruction(
+                accounts::WithdrawFundsV2 {
+                    donation_data: self.campaign.donation_data,
+                    creator_data: self.campaign.creator_data,
+                    donation_protocol: self.campaign.donation_protocol,
+                    holding_wallet: self.campaign.holding_wallet,
+                    holding_wallet_owner: self.campaign.holding_wallet_owner,
+                    recipient_token_wallet: self.recipient_token_wallet,
+                    donation_mint: self.campaign.donation_mint,
+                    default_donation_mint: self.default_donation_mint,
+                    authorized_clmm_pool: pool.authorized_clmm_pool,
+                    pool_state: pool.pool_state,
+                    fee_vault: self.fee_vault,
+                    payout_split: self.campaign.payout_split,
+                    payer: self.payer,
+                    token_program: anchor_spl::token::ID,
+                },
+                instruction::WithdrawFundsV2 {},
+                &remaining_accounts,
+            ),
+        }
+    }
+}
+
+pub struct CreatePayoutSplitBuilder {
+    donation_protocol: Pubkey,
+    donation_data: Pubkey,
+    creator_wallet: Pubkey,
+    recipients: Vec<(Pubkey, u16)>,
+}
+
+impl CreatePayoutSplitBuilder {
+    pub fn new(donation_protocol: Pubkey, donation_data: Pubkey, creator_wallet: Pubkey) -> Self {
+        Self {
+            donation_protocol,
+            donation_data,
+            creator_wallet,
+            recipients: Vec::new(),
+        }
+    }
+
+    /// Shares of all recipients must add up to `BPS_DENOMINATOR`
+    pub fn recipient(mut self, token_wallet: Pubkey, share_bps: u16) -> Self {
+        self.recipients.push((token_wallet, share_bps));
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let token_wallets: Vec<Pubkey> = self.recipients.iter().map(|(key, _)| *key).collect();
+        build_instruction(
+            accounts::CreatePayoutSplit {
+                payout_split: find_payout_split(&self.donation_data).0,
+                donation_data: self.donation_data,
+                creator_data: find_creator_data(&self.donation_protocol, &self.creator_wallet).0,
+                creator_wallet_address: self.creator_wallet,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            instruction::CreatePayoutSplit {
+                shares_bps: self.recipients.iter().map(|(_, share)| *share).collect(),
+            },
+            &readonly_accounts(&token_wallets),
+        )
+    }
+}
@@ -0,0 +1,609 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, system_program, sysvar},
+};
+use anchor_spl::associated_token::get_associated_token_address;
+use donaproto::{accounts, instruction};
+
+use super::{build_instruction, writable_accounts};
+use crate::{
+    keys::{CampaignKeys, PoolKeys, ProtocolKeys},
+    pda::{
+        find_contributor_data, find_donation_receipt, find_match_pledge_escrow,
+        find_receipt_metadata, find_receipt_mint_authority, find_reward_vesting,
+    },
+};
+
+/// Vesting account of the contributor when the protocol vests rewards
+fn reward_vesting_for(protocol: &ProtocolKeys, contributor_wallet: &Pubkey) -> Option<Pubkey> {
+    protocol
+        .is_reward_vesting
+        .then(|| find_reward_vesting(&protocol.donation_protocol, contributor_wallet).0)
+}
+
+fn match_pledge_escrow_for(campaign: &CampaignKeys) -> Option<Pubkey> {
+    campaign
+        .match_pledge
+        .map(|match_pledge| find_match_pledge_escrow(&match_pledge).0)
+}
+
+pub struct InitializeContributorBuilder {
+    donation_protocol: Pubkey,
+    contributor_wallet: Pubkey,
+    payer: Pubkey,
+}
+
+impl InitializeContributorBuilder {
+    pub fn new(donation_protocol: Pubkey, contributor_wallet: Pubkey) -> Self {
+        Self {
+            donation_protocol,
+            contributor_wallet,
+            payer: contributor_wallet,
+        }
+    }
+
+    pub fn payer(mut self, payer: Pubkey) -> Self {
+        self.payer = payer;
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let (contributor_data, bump) =
+            find_contributor_data(&self.donation_protocol, &self.contributor_wallet);
+        build_instruction(
+            accounts::InitializeContributor {
+                contributor_data,
+                donation_protocol: self.donation_protocol,
+                contributor_wallet_address: self.contributor_wallet,
+                payer: self.payer,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            instruction::InitializeContributor { bump },
+            &[],
+        )
+    }
+}
+
+pub struct DonateBuilder {
+    protocol: ProtocolKeys,
+    campaign: CampaignKeys,
+    user_wallet: Pubkey,
+    user_token_wallet: Pubkey,
+    user_reward_token_wallet: Pubkey,
+    funding_round: Option<Pubkey>,
+    pool: Option<PoolKeys>,
+    receipt_mint: Option<Pubkey>,
+    amount: u64,
+}
+
+/// Optional receipt accounts of `donate`/`donate_v2`
+struct ReceiptKeys {
+    receipt_mint: Option<Pubkey>,
+    receipt_token_account: Option<Pubkey>,
+    receipt_metadata: Option<Pubkey>,
+    receipt_mint_authority: Option<Pubkey>,
+    associated_token_program: Option<Pubkey>,
+    memo_program: Option<Pubkey>,
+}
+
+impl ReceiptKeys {
+    fn new(protocol: &ProtocolKeys, user_wallet: &Pubkey, receipt_mint: Option<Pubkey>) -> Self {
+        Self {
+            receipt_mint,
+            receipt_token_account: receipt_mint
+                .map(|receipt_mint| get_associated_token_address(user_wallet, &receipt_mint)),
+            receipt_metadata: receipt_mint
+                .map(|receipt_mint| find_receipt_metadata(&receipt_mint).0),
+            receipt_mint_authority: receipt_mint
+                .map(|_| find_receipt_mint_authority(&protocol.donation_protocol).0),
+            associated_token_program: receipt_mint.map(|_| anchor_spl::associated_token::ID),
+            memo_program: receipt_mint.map(|_| anchor_spl::memo::ID),
+        }
+    }
+}
+
+impl DonateBuilder {
+    /// Token wallets default to the associated token accounts of `user_wallet`
+    pub fn new(
+        protocol: &ProtocolKeys,
+        campaign: &CampaignKeys,
+        user_wallet: Pubkey,
+        amount: u64,
+    ) -> Self {
+        Self {
+            protocol: *protocol,
+            campaign: *campaign,
+            user_wallet,
+            user_token_wallet: get_associated_token_address(&user_wallet, &campaign.donation_mint),
+            user_reward_token_wallet: get_associated_token_address(
+                &user_wallet,
+                &protocol.treasury_mint,
+            ),
+            funding_round: None,
+            pool: None,
+            receipt_mint: None,
+            amount,
+        }
+    }
+
+    pub fn user_token_wallet(mut self, user_token_wallet: Pubkey) -> Self {
+        self.user_token_wallet = user_token_wallet;
+        self
+    }
+
+    pub fn user_reward_token_wallet(mut self, user_reward_token_wallet: Pubkey) -> Self {
+        self.user_reward_token_wallet = user_reward_token_wallet;
+        self
+    }
+
+    /// Counts the donation towards a quadratic funding round
+    pub fn funding_round(mut self, funding_round: Pubkey) -> Self {
+        self.funding_round = Some(funding_round);
+        self
+    }
+
+    /// Switches to `donate_v2`, required when the campaign mint is not the protocol mint
+    pub fn v2(mut self, pool: PoolKeys) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Issues a receipt token for the donation,
+    /// `receipt_mint` is a new keypair co-signing the transaction
+    pub fn receipt(mut self, receipt_mint: Pubkey) -> Self {
+        self.receipt_mint = Some(receipt_mint);
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let contributor_data =
+            find_contributor_data(&self.protocol.donation_protocol, &self.user_wallet).0;
+        let donation_receipt =
+            find_donation_receipt(&self.campaign.donation_data, &self.user_wallet).0;
+        let reward_vesting = reward_vesting_for(&self.protocol, &self.user_wallet);
+        let receipt = ReceiptKeys::new(&self.protocol, &self.user_wallet, self.receipt_mint);
+        match self.pool {
+            None => build_instruction(
+                accounts::Donate {
+                    donation_data: self.campaign.donation_data,
+                    contributor_data,
+                    donation_receipt,
+                    funding_round: self.funding_round,
+                    match_pledge: self.campaign.match_pledge,
+                    match_pledge_escrow: match_pledge_escrow_for(&self.campaign),
+                    donation_tiers: self.campaign.donation_tiers,
+                    donation_protocol: self.protocol.donation_protocol,
+                    user_token_wallet: self.user_token_wallet,
+                    user_reward_token_wallet: self.user_reward_token_wallet,
+                    reward_treasury: self.protocol.treasury,
+                    reward_treasury_owner: self.protocol.treasury_owner,
+                    reward_vesting,
+                    recipient: self.campaign.recipient,
+                    holding_wallet: self.campaign.holding_wallet,
+                    donation_mint: self.campaign.donation_mint,
+                    reward_mint: self.protocol.treasury_mint,
+                    receipt_mint: receipt.receipt_mint,
+                    receipt_token_account: receipt.receipt_token_account,
+                    receipt_metadata: receipt.receipt_metadata,
+                    receipt_mint_authority: receipt.receipt_mint_authority,
+                    user_wallet: self.user_wallet,
+                    token_program: anchor_spl::token::ID,
+                    system_program: system_program::ID,
+                    associated_token_program: receipt.associated_token_program,
+                    memo_program: receipt.memo_program,
+                },
+                instruction::Donate {
+                    amount: self.amount,
+                },
+                &[],
+            ),
+            Some(pool) => build_instruction(
+                accounts::DonateV2 {
+                    donation_data: self.campaign.donation_data,
+                    contributor_data,
+                    donation_receipt,
+                    funding_round: self.funding_round,
+                    match_pledge: self.campaign.match_pledge,
+                    match_pledge_escrow: match_pledge_escrow_for(&self.campaign),
+                    donation_tiers: self.campaign.donation_tiers,
+                    donation_protocol: self.protocol.donation_protocol,
+                    user_token_wallet: self.user_token_wallet,
+                    user_reward_token_wallet: self.user_reward_token_wallet,
+                    reward_treasury: self.protocol.treasury,
+                    reward_treasury_owner: self.protocol.treasury_owner,
+                    reward_vesting,
+                    recipient: self.campaign.recipient,
+                    holding_wallet: self.campaign.holding_wallet,
+                    donation_mint: self.campaign.donation_mint,
+                    reward_mint: self.protocol.treasury_mint,
+                    default_donation_mint: self.protocol.donation_mint,
+                    authorized_clmm_pool: pool.authorized_clmm_pool,
+                    pool_state: pool.pool_state,
+                    receipt_mint: receipt.receipt_mint,
+                    receipt_token_account: receipt.receipt_token_account,
+                    receipt_metadata: receipt.receipt_metadata,
+                    receipt_mint_authority: receipt.receipt_mint_authority,
+                    user_wallet: self.user_wallet,
+                    token_program: anchor_spl::token::ID,
+                    system_program: system_program::ID,
+                    associated_token_program: receipt.associated_token_program,
+                    memo_program: receipt.memo_program,
+                },
+                instruction::DonateV2 {
+                    amount: self.amount,
+                },
+                &[],
+            ),
+        }
+    }
+}
+
+pub struct DonateBatchBuilder {
+    protocol: ProtocolKeys,
+    user_wallet: Pubkey,
+    user_token_wallet: Pubkey,
+    user_reward_token_wallet: Pubkey,
+    funding_round: Option<Pubkey>,
+    donations: Vec<(CampaignKeys, u64)>,
+}
+
+impl DonateBatchBuilder {
+    /// Token wallets default to the associated token accounts of `user_wallet`
+    pub fn new(protocol: &ProtocolKeys, user_wallet: Pubkey) -> Self {
+        Self {
+            protocol: *protocol,
+            user_wallet,
+            user_token_wallet: get_associated_token_address(&user_wallet, &protocol.donation_mint),
+            user_reward_token_wallet: get_associated_token_address(
+                &user_wallet,
+                &protocol.treasury_mint,
+            ),
+            funding_round: None,
+            donations: Vec::new(),
+        }
+    }
+
+    /// Adds `amount` for `campaign`, which must collect the protocol donation mint
+    pub fn donation(mut self, campaign: &CampaignKeys, amount: u64) -> Self {
+        self.donations.push((*campaign, amount));
+        self
+    }
+
+    pub fn user_token_wallet(mut self, user_token_wallet: Pubkey) -> Self {
+        self.user_token_wallet = user_token_wallet;
+        self
+    }
+
+    pub fn user_reward_token_wallet(mut self, user_reward_token_wallet: Pubkey) -> Self {
+        self.user_reward_token_wallet = user_reward_token_wallet;
+        self
+    }
+
+    /// Counts the donations towards a quadratic funding round including every campaign
+    pub fn funding_round(mut self, funding_round: Pubkey) -> Self {
+        self.funding_round = Some(funding_round);
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let contributor_data =
+            find_contributor_data(&self.protocol.donation_protocol, &self.user_wallet).0;
+        let mut campaign_accounts: Vec<Pubkey> = Vec::new();
+        for (campaign, _) in self.donations.iter() {
+            campaign_accounts.extend([
+                campaign.donation_data,
+                campaign.holding_wallet,
+                campaign.recipient,
+                find_donation_receipt(&campaign.donation_data, &self.user_wallet).0,
+            ]);
+            if let Some(match_pledge) = campaign.match_pledge {
+                campaign_accounts.extend([match_pledge, find_match_pledge_escrow(&match_pledge).0]);
+            }
+            if let Some(donation_tiers) = campaign.donation_tiers {
+                campaign_accounts.push(donation_tiers);
+            }
+        }
+        build_instruction(
+            accounts::DonateBatch {
+                contributor_data,
+                funding_round: self.funding_round,
+                donation_protocol: self.protocol.donation_protocol,
+                user_token_wallet: self.user_token_wallet,
+                user_reward_token_wallet: self.user_reward_token_wallet,
+                reward_treasury: self.protocol.treasury,
+                reward_treasury_owner: self.protocol.treasury_owner,
+                reward_vesting: reward_vesting_for(&self.protocol, &self.user_wallet),
+                donation_mint: self.protocol.donation_mint,
+                reward_mint: self.protocol.treasury_mint,
+                user_wallet: self.user_wallet,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::DonateBatch {
+                amounts: self.donations.iter().map(|(_, amount)| *amount).collect(),
+            },
+            &writable_accounts(&campaign_accounts),
+        )
+    }
+}
+
+pub struct DonateOnBehalfBuilder {
+    protocol: ProtocolKeys,
+    campaign: CampaignKeys,
+    beneficiary: Pubkey,
+    source_token_wallet: Pubkey,
+    beneficiary_reward_token_wallet: Pubkey,
+    token_authority: Pubkey,
+    payer: Pubkey,
+    funding_round: Option<Pubkey>,
+    amount: u64,
+}
+
+impl DonateOnBehalfBuilder {
+    /// Pulls `amount` from the associated token account of `token_authority`,
+    /// crediting the donation and its rewards to `beneficiary`
+    pub fn new(
+        protocol: &ProtocolKeys,
+        campaign: &CampaignKeys,
+        beneficiary: Pubkey,
+        token_authority: Pubkey,
+        amount: u64,
+    ) -> Self {
+        Self {
+            protocol: *protocol,
+            campaign: *campaign,
+            beneficiary,
+            source_token_wallet: get_associated_token_address(
+                &token_authority,
+                &campaign.donation_mint,
+            ),
+            beneficiary_reward_token_wallet: get_associated_token_address(
+                &beneficiary,
+                &protocol.treasury_mint,
+            ),
+            token_authority,
+            payer: token_authority,
+            funding_round: None,
+            amount,
+        }
+    }
+
+    pub fn source_token_wallet(mut self, source_token_wallet: Pubkey) -> Self {
+        self.source_token_wallet = source_token_wallet;
+        self
+    }
+
+    pub fn beneficiary_reward_token_wallet(
+        mut self,
+        beneficiary_reward_token_wallet: Pubkey,
+    ) -> Self {
+        self.beneficiary_reward_token_wallet = beneficiary_reward_token_wallet;
+        self
+    }
+
+    pub fn payer(mut self, payer: Pubkey) -> Self {
+        self.payer = payer;
+        self
+    }
+
+    pub fn funding_round(mut self, funding_round: Pubkey) -> Self {
+        self.funding_round = Some(funding_round);
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::DonateOnBehalf {
+                donation_data: self.campaign.donation_data,
+                contributor_data: find_contributor_data(
+                    &self.protocol.donation_protocol,
+                    &self.beneficiary,
+                )
+                .0,
+                donation_receipt: find_donation_receipt(
+                    &self.campaign.donation_data,
+                    &self.beneficiary,
+                )
+                .0,
+                funding_round: self.funding_round,
+                match_pledge: self.campaign.match_pledge,
+                match_pledge_escrow: match_pledge_escrow_for(&self.campaign),
+                donation_tiers: self.campaign.donation_tiers,
+                donation_protocol: self.protocol.donation_protocol,
+                source_token_wallet: self.source_token_wallet,
+                beneficiary_reward_token_wallet: self.beneficiary_reward_token_wallet,
+                reward_treasury: self.protocol.treasury,
+                reward_treasury_owner: self.protocol.treasury_owner,
+                reward_vesting: reward_vesting_for(&self.protocol, &self.beneficiary),
+                recipient: self.campaign.recipient,
+                holding_wallet: self.campaign.holding_wallet,
+                donation_mint: self.campaign.donation_mint,
+                reward_mint: self.protocol.treasury_mint,
+                beneficiary: self.beneficiary,
+                token_authority: self.token_authority,
+                payer: self.payer,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::DonateOnBehalf {
+                amount: self.amount,
+            },
+            &[],
+        )
+    }
+}
+
+pub struct RefundDonationBuilder {
+    protocol: ProtocolKeys,
+    campaign: CampaignKeys,
+    donor_wallet: Pubkey,
+    donor_token_wallet: Pubkey,
+    donor_reward_token_wallet: Pubkey,
+    funding_round: Option<Pubkey>,
+    amount: u64,
+}
+
+impl RefundDonationBuilder {
+    pub fn new(
+        protocol: &ProtocolKeys,
+        campaign: &CampaignKeys,
+        donor_wallet: Pubkey,
+        amount: u64,
+    ) -> Self {
+        Self {
+            protocol: *protocol,
+            campaign: *campaign,
+            donor_wallet,
+            donor_token_wallet: get_associated_token_address(
+                &donor_wallet,
+                &campaign.donation_mint,
+            ),
+            donor_reward_token_wallet: get_associated_token_address(
+                &donor_wallet,
+                &protocol.treasury_mint,
+            ),
+            funding_round: None,
+            amount,
+        }
+    }
+
+    pub fn donor_token_wallet(mut self, donor_token_wallet: Pubkey) -> Self {
+        self.donor_token_wallet = donor_token_wallet;
+        self
+    }
+
+    pub fn donor_reward_token_wallet(mut self, donor_reward_token_wallet: Pubkey) -> Self {
+        self.donor_reward_token_wallet = donor_reward_token_wallet;
+        self
+    }
+
+    /// Required when the refunded donation counted towards a funding round
+    pub fn funding_round(mut self, funding_round: Pubkey) -> Self {
+        self.funding_round = Some(funding_round);
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::RefundDonation {
+                donation_receipt: find_donation_receipt(
+                    &self.campaign.donation_data,
+                    &self.donor_wallet,
+                )
+                .0,
+                donation_data: self.campaign.donation_data,
+                contributor_data: find_contributor_data(
+                    &self.protocol.donation_protocol,
+                    &self.donor_wallet,
+                )
+                .0,
+                donation_protocol: self.protocol.donation_protocol,
+                recipient: self.campaign.recipient,
+                holding_wallet: self.campaign.holding_wallet,
+                holding_wallet_owner: self.campaign.holding_wallet_owner,
+                donor_token_wallet: self.donor_token_wallet,
+                donor_reward_token_wallet: self.donor_reward_token_wallet,
+                reward_treasury: self.protocol.treasury,
+                reward_vesting: reward_vesting_for(&self.protocol, &self.donor_wallet),
+                funding_round: self.funding_round,
+                match_pledge: self.campaign.match_pledge,
+                match_pledge_escrow: match_pledge_escrow_for(&self.campaign),
+                donor_wallet: self.donor_wallet,
+                token_program: anchor_spl::token::ID,
+            },
+            instruction::RefundDonation {
+                amount: self.amount,
+            },
+            &[],
+        )
+    }
+}
+
+pub struct InitializeRewardVestingBuilder {
+    donation_protocol: Pubkey,
+    contributor_wallet: Pubkey,
+    payer: Pubkey,
+}
+
+impl InitializeRewardVestingBuilder {
+    pub fn new(donation_protocol: Pubkey, contributor_wallet: Pubkey) -> Self {
+        Self {
+            donation_protocol,
+            contributor_wallet,
+            payer: contributor_wallet,
+        }
+    }
+
+    pub fn payer(mut self, payer: Pubkey) -> Self {
+        self.payer = payer;
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::InitializeRewardVesting {
+                reward_vesting: find_reward_vesting(
+                    &self.donation_protocol,
+                    &self.contributor_wallet,
+                )
+                .0,
+                donation_protocol: self.donation_protocol,
+                contributor_wallet_address: self.contributor_wallet,
+                payer: self.payer,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeRewardVesting {},
+            &[],
+        )
+    }
+}
+
+pub struct ClaimRewardsBuilder {
+    protocol: ProtocolKeys,
+    contributor_wallet: Pubkey,
+    contributor_reward_token_wallet: Pubkey,
+}
+
+impl ClaimRewardsBuilder {
+    pub fn new(protocol: &ProtocolKeys, contributor_wallet: Pubkey) -> Self {
+        Self {
+            protocol: *protocol,
+            contributor_wallet,
+            contributor_reward_token_wallet: get_associated_token_address(
+                &contributor_wallet,
+                &protocol.treasury_mint,
+            ),
+        }
+    }
+
+    pub fn contributor_reward_token_wallet(
+        mut self,
+        contributor_reward_token_wallet: Pubkey,
+    ) -> Self {
+        self.contributor_reward_token_wallet = contributor_reward_token_wallet;
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let donation_protocol = &self.protocol.donation_protocol;
+        build_instruction(
+            accounts::ClaimRewards {
+                reward_vesting: find_reward_vesting(donation_protocol, &self.contributor_wallet).0,
+                contributor_data: find_contributor_data(
+                    donation_protocol,
+                    &self.contributor_wallet,
+                )
+                .0,
+                donation_protocol: *donation_protocol,
+                reward_treasury: self.protocol.treasury,
+                reward_treasury_owner: self.protocol.treasury_owner,
+                contributor_reward_token_wallet: self.contributor_reward_token_wallet,
+                contributor_wallet: self.contributor_wallet,
+                token_program: anchor_spl::token::ID,
+            },
+            instruction::ClaimRewards {},
+            &[],
+        )
+    }
+}
@@ -0,0 +1,290 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, system_program, sysvar},
+};
+use donaproto::{accounts, instruction};
+
+use super::{build_instruction, readonly_accounts, writable_accounts};
+use crate::{
+    keys::CampaignKeys,
+    pda::{
+        find_fee_vault, find_match_pledge, find_match_pledge_escrow, find_matching_vault,
+        find_matching_vault_owner,
+    },
+};
+
+pub struct CreateFundingRoundBuilder {
+    funding_round: Pubkey,
+    donation_protocol: Pubkey,
+    matching_mint: Pubkey,
+    authority: Pubkey,
+    start_timestamp: u64,
+    end_timestamp: u64,
+    campaigns: Vec<Pubkey>,
+}
+
+impl CreateFundingRoundBuilder {
+    /// `funding_round` is a new keypair co-signing the transaction
+    pub fn new(
+        funding_round: Pubkey,
+        donation_protocol: Pubkey,
+        matching_mint: Pubkey,
+        authority: Pubkey,
+        start_timestamp: u64,
+        end_timestamp: u64,
+    ) -> Self {
+        Self {
+            funding_round,
+            donation_protocol,
+            matching_mint,
+            authority,
+            start_timestamp,
+            end_timestamp,
+            campaigns: Vec::new(),
+        }
+    }
+
+    /// Campaigns must share `matching_mint` and must not be hard capped
+    pub fn campaign(mut self, donation_data: Pubkey) -> Self {
+        self.campaigns.push(donation_data);
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::CreateFundingRound {
+                funding_round: self.funding_round,
+                matching_vault: find_matching_vault(&self.funding_round, &self.matching_mint).0,
+                matching_vault_owner: find_matching_vault_owner(&self.funding_round).0,
+                donation_protocol: self.donation_protocol,
+                matching_mint: self.matching_mint,
+                authority: self.authority,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::CreateFundingRound {
+                start_timestamp: self.start_timestamp,
+                end_timestamp: self.end_timestamp,
+            },
+            &readonly_accounts(&self.campaigns),
+        )
+    }
+}
+
+pub struct FinalizeRoundBuilder {
+    funding_round: Pubkey,
+    matching_mint: Pubkey,
+}
+
+impl FinalizeRoundBuilder {
+    pub fn new(funding_round: Pubkey, matching_mint: Pubkey) -> Self {
+        Self {
+            funding_round,
+            matching_mint,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::FinalizeRound {
+                funding_round: self.funding_round,
+                matching_vault: find_matching_vault(&self.funding_round, &self.matching_mint).0,
+            },
+            instruction::FinalizeRound {},
+            &[],
+        )
+    }
+}
+
+pub struct DistributeMatchBuilder {
+    funding_round: Pubkey,
+    campaign: CampaignKeys,
+    fee_vault: Option<Pubkey>,
+    payout_recipients: Vec<Pubkey>,
+}
+
+impl DistributeMatchBuilder {
+    pub fn new(funding_round: Pubkey, campaign: &CampaignKeys) -> Self {
+        Self {
+            funding_round,
+            campaign: *campaign,
+            fee_vault: None,
+            payout_recipients: Vec::new(),
+        }
+    }
+
+    /// Required when the campaign is closed and charges a protocol fee
+    pub fn with_fee_vault(mut self) -> Self {
+        self.fee_vault = Some(
+            find_fee_vault(
+                &self.campaign.donation_protocol,
+                &self.campaign.donation_mint,
+            )
+            .0,
+        );
+        self
+    }
+
+    /// Recipient token wallets of the closed campaign payout split, in split order
+    pub fn payout_recipients(mut self, payout_recipients: Vec<Pubkey>) -> Self {
+        self.payout_recipients = payout_recipients;
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::DistributeMatch {
+                funding_round: self.funding_round,
+                donation_data: self.campaign.donation_data,
+                donation_protocol: self.campaign.donation_protocol,
+                creator_data: self.campaign.creator_data,
+                holding_wallet: self.campaign.holding_wallet,
+                holding_wallet_owner: self.campaign.holding_wallet_owner,
+                recipient: self.campaign.recipient,
+                matching_vault: find_matching_vault(
+                    &self.funding_round,
+                    &self.campaign.donation_mint,
+                )
+                .0,
+                matching_vault_owner: find_matching_vault_owner(&self.funding_round).0,
+                fee_vault: self.fee_vault,
+                payout_split: self.campaign.payout_split,
+                token_program: anchor_spl::token::ID,
+            },
+            instruction::DistributeMatch {},
+            &writable_accounts(&self.payout_recipients),
+        )
+    }
+}
+
+pub struct SweepMatchingVaultBuilder {
+    funding_round: Pubkey,
+    matching_mint: Pubkey,
+    destination: Pubkey,
+    authority: Pubkey,
+}
+
+impl SweepMatchingVaultBuilder {
+    pub fn new(
+        funding_round: Pubkey,
+        matching_mint: Pubkey,
+        destination: Pubkey,
+        authority: Pubkey,
+    ) -> Self {
+        Self {
+            funding_round,
+            matching_mint,
+            destination,
+            authority,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::SweepMatchingVault {
+                funding_round: self.funding_round,
+                matching_vault: find_matching_vault(&self.funding_round, &self.matching_mint).0,
+                matching_vault_owner: find_matching_vault_owner(&self.funding_round).0,
+                destination: self.destination,
+                authority: self.authority,
+                token_program: anchor_spl::token::ID,
+            },
+            instruction::SweepMatchingVault {},
+            &[],
+        )
+    }
+}
+
+pub struct CreateMatchPledgeBuilder {
+    campaign: CampaignKeys,
+    creator_wallet: Pubkey,
+    sponsor_wallet: Pubkey,
+    sponsor_token_wallet: Pubkey,
+    ratio_bps: u16,
+    cap: u64,
+    deadline: u64,
+}
+
+impl CreateMatchPledgeBuilder {
+    /// Escrows `cap` out of `sponsor_token_wallet` at creation,
+    /// signed by both the sponsor and the campaign creator
+    pub fn new(
+        campaign: &CampaignKeys,
+        creator_wallet: Pubkey,
+        sponsor_wallet: Pubkey,
+        sponsor_token_wallet: Pubkey,
+        ratio_bps: u16,
+        cap: u64,
+        deadline: u64,
+    ) -> Self {
+        Self {
+            campaign: *campaign,
+            creator_wallet,
+            sponsor_wallet,
+            sponsor_token_wallet,
+            ratio_bps,
+            cap,
+            deadline,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let match_pledge = find_match_pledge(&self.campaign.donation_data).0;
+        build_instruction(
+            accounts::CreateMatchPledge {
+                match_pledge,
+                match_pledge_escrow: find_match_pledge_escrow(&match_pledge).0,
+                donation_data: self.campaign.donation_data,
+                donation_mint: self.campaign.donation_mint,
+                sponsor_token_wallet: self.sponsor_token_wallet,
+                sponsor_wallet: self.sponsor_wallet,
+                creator_wallet: self.creator_wallet,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            instruction::CreateMatchPledge {
+                ratio_bps: self.ratio_bps,
+                cap: self.cap,
+                deadline: self.deadline,
+            },
+            &[],
+        )
+    }
+}
+
+pub struct ReclaimMatchPledgeBuilder {
+    donation_data: Pubkey,
+    sponsor_wallet: Pubkey,
+    sponsor_token_wallet: Pubkey,
+}
+
+impl ReclaimMatchPledgeBuilder {
+    pub fn new(
+        donation_data: Pubkey,
+        sponsor_wallet: Pubkey,
+        sponsor_token_wallet: Pubkey,
+    ) -> Self {
+        Self {
+            donation_data,
+            sponsor_wallet,
+            sponsor_token_wallet,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let match_pledge = find_match_pledge(&self.donation_data).0;
+        build_instruction(
+            accounts::ReclaimMatchPledge {
+                match_pledge,
+                match_pledge_escrow: find_match_pledge_escrow(&match_pledge).0,
+                donation_data: self.donation_data,
+                sponsor_token_wallet: self.sponsor_token_wallet,
+                sponsor_wallet: self.sponsor_wallet,
+                token_program: anchor_spl::token::ID,
+            },
+            instruction::ReclaimMatchPledge {},
+            &[],
+        )
+    }
+}
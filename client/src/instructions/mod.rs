@@ -0,0 +1,44 @@
+//! Builders for every donaproto entrypoint, each producing a ready to sign `Instruction`
+
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::instruction::Instruction,
+    InstructionData, ToAccountMetas,
+};
+
+pub mod campaign;
+pub use campaign::*;
+pub mod donor;
+pub use donor::*;
+pub mod matching;
+pub use matching::*;
+pub mod protocol;
+pub use protocol::*;
+pub mod subscription;
+pub use subscription::*;
+
+pub(crate) fn build_instruction(
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+    remaining_accounts: &[AccountMeta],
+) -> Instruction {
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend_from_slice(remaining_accounts);
+    Instruction {
+        program_id: donaproto::ID,
+        accounts: metas,
+        data: data.data(),
+    }
+}
+
+pub(crate) fn readonly_accounts(keys: &[Pubkey]) -> Vec<AccountMeta> {
+    keys.iter()
+        .map(|key| AccountMeta::new_readonly(*key, false))
+        .collect()
+}
+
+pub(crate) fn writable_accounts(keys: &[Pubkey]) -> Vec<AccountMeta> {
+    keys.iter()
+        .map(|key| AccountMeta::new(*key, false))
+        .collect()
+}
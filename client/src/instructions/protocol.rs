@@ -0,0 +1,578 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, system_program, sysvar},
+};
+use donaproto::{accounts, instruction, states::EmissionCarryOver};
+
+use super::build_instruction;
+use crate::{
+    keys::ProtocolKeys,
+    pda::{
+        find_authorized_clmm, find_authorized_clmm_pool, find_fee_vault, find_fee_vault_owner,
+        find_mint_fee, find_treasury_owner,
+    },
+};
+
+pub struct InitializeDonationProtocolBuilder {
+    donation_protocol: Pubkey,
+    treasury: Pubkey,
+    treasury_mint: Pubkey,
+    donation_mint: Pubkey,
+    payer: Pubkey,
+    min_amount_to_earn: u64,
+    min_amount_to_collect: u64,
+}
+
+impl InitializeDonationProtocolBuilder {
+    /// `donation_protocol` is a new keypair co-signing the transaction,
+    /// `treasury` must be owned by `find_treasury_owner(donation_protocol)`
+    pub fn new(
+        donation_protocol: Pubkey,
+        treasury: Pubkey,
+        treasury_mint: Pubkey,
+        donation_mint: Pubkey,
+        payer: Pubkey,
+    ) -> Self {
+        Self {
+            donation_protocol,
+            treasury,
+            treasury_mint,
+            donation_mint,
+            payer,
+            min_amount_to_earn: 0,
+            min_amount_to_collect: 0,
+        }
+    }
+
+    pub fn min_amount_to_earn(mut self, min_amount_to_earn: u64) -> Self {
+        self.min_amount_to_earn = min_amount_to_earn;
+        self
+    }
+
+    pub fn min_amount_to_collect(mut self, min_amount_to_collect: u64) -> Self {
+        self.min_amount_to_collect = min_amount_to_collect;
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let (treasury_owner, treasury_owner_bump) = find_treasury_owner(&self.donation_protocol);
+        build_instruction(
+            accounts::InitializeDonationProtocol {
+                donation_protocol_data: self.donation_protocol,
+                treasury: self.treasury,
+                treasury_owner,
+                treasury_mint: self.treasury_mint,
+                donation_mint: self.donation_mint,
+                payer: self.payer,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            instruction::InitializeDonationProtocol {
+                min_amount_to_earn: self.min_amount_to_earn,
+                min_amount_to_collect: self.min_amount_to_collect,
+                treasury_owner_bump,
+            },
+            &[],
+        )
+    }
+}
+
+pub struct AuthorizeClmmBuilder {
+    donation_protocol: Pubkey,
+    clmm_program_id: Pubkey,
+    authority: Pubkey,
+}
+
+impl AuthorizeClmmBuilder {
+    pub fn new(donation_protocol: Pubkey, clmm_program_id: Pubkey, authority: Pubkey) -> Self {
+        Self {
+            donation_protocol,
+            clmm_program_id,
+            authority,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::AuthorizeClmm {
+                authorized_clmm: find_authorized_clmm(
+                    &self.donation_protocol,
+                    &self.clmm_program_id,
+                )
+                .0,
+                donation_protocol: self.donation_protocol,
+                payer: self.authority,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            instruction::AuthorizeClmm {
+                program_id: self.clmm_program_id,
+            },
+            &[],
+        )
+    }
+}
+
+pub struct AuthorizeClmmPoolBuilder {
+    donation_protocol: Pubkey,
+    clmm_program_id: Pubkey,
+    pool_state: Pubkey,
+    amm_config: Pubkey,
+    donation_amm_mint: Pubkey,
+    authority: Pubkey,
+}
+
+impl AuthorizeClmmPoolBuilder {
+    /// `amm_config` and `donation_amm_mint` are read from the pool state,
+    /// see `DonaprotoRpc::authorize_clmm_pool` to resolve them
+    pub fn new(
+        donation_protocol: Pubkey,
+        clmm_program_id: Pubkey,
+        pool_state: Pubkey,
+        amm_config: Pubkey,
+        donation_amm_mint: Pubkey,
+        authority: Pubkey,
+    ) -> Self {
+        Self {
+            donation_protocol,
+            clmm_program_id,
+            pool_state,
+            amm_config,
+            donation_amm_mint,
+            authority,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::AuthorizeClmmPool {
+                authorized_clmm_pool: find_authorized_clmm_pool(
+                    &self.donation_protocol,
+                    &self.pool_state,
+                )
+                .0,
+                pool_state: self.pool_state,
+                amm_config: self.amm_config,
+                donation_protocol: self.donation_protocol,
+                donation_amm_mint: self.donation_amm_mint,
+                authorized_clmm: find_authorized_clmm(
+                    &self.donation_protocol,
+                    &self.clmm_program_id,
+                )
+                .0,
+                payer: self.authority,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            instruction::AuthorizeClmmPool {},
+            &[],
+        )
+    }
+}
+
+pub struct SetProtocolFeeBuilder {
+    donation_protocol: Pubkey,
+    authority: Pubkey,
+    fee_bps: u16,
+}
+
+impl SetProtocolFeeBuilder {
+    pub fn new(donation_protocol: Pubkey, authority: Pubkey, fee_bps: u16) -> Self {
+        Self {
+            donation_protocol,
+            authority,
+            fee_bps,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::SetProtocolFee {
+                donation_protocol: self.donation_protocol,
+                authority: self.authority,
+            },
+            instruction::SetProtocolFee {
+                fee_bps: self.fee_bps,
+            },
+            &[],
+        )
+    }
+}
+
+pub struct SetMintFeeBuilder {
+    donation_protocol: Pubkey,
+    mint: Pubkey,
+    authority: Pubkey,
+    fee_bps: u16,
+    authorized_clmm_pool: Option<Pubkey>,
+}
+
+impl SetMintFeeBuilder {
+    pub fn new(donation_protocol: Pubkey, mint: Pubkey, authority: Pubkey, fee_bps: u16) -> Self {
+        Self {
+            donation_protocol,
+            mint,
+            authority,
+            fee_bps,
+            authorized_clmm_pool: None,
+        }
+    }
+
+    /// Mints other than the protocol donation mint are authorized through a pool
+    pub fn authorized_pool(mut self, pool_state: &Pubkey) -> Self {
+        self.authorized_clmm_pool =
+            Some(find_authorized_clmm_pool(&self.donation_protocol, pool_state).0);
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::SetMintFee {
+                mint_fee: find_mint_fee(&self.donation_protocol, &self.mint).0,
+                donation_protocol: self.donation_protocol,
+                mint: self.mint,
+                authorized_clmm_pool: self.authorized_clmm_pool,
+                authority: self.authority,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            instruction::SetMintFee {
+                fee_bps: self.fee_bps,
+            },
+            &[],
+        )
+    }
+}
+
+pub struct InitializeFeeVaultBuilder {
+    donation_protocol: Pubkey,
+    mint: Pubkey,
+    payer: Pubkey,
+}
+
+impl InitializeFeeVaultBuilder {
+    pub fn new(donation_protocol: Pubkey, mint: Pubkey, payer: Pubkey) -> Self {
+        Self {
+            donation_protocol,
+            mint,
+            payer,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::InitializeFeeVault {
+                fee_vault: find_fee_vault(&self.donation_protocol, &self.mint).0,
+                fee_vault_owner: find_fee_vault_owner(&self.donation_protocol).0,
+                donation_protocol: self.donation_protocol,
+                mint: self.mint,
+                payer: self.payer,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            instruction::InitializeFeeVault {},
+            &[],
+        )
+    }
+}
+
+pub struct CollectFeesBuilder {
+    donation_protocol: Pubkey,
+    mint: Pubkey,
+    destination: Pubkey,
+    authority: Pubkey,
+    amount: u64,
+}
+
+impl CollectFeesBuilder {
+    pub fn new(
+        donation_protocol: Pubkey,
+        mint: Pubkey,
+        destination: Pubkey,
+        authority: Pubkey,
+        amount: u64,
+    ) -> Self {
+        Self {
+            donation_protocol,
+            mint,
+            destination,
+            authority,
+            amount,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::CollectFees {
+                fee_vault: find_fee_vault(&self.donation_protocol, &self.mint).0,
+                fee_vault_owner: find_fee_vault_owner(&self.donation_protocol).0,
+                destination: self.destination,
+                donation_protocol: self.donation_protocol,
+                authority: self.authority,
+                token_program: anchor_spl::token::ID,
+            },
+            instruction::CollectFees {
+                amount: self.amount,
+            },
+            &[],
+        )
+    }
+}
+
+pub struct FundTreasuryBuilder {
+    protocol: ProtocolKeys,
+    source_token_wallet: Pubkey,
+    funder: Pubkey,
+    amount: u64,
+}
+
+impl FundTreasuryBuilder {
+    pub fn new(
+        protocol: &ProtocolKeys,
+        source_token_wallet: Pubkey,
+        funder: Pubkey,
+        amount: u64,
+    ) -> Self {
+        Self {
+            protocol: *protocol,
+            source_token_wallet,
+            funder,
+            amount,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::FundTreasury {
+                treasury: self.protocol.treasury,
+                source_token_wallet: self.source_token_wallet,
+                donation_protocol: self.protocol.donation_protocol,
+                funder: self.funder,
+                token_program: anchor_spl::token::ID,
+            },
+            instruction::FundTreasury {
+                amount: self.amount,
+            },
+            &[],
+        )
+    }
+}
+
+pub struct WithdrawTreasuryBuilder {
+    protocol: ProtocolKeys,
+    destination: Pubkey,
+    authority: Pubkey,
+    amount: u64,
+}
+
+impl WithdrawTreasuryBuilder {
+    pub fn new(
+        protocol: &ProtocolKeys,
+        destination: Pubkey,
+        authority: Pubkey,
+        amount: u64,
+    ) -> Self {
+        Self {
+            protocol: *protocol,
+            destination,
+            authority,
+            amount,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::WithdrawTreasury {
+                treasury: self.protocol.treasury,
+                treasury_owner: self.protocol.treasury_owner,
+                destination: self.destination,
+                donation_protocol: self.protocol.donation_protocol,
+                authority: self.authority,
+                token_program: anchor_spl::token::ID,
+            },
+            instruction::WithdrawTreasury {
+                amount: self.amount,
+            },
+            &[],
+        )
+    }
+}
+
+pub struct RotateTreasuryBuilder {
+    protocol: ProtocolKeys,
+    new_treasury: Pubkey,
+    authority: Pubkey,
+}
+
+impl RotateTreasuryBuilder {
+    pub fn new(protocol: &ProtocolKeys, new_treasury: Pubkey, authority: Pubkey) -> Self {
+        Self {
+            protocol: *protocol,
+            new_treasury,
+            authority,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::RotateTreasury {
+                treasury: self.protocol.treasury,
+                new_treasury: self.new_treasury,
+                treasury_owner: self.protocol.treasury_owner,
+                donation_protocol: self.protocol.donation_protocol,
+                authority: self.authority,
+                token_program: anchor_spl::token::ID,
+            },
+            instruction::RotateTreasury {},
+            &[],
+        )
+    }
+}
+
+pub struct SetEmissionScheduleBuilder {
+    donation_protocol: Pubkey,
+    authority: Pubkey,
+    epoch_budget: u64,
+    epoch_duration: u64,
+    epoch_decay_bps: u16,
+    emission_carry_over: EmissionCarryOver,
+}
+
+impl SetEmissionScheduleBuilder {
+    /// A zero `epoch_duration` disables the emission schedule
+    pub fn new(
+        donation_protocol: Pubkey,
+        authority: Pubkey,
+        epoch_budget: u64,
+        epoch_duration: u64,
+    ) -> Self {
+        Self {
+            donation_protocol,
+            authority,
+            epoch_budget,
+            epoch_duration,
+            epoch_decay_bps: 0,
+            emission_carry_over: EmissionCarryOver::default(),
+        }
+    }
+
+    pub fn epoch_decay_bps(mut self, epoch_decay_bps: u16) -> Self {
+        self.epoch_decay_bps = epoch_decay_bps;
+        self
+    }
+
+    pub fn emission_carry_over(mut self, emission_carry_over: EmissionCarryOver) -> Self {
+        self.emission_carry_over = emission_carry_over;
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::SetEmissionSchedule {
+                donation_protocol: self.donation_protocol,
+                authority: self.authority,
+            },
+            instruction::SetEmissionSchedule {
+                epoch_budget: self.epoch_budget,
+                epoch_duration: self.epoch_duration,
+                epoch_decay_bps: self.epoch_decay_bps,
+                emission_carry_over: self.emission_carry_over,
+            },
+            &[],
+        )
+    }
+}
+
+pub struct SetRewardVestingBuilder {
+    donation_protocol: Pubkey,
+    authority: Pubkey,
+    vesting_cliff: u64,
+    vesting_duration: u64,
+}
+
+impl SetRewardVestingBuilder {
+    /// A zero `vesting_duration` pays rewards out immediately
+    pub fn new(
+        donation_protocol: Pubkey,
+        authority: Pubkey,
+        vesting_cliff: u64,
+        vesting_duration: u64,
+    ) -> Self {
+        Self {
+            donation_protocol,
+            authority,
+            vesting_cliff,
+            vesting_duration,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::SetRewardVesting {
+                donation_protocol: self.donation_protocol,
+                authority: self.authority,
+            },
+            instruction::SetRewardVesting {
+                vesting_cliff: self.vesting_cliff,
+                vesting_duration: self.vesting_duration,
+            },
+            &[],
+        )
+    }
+}
+
+pub struct SetSelfFundingCapBuilder {
+    donation_protocol: Pubkey,
+    authority: Pubkey,
+    max_self_funded_bps: u16,
+}
+
+impl SetSelfFundingCapBuilder {
+    /// A zero `max_self_funded_bps` disables the cap
+    pub fn new(donation_protocol: Pubkey, authority: Pubkey, max_self_funded_bps: u16) -> Self {
+        Self {
+            donation_protocol,
+            authority,
+            max_self_funded_bps,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::SetSelfFundingCap {
+                donation_protocol: self.donation_protocol,
+                authority: self.authority,
+            },
+            instruction::SetSelfFundingCap {
+                max_self_funded_bps: self.max_self_funded_bps,
+            },
+            &[],
+        )
+    }
+}
+
+pub struct MigrateAccountBuilder {
+    account: Pubkey,
+    payer: Pubkey,
+}
+
+impl MigrateAccountBuilder {
+    /// `account` is a DonationData, CreatorData or ContributorData written before the
+    /// current layout version, `payer` funds the rent for the grown account
+    pub fn new(account: Pubkey, payer: Pubkey) -> Self {
+        Self { account, payer }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::MigrateAccount {
+                account: self.account,
+                payer: self.payer,
+                system_program: system_program::ID,
+            },
+            instruction::MigrateAccount {},
+            &[],
+        )
+    }
+}
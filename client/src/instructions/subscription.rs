@@ -0,0 +1,188 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, system_program},
+};
+use anchor_spl::associated_token::get_associated_token_address;
+use donaproto::{accounts, instruction};
+
+use super::build_instruction;
+use crate::{
+    keys::{CampaignKeys, ProtocolKeys},
+    pda::{
+        find_contributor_data, find_donation_receipt, find_match_pledge_escrow,
+        find_reward_vesting, find_subscription,
+    },
+};
+
+pub struct CreateSubscriptionBuilder {
+    campaign: CampaignKeys,
+    donor_wallet: Pubkey,
+    donor_token_wallet: Pubkey,
+    amount: u64,
+    period: u64,
+    ending_timestamp: u64,
+}
+
+impl CreateSubscriptionBuilder {
+    /// Delegates `amount` per `period` of the donor associated token account to the subscription
+    pub fn new(
+        campaign: &CampaignKeys,
+        donor_wallet: Pubkey,
+        amount: u64,
+        period: u64,
+        ending_timestamp: u64,
+    ) -> Self {
+        Self {
+            campaign: *campaign,
+            donor_wallet,
+            donor_token_wallet: get_associated_token_address(
+                &donor_wallet,
+                &campaign.donation_mint,
+            ),
+            amount,
+            period,
+            ending_timestamp,
+        }
+    }
+
+    pub fn donor_token_wallet(mut self, donor_token_wallet: Pubkey) -> Self {
+        self.donor_token_wallet = donor_token_wallet;
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::CreateSubscription {
+                subscription: find_subscription(&self.campaign.donation_data, &self.donor_wallet).0,
+                donation_data: self.campaign.donation_data,
+                donation_protocol: self.campaign.donation_protocol,
+                donor_token_wallet: self.donor_token_wallet,
+                donor_wallet: self.donor_wallet,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::CreateSubscription {
+                amount: self.amount,
+                period: self.period,
+                ending_timestamp: self.ending_timestamp,
+            },
+            &[],
+        )
+    }
+}
+
+pub struct ExecuteSubscriptionBuilder {
+    protocol: ProtocolKeys,
+    campaign: CampaignKeys,
+    donor: Pubkey,
+    donor_token_wallet: Pubkey,
+    donor_reward_token_wallet: Pubkey,
+    funding_round: Option<Pubkey>,
+    payer: Pubkey,
+}
+
+impl ExecuteSubscriptionBuilder {
+    /// Permissionless, `payer` covers rent of the accounts created on the first installment
+    pub fn new(
+        protocol: &ProtocolKeys,
+        campaign: &CampaignKeys,
+        donor: Pubkey,
+        payer: Pubkey,
+    ) -> Self {
+        Self {
+            protocol: *protocol,
+            campaign: *campaign,
+            donor,
+            donor_token_wallet: get_associated_token_address(&donor, &campaign.donation_mint),
+            donor_reward_token_wallet: get_associated_token_address(
+                &donor,
+                &protocol.treasury_mint,
+            ),
+            funding_round: None,
+            payer,
+        }
+    }
+
+    /// Must match the token wallet the subscription was created with
+    pub fn donor_token_wallet(mut self, donor_token_wallet: Pubkey) -> Self {
+        self.donor_token_wallet = donor_token_wallet;
+        self
+    }
+
+    pub fn donor_reward_token_wallet(mut self, donor_reward_token_wallet: Pubkey) -> Self {
+        self.donor_reward_token_wallet = donor_reward_token_wallet;
+        self
+    }
+
+    pub fn funding_round(mut self, funding_round: Pubkey) -> Self {
+        self.funding_round = Some(funding_round);
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let donation_protocol = &self.protocol.donation_protocol;
+        build_instruction(
+            accounts::ExecuteSubscription {
+                subscription: find_subscription(&self.campaign.donation_data, &self.donor).0,
+                donation_data: self.campaign.donation_data,
+                contributor_data: find_contributor_data(donation_protocol, &self.donor).0,
+                donation_receipt: find_donation_receipt(&self.campaign.donation_data, &self.donor)
+                    .0,
+                funding_round: self.funding_round,
+                match_pledge: self.campaign.match_pledge,
+                match_pledge_escrow: self
+                    .campaign
+                    .match_pledge
+                    .map(|match_pledge| find_match_pledge_escrow(&match_pledge).0),
+                donation_tiers: self.campaign.donation_tiers,
+                donation_protocol: *donation_protocol,
+                donor_token_wallet: self.donor_token_wallet,
+                donor_reward_token_wallet: self.donor_reward_token_wallet,
+                reward_treasury: self.protocol.treasury,
+                reward_treasury_owner: self.protocol.treasury_owner,
+                reward_vesting: self
+                    .protocol
+                    .is_reward_vesting
+                    .then(|| find_reward_vesting(donation_protocol, &self.donor).0),
+                recipient: self.campaign.recipient,
+                holding_wallet: self.campaign.holding_wallet,
+                donation_mint: self.campaign.donation_mint,
+                reward_mint: self.protocol.treasury_mint,
+                payer: self.payer,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::ExecuteSubscription {},
+            &[],
+        )
+    }
+}
+
+pub struct CancelSubscriptionBuilder {
+    donation_data: Pubkey,
+    donor_wallet: Pubkey,
+    donor_token_wallet: Pubkey,
+}
+
+impl CancelSubscriptionBuilder {
+    pub fn new(donation_data: Pubkey, donor_wallet: Pubkey, donor_token_wallet: Pubkey) -> Self {
+        Self {
+            donation_data,
+            donor_wallet,
+            donor_token_wallet,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        build_instruction(
+            accounts::CancelSubscription {
+                subscription: find_subscription(&self.donation_data, &self.donor_wallet).0,
+                donor_token_wallet: self.donor_token_wallet,
+                donor_wallet: self.donor_wallet,
+                token_program: anchor_spl::token::ID,
+            },
+            instruction::CancelSubscription {},
+            &[],
+        )
+    }
+}
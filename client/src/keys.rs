@@ -0,0 +1,91 @@
+//! Account keys shared by most instructions, read once from on-chain state
+
+use anchor_lang::prelude::Pubkey;
+use donaproto::states::{DonationData, DonationProtocolData};
+
+use crate::pda::{find_authorized_clmm_pool, find_holding_wallet_owner, find_treasury_owner};
+
+/// Keys of a donation protocol and its reward treasury
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProtocolKeys {
+    pub donation_protocol: Pubkey,
+    pub donation_mint: Pubkey,
+    pub treasury_mint: Pubkey,
+    pub treasury: Pubkey,
+    pub treasury_owner: Pubkey,
+    /// Rewards accrue into `RewardVesting` accounts instead of being paid out
+    pub is_reward_vesting: bool,
+}
+
+impl ProtocolKeys {
+    pub fn new(donation_protocol: Pubkey, data: &DonationProtocolData) -> Self {
+        Self {
+            donation_protocol,
+            donation_mint: data.donation_mint,
+            treasury_mint: data.treasury_mint,
+            treasury: data.treasury,
+            treasury_owner: find_treasury_owner(&donation_protocol).0,
+            is_reward_vesting: data.is_reward_vesting(),
+        }
+    }
+}
+
+/// Keys of a single donation campaign
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CampaignKeys {
+    pub donation_data: Pubkey,
+    pub donation_protocol: Pubkey,
+    pub donation_mint: Pubkey,
+    pub holding_wallet: Pubkey,
+    pub holding_wallet_owner: Pubkey,
+    pub recipient: Pubkey,
+    pub creator_data: Pubkey,
+    /// `None` when the campaign has no payout split
+    pub payout_split: Option<Pubkey>,
+    /// `None` when the campaign has no sponsor match pledge
+    pub match_pledge: Option<Pubkey>,
+    /// `None` when the campaign has no perk tiers
+    pub donation_tiers: Option<Pubkey>,
+}
+
+impl CampaignKeys {
+    pub fn new(donation_data: Pubkey, data: &DonationData) -> Self {
+        Self {
+            donation_data,
+            donation_protocol: data.donation_protocol,
+            donation_mint: data.donation_mint,
+            holding_wallet: data.holding_wallet,
+            holding_wallet_owner: find_holding_wallet_owner(&donation_data).0,
+            recipient: data.recipient,
+            creator_data: data.creator_data,
+            payout_split: non_default(data.payout_split),
+            match_pledge: non_default(data.match_pledge),
+            donation_tiers: non_default(data.donation_tiers),
+        }
+    }
+}
+
+/// On-chain optional references are stored as the default pubkey
+pub(crate) fn non_default(key: Pubkey) -> Option<Pubkey> {
+    if key == Pubkey::default() {
+        None
+    } else {
+        Some(key)
+    }
+}
+
+/// Authorized Raydium CLMM pool pricing a v2 donation mint against the protocol mint
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolKeys {
+    pub pool_state: Pubkey,
+    pub authorized_clmm_pool: Pubkey,
+}
+
+impl PoolKeys {
+    pub fn new(donation_protocol: &Pubkey, pool_state: Pubkey) -> Self {
+        Self {
+            pool_state,
+            authorized_clmm_pool: find_authorized_clmm_pool(donation_protocol, &pool_state).0,
+        }
+    }
+}
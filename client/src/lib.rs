@@ -0,0 +1,23 @@
+//! Client side helpers for the donaproto program.
+//!
+//! `pda` derives every program address, `instructions` builds an `Instruction` for each
+//! `#[program]` entrypoint and `DonaprotoRpc` resolves builders from on-chain state,
+//! including the authorized CLMM pool v2 instructions need for a donation mint.
+//! `decode` turns raw account data of any donaproto account into a readable form,
+//! `events` extracts the events donaproto emitted from transaction logs.
+
+pub mod decode;
+pub mod error;
+pub mod events;
+pub mod instructions;
+pub mod keys;
+pub mod pda;
+pub mod resolver;
+
+pub use decode::{DecodeContext, DecodedAccount, DonaprotoAccount};
+pub use donaproto::ID as PROGRAM_ID;
+pub use error::{ClientError, Result};
+pub use events::{parse_logs, DonaprotoEvent};
+pub use instructions::*;
+pub use keys::{CampaignKeys, PoolKeys, ProtocolKeys};
+pub use resolver::DonaprotoRpc;
@@ -0,0 +1,232 @@
+//! Program derived addresses used by donaproto, mirroring the seeds in `instructions/`
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address;
+use donaproto::instructions::{
+    AUTHORIZED_CLMM_POOL_PREFIX, AUTHORIZED_CLMM_PREFIX, CONTRIBUTOR_PREFIX, CREATOR_PREFIX,
+    DONATION_RECEIPT_PREFIX, DONATION_TIERS_PREFIX, FEE_VAULT_PREFIX, HOLDING_PREFIX,
+    MATCHING_VAULT_PREFIX, MATCH_PLEDGE_ESCROW_PREFIX, MATCH_PLEDGE_PREFIX, MINT_FEE_PREFIX,
+    PAYOUT_SPLIT_PREFIX, RECEIPT_METADATA_PREFIX, RECEIPT_MINT_AUTHORITY_PREFIX,
+    REWARD_VESTING_PREFIX, SUBSCRIPTION_PREFIX, TREASURY_PREFIX,
+};
+
+/// ["treasury", donation_protocol], owner of the reward treasury
+pub fn find_treasury_owner(donation_protocol: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[TREASURY_PREFIX.as_bytes(), donation_protocol.as_ref()],
+        &donaproto::ID,
+    )
+}
+
+/// Associated token account of the treasury owner, the conventional reward treasury
+pub fn find_treasury(donation_protocol: &Pubkey, treasury_mint: &Pubkey) -> Pubkey {
+    let (treasury_owner, _) = find_treasury_owner(donation_protocol);
+    get_associated_token_address(&treasury_owner, treasury_mint)
+}
+
+/// ["creator", donation_protocol, creator_wallet]
+pub fn find_creator_data(donation_protocol: &Pubkey, creator_wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CREATOR_PREFIX.as_bytes(),
+            donation_protocol.as_ref(),
+            creator_wallet.as_ref(),
+        ],
+        &donaproto::ID,
+    )
+}
+
+/// ["contributor", donation_protocol, contributor_wallet]
+pub fn find_contributor_data(
+    donation_protocol: &Pubkey,
+    contributor_wallet: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CONTRIBUTOR_PREFIX.as_bytes(),
+            donation_protocol.as_ref(),
+            contributor_wallet.as_ref(),
+        ],
+        &donaproto::ID,
+    )
+}
+
+/// ["holding", donation_data], owner of the donation holding wallet
+pub fn find_holding_wallet_owner(donation_data: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[HOLDING_PREFIX.as_bytes(), donation_data.as_ref()],
+        &donaproto::ID,
+    )
+}
+
+/// Associated token account of the holding wallet owner, the conventional holding wallet
+pub fn find_holding_wallet(donation_data: &Pubkey, donation_mint: &Pubkey) -> Pubkey {
+    let (holding_wallet_owner, _) = find_holding_wallet_owner(donation_data);
+    get_associated_token_address(&holding_wallet_owner, donation_mint)
+}
+
+/// ["authorized_clmm", donation_protocol, clmm_program_id]
+pub fn find_authorized_clmm(donation_protocol: &Pubkey, clmm_program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            AUTHORIZED_CLMM_PREFIX.as_bytes(),
+            donation_protocol.as_ref(),
+            clmm_program_id.as_ref(),
+        ],
+        &donaproto::ID,
+    )
+}
+
+/// ["authorized_clmm_pool", donation_protocol, pool_state]
+pub fn find_authorized_clmm_pool(donation_protocol: &Pubkey, pool_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            AUTHORIZED_CLMM_POOL_PREFIX.as_bytes(),
+            donation_protocol.as_ref(),
+            pool_state.as_ref(),
+        ],
+        &donaproto::ID,
+    )
+}
+
+/// ["subscription", donation_data, donor]
+pub fn find_subscription(donation_data: &Pubkey, donor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SUBSCRIPTION_PREFIX.as_bytes(),
+            donation_data.as_ref(),
+            donor.as_ref(),
+        ],
+        &donaproto::ID,
+    )
+}
+
+/// ["payout_split", donation_data]
+pub fn find_payout_split(donation_data: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PAYOUT_SPLIT_PREFIX.as_bytes(), donation_data.as_ref()],
+        &donaproto::ID,
+    )
+}
+
+/// ["mint_fee", donation_protocol, mint], may be uninitialized
+pub fn find_mint_fee(donation_protocol: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            MINT_FEE_PREFIX.as_bytes(),
+            donation_protocol.as_ref(),
+            mint.as_ref(),
+        ],
+        &donaproto::ID,
+    )
+}
+
+/// ["fee_vault", donation_protocol], owner of every fee vault
+pub fn find_fee_vault_owner(donation_protocol: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[FEE_VAULT_PREFIX.as_bytes(), donation_protocol.as_ref()],
+        &donaproto::ID,
+    )
+}
+
+/// ["fee_vault", donation_protocol, mint]
+pub fn find_fee_vault(donation_protocol: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            FEE_VAULT_PREFIX.as_bytes(),
+            donation_protocol.as_ref(),
+            mint.as_ref(),
+        ],
+        &donaproto::ID,
+    )
+}
+
+/// ["reward_vesting", donation_protocol, contributor_wallet]
+pub fn find_reward_vesting(
+    donation_protocol: &Pubkey,
+    contributor_wallet: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            REWARD_VESTING_PREFIX.as_bytes(),
+            donation_protocol.as_ref(),
+            contributor_wallet.as_ref(),
+        ],
+        &donaproto::ID,
+    )
+}
+
+/// ["donation_receipt", donation_data, donor]
+pub fn find_donation_receipt(donation_data: &Pubkey, donor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            DONATION_RECEIPT_PREFIX.as_bytes(),
+            donation_data.as_ref(),
+            donor.as_ref(),
+        ],
+        &donaproto::ID,
+    )
+}
+
+/// ["matching_vault", funding_round], owner of the round matching vault
+pub fn find_matching_vault_owner(funding_round: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MATCHING_VAULT_PREFIX.as_bytes(), funding_round.as_ref()],
+        &donaproto::ID,
+    )
+}
+
+/// ["matching_vault", funding_round, matching_mint]
+pub fn find_matching_vault(funding_round: &Pubkey, matching_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            MATCHING_VAULT_PREFIX.as_bytes(),
+            funding_round.as_ref(),
+            matching_mint.as_ref(),
+        ],
+        &donaproto::ID,
+    )
+}
+
+/// ["match_pledge", donation_data]
+pub fn find_match_pledge(donation_data: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MATCH_PLEDGE_PREFIX.as_bytes(), donation_data.as_ref()],
+        &donaproto::ID,
+    )
+}
+
+/// ["match_pledge_escrow", match_pledge]
+pub fn find_match_pledge_escrow(match_pledge: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MATCH_PLEDGE_ESCROW_PREFIX.as_bytes(), match_pledge.as_ref()],
+        &donaproto::ID,
+    )
+}
+
+/// ["donation_tiers", donation_data]
+pub fn find_donation_tiers(donation_data: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[DONATION_TIERS_PREFIX.as_bytes(), donation_data.as_ref()],
+        &donaproto::ID,
+    )
+}
+
+/// ["receipt_metadata", receipt_mint]
+pub fn find_receipt_metadata(receipt_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[RECEIPT_METADATA_PREFIX.as_bytes(), receipt_mint.as_ref()],
+        &donaproto::ID,
+    )
+}
+
+/// ["receipt_mint_authority", donation_protocol]
+pub fn find_receipt_mint_authority(donation_protocol: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            RECEIPT_MINT_AUTHORITY_PREFIX.as_bytes(),
+            donation_protocol.as_ref(),
+        ],
+        &donaproto::ID,
+    )
+}
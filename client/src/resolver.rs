@@ -0,0 +1,245 @@
+//! Builders with their accounts resolved from on-chain state
+
+use std::mem;
+
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, Discriminator};
+use donaproto::states::{AuthorizedClmmPool, DonationData, DonationProtocolData, PayoutSplit};
+use raydium_amm_v3::states::PoolState;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+
+use crate::{
+    error::{ClientError, Result},
+    instructions::{
+        AuthorizeClmmPoolBuilder, CreateDonationBuilder, DonateBuilder, WithdrawFundsBuilder,
+    },
+    keys::{CampaignKeys, PoolKeys, ProtocolKeys},
+};
+
+// AuthorizedClmmPool layout: discriminator, pool_state, program_id, mint, donation_protocol
+const AUTHORIZED_CLMM_POOL_MINT_OFFSET: usize = 8 + 32 + 32;
+const AUTHORIZED_CLMM_POOL_PROTOCOL_OFFSET: usize = AUTHORIZED_CLMM_POOL_MINT_OFFSET + 32;
+
+pub struct DonaprotoRpc {
+    rpc: RpcClient,
+}
+
+impl DonaprotoRpc {
+    pub fn new(rpc: RpcClient) -> Self {
+        Self { rpc }
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    pub fn fetch<T: AccountDeserialize>(&self, key: &Pubkey) -> Result<T> {
+        let data = self.rpc.get_account_data(key)?;
+        T::try_deserialize(&mut data.as_slice())
+            .map_err(|err| ClientError::InvalidAccount(*key, err))
+    }
+
+    /// `DonationData` is zero copy and read without `AccountDeserialize`
+    pub fn fetch_donation_data(&self, key: &Pubkey) -> Result<DonationData> {
+        let data = self.rpc.get_account_data(key)?;
+        DonationData::try_from_account_data(&data)
+            .map_err(|err| ClientError::InvalidAccount(*key, err))
+    }
+
+    pub fn protocol_keys(&self, donation_protocol: &Pubkey) -> Result<ProtocolKeys> {
+        let data: DonationProtocolData = self.fetch(donation_protocol)?;
+        Ok(ProtocolKeys::new(*donation_protocol, &data))
+    }
+
+    pub fn campaign_keys(&self, donation_data: &Pubkey) -> Result<CampaignKeys> {
+        let data = self.fetch_donation_data(donation_data)?;
+        Ok(CampaignKeys::new(*donation_data, &data))
+    }
+
+    pub fn fetch_pool_state(&self, pool_state: &Pubkey) -> Result<PoolState> {
+        let data = self.rpc.get_account_data(pool_state)?;
+        decode_pool_state(&data).ok_or(ClientError::InvalidPoolState(*pool_state))
+    }
+
+    /// Every pool the protocol authorized for `mint`
+    pub fn authorized_pools(
+        &self,
+        donation_protocol: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<Vec<(Pubkey, AuthorizedClmmPool)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                    0,
+                    &AuthorizedClmmPool::DISCRIMINATOR,
+                )),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                    AUTHORIZED_CLMM_POOL_MINT_OFFSET,
+                    mint.as_ref(),
+                )),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                    AUTHORIZED_CLMM_POOL_PROTOCOL_OFFSET,
+                    donation_protocol.as_ref(),
+                )),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        self.rpc
+            .get_program_accounts_with_config(&donaproto::ID, config)?
+            .into_iter()
+            .map(|(key, account)| {
+                AuthorizedClmmPool::try_deserialize(&mut account.data.as_slice())
+                    .map(|pool| (key, pool))
+                    .map_err(|err| ClientError::InvalidAccount(key, err))
+            })
+            .collect()
+    }
+
+    /// Deepest authorized pool pricing `mint` against the protocol mint,
+    /// any of them passes the program checks but liquid pools are harder to move
+    pub fn find_pool(&self, protocol: &ProtocolKeys, mint: &Pubkey) -> Result<PoolKeys> {
+        let authorized_pools = self.authorized_pools(&protocol.donation_protocol, mint)?;
+        let pool_state_keys: Vec<Pubkey> = authorized_pools
+            .iter()
+            .map(|(_, authorized_pool)| authorized_pool.pool_state)
+            .collect();
+        let pool_state_accounts = self.rpc.get_multiple_accounts(&pool_state_keys)?;
+
+        let mut best: Option<(Pubkey, u128)> = None;
+        for ((_, authorized_pool), account) in authorized_pools.iter().zip(pool_state_accounts) {
+            let account = match account {
+                Some(account) if account.owner == authorized_pool.program_id => account,
+                _ => continue,
+            };
+            let pool_state = match decode_pool_state(&account.data) {
+                Some(pool_state) => pool_state,
+                None => continue,
+            };
+            if !pairs_mints(&pool_state, &protocol.donation_mint, mint) {
+                continue;
+            }
+            let liquidity = pool_state.liquidity;
+            if best.map_or(true, |(_, best_liquidity)| liquidity > best_liquidity) {
+                best = Some((authorized_pool.pool_state, liquidity));
+            }
+        }
+
+        best.map(|(pool_state, _)| PoolKeys::new(&protocol.donation_protocol, pool_state))
+            .ok_or(ClientError::NoAuthorizedPool(*mint))
+    }
+
+    /// `donate`, or `donate_v2` with a resolved pool when the campaign mint is not the protocol one
+    pub fn donate(
+        &self,
+        donation_data: &Pubkey,
+        user_wallet: Pubkey,
+        amount: u64,
+    ) -> Result<DonateBuilder> {
+        let campaign = self.campaign_keys(donation_data)?;
+        let protocol = self.protocol_keys(&campaign.donation_protocol)?;
+        let builder = DonateBuilder::new(&protocol, &campaign, user_wallet, amount);
+        if campaign.donation_mint == protocol.donation_mint {
+            return Ok(builder);
+        }
+        Ok(builder.v2(self.find_pool(&protocol, &campaign.donation_mint)?))
+    }
+
+    /// `withdraw_funds(_v2)` with the fee vault and payout split recipients the campaign needs
+    pub fn withdraw_funds(
+        &self,
+        donation_data: &Pubkey,
+        recipient_token_wallet: Pubkey,
+        payer: Pubkey,
+    ) -> Result<WithdrawFundsBuilder> {
+        let data = self.fetch_donation_data(donation_data)?;
+        let campaign = CampaignKeys::new(*donation_data, &data);
+        let protocol = self.protocol_keys(&campaign.donation_protocol)?;
+
+        let mut builder = WithdrawFundsBuilder::new(&campaign, recipient_token_wallet, payer);
+        if data.fee_bps > 0 {
+            builder = builder.with_fee_vault();
+        }
+        if let Some(payout_split) = campaign.payout_split {
+            let payout_split: PayoutSplit = self.fetch(&payout_split)?;
+            builder = builder.payout_recipients(
+                payout_split
+                    .recipients
+                    .iter()
+                    .map(|recipient| recipient.token_wallet)
+                    .collect(),
+            );
+        }
+        if campaign.donation_mint != protocol.donation_mint {
+            let pool = self.find_pool(&protocol, &campaign.donation_mint)?;
+            builder = builder.v2(protocol.donation_mint, pool);
+        }
+
+        Ok(builder)
+    }
+
+    /// Switches an already configured builder to `create_donation_v2` when `donation_mint` needs it
+    pub fn create_donation(
+        &self,
+        builder: CreateDonationBuilder,
+        donation_protocol: &Pubkey,
+        donation_mint: &Pubkey,
+    ) -> Result<CreateDonationBuilder> {
+        let protocol = self.protocol_keys(donation_protocol)?;
+        if *donation_mint == protocol.donation_mint {
+            return Ok(builder);
+        }
+        let pool = self.find_pool(&protocol, donation_mint)?;
+        Ok(builder.v2(protocol.donation_mint, pool))
+    }
+
+    /// Reads the clmm program, config and paired mint out of the pool state
+    pub fn authorize_clmm_pool(
+        &self,
+        donation_protocol: &Pubkey,
+        pool_state: &Pubkey,
+        authority: Pubkey,
+    ) -> Result<AuthorizeClmmPoolBuilder> {
+        let protocol = self.protocol_keys(donation_protocol)?;
+        let account = self.rpc.get_account(pool_state)?;
+        let state =
+            decode_pool_state(&account.data).ok_or(ClientError::InvalidPoolState(*pool_state))?;
+        let donation_amm_mint = if state.token_mint_0 == protocol.donation_mint {
+            state.token_mint_1
+        } else if state.token_mint_1 == protocol.donation_mint {
+            state.token_mint_0
+        } else {
+            return Err(ClientError::InvalidPoolState(*pool_state));
+        };
+
+        Ok(AuthorizeClmmPoolBuilder::new(
+            *donation_protocol,
+            account.owner,
+            *pool_state,
+            state.amm_config,
+            donation_amm_mint,
+            authority,
+        ))
+    }
+}
+
+/// Zero copy accounts are not borsh encoded, copy the struct out of the account data
+pub fn decode_pool_state(data: &[u8]) -> Option<PoolState> {
+    if data.len() < 8 + mem::size_of::<PoolState>() || data[..8] != PoolState::DISCRIMINATOR {
+        return None;
+    }
+    bytemuck::try_pod_read_unaligned(&data[8..8 + mem::size_of::<PoolState>()]).ok()
+}
+
+fn pairs_mints(pool_state: &PoolState, default_mint: &Pubkey, mint: &Pubkey) -> bool {
+    let (token_mint_0, token_mint_1) = (pool_state.token_mint_0, pool_state.token_mint_1);
+    (token_mint_0 == *default_mint && token_mint_1 == *mint)
+        || (token_mint_1 == *default_mint && token_mint_0 == *mint)
+}
@@ -0,0 +1,253 @@
+//! Read-only JSON API over the indexed tables
+//!
+//! - `GET /protocols`
+//! - `GET /campaigns?protocol=&creator=&mint=&open=&limit=&offset=`, newest first
+//! - `GET /campaigns/<address>` with its donations and withdrawals
+//! - `GET /leaderboard?protocol=&limit=`, contributors by donated default amount
+//! - `GET /mints?protocol=`, campaign totals per donation mint
+
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use tiny_http::{Header, Method, Response, Server};
+
+use crate::db::{Database, Param};
+
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 1000;
+
+pub fn serve(db: &mut Database, listen: &str) -> Result<()> {
+    let server = Server::http(listen).map_err(|err| anyhow!(err))?;
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .map_err(|_| anyhow!("invalid header"))?;
+
+    for request in server.incoming_requests() {
+        let (status, body) = if *request.method() != Method::Get {
+            (405, json!({ "error": "method not allowed" }))
+        } else {
+            match route(db, request.url()) {
+                Ok(body) => (200, body),
+                Err(err) => {
+                    let status = match err.downcast_ref::<ApiError>() {
+                        Some(ApiError::BadRequest(_)) => 400,
+                        Some(ApiError::NotFound(_)) => 404,
+                        None => 500,
+                    };
+                    (status, json!({ "error": err.to_string() }))
+                }
+            }
+        };
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(content_type.clone());
+        request.respond(response)?;
+    }
+
+    Ok(())
+}
+
+/// Errors caused by the request rather than by the database
+#[derive(Debug)]
+enum ApiError {
+    BadRequest(String),
+    NotFound(String),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadRequest(message) | Self::NotFound(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+fn route(db: &mut Database, url: &str) -> Result<Value> {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let query = Query::parse(query);
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match segments.as_slice() {
+        ["protocols"] => list_protocols(db),
+        ["campaigns"] => list_campaigns(db, &query),
+        ["campaigns", address] => get_campaign(db, address),
+        ["leaderboard"] => leaderboard(db, &query),
+        ["mints"] => mint_totals(db, &query),
+        _ => Err(ApiError::NotFound(format!("{} not found", path)).into()),
+    }
+}
+
+struct Query<'a>(HashMap<&'a str, &'a str>);
+
+impl<'a> Query<'a> {
+    fn parse(query: &'a str) -> Self {
+        Self(
+            query
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .collect(),
+        )
+    }
+
+    fn pubkey(&self, name: &str) -> Result<Option<Param>> {
+        match self.0.get(name) {
+            Some(value) => Ok(Some(Param::text(parse_pubkey(name, value)?))),
+            None => Ok(None),
+        }
+    }
+
+    fn bool(&self, name: &str) -> Result<Option<bool>> {
+        match self.0.get(name) {
+            Some(&"true") => Ok(Some(true)),
+            Some(&"false") => Ok(Some(false)),
+            Some(_) => Err(ApiError::BadRequest(format!("{} must be true or false", name)).into()),
+            None => Ok(None),
+        }
+    }
+
+    fn int(&self, name: &str, default: i64, max: i64) -> Result<i64> {
+        match self.0.get(name) {
+            Some(value) => match value.parse::<i64>() {
+                Ok(value) if (0..=max).contains(&value) => Ok(value),
+                _ => Err(
+                    ApiError::BadRequest(format!("{} must be between 0 and {}", name, max)).into(),
+                ),
+            },
+            None => Ok(default),
+        }
+    }
+}
+
+fn parse_pubkey(name: &str, value: &str) -> Result<Pubkey> {
+    match Pubkey::from_str(value) {
+        Ok(pubkey) => Ok(pubkey),
+        Err(_) => Err(ApiError::BadRequest(format!("{} is not a valid pubkey", name)).into()),
+    }
+}
+
+/// `WHERE` clause built from optional filters, numbering `$N` placeholders in order
+#[derive(Default)]
+struct Filter {
+    clauses: Vec<String>,
+    params: Vec<Param>,
+}
+
+impl Filter {
+    fn push(&mut self, param: Param) -> String {
+        self.params.push(param);
+        format!("${}", self.params.len())
+    }
+
+    fn eq(&mut self, column: &str, param: Option<Param>) {
+        if let Some(param) = param {
+            let placeholder = self.push(param);
+            self.clauses.push(format!("{} = {}", column, placeholder));
+        }
+    }
+
+    fn sql(&self) -> String {
+        if self.clauses.is_empty() {
+            return String::new();
+        }
+        format!(" WHERE {}", self.clauses.join(" AND "))
+    }
+}
+
+fn list_protocols(db: &mut Database) -> Result<Value> {
+    let protocols = db.query("SELECT * FROM protocols ORDER BY slot", &[])?;
+
+    Ok(Value::from(protocols))
+}
+
+fn list_campaigns(db: &mut Database, query: &Query) -> Result<Value> {
+    let mut filter = Filter::default();
+    filter.eq("protocol", query.pubkey("protocol")?);
+    filter.eq("creator_wallet", query.pubkey("creator")?);
+    filter.eq("donation_mint", query.pubkey("mint")?);
+    let is_open = query.bool("open")?;
+    filter.eq("is_closed", is_open.map(|is_open| Param::Bool(!is_open)));
+    let where_clause = filter.sql();
+    let limit = filter.push(Param::Int(query.int("limit", DEFAULT_LIMIT, MAX_LIMIT)?));
+    let offset = filter.push(Param::Int(query.int("offset", 0, i64::MAX)?));
+
+    let campaigns = db.query(
+        &format!(
+            "SELECT * FROM campaigns{} ORDER BY slot DESC, address LIMIT {} OFFSET {}",
+            where_clause, limit, offset
+        ),
+        &filter.params,
+    )?;
+
+    Ok(Value::from(campaigns))
+}
+
+fn get_campaign(db: &mut Database, address: &str) -> Result<Value> {
+    let address = Param::text(parse_pubkey("address", address)?);
+    let mut campaign = match db
+        .query(
+            "SELECT * FROM campaigns WHERE address = $1",
+            std::slice::from_ref(&address),
+        )?
+        .pop()
+    {
+        Some(campaign) => campaign,
+        None => return Err(ApiError::NotFound("campaign not found".to_string()).into()),
+    };
+
+    campaign["donations"] = Value::from(db.query(
+        "SELECT * FROM donations WHERE campaign = $1 ORDER BY slot, signature, event_index",
+        std::slice::from_ref(&address),
+    )?);
+    campaign["withdrawals"] = Value::from(db.query(
+        "SELECT * FROM withdrawals WHERE campaign = $1 ORDER BY slot, signature, event_index",
+        &[address],
+    )?);
+
+    Ok(campaign)
+}
+
+fn leaderboard(db: &mut Database, query: &Query) -> Result<Value> {
+    let mut filter = Filter::default();
+    filter.eq("protocol", query.pubkey("protocol")?);
+    let where_clause = filter.sql();
+    let limit = filter.push(Param::Int(query.int("limit", DEFAULT_LIMIT, MAX_LIMIT)?));
+
+    let contributors = db.query(
+        &format!(
+            "SELECT * FROM contributors{}
+            ORDER BY total_default_amount_donated DESC, address LIMIT {}",
+            where_clause, limit
+        ),
+        &filter.params,
+    )?;
+
+    Ok(Value::from(contributors))
+}
+
+fn mint_totals(db: &mut Database, query: &Query) -> Result<Value> {
+    let mut filter = Filter::default();
+    filter.eq("protocol", query.pubkey("protocol")?);
+
+    // Postgres sums BIGINT into NUMERIC
+    let totals = db.query(
+        &format!(
+            "SELECT donation_mint,
+                COUNT(*) AS campaigns_count,
+                CAST(SUM(donations_count) AS BIGINT) AS donations_count,
+                CAST(SUM(total_amount_received) AS BIGINT) AS total_amount_received,
+                CAST(SUM(total_default_amount_donated) AS BIGINT)
+                    AS total_default_amount_donated,
+                CAST(SUM(amount_withdrawn) AS BIGINT) AS amount_withdrawn,
+                CAST(SUM(fee_amount) AS BIGINT) AS fee_amount
+            FROM campaigns{}
+            GROUP BY donation_mint ORDER BY donation_mint",
+            filter.sql()
+        ),
+        &filter.params,
+    )?;
+
+    Ok(Value::from(totals))
+}
@@ -0,0 +1,284 @@
+//! SQLite and Postgres behind one dialect: statements use `$N` placeholders, `BIGINT`,
+//! `BOOLEAN` and `ON CONFLICT` clauses, which both databases understand
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use postgres::types::{ToSql, Type};
+use rusqlite::types::{ToSqlOutput, ValueRef};
+use serde_json::{Map, Value};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS protocols (
+    address TEXT PRIMARY KEY,
+    authority TEXT NOT NULL,
+    donation_mint TEXT NOT NULL,
+    treasury_mint TEXT NOT NULL,
+    treasury TEXT NOT NULL,
+    min_amount_to_earn BIGINT NOT NULL,
+    min_amount_to_collect BIGINT NOT NULL,
+    slot BIGINT NOT NULL,
+    block_time BIGINT
+);
+CREATE TABLE IF NOT EXISTS campaigns (
+    address TEXT PRIMARY KEY,
+    protocol TEXT NOT NULL,
+    creator_wallet TEXT NOT NULL,
+    recipient TEXT NOT NULL,
+    donation_mint TEXT NOT NULL,
+    amount_collecting BIGINT NOT NULL,
+    default_amount_collecting BIGINT NOT NULL,
+    ending_timestamp BIGINT NOT NULL,
+    ipfs_hash TEXT NOT NULL,
+    total_amount_received BIGINT NOT NULL DEFAULT 0,
+    total_default_amount_donated BIGINT NOT NULL DEFAULT 0,
+    total_amount_matched BIGINT NOT NULL DEFAULT 0,
+    donations_count BIGINT NOT NULL DEFAULT 0,
+    is_closed BOOLEAN NOT NULL DEFAULT FALSE,
+    amount_withdrawn BIGINT NOT NULL DEFAULT 0,
+    fee_amount BIGINT NOT NULL DEFAULT 0,
+    slot BIGINT NOT NULL,
+    block_time BIGINT
+);
+CREATE INDEX IF NOT EXISTS campaigns_protocol ON campaigns (protocol);
+CREATE INDEX IF NOT EXISTS campaigns_creator_wallet ON campaigns (creator_wallet);
+CREATE TABLE IF NOT EXISTS donations (
+    signature TEXT NOT NULL,
+    event_index BIGINT NOT NULL,
+    slot BIGINT NOT NULL,
+    block_time BIGINT,
+    protocol TEXT NOT NULL,
+    campaign TEXT NOT NULL,
+    contributor TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    amount BIGINT NOT NULL,
+    default_amount BIGINT NOT NULL,
+    reward_amount BIGINT NOT NULL,
+    matched_amount BIGINT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS donations_campaign ON donations (campaign);
+CREATE INDEX IF NOT EXISTS donations_contributor ON donations (contributor);
+CREATE TABLE IF NOT EXISTS rewards (
+    signature TEXT NOT NULL,
+    event_index BIGINT NOT NULL,
+    slot BIGINT NOT NULL,
+    block_time BIGINT,
+    protocol TEXT NOT NULL,
+    contributor TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    amount BIGINT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS rewards_contributor ON rewards (contributor);
+CREATE TABLE IF NOT EXISTS withdrawals (
+    signature TEXT NOT NULL,
+    event_index BIGINT NOT NULL,
+    slot BIGINT NOT NULL,
+    block_time BIGINT,
+    protocol TEXT NOT NULL,
+    campaign TEXT NOT NULL,
+    recipient TEXT NOT NULL,
+    amount BIGINT NOT NULL,
+    fee_amount BIGINT NOT NULL,
+    default_amount BIGINT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS withdrawals_campaign ON withdrawals (campaign);
+CREATE TABLE IF NOT EXISTS contributors (
+    protocol TEXT NOT NULL,
+    address TEXT NOT NULL,
+    total_default_amount_donated BIGINT NOT NULL,
+    total_reward_amount BIGINT NOT NULL,
+    total_amount_claimed BIGINT NOT NULL,
+    donations_count BIGINT NOT NULL,
+    PRIMARY KEY (protocol, address)
+);
+CREATE TABLE IF NOT EXISTS cursors (
+    source TEXT PRIMARY KEY,
+    signature TEXT NOT NULL,
+    slot BIGINT NOT NULL
+);
+";
+
+pub enum Param {
+    Int(i64),
+    OptInt(Option<i64>),
+    Text(String),
+    Bool(bool),
+}
+
+/// Amounts are stored as BIGINT, larger values are rejected instead of wrapping
+pub fn to_bigint(amount: u64) -> Result<i64> {
+    match i64::try_from(amount) {
+        Ok(amount) => Ok(amount),
+        Err(_) => Err(anyhow!("{} does not fit into BIGINT", amount)),
+    }
+}
+
+impl Param {
+    pub fn amount(amount: u64) -> Result<Self> {
+        Ok(Self::Int(to_bigint(amount)?))
+    }
+
+    pub fn text(text: impl ToString) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+impl rusqlite::ToSql for Param {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(match self {
+            Self::Int(value) => ToSqlOutput::from(*value),
+            Self::OptInt(Some(value)) => ToSqlOutput::from(*value),
+            Self::OptInt(None) => ToSqlOutput::from(rusqlite::types::Null),
+            Self::Text(value) => ToSqlOutput::from(value.as_str()),
+            Self::Bool(value) => ToSqlOutput::from(*value),
+        })
+    }
+}
+
+fn postgres_params(params: &[Param]) -> Vec<&(dyn ToSql + Sync)> {
+    params
+        .iter()
+        .map(|param| match param {
+            Param::Int(value) => value as &(dyn ToSql + Sync),
+            Param::OptInt(value) => value,
+            Param::Text(value) => value,
+            Param::Bool(value) => value,
+        })
+        .collect()
+}
+
+/// SQLite reads `$N` as a named parameter, `?N` binds by position like Postgres does
+fn sqlite_sql(sql: &str) -> String {
+    sql.replace('$', "?")
+}
+
+pub enum Database {
+    Sqlite(rusqlite::Connection),
+    Postgres(postgres::Client),
+}
+
+impl Database {
+    /// `postgres://` and `postgresql://` urls connect to Postgres, anything else is a SQLite file
+    pub fn open(url: &str) -> Result<Self> {
+        let mut database = if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+            Self::Postgres(postgres::Client::connect(url, postgres::NoTls)?)
+        } else {
+            let connection = rusqlite::Connection::open(url)?;
+            // the query API reads while `sync` writes from another process
+            connection.busy_timeout(Duration::from_secs(5))?;
+            connection.execute_batch("PRAGMA journal_mode = WAL;")?;
+            Self::Sqlite(connection)
+        };
+        database.batch(SCHEMA)?;
+
+        Ok(database)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        let mut database = Self::Sqlite(rusqlite::Connection::open_in_memory()?);
+        database.batch(SCHEMA)?;
+
+        Ok(database)
+    }
+
+    pub fn batch(&mut self, sql: &str) -> Result<()> {
+        match self {
+            Self::Sqlite(connection) => connection.execute_batch(sql)?,
+            Self::Postgres(client) => client.batch_execute(sql)?,
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of affected rows
+    pub fn execute(&mut self, sql: &str, params: &[Param]) -> Result<u64> {
+        let affected_rows = match self {
+            Self::Sqlite(connection) => {
+                connection.execute(&sqlite_sql(sql), rusqlite::params_from_iter(params))? as u64
+            }
+            Self::Postgres(client) => client.execute(sql, &postgres_params(params))?,
+        };
+
+        Ok(affected_rows)
+    }
+
+    /// Rows as JSON objects keyed by column name
+    pub fn query(&mut self, sql: &str, params: &[Param]) -> Result<Vec<Value>> {
+        match self {
+            Self::Sqlite(connection) => {
+                let mut statement = connection.prepare(&sqlite_sql(sql))?;
+                // SQLite stores booleans as integers, the declared type tells them apart
+                let columns: Vec<(String, bool)> = statement
+                    .columns()
+                    .iter()
+                    .map(|column| {
+                        (
+                            column.name().to_string(),
+                            column.decl_type() == Some("BOOLEAN"),
+                        )
+                    })
+                    .collect();
+                let rows = statement
+                    .query_map(rusqlite::params_from_iter(params), |row| {
+                        let mut object = Map::new();
+                        for (index, (name, is_bool)) in columns.iter().enumerate() {
+                            let value = match row.get_ref(index)? {
+                                ValueRef::Integer(value) if *is_bool => Value::from(value != 0),
+                                ValueRef::Integer(value) => Value::from(value),
+                                ValueRef::Real(value) => Value::from(value),
+                                ValueRef::Text(value) => {
+                                    Value::from(String::from_utf8_lossy(value).into_owned())
+                                }
+                                ValueRef::Null | ValueRef::Blob(_) => Value::Null,
+                            };
+                            object.insert(name.clone(), value);
+                        }
+                        Ok(Value::Object(object))
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+
+                Ok(rows)
+            }
+            Self::Postgres(client) => {
+                let mut rows = Vec::new();
+                for row in client.query(sql, &postgres_params(params))? {
+                    let mut object = Map::new();
+                    for (index, column) in row.columns().iter().enumerate() {
+                        let type_ = column.type_();
+                        let value = if *type_ == Type::BOOL {
+                            row.try_get::<_, Option<bool>>(index)?.map(Value::from)
+                        } else if *type_ == Type::INT8 {
+                            row.try_get::<_, Option<i64>>(index)?.map(Value::from)
+                        } else if *type_ == Type::INT4 {
+                            row.try_get::<_, Option<i32>>(index)?.map(Value::from)
+                        } else {
+                            row.try_get::<_, Option<String>>(index)?.map(Value::from)
+                        };
+                        object.insert(column.name().to_string(), value.unwrap_or(Value::Null));
+                    }
+                    rows.push(Value::Object(object));
+                }
+
+                Ok(rows)
+            }
+        }
+    }
+
+    /// Commits when `apply` succeeds and rolls back otherwise
+    pub fn transaction<T>(&mut self, apply: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        self.batch("BEGIN")?;
+        match apply(self) {
+            Ok(value) => {
+                self.batch("COMMIT")?;
+                Ok(value)
+            }
+            Err(err) => {
+                self.batch("ROLLBACK")?;
+                Err(err)
+            }
+        }
+    }
+}
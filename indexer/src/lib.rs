@@ -0,0 +1,13 @@
+//! Materializes donaproto events into relational tables.
+//!
+//! `source` reads transactions from an RPC node or from saved blocks, `store` applies the
+//! events they emitted idempotently and `api` serves campaign lists, leaderboards and
+//! per-mint totals from the tables.
+
+pub mod api;
+pub mod db;
+pub mod source;
+pub mod store;
+
+pub use db::{Database, Param};
+pub use store::{index_transaction, IndexedTransaction};
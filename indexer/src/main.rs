@@ -0,0 +1,67 @@
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+use donaproto_indexer::{api, source, Database};
+
+#[derive(Parser)]
+#[command(
+    name = "donaproto-indexer",
+    about = "Index donaproto events into SQLite or Postgres and serve them over HTTP"
+)]
+struct Cli {
+    /// SQLite database file, or a postgres:// connection url
+    #[arg(
+        long,
+        global = true,
+        env = "DONAPROTO_INDEXER_DATABASE",
+        default_value = "donaproto-indexer.sqlite"
+    )]
+    database: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Index donaproto transactions newer than the last synced one from an RPC node
+    Sync {
+        #[arg(long, default_value = "http://127.0.0.1:8899")]
+        url: String,
+        /// Keep polling for new transactions
+        #[arg(long)]
+        follow: bool,
+        /// Seconds between polls with --follow
+        #[arg(long, default_value_t = 5)]
+        poll_interval: u64,
+    },
+    /// Index `getBlock` results saved as <slot>.json files in a directory
+    Replay { dir: PathBuf },
+    /// Serve the query API
+    Serve {
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut db = Database::open(&cli.database)?;
+
+    match cli.command {
+        Command::Sync {
+            url,
+            follow,
+            poll_interval,
+        } => {
+            let poll_interval = follow.then_some(Duration::from_secs(poll_interval));
+            source::sync_rpc(&mut db, &url, poll_interval)
+        }
+        Command::Replay { dir } => source::replay_blocks(&mut db, &dir),
+        Command::Serve { listen } => {
+            eprintln!("serving on http://{}", listen);
+            api::serve(&mut db, &listen)
+        }
+    }
+}
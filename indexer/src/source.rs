@@ -0,0 +1,166 @@
+//! Reads donaproto transactions from an RPC node or from saved blocks
+
+use std::{fs, path::Path, str::FromStr, thread, time::Duration};
+
+use anyhow::{anyhow, Context, Result};
+use donaproto_client::PROGRAM_ID;
+use solana_client::{
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_config::RpcTransactionConfig,
+    rpc_response::RpcConfirmedTransactionStatusWithSignature,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+use solana_transaction_status::{
+    EncodedTransaction, EncodedTransactionWithStatusMeta, UiConfirmedBlock, UiTransactionEncoding,
+};
+
+use crate::{
+    db::Database,
+    store::{cursor, index_transaction, save_cursor, IndexedTransaction, RPC_CURSOR},
+};
+
+/// Indexes donaproto transactions newer than the saved cursor, oldest first.
+/// With `poll_interval` set it keeps polling for new transactions.
+pub fn sync_rpc(db: &mut Database, url: &str, poll_interval: Option<Duration>) -> Result<()> {
+    let client = RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed());
+    loop {
+        let until = match cursor(db, RPC_CURSOR)? {
+            Some(signature) => Some(Signature::from_str(&signature)?),
+            None => None,
+        };
+        let statuses = new_signatures(&client, until)?;
+        let mut indexed_count = 0;
+        for status in &statuses {
+            if status.err.is_none() {
+                let signature = Signature::from_str(&status.signature)?;
+                let transaction = fetch_transaction(&client, &signature)?;
+                indexed_count += index_transaction(db, &transaction)?;
+            }
+            save_cursor(db, RPC_CURSOR, &status.signature, status.slot)?;
+        }
+        if !statuses.is_empty() {
+            eprintln!(
+                "indexed {} events from {} transactions",
+                indexed_count,
+                statuses.len()
+            );
+        }
+
+        match poll_interval {
+            Some(poll_interval) => thread::sleep(poll_interval),
+            None => return Ok(()),
+        }
+    }
+}
+
+/// Signatures of transactions mentioning donaproto after `until`, oldest first
+fn new_signatures(
+    client: &RpcClient,
+    until: Option<Signature>,
+) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+    let mut statuses = Vec::new();
+    let mut before = None;
+    loop {
+        // pages are returned newest first
+        let page = client.get_signatures_for_address_with_config(
+            &PROGRAM_ID,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: None,
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        )?;
+        let oldest = match page.last() {
+            Some(status) => Signature::from_str(&status.signature)?,
+            None => break,
+        };
+        before = Some(oldest);
+        statuses.extend(page);
+    }
+    statuses.reverse();
+
+    Ok(statuses)
+}
+
+fn fetch_transaction(client: &RpcClient, signature: &Signature) -> Result<IndexedTransaction> {
+    let transaction = client.get_transaction_with_config(
+        signature,
+        RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        },
+    )?;
+    let logs = transaction
+        .transaction
+        .meta
+        .and_then(|meta| Option::<Vec<String>>::from(meta.log_messages))
+        .unwrap_or_default();
+
+    Ok(IndexedTransaction {
+        signature: signature.to_string(),
+        slot: transaction.slot,
+        block_time: transaction.block_time,
+        logs,
+    })
+}
+
+/// Indexes `getBlock` results saved as `<slot>.json` in `dir`, in slot order.
+/// Transactions may use any encoding, but their log messages must be included.
+pub fn replay_blocks(db: &mut Database, dir: &Path) -> Result<()> {
+    let mut blocks = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+            continue;
+        }
+        let slot = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<u64>().ok())
+            .ok_or_else(|| anyhow!("{} is not named <slot>.json", path.display()))?;
+        blocks.push((slot, path));
+    }
+    blocks.sort();
+
+    for (slot, path) in blocks {
+        let block: UiConfirmedBlock = serde_json::from_slice(&fs::read(&path)?)
+            .with_context(|| format!("{} is not a getBlock result", path.display()))?;
+        let mut indexed_count = 0;
+        for transaction in block.transactions.unwrap_or_default() {
+            if let Some(transaction) = block_transaction(slot, block.block_time, transaction) {
+                indexed_count += index_transaction(db, &transaction)?;
+            }
+        }
+        eprintln!("slot {}: indexed {} events", slot, indexed_count);
+    }
+
+    Ok(())
+}
+
+/// None for failed transactions, which emit no events
+fn block_transaction(
+    slot: u64,
+    block_time: Option<i64>,
+    transaction: EncodedTransactionWithStatusMeta,
+) -> Option<IndexedTransaction> {
+    let meta = transaction.meta?;
+    if meta.err.is_some() {
+        return None;
+    }
+    let signature = match transaction.transaction {
+        EncodedTransaction::Json(transaction) => transaction.signatures.first().cloned(),
+        EncodedTransaction::Accounts(accounts) => accounts.signatures.first().cloned(),
+        encoded => encoded
+            .decode()
+            .and_then(|transaction| transaction.signatures.first().map(ToString::to_string)),
+    }?;
+
+    Some(IndexedTransaction {
+        signature,
+        slot,
+        block_time,
+        logs: Option::<Vec<String>>::from(meta.log_messages).unwrap_or_default(),
+    })
+}
@@ -0,0 +1,471 @@
+//! Applies donaproto events to the tables.
+//!
+//! Every event row is keyed by the signature of its transaction and its position among the
+//! donaproto events of that transaction. Aggregates are only updated when that row is new,
+//! and all events of a transaction are applied in one database transaction, so replaying
+//! any range of transactions is a no-op.
+
+use anyhow::Result;
+use donaproto::events::{
+    DonationCreated, DonationReceived, DonationRefunded, FundsWithdrawn, ProtocolInitialized,
+    RewardsClaimed,
+};
+use donaproto_client::{parse_logs, DonaprotoEvent};
+
+use crate::db::{to_bigint, Database, Param};
+
+/// Rewards transferred to the contributor right away
+pub const REWARD_TRANSFERRED: &str = "transferred";
+/// Rewards accrued into vesting, transferred later by `claim_rewards`
+pub const REWARD_VESTED: &str = "vested";
+pub const REWARD_CLAIMED: &str = "claimed";
+/// Rewards returned by a refund
+pub const REWARD_CLAWED_BACK: &str = "clawed_back";
+
+pub const DONATION: &str = "donation";
+pub const REFUND: &str = "refund";
+
+/// A successful transaction invoking donaproto
+pub struct IndexedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub logs: Vec<String>,
+}
+
+struct EventKey<'a> {
+    signature: &'a str,
+    event_index: usize,
+    slot: u64,
+    block_time: Option<i64>,
+}
+
+impl EventKey<'_> {
+    /// `signature, event_index, slot, block_time` columns
+    fn params(&self) -> Result<Vec<Param>> {
+        Ok(vec![
+            Param::text(self.signature),
+            Param::Int(self.event_index as i64),
+            Param::amount(self.slot)?,
+            Param::OptInt(self.block_time),
+        ])
+    }
+}
+
+/// Returns the number of newly indexed events
+pub fn index_transaction(db: &mut Database, transaction: &IndexedTransaction) -> Result<usize> {
+    let events = parse_logs(&transaction.logs)?;
+    if events.is_empty() {
+        return Ok(0);
+    }
+
+    db.transaction(|db| {
+        let mut indexed_count = 0;
+        for (event_index, event) in events.iter().enumerate() {
+            let key = EventKey {
+                signature: &transaction.signature,
+                event_index,
+                slot: transaction.slot,
+                block_time: transaction.block_time,
+            };
+            let is_new = match event {
+                DonaprotoEvent::ProtocolInitialized(event) => {
+                    apply_protocol_initialized(db, &key, event)?
+                }
+                DonaprotoEvent::DonationCreated(event) => apply_donation_created(db, &key, event)?,
+                DonaprotoEvent::DonationReceived(event) => {
+                    apply_donation_received(db, &key, event)?
+                }
+                DonaprotoEvent::DonationRefunded(event) => {
+                    apply_donation_refunded(db, &key, event)?
+                }
+                DonaprotoEvent::RewardsClaimed(event) => apply_rewards_claimed(db, &key, event)?,
+                DonaprotoEvent::FundsWithdrawn(event) => apply_funds_withdrawn(db, &key, event)?,
+                // round matches and treasury movements do not feed any table
+                DonaprotoEvent::MatchDistributed(_)
+                | DonaprotoEvent::TreasuryFunded(_)
+                | DonaprotoEvent::TreasuryWithdrawn(_)
+                | DonaprotoEvent::TreasuryRotated(_) => false,
+            };
+            if is_new {
+                indexed_count += 1;
+            }
+        }
+
+        Ok(indexed_count)
+    })
+}
+
+fn apply_protocol_initialized(
+    db: &mut Database,
+    key: &EventKey,
+    event: &ProtocolInitialized,
+) -> Result<bool> {
+    let inserted = db.execute(
+        "INSERT INTO protocols (address, authority, donation_mint, treasury_mint, treasury,
+            min_amount_to_earn, min_amount_to_collect, slot, block_time)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        ON CONFLICT (address) DO NOTHING",
+        &[
+            Param::text(event.donation_protocol),
+            Param::text(event.authority),
+            Param::text(event.donation_mint),
+            Param::text(event.treasury_mint),
+            Param::text(event.treasury),
+            Param::amount(event.min_amount_to_earn)?,
+            Param::amount(event.min_amount_to_collect)?,
+            Param::amount(key.slot)?,
+            Param::OptInt(key.block_time),
+        ],
+    )?;
+
+    Ok(inserted > 0)
+}
+
+fn apply_donation_created(
+    db: &mut Database,
+    key: &EventKey,
+    event: &DonationCreated,
+) -> Result<bool> {
+    let inserted = db.execute(
+        "INSERT INTO campaigns (address, protocol, creator_wallet, recipient, donation_mint,
+            amount_collecting, default_amount_collecting, ending_timestamp, ipfs_hash,
+            slot, block_time)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT (address) DO NOTHING",
+        &[
+            Param::text(event.donation_data),
+            Param::text(event.donation_protocol),
+            Param::text(event.creator_wallet),
+            Param::text(event.recipient),
+            Param::text(event.donation_mint),
+            Param::amount(event.amount_collecting)?,
+            Param::amount(event.default_amount_collecting)?,
+            Param::amount(event.ending_timestamp)?,
+            Param::text(&event.ipfs_hash),
+            Param::amount(key.slot)?,
+            Param::OptInt(key.block_time),
+        ],
+    )?;
+
+    Ok(inserted > 0)
+}
+
+fn apply_donation_received(
+    db: &mut Database,
+    key: &EventKey,
+    event: &DonationReceived,
+) -> Result<bool> {
+    if !insert_donation(
+        db,
+        key,
+        DONATION,
+        &event.donation_protocol,
+        &event.donation_data,
+        &event.contributor,
+        [
+            event.amount,
+            event.default_amount,
+            event.reward_amount,
+            event.matched_amount,
+        ],
+    )? {
+        return Ok(false);
+    }
+
+    db.execute(
+        "UPDATE campaigns SET
+            total_amount_received = total_amount_received + $2 + $3,
+            total_amount_matched = total_amount_matched + $3,
+            total_default_amount_donated = total_default_amount_donated + $4,
+            donations_count = donations_count + 1
+        WHERE address = $1",
+        &[
+            Param::text(event.donation_data),
+            Param::amount(event.amount)?,
+            Param::amount(event.matched_amount)?,
+            Param::amount(event.default_amount)?,
+        ],
+    )?;
+    update_contributor(
+        db,
+        &event.donation_protocol,
+        &event.contributor,
+        ContributorDelta {
+            default_amount_donated: Param::amount(event.default_amount)?,
+            reward_amount: Param::amount(event.reward_amount)?,
+            amount_claimed: Param::Int(0),
+            donations_count: 1,
+        },
+    )?;
+    if event.reward_amount > 0 {
+        let kind = if event.is_reward_vested {
+            REWARD_VESTED
+        } else {
+            REWARD_TRANSFERRED
+        };
+        insert_reward(
+            db,
+            key,
+            kind,
+            &event.donation_protocol,
+            &event.contributor,
+            event.reward_amount,
+        )?;
+    }
+
+    Ok(true)
+}
+
+fn apply_donation_refunded(
+    db: &mut Database,
+    key: &EventKey,
+    event: &DonationRefunded,
+) -> Result<bool> {
+    if !insert_donation(
+        db,
+        key,
+        REFUND,
+        &event.donation_protocol,
+        &event.donation_data,
+        &event.contributor,
+        [
+            event.amount,
+            event.default_amount,
+            event.reward_amount,
+            event.matched_amount,
+        ],
+    )? {
+        return Ok(false);
+    }
+
+    // refunds keep the donations count, as `ContributorData::donations_count` does
+    db.execute(
+        "UPDATE campaigns SET
+            total_amount_received = total_amount_received - $2 - $3,
+            total_amount_matched = total_amount_matched - $3,
+            total_default_amount_donated = total_default_amount_donated - $4
+        WHERE address = $1",
+        &[
+            Param::text(event.donation_data),
+            Param::amount(event.amount)?,
+            Param::amount(event.matched_amount)?,
+            Param::amount(event.default_amount)?,
+        ],
+    )?;
+    update_contributor(
+        db,
+        &event.donation_protocol,
+        &event.contributor,
+        ContributorDelta {
+            default_amount_donated: Param::Int(-to_bigint(event.default_amount)?),
+            reward_amount: Param::Int(-to_bigint(event.reward_amount)?),
+            amount_claimed: Param::Int(0),
+            donations_count: 0,
+        },
+    )?;
+    if event.reward_amount > 0 {
+        insert_reward(
+            db,
+            key,
+            REWARD_CLAWED_BACK,
+            &event.donation_protocol,
+            &event.contributor,
+            event.reward_amount,
+        )?;
+    }
+
+    Ok(true)
+}
+
+fn apply_rewards_claimed(
+    db: &mut Database,
+    key: &EventKey,
+    event: &RewardsClaimed,
+) -> Result<bool> {
+    if !insert_reward(
+        db,
+        key,
+        REWARD_CLAIMED,
+        &event.donation_protocol,
+        &event.contributor,
+        event.amount,
+    )? {
+        return Ok(false);
+    }
+
+    update_contributor(
+        db,
+        &event.donation_protocol,
+        &event.contributor,
+        ContributorDelta {
+            default_amount_donated: Param::Int(0),
+            reward_amount: Param::Int(0),
+            amount_claimed: Param::amount(event.amount)?,
+            donations_count: 0,
+        },
+    )?;
+
+    Ok(true)
+}
+
+fn apply_funds_withdrawn(
+    db: &mut Database,
+    key: &EventKey,
+    event: &FundsWithdrawn,
+) -> Result<bool> {
+    let mut params = key.params()?;
+    params.extend([
+        Param::text(event.donation_protocol),
+        Param::text(event.donation_data),
+        Param::text(event.recipient),
+        Param::amount(event.amount)?,
+        Param::amount(event.fee_amount)?,
+        Param::amount(event.default_amount)?,
+    ]);
+    let inserted = db.execute(
+        "INSERT INTO withdrawals (signature, event_index, slot, block_time, protocol, campaign,
+            recipient, amount, fee_amount, default_amount)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        ON CONFLICT (signature, event_index) DO NOTHING",
+        &params,
+    )?;
+    if inserted == 0 {
+        return Ok(false);
+    }
+
+    db.execute(
+        "UPDATE campaigns SET is_closed = TRUE, amount_withdrawn = $2, fee_amount = $3
+        WHERE address = $1",
+        &[
+            Param::text(event.donation_data),
+            Param::amount(event.amount)?,
+            Param::amount(event.fee_amount)?,
+        ],
+    )?;
+
+    Ok(true)
+}
+
+/// `amounts` are `amount, default_amount, reward_amount, matched_amount`
+fn insert_donation(
+    db: &mut Database,
+    key: &EventKey,
+    kind: &str,
+    donation_protocol: &impl ToString,
+    donation_data: &impl ToString,
+    contributor: &impl ToString,
+    amounts: [u64; 4],
+) -> Result<bool> {
+    let mut params = key.params()?;
+    params.extend([
+        Param::text(donation_protocol.to_string()),
+        Param::text(donation_data.to_string()),
+        Param::text(contributor.to_string()),
+        Param::text(kind),
+    ]);
+    for amount in amounts {
+        params.push(Param::amount(amount)?);
+    }
+    let inserted = db.execute(
+        "INSERT INTO donations (signature, event_index, slot, block_time, protocol, campaign,
+            contributor, kind, amount, default_amount, reward_amount, matched_amount)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        ON CONFLICT (signature, event_index) DO NOTHING",
+        &params,
+    )?;
+
+    Ok(inserted > 0)
+}
+
+fn insert_reward(
+    db: &mut Database,
+    key: &EventKey,
+    kind: &str,
+    donation_protocol: &impl ToString,
+    contributor: &impl ToString,
+    amount: u64,
+) -> Result<bool> {
+    let mut params = key.params()?;
+    params.extend([
+        Param::text(donation_protocol.to_string()),
+        Param::text(contributor.to_string()),
+        Param::text(kind),
+        Param::amount(amount)?,
+    ]);
+    let inserted = db.execute(
+        "INSERT INTO rewards (signature, event_index, slot, block_time, protocol, contributor,
+            kind, amount)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (signature, event_index) DO NOTHING",
+        &params,
+    )?;
+
+    Ok(inserted > 0)
+}
+
+struct ContributorDelta {
+    default_amount_donated: Param,
+    reward_amount: Param,
+    amount_claimed: Param,
+    donations_count: i64,
+}
+
+fn update_contributor(
+    db: &mut Database,
+    donation_protocol: &impl ToString,
+    contributor: &impl ToString,
+    delta: ContributorDelta,
+) -> Result<()> {
+    db.execute(
+        "INSERT INTO contributors (protocol, address, total_default_amount_donated,
+            total_reward_amount, total_amount_claimed, donations_count)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (protocol, address) DO UPDATE SET
+            total_default_amount_donated =
+                contributors.total_default_amount_donated + excluded.total_default_amount_donated,
+            total_reward_amount = contributors.total_reward_amount + excluded.total_reward_amount,
+            total_amount_claimed =
+                contributors.total_amount_claimed + excluded.total_amount_claimed,
+            donations_count = contributors.donations_count + excluded.donations_count",
+        &[
+            Param::text(donation_protocol.to_string()),
+            Param::text(contributor.to_string()),
+            delta.default_amount_donated,
+            delta.reward_amount,
+            delta.amount_claimed,
+            Param::Int(delta.donations_count),
+        ],
+    )?;
+
+    Ok(())
+}
+
+pub const RPC_CURSOR: &str = "rpc";
+
+/// Newest indexed signature of `source`
+pub fn cursor(db: &mut Database, source: &str) -> Result<Option<String>> {
+    let rows = db.query(
+        "SELECT signature FROM cursors WHERE source = $1",
+        &[Param::text(source)],
+    )?;
+
+    Ok(rows
+        .first()
+        .and_then(|row| row["signature"].as_str())
+        .map(ToString::to_string))
+}
+
+pub fn save_cursor(db: &mut Database, source: &str, signature: &str, slot: u64) -> Result<()> {
+    db.execute(
+        "INSERT INTO cursors (source, signature, slot) VALUES ($1, $2, $3)
+        ON CONFLICT (source) DO UPDATE SET signature = excluded.signature, slot = excluded.slot",
+        &[
+            Param::text(source),
+            Param::text(signature),
+            Param::amount(slot)?,
+        ],
+    )?;
+
+    Ok(())
+}
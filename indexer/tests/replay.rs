@@ -0,0 +1,204 @@
+use anchor_lang::{prelude::Pubkey, Event};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use donaproto::events::{
+    DonationCreated, DonationReceived, DonationRefunded, FundsWithdrawn, ProtocolInitialized,
+};
+use donaproto_indexer::{index_transaction, Database, IndexedTransaction, Param};
+use serde_json::{json, Value};
+
+const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGkPFcZJuRnSGVG5cvbEB";
+
+fn program_data(event: &impl Event) -> String {
+    format!("Program data: {}", STANDARD.encode(event.data()))
+}
+
+/// Logs of a donaproto instruction emitting `events` after a token transfer CPI,
+/// the CPI logs data that decodes as a donaproto event but must be ignored
+fn transaction(signature: &str, slot: u64, events: Vec<String>) -> IndexedTransaction {
+    let program_id = donaproto::ID.to_string();
+    let foreign_event = program_data(&DonationReceived {
+        donation_protocol: Pubkey::new_unique(),
+        donation_data: Pubkey::new_unique(),
+        contributor: Pubkey::new_unique(),
+        amount: 1,
+        default_amount: 1,
+        reward_amount: 1,
+        is_reward_vested: false,
+        matched_amount: 0,
+    });
+
+    let mut logs = vec![
+        format!("Program {} invoke [1]", program_id),
+        "Program log: Instruction: Donate".to_string(),
+        format!("Program {} invoke [2]", TOKEN_PROGRAM),
+        foreign_event,
+        format!("Program {} success", TOKEN_PROGRAM),
+    ];
+    logs.extend(events);
+    logs.push(format!(
+        "Program {} consumed 40000 of 200000 compute units",
+        program_id
+    ));
+    logs.push(format!("Program {} success", program_id));
+
+    IndexedTransaction {
+        signature: signature.to_string(),
+        slot,
+        block_time: Some(1_700_000_000 + slot as i64),
+        logs,
+    }
+}
+
+fn query(db: &mut Database, sql: &str, address: &Pubkey) -> Vec<Value> {
+    db.query(sql, &[Param::text(address)]).unwrap()
+}
+
+#[test]
+fn replays_are_idempotent() {
+    let protocol = Pubkey::new_unique();
+    let campaign = Pubkey::new_unique();
+    let donation_mint = Pubkey::new_unique();
+    let first_donor = Pubkey::new_unique();
+    let second_donor = Pubkey::new_unique();
+
+    let transactions = vec![
+        transaction(
+            "create",
+            1,
+            vec![
+                program_data(&ProtocolInitialized {
+                    donation_protocol: protocol,
+                    authority: Pubkey::new_unique(),
+                    donation_mint,
+                    treasury_mint: Pubkey::new_unique(),
+                    treasury: Pubkey::new_unique(),
+                    min_amount_to_earn: 10,
+                    min_amount_to_collect: 100,
+                }),
+                program_data(&DonationCreated {
+                    donation_protocol: protocol,
+                    donation_data: campaign,
+                    creator_wallet: Pubkey::new_unique(),
+                    recipient: Pubkey::new_unique(),
+                    donation_mint,
+                    amount_collecting: 1_000,
+                    default_amount_collecting: 1_000,
+                    ending_timestamp: 1_800_000_000,
+                    ipfs_hash: "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+                        .to_string(),
+                }),
+            ],
+        ),
+        transaction(
+            "first_donation",
+            2,
+            vec![program_data(&DonationReceived {
+                donation_protocol: protocol,
+                donation_data: campaign,
+                contributor: first_donor,
+                amount: 300,
+                default_amount: 300,
+                reward_amount: 300,
+                is_reward_vested: false,
+                matched_amount: 0,
+            })],
+        ),
+        transaction(
+            "second_donation",
+            3,
+            vec![program_data(&DonationReceived {
+                donation_protocol: protocol,
+                donation_data: campaign,
+                contributor: second_donor,
+                amount: 500,
+                default_amount: 500,
+                reward_amount: 500,
+                is_reward_vested: true,
+                matched_amount: 100,
+            })],
+        ),
+        transaction(
+            "refund",
+            4,
+            vec![program_data(&DonationRefunded {
+                donation_protocol: protocol,
+                donation_data: campaign,
+                contributor: first_donor,
+                amount: 100,
+                default_amount: 100,
+                reward_amount: 100,
+                matched_amount: 0,
+            })],
+        ),
+        transaction(
+            "withdraw",
+            5,
+            vec![program_data(&FundsWithdrawn {
+                donation_protocol: protocol,
+                donation_data: campaign,
+                recipient: Pubkey::new_unique(),
+                amount: 792,
+                fee_amount: 8,
+                default_amount: 792,
+            })],
+        ),
+    ];
+
+    let mut db = Database::open_in_memory().unwrap();
+    let indexed_counts: Vec<usize> = transactions
+        .iter()
+        .map(|transaction| index_transaction(&mut db, transaction).unwrap())
+        .collect();
+    assert_eq!(indexed_counts, vec![2, 1, 1, 1, 1]);
+    // replaying everything, e.g. after a restart before the cursor was saved
+    for transaction in &transactions {
+        assert_eq!(index_transaction(&mut db, transaction).unwrap(), 0);
+    }
+
+    let campaigns = query(
+        &mut db,
+        "SELECT * FROM campaigns WHERE address = $1",
+        &campaign,
+    );
+    assert_eq!(campaigns.len(), 1);
+    let campaign_row = &campaigns[0];
+    assert_eq!(campaign_row["total_amount_received"], json!(800));
+    assert_eq!(campaign_row["total_amount_matched"], json!(100));
+    assert_eq!(campaign_row["total_default_amount_donated"], json!(700));
+    assert_eq!(campaign_row["donations_count"], json!(2));
+    assert_eq!(campaign_row["is_closed"], json!(true));
+    assert_eq!(campaign_row["amount_withdrawn"], json!(792));
+    assert_eq!(campaign_row["fee_amount"], json!(8));
+
+    let leaderboard = query(
+        &mut db,
+        "SELECT * FROM contributors WHERE protocol = $1 ORDER BY total_default_amount_donated DESC",
+        &protocol,
+    );
+    assert_eq!(leaderboard.len(), 2);
+    assert_eq!(leaderboard[0]["address"], json!(second_donor.to_string()));
+    assert_eq!(leaderboard[0]["total_reward_amount"], json!(500));
+    assert_eq!(leaderboard[1]["address"], json!(first_donor.to_string()));
+    assert_eq!(leaderboard[1]["total_default_amount_donated"], json!(200));
+    assert_eq!(leaderboard[1]["total_reward_amount"], json!(200));
+
+    let rewards = query(
+        &mut db,
+        "SELECT kind FROM rewards WHERE protocol = $1 ORDER BY slot",
+        &protocol,
+    );
+    assert_eq!(
+        rewards,
+        vec![
+            json!({ "kind": "transferred" }),
+            json!({ "kind": "vested" }),
+            json!({ "kind": "clawed_back" }),
+        ]
+    );
+    let donations = query(
+        &mut db,
+        "SELECT * FROM donations WHERE campaign = $1",
+        &campaign,
+    );
+    assert_eq!(donations.len(), 3);
+}
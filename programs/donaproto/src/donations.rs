@@ -0,0 +1,224 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::{
+    errors::DonationError,
+    events::DonationReceived,
+    pledges::transfer_pledge_match,
+    rewards::{calculate_reward_amount, distribute_reward, track_reward_eligibility},
+    rounds::track_round_contribution,
+    states::{
+        ContributorData, DonationData, DonationProtocolData, DonationReceipt, DonationTiers,
+        FundingRound, MatchPledge, RewardVesting,
+    },
+};
+
+/// Part of a donation of `amount` the campaign accepts, checked before any tokens move
+pub fn accept_donation(donation_data: &DonationData, amount: u64) -> Result<u64> {
+    if donation_data.is_closed() {
+        return Err(DonationError::DonationClosed.into());
+    }
+    if amount == 0 {
+        return Err(DonationError::DonationAmountZero.into());
+    }
+
+    donation_data.accepted_amount(amount)
+}
+
+/// Donation to one campaign whose tokens are already in its holding wallet
+pub struct CampaignDonation<'a, 'info> {
+    pub donation_data: &'a mut DonationData,
+    pub donation_data_key: Pubkey,
+    pub holding_wallet: AccountInfo<'info>,
+    /// Current owner of the campaign `recipient` token account
+    pub recipient_owner: Pubkey,
+    pub donation_receipt: &'a mut Account<'info, DonationReceipt>,
+    pub donation_receipt_bump: u8,
+    /// Required when the campaign has a sponsor match pledge
+    pub match_pledge: Option<&'a mut Account<'info, MatchPledge>>,
+    pub match_pledge_escrow: Option<&'a Account<'info, TokenAccount>>,
+    /// Required when the campaign has perk tiers
+    pub donation_tiers: Option<&'a mut Account<'info, DonationTiers>>,
+    /// In donation mint units, after the hard cap
+    pub amount: u64,
+    /// `amount` in default donation mint units
+    pub default_amount: u64,
+}
+
+/// Contributor side of a donation to one or several campaigns
+pub struct Contribution<'a, 'info> {
+    pub donation_protocol: &'a mut Account<'info, DonationProtocolData>,
+    pub contributor_data: &'a mut Account<'info, ContributorData>,
+    pub contributor_data_bump: u8,
+    /// Wallet credited with the donation, its rewards and receipts
+    pub contributor: Pubkey,
+    /// Wallets funding the donation, checked against campaign creators and recipients
+    pub donors: &'a [Pubkey],
+    /// Paid from tokens of another wallet, the contributor can not refund it
+    pub is_gift: bool,
+    /// Counts every campaign of the donation towards the round
+    pub funding_round: Option<&'a mut Account<'info, FundingRound>>,
+    pub reward_vesting: Option<&'a mut Account<'info, RewardVesting>>,
+    pub token_program: AccountInfo<'info>,
+    pub reward_treasury: &'a Account<'info, TokenAccount>,
+    pub reward_treasury_owner: AccountInfo<'info>,
+    pub reward_destination: AccountInfo<'info>,
+}
+
+/// Credits campaigns and contributor for donations already transferred:
+/// a single reward is computed on the eligible total, so `min_amount_to_earn` applies to
+/// the whole donation, then every campaign records its receipt, perk tier, round contribution
+/// and sponsor match and emits `DonationReceived`
+pub fn credit_donations(
+    contribution: Contribution,
+    campaigns: &mut [CampaignDonation],
+) -> Result<()> {
+    let Contribution {
+        donation_protocol,
+        contributor_data,
+        contributor_data_bump,
+        contributor,
+        donors,
+        is_gift,
+        mut funding_round,
+        reward_vesting,
+        token_program,
+        reward_treasury,
+        reward_treasury_owner,
+        reward_destination,
+    } = contribution;
+
+    contributor_data.init_if_needed(donation_protocol.key(), contributor_data_bump)?;
+
+    let mut total_amount: u64 = 0;
+    let mut eligible_amount: u64 = 0;
+    let mut is_reward_eligible = Vec::with_capacity(campaigns.len());
+    for campaign in campaigns.iter_mut() {
+        campaign.donation_data.total_amount_received = campaign
+            .donation_data
+            .total_amount_received
+            .checked_add(campaign.amount)
+            .unwrap();
+        let is_eligible = track_reward_eligibility(
+            campaign.donation_data,
+            donors,
+            &campaign.recipient_owner,
+            campaign.amount,
+            donation_protocol.max_self_funded_bps,
+        );
+        total_amount = total_amount.checked_add(campaign.default_amount).unwrap();
+        if is_eligible {
+            eligible_amount = eligible_amount
+                .checked_add(campaign.default_amount)
+                .unwrap();
+        }
+        is_reward_eligible.push(is_eligible);
+    }
+
+    contributor_data.total_amount_donated = contributor_data
+        .total_amount_donated
+        .checked_add(total_amount)
+        .unwrap();
+    contributor_data.donations_count = contributor_data
+        .donations_count
+        .checked_add(campaigns.len() as u64)
+        .unwrap();
+
+    let reward_amount = if eligible_amount > 0 {
+        calculate_reward_amount(donation_protocol, eligible_amount, reward_treasury.amount)?
+    } else {
+        0
+    };
+    // Transfer amount of tokens from reward treasury wallet to the contributor
+    distribute_reward(
+        donation_protocol,
+        contributor_data,
+        reward_vesting,
+        token_program.clone(),
+        reward_treasury.to_account_info(),
+        reward_treasury_owner,
+        reward_destination,
+        reward_amount,
+    )?;
+
+    // reward is attributed pro rata to the eligible campaigns,
+    // the last one takes the rounding remainder
+    let last_eligible = is_reward_eligible
+        .iter()
+        .rposition(|is_eligible| *is_eligible);
+    let mut attributed_reward: u64 = 0;
+    for (index, campaign) in campaigns.iter_mut().enumerate() {
+        let campaign_reward = if Some(index) == last_eligible {
+            reward_amount - attributed_reward
+        } else if is_reward_eligible[index] {
+            (reward_amount as u128 * campaign.default_amount as u128 / eligible_amount as u128)
+                as u64
+        } else {
+            0
+        };
+        attributed_reward += campaign_reward;
+
+        campaign.donation_receipt.record(
+            campaign.donation_data_key,
+            contributor,
+            campaign.donation_receipt_bump,
+            campaign.amount,
+            campaign.default_amount,
+            campaign_reward,
+        );
+        if !is_gift {
+            campaign.donation_receipt.record_refundable(campaign.amount);
+        }
+        record_perk_tier(
+            campaign.donation_data,
+            campaign.donation_tiers.as_deref_mut(),
+            campaign.donation_receipt,
+        )?;
+        track_round_contribution(
+            funding_round.as_deref_mut(),
+            &campaign.donation_data_key,
+            campaign.donation_receipt,
+            campaign.default_amount,
+        )?;
+        let matched_amount = transfer_pledge_match(
+            campaign.donation_data,
+            campaign.match_pledge.as_deref_mut(),
+            campaign.match_pledge_escrow,
+            token_program.clone(),
+            campaign.holding_wallet.clone(),
+            campaign.amount,
+        )?;
+        campaign.donation_receipt.record_match(matched_amount);
+
+        emit!(DonationReceived {
+            donation_protocol: donation_protocol.key(),
+            donation_data: campaign.donation_data_key,
+            contributor,
+            amount: campaign.amount,
+            default_amount: campaign.default_amount,
+            reward_amount: campaign_reward,
+            is_reward_vested: donation_protocol.is_reward_vesting(),
+            matched_amount,
+        });
+    }
+
+    Ok(())
+}
+
+/// Moves the receipt to the perk tier its cumulative contribution reached
+fn record_perk_tier(
+    donation_data: &DonationData,
+    donation_tiers: Option<&mut Account<DonationTiers>>,
+    donation_receipt: &mut DonationReceipt,
+) -> Result<()> {
+    if donation_data.donation_tiers == Pubkey::default() {
+        return Ok(());
+    }
+    match donation_tiers {
+        Some(donation_tiers) if donation_tiers.key() == donation_data.donation_tiers => {
+            donation_tiers.record_contribution(donation_receipt);
+            Ok(())
+        }
+        _ => Err(DonationError::DonationTiersRequired.into()),
+    }
+}
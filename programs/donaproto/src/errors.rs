@@ -18,4 +18,94 @@ pub enum DonationError {
     DonationAmountTooLow,
     #[msg("Invalid donation mint")]
     InvalidDonationMint,
+    #[msg("Contributor data belongs to another donation protocol")]
+    InvalidContributorData,
+    #[msg("Delegated amount is lower than donation amount")]
+    InsufficientDelegatedAmount,
+    #[msg("Subscription period can not be zero")]
+    InvalidSubscriptionPeriod,
+    #[msg("Subscription installment is not due yet")]
+    SubscriptionNotDue,
+    #[msg("Subscription has ended")]
+    SubscriptionEnded,
+    #[msg("Invalid payout split")]
+    InvalidPayoutSplit,
+    #[msg("Fee can not exceed 1000 basis points")]
+    InvalidFeeBps,
+    #[msg("Fee vault is required to withdraw donation with protocol fee")]
+    FeeVaultRequired,
+    #[msg("Treasury amount can not be zero")]
+    TreasuryAmountZero,
+    #[msg("Invalid emission schedule")]
+    InvalidEmissionSchedule,
+    #[msg("Reward vesting account is required while rewards vest")]
+    RewardVestingRequired,
+    #[msg("Vesting cliff can not exceed vesting duration")]
+    InvalidVestingSchedule,
+    #[msg("Nothing to claim yet")]
+    NothingToClaim,
+    #[msg("Treasury balance is reserved for vesting rewards")]
+    TreasuryReservedForVesting,
+    #[msg("Self funding cap can not exceed 10000 basis points")]
+    InvalidSelfFundingCap,
+    #[msg("Donation can not be refunded after it has ended")]
+    RefundPeriodOver,
+    #[msg("Invalid refund amount")]
+    InvalidRefundAmount,
+    #[msg("Invalid funding round")]
+    InvalidFundingRound,
+    #[msg("Funding round is not active")]
+    RoundNotActive,
+    #[msg("Donation is not eligible for the funding round")]
+    CampaignNotInRound,
+    #[msg("Contributions to a finalized funding round can not be refunded")]
+    RoundContributionLocked,
+    #[msg("Funding round has not ended yet")]
+    RoundNotEnded,
+    #[msg("Funding round is already finalized")]
+    RoundAlreadyFinalized,
+    #[msg("Funding round is not finalized yet")]
+    RoundNotFinalized,
+    #[msg("Match is already distributed")]
+    MatchAlreadyDistributed,
+    #[msg("Invalid match pledge")]
+    InvalidMatchPledge,
+    #[msg("Match pledge accounts are required for this donation")]
+    MatchPledgeRequired,
+    #[msg("Match pledge is still active")]
+    MatchPledgeActive,
+    #[msg("Donation would exceed the donation hard cap")]
+    HardCapExceeded,
+    #[msg("Invalid perk tiers")]
+    InvalidPerkTiers,
+    #[msg("Donation tiers account is required for this donation")]
+    DonationTiersRequired,
+    #[msg("Donation with a claimed perk tier can not be refunded")]
+    PerkTierClaimed,
+    #[msg("Receipt mint requires every other receipt account")]
+    ReceiptAccountsRequired,
+    #[msg("Amount converted at the pool price does not fit into u64")]
+    AmountConversionOverflow,
+    #[msg("Account can not be migrated")]
+    InvalidMigrationAccount,
+    #[msg("Account is already at the current layout version")]
+    AccountAlreadyMigrated,
+    #[msg("Pool does not pair the donation mint with the protocol donation mint")]
+    InvalidPoolState,
+    #[msg("Invalid donation batch")]
+    InvalidDonationBatch,
+    #[msg("Account must be migrated to the current layout first")]
+    AccountNotMigrated,
+    #[msg("Token wallet is already delegated to another account")]
+    TokenWalletAlreadyDelegated,
+    #[msg("Every round match must be distributed first")]
+    MatchesNotDistributed,
+    #[msg("Matching vault is empty")]
+    NothingToSweep,
+    #[msg("Account does not belong to the donation")]
+    DonationAccountMismatch,
+    #[msg("Payout split can not be set once the donation received funds")]
+    PayoutSplitLocked,
+    #[msg("Donation does not allow refunds")]
+    RefundsDisabled,
 }
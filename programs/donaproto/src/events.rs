@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct TreasuryFunded {
+    pub donation_protocol: Pubkey,
+    pub treasury: Pubkey,
+    pub source: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TreasuryWithdrawn {
+    pub donation_protocol: Pubkey,
+    pub treasury: Pubkey,
+    pub destination: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TreasuryRotated {
+    pub donation_protocol: Pubkey,
+    pub old_treasury: Pubkey,
+    pub new_treasury: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ProtocolInitialized {
+    pub donation_protocol: Pubkey,
+    pub authority: Pubkey,
+    pub donation_mint: Pubkey,
+    pub treasury_mint: Pubkey,
+    pub treasury: Pubkey,
+    pub min_amount_to_earn: u64,
+    pub min_amount_to_collect: u64,
+}
+
+#[event]
+pub struct DonationCreated {
+    pub donation_protocol: Pubkey,
+    pub donation_data: Pubkey,
+    pub creator_wallet: Pubkey,
+    pub recipient: Pubkey,
+    pub donation_mint: Pubkey,
+    pub amount_collecting: u64,
+    /// `amount_collecting` converted to the protocol donation mint
+    pub default_amount_collecting: u64,
+    pub ending_timestamp: u64,
+    pub ipfs_hash: String,
+}
+
+#[event]
+pub struct DonationReceived {
+    pub donation_protocol: Pubkey,
+    pub donation_data: Pubkey,
+    pub contributor: Pubkey,
+    pub amount: u64,
+    /// `amount` converted to the protocol donation mint
+    pub default_amount: u64,
+    pub reward_amount: u64,
+    /// Reward accrued into vesting instead of being transferred
+    pub is_reward_vested: bool,
+    pub matched_amount: u64,
+}
+
+#[event]
+pub struct DonationRefunded {
+    pub donation_protocol: Pubkey,
+    pub donation_data: Pubkey,
+    pub contributor: Pubkey,
+    pub amount: u64,
+    pub default_amount: u64,
+    pub reward_amount: u64,
+    pub matched_amount: u64,
+}
+
+#[event]
+pub struct RewardsClaimed {
+    pub donation_protocol: Pubkey,
+    pub contributor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct FundsWithdrawn {
+    pub donation_protocol: Pubkey,
+    pub donation_data: Pubkey,
+    pub recipient: Pubkey,
+    /// Paid out to the recipient, excluding the protocol fee
+    pub amount: u64,
+    pub fee_amount: u64,
+    /// `amount` converted to the protocol donation mint
+    pub default_amount: u64,
+}
+
+#[event]
+pub struct MatchDistributed {
+    pub donation_protocol: Pubkey,
+    pub funding_round: Pubkey,
+    pub donation_data: Pubkey,
+    pub amount: u64,
+    /// Protocol fee of a match paid out to a closed donation
+    pub fee_amount: u64,
+    /// Paid out to the recipient or payout split instead of the holding wallet
+    pub is_paid_out: bool,
+}
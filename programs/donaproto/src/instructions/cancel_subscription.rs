@@ -0,0 +1,41 @@
+use anchor_lang::{prelude::*, solana_program::program_option::COption};
+use anchor_spl::token::{self, Revoke, TokenAccount};
+
+use crate::{states::Subscription, SUBSCRIPTION_PREFIX};
+
+#[derive(Accounts)]
+pub struct CancelSubscription<'info> {
+    #[account(mut, close = donor_wallet,
+      constraint = subscription.donor == donor_wallet.key(),
+      constraint = subscription.donor_token_wallet == donor_token_wallet.key(),
+      seeds = [
+        SUBSCRIPTION_PREFIX.as_bytes(),
+        subscription.donation_data.as_ref(),
+        donor_wallet.key().as_ref(),
+      ],
+      bump = subscription.bump,
+    )]
+    pub subscription: Account<'info, Subscription>,
+    #[account(mut,
+      constraint = donor_token_wallet.owner == donor_wallet.key(),
+    )]
+    pub donor_token_wallet: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub donor_wallet: Signer<'info>,
+    pub token_program: Program<'info, anchor_spl::token::Token>,
+}
+
+pub fn cancel_subscription(ctx: Context<CancelSubscription>) -> Result<()> {
+    // Revoke only if the delegate was not replaced by the donor in the meantime
+    if ctx.accounts.donor_token_wallet.delegate == COption::Some(ctx.accounts.subscription.key()) {
+        token::revoke(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Revoke {
+                source: ctx.accounts.donor_token_wallet.to_account_info(),
+                authority: ctx.accounts.donor_wallet.to_account_info(),
+            },
+        ))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{
+    errors::DonationError,
+    events::RewardsClaimed,
+    rewards::transfer_reward,
+    states::{ContributorData, DonationProtocolData, RewardVesting},
+    CONTRIBUTOR_PREFIX, REWARD_VESTING_PREFIX, TREASURY_PREFIX,
+};
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(mut,
+      seeds = [
+        REWARD_VESTING_PREFIX.as_bytes(),
+        donation_protocol.key().as_ref(),
+        contributor_wallet.key().as_ref(),
+      ],
+      bump = reward_vesting.bump,
+    )]
+    pub reward_vesting: Account<'info, RewardVesting>,
+    #[account(mut,
+      seeds = [
+        CONTRIBUTOR_PREFIX.as_bytes(),
+        donation_protocol.key().as_ref(),
+        contributor_wallet.key().as_ref(),
+      ],
+      bump = contributor_data.bump,
+    )]
+    pub contributor_data: Account<'info, ContributorData>,
+    #[account(mut)]
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+
+    #[account(mut,
+      constraint = reward_treasury.key() == donation_protocol.treasury,
+    )]
+    pub reward_treasury: Account<'info, TokenAccount>,
+    #[account(
+      seeds = [TREASURY_PREFIX.as_bytes(), donation_protocol.key().as_ref()],
+      bump = donation_protocol.treasury_owner_bump,
+    )]
+    /// CHECK: pda account ["treasury", donation_protocol_data]
+    pub reward_treasury_owner: AccountInfo<'info>,
+    #[account(mut,
+      constraint = contributor_reward_token_wallet.owner == contributor_wallet.key(),
+      constraint = contributor_reward_token_wallet.mint == donation_protocol.treasury_mint,
+    )]
+    pub contributor_reward_token_wallet: Account<'info, TokenAccount>,
+    pub contributor_wallet: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+    let now_timestamp = Clock::get().expect("Time error").unix_timestamp as u64;
+    let donation_protocol = &mut ctx.accounts.donation_protocol;
+    let reward_vesting = &mut ctx.accounts.reward_vesting;
+
+    // rewards unlock on the schedule they accrued with, not the current one
+    let claim_amount = reward_vesting.claimable_amount(now_timestamp);
+    if claim_amount == 0 {
+        return Err(DonationError::NothingToClaim.into());
+    }
+
+    transfer_reward(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.reward_treasury.to_account_info(),
+        ctx.accounts.reward_treasury_owner.to_account_info(),
+        ctx.accounts
+            .contributor_reward_token_wallet
+            .to_account_info(),
+        &donation_protocol.key(),
+        donation_protocol.treasury_owner_bump,
+        claim_amount,
+    )?;
+
+    reward_vesting.total_amount_claimed = reward_vesting
+        .total_amount_claimed
+        .checked_add(claim_amount)
+        .unwrap();
+    donation_protocol.total_reward_vesting = donation_protocol
+        .total_reward_vesting
+        .checked_sub(claim_amount)
+        .unwrap();
+    let contributor_data = &mut ctx.accounts.contributor_data;
+    contributor_data.total_amount_claimed = contributor_data
+        .total_amount_claimed
+        .checked_add(claim_amount)
+        .unwrap();
+
+    emit!(RewardsClaimed {
+        donation_protocol: donation_protocol.key(),
+        contributor: ctx.accounts.contributor_wallet.key(),
+        amount: claim_amount,
+    });
+
+    Ok(())
+}
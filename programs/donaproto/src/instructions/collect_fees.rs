@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::{states::DonationProtocolData, FEE_VAULT_PREFIX};
+
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+    #[account(mut,
+      seeds = [
+        FEE_VAULT_PREFIX.as_bytes(),
+        donation_protocol.key().as_ref(),
+        fee_vault.mint.as_ref(),
+      ],
+      bump,
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+    #[account(
+      seeds = [FEE_VAULT_PREFIX.as_bytes(), donation_protocol.key().as_ref()],
+      bump,
+    )]
+    /// CHECK: pda account ["fee_vault", donation_protocol_data]
+    pub fee_vault_owner: AccountInfo<'info>,
+    #[account(mut,
+      constraint = destination.mint == fee_vault.mint,
+    )]
+    pub destination: Account<'info, TokenAccount>,
+    #[account(
+      constraint = donation_protocol.authority == authority.key(),
+    )]
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn collect_fees(ctx: Context<CollectFees>, amount: u64) -> Result<()> {
+    let donation_protocol_key = ctx.accounts.donation_protocol.key();
+    let seeds = &[
+        FEE_VAULT_PREFIX.as_bytes(),
+        donation_protocol_key.as_ref(),
+        &[ctx.bumps.fee_vault_owner],
+    ];
+    let signer = &[&seeds[..]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.fee_vault.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.fee_vault_owner.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+    )?;
+
+    Ok(())
+}
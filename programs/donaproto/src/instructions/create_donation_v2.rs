@@ -4,14 +4,29 @@ use raydium_amm_v3::states::PoolState;
 
 use crate::{
     errors::DonationError,
-    states::{AuthorizedClmmPool, CreatorData, DonationData, DonationProtocolData, MAX_IPFS_HASH_LEN},
-    utils::calculate_amount, AUTHORIZED_CLMM_POOL_PREFIX, CREATOR_PREFIX, HOLDING_PREFIX,
+    events::DonationCreated,
+    states::{
+        AuthorizedClmmPool, CreatorData, DonationData, DonationProtocolData, DonationTiers,
+        HardCapMode, MintFee, PerkTierArgs, Versioned, DISCRIMINATOR_LEN, MAX_IPFS_HASH_LEN,
+    },
+    utils::{calculate_amount, is_default_token_mint_0},
+    AUTHORIZED_CLMM_POOL_PREFIX, CREATOR_PREFIX, DONATION_TIERS_PREFIX, HOLDING_PREFIX,
+    MINT_FEE_PREFIX,
 };
 
 #[derive(Accounts)]
 pub struct CreateDonationV2<'info> {
     #[account(init, payer = creator_wallet_address, space = DonationData::LEN)]
-    pub donation_data: Account<'info, DonationData>,
+    pub donation_data: AccountLoader<'info, DonationData>,
+    /// Required when the donation is created with perk tiers
+    #[account(init, payer = creator_wallet_address, space = DISCRIMINATOR_LEN + DonationTiers::INIT_SPACE,
+        seeds = [
+            DONATION_TIERS_PREFIX.as_bytes(),
+            donation_data.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub donation_tiers: Option<Account<'info, DonationTiers>>,
     #[account(
         constraint = donation_protocol.donation_mint == default_donation_mint.key(),
     )]
@@ -24,7 +39,7 @@ pub struct CreateDonationV2<'info> {
     pub holding_wallet: Account<'info, TokenAccount>,
     #[account(
         seeds = [
-            HOLDING_PREFIX.as_bytes(), 
+            HOLDING_PREFIX.as_bytes(),
             donation_data.to_account_info().key.as_ref(),
         ],
         bump,
@@ -63,15 +78,21 @@ pub struct CreateDonationV2<'info> {
         constraint = authorized_clmm_pool.pool_state == pool_state.key(),
     )]
     pub authorized_clmm_pool: Account<'info, AuthorizedClmmPool>,
+    pub pool_state: AccountLoader<'info, PoolState>,
     #[account(
-        constraint = (pool_state.load()?.token_mint_0 == donation_protocol.donation_mint && pool_state.load()?.token_mint_1 == donation_mint.key())
-            || (pool_state.load()?.token_mint_1 == donation_protocol.donation_mint && pool_state.load()?.token_mint_0 == donation_mint.key()),
+        seeds = [
+            MINT_FEE_PREFIX.as_bytes(),
+            donation_protocol.key().as_ref(),
+            donation_mint.key().as_ref(),
+        ],
+        bump,
     )]
-    pub pool_state: AccountLoader<'info, PoolState>,
+    /// CHECK: pda account ["mint_fee", donation_protocol, donation_mint], may be uninitialized
+    pub mint_fee: AccountInfo<'info>,
     #[account(mut)]
     pub creator_wallet_address: Signer<'info>,
     pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>
+    pub rent: Sysvar<'info, Rent>,
 }
 
 pub fn create_donation_v2(
@@ -80,6 +101,9 @@ pub fn create_donation_v2(
     ipfs_hash: String,
     ending_timestamp: u64,
     holding_bump: u8,
+    hard_cap_mode: HardCapMode,
+    tiers: Vec<PerkTierArgs>,
+    refundable: bool,
 ) -> Result<()> {
     let now_timestamp = Clock::get().expect("Time error").unix_timestamp as u64;
     if ending_timestamp <= now_timestamp {
@@ -92,33 +116,82 @@ pub fn create_donation_v2(
         return Err(DonationError::DonationAmountTooLow.into());
     }
 
-    let donation_data = &mut ctx.accounts.donation_data;
+    let mut donation_data = ctx.accounts.donation_data.load_init()?;
     donation_data.amount_collecting = amount;
     donation_data.ending_timestamp = ending_timestamp;
-    donation_data.is_closed = false;
     donation_data.recipient = ctx.accounts.recipient.key();
     donation_data.creator_data = ctx.accounts.creator_data.key();
     donation_data.donation_protocol = ctx.accounts.donation_protocol.key();
     donation_data.holding_wallet = ctx.accounts.holding_wallet.key();
     donation_data.donation_mint = ctx.accounts.donation_mint.key();
     donation_data.holding_bump = holding_bump;
-    donation_data.ipfs_hash = ipfs_hash;
+    donation_data.set_ipfs_hash(&ipfs_hash)?;
+    donation_data.creator_wallet = ctx.accounts.creator_wallet_address.key();
+    donation_data.hard_cap_mode = hard_cap_mode as u8;
+    donation_data.version = DonationData::VERSION;
+    // refunds are opt-in, donors see the choice before giving
+    donation_data.is_refundable = refundable as u8;
+    // perk tiers can not change once donations arrive
+    match ctx.accounts.donation_tiers.as_mut() {
+        Some(donation_tiers) => {
+            donation_tiers.init(
+                ctx.accounts.donation_data.key(),
+                tiers,
+                ctx.bumps.donation_tiers.unwrap(),
+            )?;
+            donation_data.donation_tiers = donation_tiers.key();
+        }
+        None if !tiers.is_empty() => return Err(DonationError::InvalidPerkTiers.into()),
+        None => {}
+    }
+    // fee rate is locked for the whole donation lifetime
+    donation_data.fee_bps = MintFee::resolve_fee_bps(
+        &ctx.accounts.mint_fee,
+        ctx.accounts.donation_protocol.fee_bps,
+    )?;
 
     let default_donation_mint = &ctx.accounts.default_donation_mint;
     let donation_mint = &ctx.accounts.donation_mint;
-    let is_default_token_mint_0 = ctx.accounts.pool_state.load()?.token_mint_0 == ctx.accounts.donation_protocol.donation_mint;
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let is_default_token_mint_0 = match is_default_token_mint_0(
+        &pool_state,
+        &ctx.accounts.donation_protocol.donation_mint,
+        &donation_mint.key(),
+    ) {
+        Some(is_default_token_mint_0) => is_default_token_mint_0,
+        None => return Err(DonationError::InvalidPoolState.into()),
+    };
 
-    let amount = calculate_amount(
+    let amount = match calculate_amount(
         default_donation_mint.decimals,
         donation_mint.decimals,
         amount,
-        ctx.accounts.pool_state.load()?.sqrt_price_x64,
+        pool_state.sqrt_price_x64,
         is_default_token_mint_0,
-    );
+    ) {
+        Some(amount) => amount,
+        None => return Err(DonationError::AmountConversionOverflow.into()),
+    };
 
     let creator_data = &mut ctx.accounts.creator_data;
-    creator_data.total_amount_collecting = creator_data.total_amount_collecting.checked_add(amount).unwrap();
-    creator_data.donations_created_count = creator_data.donations_created_count.checked_add(1).unwrap();
+    creator_data.total_amount_collecting = creator_data
+        .total_amount_collecting
+        .checked_add(amount)
+        .unwrap();
+    creator_data.donations_created_count =
+        creator_data.donations_created_count.checked_add(1).unwrap();
+
+    emit!(DonationCreated {
+        donation_protocol: donation_data.donation_protocol,
+        donation_data: ctx.accounts.donation_data.key(),
+        creator_wallet: donation_data.creator_wallet,
+        recipient: donation_data.recipient,
+        donation_mint: donation_data.donation_mint,
+        amount_collecting: donation_data.amount_collecting,
+        default_amount_collecting: amount,
+        ending_timestamp: donation_data.ending_timestamp,
+        ipfs_hash,
+    });
 
     Ok(())
 }
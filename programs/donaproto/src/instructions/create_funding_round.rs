@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::{
+    errors::DonationError,
+    states::{
+        DonationData, DonationProtocolData, FundingRound, RoundCampaign, DISCRIMINATOR_LEN,
+        MAX_ROUND_CAMPAIGNS,
+    },
+};
+
+pub const MATCHING_VAULT_PREFIX: &str = "matching_vault";
+
+/// Eligible donations are passed as remaining accounts
+#[derive(Accounts)]
+pub struct CreateFundingRound<'info> {
+    #[account(init, payer = authority, space = DISCRIMINATOR_LEN + FundingRound::INIT_SPACE)]
+    pub funding_round: Account<'info, FundingRound>,
+    #[account(init, payer = authority,
+      seeds = [
+        MATCHING_VAULT_PREFIX.as_bytes(),
+        funding_round.key().as_ref(),
+        matching_mint.key().as_ref(),
+      ],
+      bump,
+      token::mint = matching_mint,
+      token::authority = matching_vault_owner,
+    )]
+    pub matching_vault: Account<'info, TokenAccount>,
+    #[account(
+      seeds = [MATCHING_VAULT_PREFIX.as_bytes(), funding_round.key().as_ref()],
+      bump,
+    )]
+    /// CHECK: pda account ["matching_vault", funding_round]
+    pub matching_vault_owner: AccountInfo<'info>,
+    #[account(
+      constraint = donation_protocol.authority == authority.key(),
+    )]
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+    #[account(
+      constraint = matching_mint.key() == donation_protocol.donation_mint,
+    )]
+    pub matching_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Anyone can fund the round by transferring matching mint tokens to the matching vault
+pub fn create_funding_round<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateFundingRound<'info>>,
+    start_timestamp: u64,
+    end_timestamp: u64,
+) -> Result<()> {
+    if start_timestamp >= end_timestamp
+        || ctx.remaining_accounts.is_empty()
+        || ctx.remaining_accounts.len() > MAX_ROUND_CAMPAIGNS
+    {
+        return Err(DonationError::InvalidFundingRound.into());
+    }
+
+    let mut campaigns: Vec<RoundCampaign> = Vec::with_capacity(ctx.remaining_accounts.len());
+    for donation_data_info in ctx.remaining_accounts.iter() {
+        let donation_data_loader = AccountLoader::<DonationData>::try_from(donation_data_info)?;
+        let donation_data = DonationData::load_checked(&donation_data_loader)?;
+        if donation_data.donation_protocol != ctx.accounts.donation_protocol.key()
+            || donation_data.is_closed()
+            // matches would push hard capped donations past their goal
+            || donation_data.is_hard_capped()
+            || campaigns.iter().any(|c| c.donation_data == donation_data_info.key())
+        {
+            return Err(DonationError::InvalidFundingRound.into());
+        }
+        // matches are paid into the donation holding wallet
+        if donation_data.donation_mint != ctx.accounts.matching_mint.key() {
+            return Err(DonationError::InvalidDonationMint.into());
+        }
+        campaigns.push(RoundCampaign {
+            donation_data: donation_data_info.key(),
+            ..Default::default()
+        });
+    }
+
+    let funding_round = &mut ctx.accounts.funding_round;
+    funding_round.donation_protocol = ctx.accounts.donation_protocol.key();
+    funding_round.authority = ctx.accounts.authority.key();
+    funding_round.matching_mint = ctx.accounts.matching_mint.key();
+    funding_round.matching_vault = ctx.accounts.matching_vault.key();
+    funding_round.start_timestamp = start_timestamp;
+    funding_round.end_timestamp = end_timestamp;
+    funding_round.is_finalized = false;
+    funding_round.matching_pool_amount = 0;
+    funding_round.campaigns = campaigns;
+    funding_round.matching_vault_owner_bump = ctx.bumps.matching_vault_owner;
+
+    Ok(())
+}
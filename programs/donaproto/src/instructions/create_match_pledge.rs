@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::{
+    errors::DonationError,
+    states::{DonationData, MatchPledge, DISCRIMINATOR_LEN},
+};
+
+pub const MATCH_PLEDGE_PREFIX: &str = "match_pledge";
+pub const MATCH_PLEDGE_ESCROW_PREFIX: &str = "match_pledge_escrow";
+
+#[derive(Accounts)]
+pub struct CreateMatchPledge<'info> {
+    #[account(init, payer = sponsor_wallet, space = DISCRIMINATOR_LEN + MatchPledge::INIT_SPACE,
+      seeds = [
+        MATCH_PLEDGE_PREFIX.as_bytes(),
+        donation_data.key().as_ref(),
+      ],
+      bump,
+    )]
+    pub match_pledge: Account<'info, MatchPledge>,
+    #[account(init, payer = sponsor_wallet,
+      seeds = [
+        MATCH_PLEDGE_ESCROW_PREFIX.as_bytes(),
+        match_pledge.key().as_ref(),
+      ],
+      bump,
+      token::mint = donation_mint,
+      token::authority = match_pledge,
+    )]
+    pub match_pledge_escrow: Account<'info, TokenAccount>,
+    /// Checked against the other accounts in the handler, which loads it once
+    #[account(mut)]
+    pub donation_data: AccountLoader<'info, DonationData>,
+    pub donation_mint: Account<'info, Mint>,
+    #[account(mut,
+      constraint = sponsor_token_wallet.owner == sponsor_wallet.key(),
+      constraint = sponsor_token_wallet.mint == donation_mint.key(),
+    )]
+    pub sponsor_token_wallet: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub sponsor_wallet: Signer<'info>,
+    /// Campaign creator consents to the pledge, a campaign holds a single one
+    pub creator_wallet: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Escrows `cap` tokens from the sponsor, matched at `ratio_bps` of every donation until `deadline`
+pub fn create_match_pledge(
+    ctx: Context<CreateMatchPledge>,
+    ratio_bps: u16,
+    cap: u64,
+    deadline: u64,
+) -> Result<()> {
+    let now_timestamp = Clock::get().expect("Time error").unix_timestamp as u64;
+    let mut donation_data = DonationData::load_checked_mut(&ctx.accounts.donation_data)?;
+    if ctx.accounts.donation_mint.key() != donation_data.donation_mint {
+        return Err(DonationError::InvalidDonationMint.into());
+    }
+    if ctx.accounts.creator_wallet.key() != donation_data.creator_wallet {
+        return Err(DonationError::DonationAccountMismatch.into());
+    }
+    if donation_data.is_closed() {
+        return Err(DonationError::DonationClosed.into());
+    }
+    if ratio_bps == 0 || cap == 0 || deadline <= now_timestamp {
+        return Err(DonationError::InvalidMatchPledge.into());
+    }
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.sponsor_token_wallet.to_account_info(),
+                to: ctx.accounts.match_pledge_escrow.to_account_info(),
+                authority: ctx.accounts.sponsor_wallet.to_account_info(),
+            },
+        ),
+        cap,
+    )?;
+
+    let match_pledge = &mut ctx.accounts.match_pledge;
+    match_pledge.donation_data = ctx.accounts.donation_data.key();
+    match_pledge.sponsor = ctx.accounts.sponsor_wallet.key();
+    match_pledge.escrow = ctx.accounts.match_pledge_escrow.key();
+    match_pledge.ratio_bps = ratio_bps;
+    match_pledge.cap = cap;
+    match_pledge.total_amount_matched = 0;
+    match_pledge.deadline = deadline;
+    match_pledge.bump = ctx.bumps.match_pledge;
+    donation_data.match_pledge = match_pledge.key();
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::{
+    errors::DonationError,
+    states::{
+        CreatorData, DonationData, PayoutSplit, SplitRecipient, BPS_DENOMINATOR, DISCRIMINATOR_LEN,
+        MAX_SPLIT_RECIPIENTS,
+    },
+    CREATOR_PREFIX,
+};
+
+pub const PAYOUT_SPLIT_PREFIX: &str = "payout_split";
+
+/// Recipient token wallets are passed as remaining accounts,
+/// in the same order as `shares_bps`
+#[derive(Accounts)]
+pub struct CreatePayoutSplit<'info> {
+    #[account(init, payer = creator_wallet_address, space = DISCRIMINATOR_LEN + PayoutSplit::INIT_SPACE,
+      seeds = [
+        PAYOUT_SPLIT_PREFIX.as_bytes(),
+        donation_data.key().as_ref(),
+      ],
+      bump,
+    )]
+    pub payout_split: Account<'info, PayoutSplit>,
+    /// Checked against `creator_data` in the handler, which loads it once
+    #[account(mut)]
+    pub donation_data: AccountLoader<'info, DonationData>,
+    #[account(
+      seeds = [
+        CREATOR_PREFIX.as_bytes(),
+        creator_data.donation_protocol.as_ref(),
+        creator_wallet_address.key().as_ref(),
+      ],
+      bump,
+    )]
+    pub creator_data: Account<'info, CreatorData>,
+    #[account(mut)]
+    pub creator_wallet_address: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn create_payout_split<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePayoutSplit<'info>>,
+    shares_bps: Vec<u16>,
+) -> Result<()> {
+    let mut donation_data = DonationData::load_checked_mut(&ctx.accounts.donation_data)?;
+    if donation_data.creator_data != ctx.accounts.creator_data.key() {
+        return Err(DonationError::DonationAccountMismatch.into());
+    }
+    if donation_data.is_closed() {
+        return Err(DonationError::DonationClosed.into());
+    }
+    // donors gave to the recipient shown at the time of donating
+    if donation_data.total_amount_received > 0 {
+        return Err(DonationError::PayoutSplitLocked.into());
+    }
+    if shares_bps.is_empty()
+        || shares_bps.len() > MAX_SPLIT_RECIPIENTS
+        || shares_bps.len() != ctx.remaining_accounts.len()
+    {
+        return Err(DonationError::InvalidPayoutSplit.into());
+    }
+    let total_bps = shares_bps
+        .iter()
+        .try_fold(0u16, |total, share| total.checked_add(*share));
+    if total_bps != Some(BPS_DENOMINATOR) {
+        return Err(DonationError::InvalidPayoutSplit.into());
+    }
+
+    let mut recipients: Vec<SplitRecipient> = Vec::with_capacity(shares_bps.len());
+    for (recipient_info, share_bps) in ctx.remaining_accounts.iter().zip(shares_bps) {
+        let recipient = Account::<TokenAccount>::try_from(recipient_info)?;
+        if recipient.mint != donation_data.donation_mint {
+            return Err(DonationError::InvalidDonationMint.into());
+        }
+        if share_bps == 0 || recipients.iter().any(|r| r.token_wallet == recipient.key()) {
+            return Err(DonationError::InvalidPayoutSplit.into());
+        }
+        recipients.push(SplitRecipient {
+            token_wallet: recipient.key(),
+            share_bps,
+            total_amount_received: 0,
+        });
+    }
+
+    let payout_split = &mut ctx.accounts.payout_split;
+    payout_split.donation_data = ctx.accounts.donation_data.key();
+    payout_split.recipients = recipients;
+    payout_split.bump = ctx.bumps.payout_split;
+    donation_data.payout_split = payout_split.key();
+
+    Ok(())
+}
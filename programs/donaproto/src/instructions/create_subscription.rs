@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Approve, TokenAccount};
+
+use crate::{
+    errors::DonationError,
+    states::{DonationData, DonationProtocolData, Subscription, DISCRIMINATOR_LEN},
+};
+
+pub const SUBSCRIPTION_PREFIX: &str = "subscription";
+
+#[derive(Accounts)]
+pub struct CreateSubscription<'info> {
+    #[account(init, payer = donor_wallet, space = DISCRIMINATOR_LEN + Subscription::INIT_SPACE,
+      seeds = [
+        SUBSCRIPTION_PREFIX.as_bytes(),
+        donation_data.key().as_ref(),
+        donor_wallet.key().as_ref(),
+      ],
+      bump,
+    )]
+    pub subscription: Account<'info, Subscription>,
+    /// Checked against `donation_protocol` in the handler, which loads it once
+    pub donation_data: AccountLoader<'info, DonationData>,
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+    /// Token account the installments are pulled from, the subscription pda becomes
+    /// its delegate. A token account has a single delegate, so accounts already
+    /// delegated to someone else are rejected: use a dedicated source account instead
+    #[account(mut,
+      constraint = donor_token_wallet.owner == donor_wallet.key(),
+      constraint = donor_token_wallet.mint == donation_protocol.donation_mint,
+    )]
+    pub donor_token_wallet: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub donor_wallet: Signer<'info>,
+    pub token_program: Program<'info, anchor_spl::token::Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_subscription(
+    ctx: Context<CreateSubscription>,
+    amount: u64,
+    period: u64,
+    ending_timestamp: u64,
+) -> Result<()> {
+    let now_timestamp = Clock::get().expect("Time error").unix_timestamp as u64;
+    if ending_timestamp <= now_timestamp {
+        return Err(DonationError::InvalidEndingTimestamp.into());
+    }
+    if amount == 0 {
+        return Err(DonationError::DonationAmountZero.into());
+    }
+    if period == 0 {
+        return Err(DonationError::InvalidSubscriptionPeriod.into());
+    }
+    let donation_data = DonationData::load_checked(&ctx.accounts.donation_data)?;
+    if donation_data.donation_protocol != ctx.accounts.donation_protocol.key() {
+        return Err(DonationError::DonationAccountMismatch.into());
+    }
+    // donate_v2 is not supported, bc installments are credited like donate
+    if donation_data.donation_mint != ctx.accounts.donation_protocol.donation_mint {
+        return Err(DonationError::InvalidDonationMint.into());
+    }
+    if donation_data.is_closed() {
+        return Err(DonationError::DonationClosed.into());
+    }
+    if ctx.accounts.donor_token_wallet.delegate.is_some() {
+        return Err(DonationError::TokenWalletAlreadyDelegated.into());
+    }
+
+    // first installment is due right away
+    let installments_count = (ending_timestamp - now_timestamp)
+        .checked_div(period)
+        .unwrap()
+        .checked_add(1)
+        .unwrap();
+    let allowance = amount.saturating_mul(installments_count);
+
+    token::approve(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Approve {
+                to: ctx.accounts.donor_token_wallet.to_account_info(),
+                delegate: ctx.accounts.subscription.to_account_info(),
+                authority: ctx.accounts.donor_wallet.to_account_info(),
+            },
+        ),
+        allowance,
+    )?;
+
+    let subscription = &mut ctx.accounts.subscription;
+    subscription.donor = ctx.accounts.donor_wallet.key();
+    subscription.donor_token_wallet = ctx.accounts.donor_token_wallet.key();
+    subscription.donation_data = ctx.accounts.donation_data.key();
+    subscription.donation_protocol = ctx.accounts.donation_protocol.key();
+    subscription.amount = amount;
+    subscription.period = period;
+    subscription.next_due = now_timestamp;
+    subscription.ending_timestamp = ending_timestamp;
+    subscription.installments_paid = 0;
+    subscription.bump = ctx.bumps.subscription;
+
+    Ok(())
+}
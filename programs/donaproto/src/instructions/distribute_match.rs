@@ -0,0 +1,203 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::{
+    errors::DonationError,
+    events::MatchDistributed,
+    payouts::{calculate_protocol_fee, transfer_split_payouts},
+    states::{CreatorData, DonationData, DonationProtocolData, FundingRound, PayoutSplit},
+    FEE_VAULT_PREFIX, HOLDING_PREFIX, MATCHING_VAULT_PREFIX,
+};
+
+#[derive(Accounts)]
+pub struct DistributeMatch<'info> {
+    #[account(mut,
+      constraint = funding_round.matching_vault == matching_vault.key(),
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+    /// Checked against the other accounts in the handler, which loads it once
+    #[account(mut)]
+    pub donation_data: AccountLoader<'info, DonationData>,
+    #[account(
+      constraint = funding_round.donation_protocol == donation_protocol.key(),
+    )]
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+    #[account(mut)]
+    pub creator_data: Account<'info, CreatorData>,
+    #[account(mut)]
+    pub holding_wallet: Account<'info, TokenAccount>,
+    /// CHECK: pda account ["holding", donation_data], checked in the handler
+    pub holding_wallet_owner: AccountInfo<'info>,
+    /// Receives the match once the campaign has withdrawn, unless it has a payout split
+    #[account(mut)]
+    pub recipient: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub matching_vault: Account<'info, TokenAccount>,
+    #[account(
+      seeds = [MATCHING_VAULT_PREFIX.as_bytes(), funding_round.key().as_ref()],
+      bump = funding_round.matching_vault_owner_bump,
+    )]
+    /// CHECK: pda account ["matching_vault", funding_round]
+    pub matching_vault_owner: AccountInfo<'info>,
+    /// Required when the donation is closed and its fee is not zero
+    #[account(mut,
+      seeds = [
+        FEE_VAULT_PREFIX.as_bytes(),
+        donation_protocol.key().as_ref(),
+        holding_wallet.mint.as_ref(),
+      ],
+      bump,
+    )]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
+    /// Required when the donation is closed and has a payout split,
+    /// its recipient token wallets are passed as remaining accounts
+    #[account(mut,
+      constraint = payout_split.donation_data == donation_data.key(),
+    )]
+    pub payout_split: Option<Account<'info, PayoutSplit>>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Permissionless, pays the donation match from the matching vault into its holding wallet.
+/// A closed donation pays it out right away the same way `withdraw_funds` does
+pub fn distribute_match<'info>(
+    ctx: Context<'_, '_, '_, 'info, DistributeMatch<'info>>,
+) -> Result<()> {
+    let donation_data_key = ctx.accounts.donation_data.key();
+    let funding_round_key = ctx.accounts.funding_round.key();
+    let funding_round = &mut ctx.accounts.funding_round;
+    let mut donation_data = DonationData::load_checked_mut(&ctx.accounts.donation_data)?;
+    donation_data.check_donation_accounts(
+        &ctx.accounts.donation_protocol.key(),
+        &ctx.accounts.holding_wallet.key(),
+        &ctx.accounts.recipient.key(),
+    )?;
+    if donation_data.creator_data != ctx.accounts.creator_data.key() {
+        return Err(DonationError::DonationAccountMismatch.into());
+    }
+    donation_data
+        .check_holding_wallet_owner(&donation_data_key, &ctx.accounts.holding_wallet_owner.key())?;
+    if !funding_round.is_finalized {
+        return Err(DonationError::RoundNotFinalized.into());
+    }
+
+    let matching_vault_owner_bump = funding_round.matching_vault_owner_bump;
+    let campaign = match funding_round.campaign_mut(&donation_data_key) {
+        Some(campaign) => campaign,
+        None => return Err(DonationError::CampaignNotInRound.into()),
+    };
+    if campaign.is_distributed {
+        return Err(DonationError::MatchAlreadyDistributed.into());
+    }
+    campaign.is_distributed = true;
+    let match_amount = campaign.match_amount;
+
+    if match_amount > 0 {
+        let seeds = &[
+            MATCHING_VAULT_PREFIX.as_bytes(),
+            funding_round_key.as_ref(),
+            &[matching_vault_owner_bump],
+        ];
+        let signer = &[&seeds[..]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.matching_vault.to_account_info(),
+                    to: ctx.accounts.holding_wallet.to_account_info(),
+                    authority: ctx.accounts.matching_vault_owner.to_account_info(),
+                },
+                signer,
+            ),
+            match_amount,
+        )?;
+    }
+
+    donation_data.total_amount_received = donation_data
+        .total_amount_received
+        .checked_add(match_amount)
+        .unwrap();
+    donation_data.total_amount_matched = donation_data
+        .total_amount_matched
+        .checked_add(match_amount)
+        .unwrap();
+
+    let mut fee_amount = 0;
+    if donation_data.is_closed() && match_amount > 0 {
+        fee_amount = calculate_protocol_fee(match_amount, donation_data.fee_bps);
+        let payout_amount = match_amount.checked_sub(fee_amount).unwrap();
+        donation_data.fee_amount = donation_data.fee_amount.checked_add(fee_amount).unwrap();
+
+        let seeds = &[
+            HOLDING_PREFIX.as_bytes(),
+            donation_data_key.as_ref(),
+            &[donation_data.holding_bump],
+        ];
+        let signer = &[&seeds[..]];
+        if fee_amount > 0 {
+            let fee_vault = match ctx.accounts.fee_vault.as_ref() {
+                Some(fee_vault) => fee_vault,
+                None => return Err(DonationError::FeeVaultRequired.into()),
+            };
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.holding_wallet.to_account_info(),
+                        to: fee_vault.to_account_info(),
+                        authority: ctx.accounts.holding_wallet_owner.to_account_info(),
+                    },
+                    signer,
+                ),
+                fee_amount,
+            )?;
+        }
+        if donation_data.payout_split == Pubkey::default() {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.holding_wallet.to_account_info(),
+                        to: ctx.accounts.recipient.to_account_info(),
+                        authority: ctx.accounts.holding_wallet_owner.to_account_info(),
+                    },
+                    signer,
+                ),
+                payout_amount,
+            )?;
+        } else {
+            let payout_split = match ctx.accounts.payout_split.as_mut() {
+                Some(payout_split) if payout_split.key() == donation_data.payout_split => {
+                    payout_split
+                }
+                _ => return Err(DonationError::InvalidPayoutSplit.into()),
+            };
+            transfer_split_payouts(
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.holding_wallet.to_account_info(),
+                ctx.accounts.holding_wallet_owner.to_account_info(),
+                payout_split,
+                ctx.remaining_accounts,
+                signer,
+                payout_amount,
+            )?;
+        }
+
+        let creator_data = &mut ctx.accounts.creator_data;
+        creator_data.total_amount_received = creator_data
+            .total_amount_received
+            .checked_add(payout_amount)
+            .unwrap();
+    }
+
+    emit!(MatchDistributed {
+        donation_protocol: donation_data.donation_protocol,
+        funding_round: funding_round_key,
+        donation_data: donation_data_key,
+        amount: match_amount,
+        fee_amount,
+        is_paid_out: donation_data.is_closed(),
+    });
+
+    Ok(())
+}
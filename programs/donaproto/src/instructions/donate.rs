@@ -1,29 +1,64 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, TokenAccount, Transfer};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    memo::Memo,
+    token::{self, Mint, TokenAccount, Transfer},
+};
 
 use crate::{
+    donations::{accept_donation, credit_donations, CampaignDonation, Contribution},
     errors::DonationError,
-    states::{ContributorData, DonationData, DonationProtocolData},
+    receipts::{issue_donation_receipt, ReceiptAccounts, ReceiptDonation},
+    states::{
+        ContributorData, DonationData, DonationProtocolData, DonationReceipt, DonationTiers,
+        FundingRound, MatchPledge, RewardVesting, DISCRIMINATOR_LEN,
+    },
     CONTRIBUTOR_PREFIX, TREASURY_PREFIX,
 };
 
+pub const DONATION_RECEIPT_PREFIX: &str = "donation_receipt";
+pub const RECEIPT_METADATA_PREFIX: &str = "receipt_metadata";
+pub const RECEIPT_MINT_AUTHORITY_PREFIX: &str = "receipt_mint_authority";
+
 #[derive(Accounts)]
 pub struct Donate<'info> {
-    #[account(mut,
-      constraint = donation_data.donation_protocol == donation_protocol.key(),
-      constraint = donation_data.holding_wallet == holding_wallet.key(),
-    )]
-    pub donation_data: Account<'info, DonationData>,
-    #[account(mut,
-      constraint = contributor_data.donation_protocol == donation_protocol.key(),
+    /// Checked against the other accounts in the handler, which loads it once
+    #[account(mut)]
+    pub donation_data: AccountLoader<'info, DonationData>,
+    #[account(init_if_needed, payer = user_wallet, space = ContributorData::LEN,
       seeds = [
         CONTRIBUTOR_PREFIX.as_bytes(),
         donation_protocol.to_account_info().key.as_ref(),
         user_wallet.to_account_info().key.as_ref(),
       ],
-      bump = contributor_data.bump,
+      bump,
     )]
     pub contributor_data: Account<'info, ContributorData>,
+    #[account(init_if_needed, payer = user_wallet, space = DISCRIMINATOR_LEN + DonationReceipt::INIT_SPACE,
+      seeds = [
+        DONATION_RECEIPT_PREFIX.as_bytes(),
+        donation_data.key().as_ref(),
+        user_wallet.key().as_ref(),
+      ],
+      bump,
+    )]
+    pub donation_receipt: Account<'info, DonationReceipt>,
+    /// Set when the donation counts towards a funding round
+    #[account(mut)]
+    pub funding_round: Option<Account<'info, FundingRound>>,
+    /// Required when the donation has a sponsor match pledge
+    #[account(mut,
+      constraint = match_pledge.donation_data == donation_data.key(),
+    )]
+    pub match_pledge: Option<Account<'info, MatchPledge>>,
+    #[account(mut)]
+    pub match_pledge_escrow: Option<Account<'info, TokenAccount>>,
+    /// Required when the donation has perk tiers
+    #[account(mut,
+      constraint = donation_tiers.donation_data == donation_data.key(),
+    )]
+    pub donation_tiers: Option<Account<'info, DonationTiers>>,
+    #[account(mut)]
     pub donation_protocol: Account<'info, DonationProtocolData>,
 
     #[account(mut,
@@ -47,10 +82,17 @@ pub struct Donate<'info> {
     )]
     /// CHECK: pda account ["treasury", donation_protocol_data]
     pub reward_treasury_owner: AccountInfo<'info>,
+    /// Required when the protocol vests rewards
+    #[account(mut,
+      constraint = reward_vesting.donation_protocol == donation_protocol.key(),
+      constraint = reward_vesting.contributor == user_wallet.key(),
+    )]
+    pub reward_vesting: Option<Account<'info, RewardVesting>>,
 
+    /// Campaign recipient, donations of its current owner are self-funded
+    pub recipient: Account<'info, TokenAccount>,
     #[account(mut,
       constraint = holding_wallet.mint == donation_mint.key(),
-      constraint = holding_wallet.key() == donation_data.holding_wallet,
     )]
     pub holding_wallet: Account<'info, TokenAccount>,
     #[account(
@@ -62,21 +104,50 @@ pub struct Donate<'info> {
     )]
     pub reward_mint: Account<'info, Mint>,
 
+    /// Set to issue a one-of-one receipt token for the donation, a new keypair co-signing
+    /// the transaction. Every other receipt account is then required
+    #[account(init, payer = user_wallet,
+      mint::decimals = 0,
+      mint::authority = receipt_mint_authority,
+      mint::freeze_authority = receipt_mint_authority,
+    )]
+    pub receipt_mint: Option<Box<Account<'info, Mint>>>,
+    #[account(init, payer = user_wallet,
+      associated_token::mint = receipt_mint,
+      associated_token::authority = user_wallet,
+    )]
+    pub receipt_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut)]
+    /// CHECK: pda account ["receipt_metadata", receipt_mint], created by the handler
+    pub receipt_metadata: Option<AccountInfo<'info>>,
+    #[account(
+      seeds = [
+        RECEIPT_MINT_AUTHORITY_PREFIX.as_bytes(),
+        donation_protocol.key().as_ref(),
+      ],
+      bump,
+    )]
+    /// CHECK: pda account ["receipt_mint_authority", donation_protocol_data]
+    pub receipt_mint_authority: Option<AccountInfo<'info>>,
+
     #[account(mut)]
     pub user_wallet: Signer<'info>,
     pub token_program: Program<'info, anchor_spl::token::Token>,
+    pub system_program: Program<'info, System>,
+    /// Required with `receipt_mint`
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    /// Required with `receipt_mint`
+    pub memo_program: Option<Program<'info, Memo>>,
 }
 
 pub fn donate(ctx: Context<Donate>, amount: u64) -> Result<()> {
-    let donation_data = &mut ctx.accounts.donation_data;
-
-    if donation_data.is_closed {
-        return Err(DonationError::DonationClosed.into());
-    }
-
-    if amount == 0 {
-        return Err(DonationError::DonationAmountZero.into());
-    }
+    let mut donation_data = DonationData::load_checked_mut(&ctx.accounts.donation_data)?;
+    donation_data.check_donation_accounts(
+        &ctx.accounts.donation_protocol.key(),
+        &ctx.accounts.holding_wallet.key(),
+        &ctx.accounts.recipient.key(),
+    )?;
+    let amount = accept_donation(&donation_data, amount)?;
 
     // use donate_v2 if donation mint is different
     // bc fair calculation of rewards is linkend with donation protocol mint
@@ -97,61 +168,58 @@ pub fn donate(ctx: Context<Donate>, amount: u64) -> Result<()> {
         amount,
     )?;
 
-    let contributor_data = &mut ctx.accounts.contributor_data;
-    donation_data.total_amount_received = donation_data
-        .total_amount_received
-        .checked_add(amount)
-        .unwrap();
-    contributor_data.total_amount_donated = contributor_data
-        .total_amount_donated
-        .checked_add(amount)
-        .unwrap();
-    contributor_data.donations_count = contributor_data.donations_count.checked_add(1).unwrap();
-    let donation_protocol = &ctx.accounts.donation_protocol;
-
-    if amount >= donation_protocol.min_amount_to_earn {
-        // TODO: add calculation for reward amount
-        let reward_treasury_balance = ctx.accounts.reward_treasury.amount;
-        let mut reward_amount = amount;
-        if reward_amount > reward_treasury_balance {
-            reward_amount = reward_treasury_balance.checked_div(100).unwrap();
-        }
-        // END
-
-        // Transfer amount of tokens from reward treasury wallet to user
-        let seeds = &[
-            TREASURY_PREFIX.as_bytes(),
-            ctx.accounts
-                .donation_protocol
-                .to_account_info()
-                .key
-                .as_ref(),
-            &[ctx.accounts.donation_protocol.treasury_owner_bump],
-        ];
-        let signer = &[&seeds[..]];
-        if reward_amount > 0 {
-            token::transfer(
-                CpiContext::new_with_signer(
-                    ctx.accounts.token_program.to_account_info().clone(),
-                    Transfer {
-                        from: ctx.accounts.reward_treasury.to_account_info().clone(),
-                        to: ctx
-                            .accounts
-                            .user_reward_token_wallet
-                            .to_account_info()
-                            .clone(),
-                        authority: ctx.accounts.reward_treasury_owner.to_account_info().clone(),
-                    },
-                    signer,
-                ),
-                reward_amount,
-            )?;
-            contributor_data.total_amount_earned = contributor_data
-                .total_amount_earned
-                .checked_add(reward_amount)
-                .unwrap();
-        }
-    }
+    credit_donations(
+        Contribution {
+            donation_protocol: &mut ctx.accounts.donation_protocol,
+            contributor_data: &mut ctx.accounts.contributor_data,
+            contributor_data_bump: ctx.bumps.contributor_data,
+            contributor: ctx.accounts.user_wallet.key(),
+            donors: &[ctx.accounts.user_wallet.key()],
+            is_gift: false,
+            funding_round: ctx.accounts.funding_round.as_mut(),
+            reward_vesting: ctx.accounts.reward_vesting.as_mut(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            reward_treasury: &ctx.accounts.reward_treasury,
+            reward_treasury_owner: ctx.accounts.reward_treasury_owner.to_account_info(),
+            reward_destination: ctx.accounts.user_reward_token_wallet.to_account_info(),
+        },
+        &mut [CampaignDonation {
+            donation_data: &mut donation_data,
+            donation_data_key: ctx.accounts.donation_data.key(),
+            holding_wallet: ctx.accounts.holding_wallet.to_account_info(),
+            recipient_owner: ctx.accounts.recipient.owner,
+            donation_receipt: &mut ctx.accounts.donation_receipt,
+            donation_receipt_bump: ctx.bumps.donation_receipt,
+            match_pledge: ctx.accounts.match_pledge.as_mut(),
+            match_pledge_escrow: ctx.accounts.match_pledge_escrow.as_ref(),
+            donation_tiers: ctx.accounts.donation_tiers.as_mut(),
+            amount,
+            default_amount: amount,
+        }],
+    )?;
 
-    Ok(())
+    issue_donation_receipt(
+        ReceiptAccounts {
+            receipt_mint: ctx.accounts.receipt_mint.as_deref(),
+            receipt_token_account: ctx.accounts.receipt_token_account.as_deref(),
+            receipt_metadata: ctx.accounts.receipt_metadata.as_ref(),
+            receipt_mint_authority: ctx
+                .accounts
+                .receipt_mint_authority
+                .as_ref()
+                .zip(ctx.bumps.receipt_mint_authority),
+            memo_program: ctx.accounts.memo_program.as_ref(),
+            payer: ctx.accounts.user_wallet.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+        ReceiptDonation {
+            donation_protocol: donation_data.donation_protocol,
+            donation_data: ctx.accounts.donation_data.key(),
+            donation_mint: donation_data.donation_mint,
+            donor: ctx.accounts.user_wallet.key(),
+            amount,
+        },
+        &mut ctx.accounts.donation_receipt,
+    )
 }
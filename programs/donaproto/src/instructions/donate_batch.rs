@@ -0,0 +1,322 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, CreateAccount},
+};
+use anchor_spl::token::{self, Mint, TokenAccount, Transfer};
+
+use crate::{
+    donations::{accept_donation, credit_donations, CampaignDonation, Contribution},
+    errors::DonationError,
+    states::{
+        ContributorData, DonationData, DonationProtocolData, DonationReceipt, DonationTiers,
+        FundingRound, MatchPledge, RewardVesting, DISCRIMINATOR_LEN,
+    },
+    CONTRIBUTOR_PREFIX, DONATION_RECEIPT_PREFIX, TREASURY_PREFIX,
+};
+
+pub const MAX_BATCH_DONATIONS: usize = 10;
+
+/// Campaigns are passed as remaining accounts, for every amount its writable `DonationData`,
+/// holding wallet, recipient and donation receipt pda, followed by its match pledge
+/// and pledge escrow when the campaign has a sponsor match pledge,
+/// then by its donation tiers when the campaign has perk tiers
+#[derive(Accounts)]
+pub struct DonateBatch<'info> {
+    #[account(init_if_needed, payer = user_wallet, space = ContributorData::LEN,
+      seeds = [
+        CONTRIBUTOR_PREFIX.as_bytes(),
+        donation_protocol.to_account_info().key.as_ref(),
+        user_wallet.to_account_info().key.as_ref(),
+      ],
+      bump,
+    )]
+    pub contributor_data: Account<'info, ContributorData>,
+    /// Set when the donations count towards a funding round, every campaign must be in it
+    #[account(mut)]
+    pub funding_round: Option<Account<'info, FundingRound>>,
+    #[account(mut)]
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+
+    #[account(mut,
+      constraint = user_token_wallet.owner == *user_wallet.key,
+      constraint = user_token_wallet.mint == donation_mint.key(),
+    )]
+    pub user_token_wallet: Account<'info, TokenAccount>,
+    #[account(mut,
+      constraint = user_reward_token_wallet.owner == *user_wallet.key,
+      constraint = user_reward_token_wallet.mint == reward_mint.key(),
+    )]
+    pub user_reward_token_wallet: Account<'info, TokenAccount>,
+    #[account(mut,
+      constraint = donation_protocol.treasury_mint.key() == reward_mint.key(),
+      constraint = reward_treasury.key() == donation_protocol.treasury.key(),
+    )]
+    pub reward_treasury: Account<'info, TokenAccount>,
+    #[account(
+      seeds = [TREASURY_PREFIX.as_bytes(), donation_protocol.key().as_ref()],
+      bump = donation_protocol.treasury_owner_bump,
+    )]
+    /// CHECK: pda account ["treasury", donation_protocol_data]
+    pub reward_treasury_owner: AccountInfo<'info>,
+    /// Required when the protocol vests rewards
+    #[account(mut,
+      constraint = reward_vesting.donation_protocol == donation_protocol.key(),
+      constraint = reward_vesting.contributor == user_wallet.key(),
+    )]
+    pub reward_vesting: Option<Account<'info, RewardVesting>>,
+
+    #[account(
+      constraint = donation_protocol.donation_mint.key() == donation_mint.key(),
+    )]
+    pub donation_mint: Account<'info, Mint>,
+    #[account(
+      constraint = donation_protocol.treasury_mint.key() == reward_mint.key(),
+    )]
+    pub reward_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub user_wallet: Signer<'info>,
+    pub token_program: Program<'info, anchor_spl::token::Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts of one campaign of the batch taken from the remaining accounts
+struct BatchCampaign<'info> {
+    holding_wallet: AccountInfo<'info>,
+    recipient_owner: Pubkey,
+    donation_receipt: Account<'info, DonationReceipt>,
+    donation_receipt_bump: u8,
+    match_pledge: Option<Account<'info, MatchPledge>>,
+    match_pledge_escrow: Option<Account<'info, TokenAccount>>,
+    donation_tiers: Option<Account<'info, DonationTiers>>,
+    amount: u64,
+}
+
+/// Splits a gift across several campaigns in the protocol donation mint.
+/// Every campaign gets its receipt, round contribution and sponsor match like with `donate`,
+/// the contributor is credited once and the reward is computed on the eligible total.
+pub fn donate_batch<'info>(
+    ctx: Context<'_, '_, '_, 'info, DonateBatch<'info>>,
+    amounts: Vec<u64>,
+) -> Result<()> {
+    if amounts.is_empty() || amounts.len() > MAX_BATCH_DONATIONS {
+        return Err(DonationError::InvalidDonationBatch.into());
+    }
+
+    let mut remaining_accounts = ctx.remaining_accounts.iter();
+    let mut donation_data_loaders: Vec<AccountLoader<'info, DonationData>> =
+        Vec::with_capacity(amounts.len());
+    let mut campaigns: Vec<BatchCampaign<'info>> = Vec::with_capacity(amounts.len());
+    for amount in amounts {
+        let (donation_data_info, holding_wallet_info, recipient_info, donation_receipt_info) =
+            match (
+                remaining_accounts.next(),
+                remaining_accounts.next(),
+                remaining_accounts.next(),
+                remaining_accounts.next(),
+            ) {
+                (
+                    Some(donation_data),
+                    Some(holding_wallet),
+                    Some(recipient),
+                    Some(donation_receipt),
+                ) => (donation_data, holding_wallet, recipient, donation_receipt),
+                _ => return Err(DonationError::InvalidDonationBatch.into()),
+            };
+        if !donation_data_info.is_writable
+            || !holding_wallet_info.is_writable
+            || donation_data_loaders
+                .iter()
+                .any(|loader| loader.key() == donation_data_info.key())
+        {
+            return Err(DonationError::InvalidDonationBatch.into());
+        }
+
+        // checks the owner and the discriminator, the size is checked before the data is mapped
+        let donation_data_loader = AccountLoader::<DonationData>::try_from(donation_data_info)?;
+        let holding_wallet = Account::<TokenAccount>::try_from(holding_wallet_info)?;
+        let recipient = Account::<TokenAccount>::try_from(recipient_info)?;
+
+        let (amount, has_match_pledge, has_donation_tiers) = {
+            let donation_data = DonationData::load_checked(&donation_data_loader)?;
+            if donation_data.donation_protocol != ctx.accounts.donation_protocol.key()
+                || donation_data.holding_wallet != holding_wallet.key()
+                || donation_data.recipient != recipient.key()
+            {
+                return Err(DonationError::InvalidDonationBatch.into());
+            }
+            // use donate_v2 for campaigns in other mints
+            if donation_data.donation_mint != ctx.accounts.donation_mint.key()
+                || holding_wallet.mint != ctx.accounts.donation_mint.key()
+            {
+                return Err(DonationError::InvalidDonationMint.into());
+            }
+            (
+                accept_donation(&donation_data, amount)?,
+                donation_data.match_pledge != Pubkey::default(),
+                donation_data.donation_tiers != Pubkey::default(),
+            )
+        };
+        // pledged campaigns are followed by their pledge and its escrow
+        let (match_pledge, match_pledge_escrow) = if has_match_pledge {
+            match (remaining_accounts.next(), remaining_accounts.next()) {
+                (Some(match_pledge), Some(match_pledge_escrow))
+                    if match_pledge.is_writable && match_pledge_escrow.is_writable =>
+                {
+                    (
+                        Some(Account::<MatchPledge>::try_from(match_pledge)?),
+                        Some(Account::<TokenAccount>::try_from(match_pledge_escrow)?),
+                    )
+                }
+                _ => return Err(DonationError::MatchPledgeRequired.into()),
+            }
+        } else {
+            (None, None)
+        };
+        let donation_tiers = if has_donation_tiers {
+            match remaining_accounts.next() {
+                Some(donation_tiers) if donation_tiers.is_writable => {
+                    Some(Account::<DonationTiers>::try_from(donation_tiers)?)
+                }
+                _ => return Err(DonationError::DonationTiersRequired.into()),
+            }
+        } else {
+            None
+        };
+        let (donation_receipt, donation_receipt_bump) = load_donation_receipt(
+            ctx.accounts,
+            donation_receipt_info,
+            &donation_data_info.key(),
+        )?;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_token_wallet.to_account_info(),
+                    to: holding_wallet_info.clone(),
+                    authority: ctx.accounts.user_wallet.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        donation_data_loaders.push(donation_data_loader);
+        campaigns.push(BatchCampaign {
+            holding_wallet: holding_wallet_info.clone(),
+            recipient_owner: recipient.owner,
+            donation_receipt,
+            donation_receipt_bump,
+            match_pledge,
+            match_pledge_escrow,
+            donation_tiers,
+            amount,
+        });
+    }
+    if remaining_accounts.next().is_some() {
+        return Err(DonationError::InvalidDonationBatch.into());
+    }
+
+    {
+        let mut loaded_donation_data = donation_data_loaders
+            .iter()
+            .map(DonationData::load_checked_mut)
+            .collect::<Result<Vec<_>>>()?;
+        let mut campaign_donations: Vec<CampaignDonation> = campaigns
+            .iter_mut()
+            .zip(loaded_donation_data.iter_mut())
+            .zip(donation_data_loaders.iter())
+            .map(|((campaign, donation_data), loader)| CampaignDonation {
+                donation_data: &mut **donation_data,
+                donation_data_key: loader.key(),
+                holding_wallet: campaign.holding_wallet.clone(),
+                recipient_owner: campaign.recipient_owner,
+                donation_receipt: &mut campaign.donation_receipt,
+                donation_receipt_bump: campaign.donation_receipt_bump,
+                match_pledge: campaign.match_pledge.as_mut(),
+                match_pledge_escrow: campaign.match_pledge_escrow.as_ref(),
+                donation_tiers: campaign.donation_tiers.as_mut(),
+                amount: campaign.amount,
+                default_amount: campaign.amount,
+            })
+            .collect();
+
+        credit_donations(
+            Contribution {
+                donation_protocol: &mut ctx.accounts.donation_protocol,
+                contributor_data: &mut ctx.accounts.contributor_data,
+                contributor_data_bump: ctx.bumps.contributor_data,
+                contributor: ctx.accounts.user_wallet.key(),
+                donors: &[ctx.accounts.user_wallet.key()],
+                is_gift: false,
+                funding_round: ctx.accounts.funding_round.as_mut(),
+                reward_vesting: ctx.accounts.reward_vesting.as_mut(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                reward_treasury: &ctx.accounts.reward_treasury,
+                reward_treasury_owner: ctx.accounts.reward_treasury_owner.to_account_info(),
+                reward_destination: ctx.accounts.user_reward_token_wallet.to_account_info(),
+            },
+            &mut campaign_donations,
+        )?;
+    }
+
+    // anchor only writes back the accounts of the instruction context
+    for campaign in campaigns.iter() {
+        campaign.donation_receipt.exit(&crate::ID)?;
+        if let Some(match_pledge) = campaign.match_pledge.as_ref() {
+            match_pledge.exit(&crate::ID)?;
+        }
+        if let Some(donation_tiers) = campaign.donation_tiers.as_ref() {
+            donation_tiers.exit(&crate::ID)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Receipt pda of the contributor for `donation_data`, created on their first donation
+fn load_donation_receipt<'info>(
+    accounts: &DonateBatch<'info>,
+    donation_receipt_info: &AccountInfo<'info>,
+    donation_data: &Pubkey,
+) -> Result<(Account<'info, DonationReceipt>, u8)> {
+    let user_wallet = accounts.user_wallet.key();
+    let (donation_receipt_key, bump) = Pubkey::find_program_address(
+        &[
+            DONATION_RECEIPT_PREFIX.as_bytes(),
+            donation_data.as_ref(),
+            user_wallet.as_ref(),
+        ],
+        &crate::ID,
+    );
+    if donation_receipt_info.key() != donation_receipt_key || !donation_receipt_info.is_writable {
+        return Err(DonationError::InvalidDonationBatch.into());
+    }
+    if donation_receipt_info.owner == &crate::ID {
+        return Ok((Account::try_from(donation_receipt_info)?, bump));
+    }
+
+    let space = DISCRIMINATOR_LEN + DonationReceipt::INIT_SPACE;
+    let seeds = &[
+        DONATION_RECEIPT_PREFIX.as_bytes(),
+        donation_data.as_ref(),
+        user_wallet.as_ref(),
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: accounts.user_wallet.to_account_info(),
+                to: donation_receipt_info.clone(),
+            },
+            signer,
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &crate::ID,
+    )?;
+
+    Ok((Account::try_from_unchecked(donation_receipt_info)?, bump))
+}
@@ -0,0 +1,191 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, TokenAccount, Transfer};
+
+use crate::{
+    donations::{accept_donation, credit_donations, CampaignDonation, Contribution},
+    errors::DonationError,
+    states::{
+        ContributorData, DonationData, DonationProtocolData, DonationReceipt, DonationTiers,
+        FundingRound, MatchPledge, RewardVesting, Subscription, DISCRIMINATOR_LEN,
+    },
+    CONTRIBUTOR_PREFIX, DONATION_RECEIPT_PREFIX, SUBSCRIPTION_PREFIX, TREASURY_PREFIX,
+};
+
+/// Permissionless crank pulling one due installment of a subscription
+#[derive(Accounts)]
+pub struct ExecuteSubscription<'info> {
+    #[account(mut,
+      constraint = subscription.donation_protocol == donation_protocol.key(),
+      constraint = subscription.donation_data == donation_data.key(),
+      constraint = subscription.donor_token_wallet == donor_token_wallet.key(),
+      seeds = [
+        SUBSCRIPTION_PREFIX.as_bytes(),
+        donation_data.key().as_ref(),
+        subscription.donor.as_ref(),
+      ],
+      bump = subscription.bump,
+    )]
+    pub subscription: Account<'info, Subscription>,
+    /// Checked against the other accounts in the handler, which loads it once
+    #[account(mut)]
+    pub donation_data: AccountLoader<'info, DonationData>,
+    #[account(init_if_needed, payer = payer, space = ContributorData::LEN,
+      seeds = [
+        CONTRIBUTOR_PREFIX.as_bytes(),
+        donation_protocol.to_account_info().key.as_ref(),
+        subscription.donor.as_ref(),
+      ],
+      bump,
+    )]
+    pub contributor_data: Account<'info, ContributorData>,
+    #[account(init_if_needed, payer = payer, space = DISCRIMINATOR_LEN + DonationReceipt::INIT_SPACE,
+      seeds = [
+        DONATION_RECEIPT_PREFIX.as_bytes(),
+        donation_data.key().as_ref(),
+        subscription.donor.as_ref(),
+      ],
+      bump,
+    )]
+    pub donation_receipt: Account<'info, DonationReceipt>,
+    /// Set when the donation counts towards a funding round
+    #[account(mut)]
+    pub funding_round: Option<Account<'info, FundingRound>>,
+    /// Required when the donation has a sponsor match pledge
+    #[account(mut,
+      constraint = match_pledge.donation_data == donation_data.key(),
+    )]
+    pub match_pledge: Option<Account<'info, MatchPledge>>,
+    #[account(mut)]
+    pub match_pledge_escrow: Option<Account<'info, TokenAccount>>,
+    /// Required when the donation has perk tiers
+    #[account(mut,
+      constraint = donation_tiers.donation_data == donation_data.key(),
+    )]
+    pub donation_tiers: Option<Account<'info, DonationTiers>>,
+    #[account(mut)]
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+
+    #[account(mut,
+      constraint = donor_token_wallet.mint == donation_mint.key(),
+    )]
+    pub donor_token_wallet: Account<'info, TokenAccount>,
+    #[account(mut,
+      constraint = donor_reward_token_wallet.owner == subscription.donor,
+      constraint = donor_reward_token_wallet.mint == reward_mint.key(),
+    )]
+    pub donor_reward_token_wallet: Account<'info, TokenAccount>,
+    #[account(mut,
+      constraint = donation_protocol.treasury_mint.key() == reward_mint.key(),
+      constraint = reward_treasury.key() == donation_protocol.treasury.key(),
+    )]
+    pub reward_treasury: Account<'info, TokenAccount>,
+    #[account(
+      seeds = [TREASURY_PREFIX.as_bytes(), donation_protocol.key().as_ref()],
+      bump = donation_protocol.treasury_owner_bump,
+    )]
+    /// CHECK: pda account ["treasury", donation_protocol_data]
+    pub reward_treasury_owner: AccountInfo<'info>,
+    /// Required when the protocol vests rewards
+    #[account(mut,
+      constraint = reward_vesting.donation_protocol == donation_protocol.key(),
+      constraint = reward_vesting.contributor == subscription.donor,
+    )]
+    pub reward_vesting: Option<Account<'info, RewardVesting>>,
+
+    /// Campaign recipient, donations of its current owner are self-funded
+    pub recipient: Account<'info, TokenAccount>,
+    #[account(mut,
+      constraint = holding_wallet.mint == donation_mint.key(),
+    )]
+    pub holding_wallet: Account<'info, TokenAccount>,
+    #[account(
+      constraint = donation_protocol.donation_mint.key() == donation_mint.key(),
+    )]
+    pub donation_mint: Account<'info, Mint>,
+    #[account(
+      constraint = donation_protocol.treasury_mint.key() == reward_mint.key(),
+    )]
+    pub reward_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, anchor_spl::token::Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn execute_subscription(ctx: Context<ExecuteSubscription>) -> Result<()> {
+    let now_timestamp = Clock::get().expect("Time error").unix_timestamp as u64;
+    let subscription = &mut ctx.accounts.subscription;
+    let mut donation_data = DonationData::load_checked_mut(&ctx.accounts.donation_data)?;
+    donation_data.check_donation_accounts(
+        &ctx.accounts.donation_protocol.key(),
+        &ctx.accounts.holding_wallet.key(),
+        &ctx.accounts.recipient.key(),
+    )?;
+
+    let amount = accept_donation(&donation_data, subscription.amount)?;
+    if subscription.next_due > subscription.ending_timestamp {
+        return Err(DonationError::SubscriptionEnded.into());
+    }
+    if subscription.next_due > now_timestamp {
+        return Err(DonationError::SubscriptionNotDue.into());
+    }
+
+    // Transfer installment from donor to donation holding wallet, signed by subscription pda
+    let seeds = &[
+        SUBSCRIPTION_PREFIX.as_bytes(),
+        subscription.donation_data.as_ref(),
+        subscription.donor.as_ref(),
+        &[subscription.bump],
+    ];
+    let signer = &[&seeds[..]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.donor_token_wallet.to_account_info(),
+                to: ctx.accounts.holding_wallet.to_account_info(),
+                authority: subscription.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+    )?;
+
+    subscription.next_due = subscription
+        .next_due
+        .checked_add(subscription.period)
+        .unwrap();
+    subscription.installments_paid = subscription.installments_paid.checked_add(1).unwrap();
+    let donor = subscription.donor;
+
+    credit_donations(
+        Contribution {
+            donation_protocol: &mut ctx.accounts.donation_protocol,
+            contributor_data: &mut ctx.accounts.contributor_data,
+            contributor_data_bump: ctx.bumps.contributor_data,
+            contributor: donor,
+            donors: &[donor],
+            is_gift: false,
+            funding_round: ctx.accounts.funding_round.as_mut(),
+            reward_vesting: ctx.accounts.reward_vesting.as_mut(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            reward_treasury: &ctx.accounts.reward_treasury,
+            reward_treasury_owner: ctx.accounts.reward_treasury_owner.to_account_info(),
+            reward_destination: ctx.accounts.donor_reward_token_wallet.to_account_info(),
+        },
+        &mut [CampaignDonation {
+            donation_data: &mut donation_data,
+            donation_data_key: ctx.accounts.donation_data.key(),
+            holding_wallet: ctx.accounts.holding_wallet.to_account_info(),
+            recipient_owner: ctx.accounts.recipient.owner,
+            donation_receipt: &mut ctx.accounts.donation_receipt,
+            donation_receipt_bump: ctx.bumps.donation_receipt,
+            match_pledge: ctx.accounts.match_pledge.as_mut(),
+            match_pledge_escrow: ctx.accounts.match_pledge_escrow.as_ref(),
+            donation_tiers: ctx.accounts.donation_tiers.as_mut(),
+            amount,
+            default_amount: amount,
+        }],
+    )
+}
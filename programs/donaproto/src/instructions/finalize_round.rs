@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::{errors::DonationError, states::FundingRound};
+
+#[derive(Accounts)]
+pub struct FinalizeRound<'info> {
+    #[account(mut,
+      constraint = funding_round.matching_vault == matching_vault.key(),
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+    pub matching_vault: Account<'info, TokenAccount>,
+}
+
+/// Permissionless, locks the matching pool and computes quadratic matches once the round is over
+pub fn finalize_round(ctx: Context<FinalizeRound>) -> Result<()> {
+    let now_timestamp = Clock::get().expect("Time error").unix_timestamp as u64;
+    let funding_round = &mut ctx.accounts.funding_round;
+    if funding_round.is_finalized {
+        return Err(DonationError::RoundAlreadyFinalized.into());
+    }
+    if now_timestamp < funding_round.end_timestamp {
+        return Err(DonationError::RoundNotEnded.into());
+    }
+
+    funding_round.calculate_matches(ctx.accounts.matching_vault.amount);
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::{errors::DonationError, events::TreasuryFunded, states::DonationProtocolData};
+
+#[derive(Accounts)]
+pub struct FundTreasury<'info> {
+    #[account(mut,
+      constraint = treasury.key() == donation_protocol.treasury,
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    #[account(mut,
+      constraint = source_token_wallet.owner == funder.key(),
+      constraint = source_token_wallet.mint == donation_protocol.treasury_mint,
+    )]
+    pub source_token_wallet: Account<'info, TokenAccount>,
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+    pub funder: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Permissionless top up of the reward treasury
+pub fn fund_treasury(ctx: Context<FundTreasury>, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return Err(DonationError::TreasuryAmountZero.into());
+    }
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.source_token_wallet.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+                authority: ctx.accounts.funder.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    emit!(TreasuryFunded {
+        donation_protocol: ctx.accounts.donation_protocol.key(),
+        treasury: ctx.accounts.treasury.key(),
+        source: ctx.accounts.source_token_wallet.key(),
+        amount,
+    });
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 
-use crate::states::{ContributorData, DonationProtocolData};
+use crate::states::{ContributorData, DonationProtocolData, Versioned};
 
 pub const CONTRIBUTOR_PREFIX: &str = "contributor";
 
@@ -30,8 +30,11 @@ pub fn initialize_contributor(ctx: Context<InitializeContributor>, bump: u8) ->
     contributor_data.total_amount_donated = 0;
     contributor_data.total_amount_earned = 0;
     contributor_data.donations_count = 0;
+    contributor_data.total_amount_vested = 0;
+    contributor_data.total_amount_claimed = 0;
     contributor_data.donation_protocol = ctx.accounts.donation_protocol.key();
     contributor_data.bump = bump;
+    contributor_data.version = ContributorData::VERSION;
 
     Ok(())
 }
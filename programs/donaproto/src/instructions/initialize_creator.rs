@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 
-use crate::states::{CreatorData, DonationProtocolData};
+use crate::states::{CreatorData, DonationProtocolData, Versioned};
 
 pub const CREATOR_PREFIX: &str = "creator";
 
@@ -33,6 +33,7 @@ pub fn initialize_creator(ctx: Context<InitializeCreator>) -> Result<()> {
   creator_data.donations_created_count = 0;
   creator_data.total_amount_collecting = 0;
   creator_data.total_amount_received = 0;
+  creator_data.version = CreatorData::VERSION;
 
   Ok(())
 }
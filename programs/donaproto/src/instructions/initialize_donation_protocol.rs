@@ -1,7 +1,10 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, TokenAccount};
 
-use crate::states::{DonationProtocolData, DISCRIMINATOR_LEN};
+use crate::{
+    events::ProtocolInitialized,
+    states::{DonationProtocolData, Versioned, DISCRIMINATOR_LEN},
+};
 
 pub const TREASURY_PREFIX: &str = "treasury";
 
@@ -43,6 +46,21 @@ pub fn initialize_donation_protocol(
     donation_data.treasury_owner_bump = treasury_owner_bump;
     donation_data.min_amount_to_collect = min_amount_to_collect;
     donation_data.authority = *ctx.accounts.payer.key;
+    donation_data.fee_bps = 0;
+    // emission schedule is disabled until set by authority
+    donation_data.epoch_duration = 0;
+    donation_data.max_self_funded_bps = 0;
+    donation_data.version = DonationProtocolData::VERSION;
+
+    emit!(ProtocolInitialized {
+        donation_protocol: donation_data.key(),
+        authority: donation_data.authority,
+        donation_mint: donation_data.donation_mint,
+        treasury_mint: donation_data.treasury_mint,
+        treasury: donation_data.treasury,
+        min_amount_to_earn,
+        min_amount_to_collect,
+    });
 
     Ok(())
 }
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::states::DonationProtocolData;
+
+pub const FEE_VAULT_PREFIX: &str = "fee_vault";
+
+#[derive(Accounts)]
+pub struct InitializeFeeVault<'info> {
+    #[account(init, payer = payer,
+      seeds = [
+        FEE_VAULT_PREFIX.as_bytes(),
+        donation_protocol.key().as_ref(),
+        mint.key().as_ref(),
+      ],
+      bump,
+      token::mint = mint,
+      token::authority = fee_vault_owner,
+    )]
+    pub fee_vault: Account<'info, TokenAccount>,
+    #[account(
+      seeds = [FEE_VAULT_PREFIX.as_bytes(), donation_protocol.key().as_ref()],
+      bump,
+    )]
+    /// CHECK: pda account ["fee_vault", donation_protocol_data]
+    pub fee_vault_owner: AccountInfo<'info>,
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Permissionless, creates the protocol owned token account collecting fees in `mint`
+pub fn initialize_fee_vault(_ctx: Context<InitializeFeeVault>) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::states::{DonationProtocolData, RewardVesting, DISCRIMINATOR_LEN};
+
+pub const REWARD_VESTING_PREFIX: &str = "reward_vesting";
+
+#[derive(Accounts)]
+pub struct InitializeRewardVesting<'info> {
+    #[account(init, payer = payer, space = DISCRIMINATOR_LEN + RewardVesting::INIT_SPACE,
+      seeds = [
+        REWARD_VESTING_PREFIX.as_bytes(),
+        donation_protocol.key().as_ref(),
+        contributor_wallet_address.key.as_ref(),
+      ],
+      bump,
+    )]
+    pub reward_vesting: Account<'info, RewardVesting>,
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+    /// CHECK: System account for which the RewardVesting is being initialized
+    pub contributor_wallet_address: AccountInfo<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_reward_vesting(ctx: Context<InitializeRewardVesting>) -> Result<()> {
+    let reward_vesting = &mut ctx.accounts.reward_vesting;
+    reward_vesting.contributor = ctx.accounts.contributor_wallet_address.key();
+    reward_vesting.donation_protocol = ctx.accounts.donation_protocol.key();
+    reward_vesting.total_amount_vested = 0;
+    reward_vesting.total_amount_claimed = 0;
+    reward_vesting.start_timestamp = 0;
+    reward_vesting.vesting_cliff = 0;
+    reward_vesting.vesting_duration = 0;
+    reward_vesting.claimed_at_start = 0;
+    reward_vesting.bump = ctx.bumps.reward_vesting;
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Transfer},
+    Discriminator,
+};
+
+use crate::{
+    errors::DonationError,
+    states::{
+        ContributorData, CreatorData, DonationData, DonationProtocolData, LegacyDonationData,
+        Migrate, Versioned, DISCRIMINATOR_LEN,
+    },
+};
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    /// CHECK: DonationData, DonationProtocolData, CreatorData or ContributorData
+    /// in an older layout, told apart by its discriminator and deserialized in the handler
+    #[account(mut, owner = crate::ID)]
+    pub account: AccountInfo<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Permissionless upgrade of an account to its current layout,
+/// the payer funds the rent for the grown account
+pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+    let discriminator: [u8; DISCRIMINATOR_LEN] = {
+        let data = ctx.accounts.account.try_borrow_data()?;
+        match data.get(..DISCRIMINATOR_LEN) {
+            Some(discriminator) => discriminator.try_into().unwrap(),
+            None => return Err(DonationError::InvalidMigrationAccount.into()),
+        }
+    };
+
+    if discriminator == DonationData::DISCRIMINATOR {
+        migrate_donation_data(ctx.accounts)
+    } else if discriminator == DonationProtocolData::DISCRIMINATOR {
+        migrate::<DonationProtocolData>(ctx.accounts)
+    } else if discriminator == CreatorData::DISCRIMINATOR {
+        migrate::<CreatorData>(ctx.accounts)
+    } else if discriminator == ContributorData::DISCRIMINATOR {
+        migrate::<ContributorData>(ctx.accounts)
+    } else {
+        Err(DonationError::InvalidMigrationAccount.into())
+    }
+}
+
+/// Resizes `account` to `space`, topping its lamports up to rent exemption from the payer.
+/// Fields added since the account was written read as zero.
+fn resize(accounts: &MigrateAccount, space: usize) -> Result<()> {
+    let account = &accounts.account;
+    if account.data_len() < space {
+        let rent_delta = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(account.lamports());
+        if rent_delta > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: accounts.payer.to_account_info(),
+                        to: account.clone(),
+                    },
+                ),
+                rent_delta,
+            )?;
+        }
+    }
+    account.realloc(space, true)?;
+
+    Ok(())
+}
+
+fn migrate<T: Migrate>(accounts: &MigrateAccount) -> Result<()> {
+    let account = &accounts.account;
+    if account.data_len() < T::SPACE {
+        resize(accounts, T::SPACE)?;
+    }
+
+    let mut data = {
+        let buffer = account.try_borrow_data()?;
+        T::try_deserialize(&mut &buffer[..])?
+    };
+    if data.version() >= T::VERSION {
+        return Err(DonationError::AccountAlreadyMigrated.into());
+    }
+    data.migrate();
+
+    let mut buffer = account.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut buffer;
+    data.try_serialize(&mut writer)
+}
+
+/// Rewrites a borsh `DonationData` in the zero copy layout, which has a fixed size
+/// no borsh version ever had
+fn migrate_donation_data(accounts: &MigrateAccount) -> Result<()> {
+    let account = &accounts.account;
+    if account.data_len() == DonationData::LEN {
+        return Err(DonationError::AccountAlreadyMigrated.into());
+    }
+
+    let donation_data = {
+        let buffer = account.try_borrow_data()?;
+        LegacyDonationData::try_from_account_data(&buffer)?.to_donation_data()?
+    };
+    resize(accounts, DonationData::LEN)?;
+
+    let mut buffer = account.try_borrow_mut_data()?;
+    buffer[DISCRIMINATOR_LEN..].copy_from_slice(bytemuck::bytes_of(&donation_data));
+
+    Ok(())
+}
@@ -20,3 +20,56 @@ pub mod donate_v2;
 pub use donate_v2::*;
 pub mod withdraw_funds_v2;
 pub use withdraw_funds_v2::*;
+pub mod donate_on_behalf;
+pub use donate_on_behalf::*;
+pub mod create_subscription;
+pub use create_subscription::*;
+pub mod execute_subscription;
+pub use execute_subscription::*;
+pub mod cancel_subscription;
+pub use cancel_subscription::*;
+pub mod create_payout_split;
+pub use create_payout_split::*;
+pub mod set_protocol_fee;
+pub use set_protocol_fee::*;
+pub mod set_mint_fee;
+pub use set_mint_fee::*;
+pub mod initialize_fee_vault;
+pub use initialize_fee_vault::*;
+pub mod collect_fees;
+pub use collect_fees::*;
+pub mod fund_treasury;
+pub use fund_treasury::*;
+pub mod withdraw_treasury;
+pub use withdraw_treasury::*;
+pub mod rotate_treasury;
+pub use rotate_treasury::*;
+pub mod set_emission_schedule;
+pub use set_emission_schedule::*;
+
+pub mod initialize_reward_vesting;
+pub use initialize_reward_vesting::*;
+pub mod set_reward_vesting;
+pub use set_reward_vesting::*;
+pub mod claim_rewards;
+pub use claim_rewards::*;
+pub mod set_self_funding_cap;
+pub use set_self_funding_cap::*;
+pub mod refund_donation;
+pub use refund_donation::*;
+pub mod create_funding_round;
+pub use create_funding_round::*;
+pub mod finalize_round;
+pub use finalize_round::*;
+pub mod distribute_match;
+pub use distribute_match::*;
+pub mod sweep_matching_vault;
+pub use sweep_matching_vault::*;
+pub mod create_match_pledge;
+pub use create_match_pledge::*;
+pub mod reclaim_match_pledge;
+pub use reclaim_match_pledge::*;
+pub mod migrate_account;
+pub use migrate_account::*;
+pub mod donate_batch;
+pub use donate_batch::*;
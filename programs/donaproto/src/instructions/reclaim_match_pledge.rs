@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::{
+    errors::DonationError,
+    states::{DonationData, MatchPledge},
+    MATCH_PLEDGE_PREFIX,
+};
+
+#[derive(Accounts)]
+pub struct ReclaimMatchPledge<'info> {
+    #[account(
+      constraint = match_pledge.sponsor == sponsor_wallet.key(),
+      constraint = match_pledge.escrow == match_pledge_escrow.key(),
+      seeds = [
+        MATCH_PLEDGE_PREFIX.as_bytes(),
+        donation_data.key().as_ref(),
+      ],
+      bump = match_pledge.bump,
+    )]
+    pub match_pledge: Account<'info, MatchPledge>,
+    #[account(mut)]
+    pub match_pledge_escrow: Account<'info, TokenAccount>,
+    pub donation_data: AccountLoader<'info, DonationData>,
+    #[account(mut,
+      constraint = sponsor_token_wallet.mint == match_pledge_escrow.mint,
+    )]
+    pub sponsor_token_wallet: Account<'info, TokenAccount>,
+    pub sponsor_wallet: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Returns what is left in the escrow once the pledge deadline passed or the donation is closed
+pub fn reclaim_match_pledge(ctx: Context<ReclaimMatchPledge>) -> Result<()> {
+    let now_timestamp = Clock::get().expect("Time error").unix_timestamp as u64;
+    let match_pledge = &ctx.accounts.match_pledge;
+    if now_timestamp <= match_pledge.deadline
+        && !DonationData::load_checked(&ctx.accounts.donation_data)?.is_closed()
+    {
+        return Err(DonationError::MatchPledgeActive.into());
+    }
+
+    let seeds = &[
+        MATCH_PLEDGE_PREFIX.as_bytes(),
+        match_pledge.donation_data.as_ref(),
+        &[match_pledge.bump],
+    ];
+    let signer = &[&seeds[..]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.match_pledge_escrow.to_account_info(),
+                to: ctx.accounts.sponsor_token_wallet.to_account_info(),
+                authority: match_pledge.to_account_info(),
+            },
+            signer,
+        ),
+        ctx.accounts.match_pledge_escrow.amount,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,267 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::{
+    errors::DonationError,
+    events::DonationRefunded,
+    rounds::untrack_round_contribution,
+    states::{
+        ContributorData, DonationData, DonationProtocolData, DonationReceipt, FundingRound,
+        MatchPledge, RewardVesting,
+    },
+    CONTRIBUTOR_PREFIX, DONATION_RECEIPT_PREFIX, HOLDING_PREFIX,
+};
+
+/// Donor takes back (part of) their contribution while a refundable donation is still running.
+/// Rewards earned on the refunded part are clawed back from unclaimed vesting first,
+/// the rest is returned by the donor from `donor_reward_token_wallet`.
+/// Sponsor match of the refunded part goes back to the pledge escrow
+#[derive(Accounts)]
+pub struct RefundDonation<'info> {
+    #[account(mut,
+      constraint = donation_receipt.donation_data == donation_data.key(),
+      seeds = [
+        DONATION_RECEIPT_PREFIX.as_bytes(),
+        donation_data.key().as_ref(),
+        donor_wallet.key().as_ref(),
+      ],
+      bump = donation_receipt.bump,
+    )]
+    pub donation_receipt: Account<'info, DonationReceipt>,
+    /// Checked against the other accounts in the handler, which loads it once
+    #[account(mut)]
+    pub donation_data: AccountLoader<'info, DonationData>,
+    #[account(mut,
+      seeds = [
+        CONTRIBUTOR_PREFIX.as_bytes(),
+        donation_protocol.key().as_ref(),
+        donor_wallet.key().as_ref(),
+      ],
+      bump = contributor_data.bump,
+    )]
+    pub contributor_data: Account<'info, ContributorData>,
+    #[account(mut)]
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+
+    #[account(mut)]
+    pub holding_wallet: Account<'info, TokenAccount>,
+    /// Campaign recipient, refunds of its current owner reduce the self-funded amount
+    pub recipient: Account<'info, TokenAccount>,
+    /// CHECK: pda account ["holding", donation_data], checked in the handler
+    pub holding_wallet_owner: AccountInfo<'info>,
+    #[account(mut,
+      constraint = donor_token_wallet.owner == donor_wallet.key(),
+    )]
+    pub donor_token_wallet: Account<'info, TokenAccount>,
+    #[account(mut,
+      constraint = donor_reward_token_wallet.owner == donor_wallet.key(),
+      constraint = donor_reward_token_wallet.mint == donation_protocol.treasury_mint,
+    )]
+    pub donor_reward_token_wallet: Account<'info, TokenAccount>,
+    #[account(mut,
+      constraint = reward_treasury.key() == donation_protocol.treasury,
+    )]
+    pub reward_treasury: Account<'info, TokenAccount>,
+    #[account(mut,
+      constraint = reward_vesting.donation_protocol == donation_protocol.key(),
+      constraint = reward_vesting.contributor == donor_wallet.key(),
+    )]
+    pub reward_vesting: Option<Account<'info, RewardVesting>>,
+    /// Required when the refunded contribution counts towards a funding round
+    #[account(mut)]
+    pub funding_round: Option<Account<'info, FundingRound>>,
+    /// Required when the refunded contribution was matched by a sponsor
+    #[account(mut,
+      constraint = match_pledge.donation_data == donation_data.key(),
+    )]
+    pub match_pledge: Option<Account<'info, MatchPledge>>,
+    #[account(mut)]
+    pub match_pledge_escrow: Option<Account<'info, TokenAccount>>,
+    pub donor_wallet: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn refund_donation(ctx: Context<RefundDonation>, amount: u64) -> Result<()> {
+    let now_timestamp = Clock::get().expect("Time error").unix_timestamp as u64;
+    let donation_data_key = ctx.accounts.donation_data.key();
+    let mut donation_data = DonationData::load_checked_mut(&ctx.accounts.donation_data)?;
+    donation_data.check_donation_accounts(
+        &ctx.accounts.donation_protocol.key(),
+        &ctx.accounts.holding_wallet.key(),
+        &ctx.accounts.recipient.key(),
+    )?;
+    donation_data
+        .check_holding_wallet_owner(&donation_data_key, &ctx.accounts.holding_wallet_owner.key())?;
+    if ctx.accounts.donor_token_wallet.mint != donation_data.donation_mint {
+        return Err(DonationError::InvalidDonationMint.into());
+    }
+    if !donation_data.is_refundable() {
+        return Err(DonationError::RefundsDisabled.into());
+    }
+    if donation_data.is_closed() {
+        return Err(DonationError::DonationClosed.into());
+    }
+    if donation_data.ending_timestamp <= now_timestamp {
+        return Err(DonationError::RefundPeriodOver.into());
+    }
+
+    let donation_receipt = &mut ctx.accounts.donation_receipt;
+    // gifts and donations covered by a minted receipt token are final
+    if amount == 0 || amount > donation_receipt.amount_refundable {
+        return Err(DonationError::InvalidRefundAmount.into());
+    }
+    // off-chain fulfilment relies on claimed tiers
+    if donation_receipt.perk_tier > 0 {
+        return Err(DonationError::PerkTierClaimed.into());
+    }
+    let default_amount =
+        donation_receipt.proportional_amount(donation_receipt.default_amount_donated, amount);
+    let reward_amount =
+        donation_receipt.proportional_amount(donation_receipt.reward_amount, amount);
+    let matched_amount =
+        donation_receipt.proportional_amount(donation_receipt.matched_amount, amount);
+    untrack_round_contribution(
+        ctx.accounts.funding_round.as_mut(),
+        &donation_data_key,
+        donation_receipt,
+        default_amount,
+    )?;
+
+    // Claw back reward, unclaimed vesting first
+    let donation_protocol = &mut ctx.accounts.donation_protocol;
+    let contributor_data = &mut ctx.accounts.contributor_data;
+    let mut vested_amount = 0;
+    if let Some(reward_vesting) = ctx.accounts.reward_vesting.as_mut() {
+        let unclaimed_amount = reward_vesting
+            .total_amount_vested
+            .checked_sub(reward_vesting.total_amount_claimed)
+            .unwrap();
+        vested_amount = reward_amount.min(unclaimed_amount);
+        reward_vesting.total_amount_vested = reward_vesting
+            .total_amount_vested
+            .checked_sub(vested_amount)
+            .unwrap();
+        donation_protocol.total_reward_vesting = donation_protocol
+            .total_reward_vesting
+            .checked_sub(vested_amount)
+            .unwrap();
+        contributor_data.total_amount_vested = contributor_data
+            .total_amount_vested
+            .checked_sub(vested_amount)
+            .unwrap();
+    }
+    let returned_amount = reward_amount.checked_sub(vested_amount).unwrap();
+    if returned_amount > 0 {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.donor_reward_token_wallet.to_account_info(),
+                    to: ctx.accounts.reward_treasury.to_account_info(),
+                    authority: ctx.accounts.donor_wallet.to_account_info(),
+                },
+            ),
+            returned_amount,
+        )?;
+    }
+    contributor_data.total_amount_earned = contributor_data
+        .total_amount_earned
+        .checked_sub(reward_amount)
+        .unwrap();
+    contributor_data.total_amount_donated = contributor_data
+        .total_amount_donated
+        .checked_sub(default_amount)
+        .unwrap();
+
+    // Transfer refund from donation holding wallet back to donor
+    let seeds = &[
+        HOLDING_PREFIX.as_bytes(),
+        donation_data_key.as_ref(),
+        &[donation_data.holding_bump],
+    ];
+    let signer = &[&seeds[..]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.holding_wallet.to_account_info(),
+                to: ctx.accounts.donor_token_wallet.to_account_info(),
+                authority: ctx.accounts.holding_wallet_owner.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+    )?;
+
+    // Return sponsor match of the refunded part to the pledge escrow
+    if matched_amount > 0 {
+        let match_pledge = match ctx.accounts.match_pledge.as_mut() {
+            Some(match_pledge) => match_pledge,
+            None => return Err(DonationError::MatchPledgeRequired.into()),
+        };
+        let match_pledge_escrow = match ctx.accounts.match_pledge_escrow.as_ref() {
+            Some(escrow) if escrow.key() == match_pledge.escrow => escrow,
+            _ => return Err(DonationError::MatchPledgeRequired.into()),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.holding_wallet.to_account_info(),
+                    to: match_pledge_escrow.to_account_info(),
+                    authority: ctx.accounts.holding_wallet_owner.to_account_info(),
+                },
+                signer,
+            ),
+            matched_amount,
+        )?;
+        match_pledge.total_amount_matched = match_pledge
+            .total_amount_matched
+            .checked_sub(matched_amount)
+            .unwrap();
+        donation_data.total_amount_matched = donation_data
+            .total_amount_matched
+            .checked_sub(matched_amount)
+            .unwrap();
+    }
+
+    donation_data.total_amount_received = donation_data
+        .total_amount_received
+        .checked_sub(amount.checked_add(matched_amount).unwrap())
+        .unwrap();
+    if donation_data.is_self_funded_by(
+        &ctx.accounts.donor_wallet.key(),
+        &ctx.accounts.recipient.owner,
+    ) {
+        donation_data.self_funded_amount = donation_data.self_funded_amount.saturating_sub(amount);
+    }
+    donation_receipt.amount_donated = donation_receipt.amount_donated.checked_sub(amount).unwrap();
+    donation_receipt.amount_refundable = donation_receipt
+        .amount_refundable
+        .checked_sub(amount)
+        .unwrap();
+    donation_receipt.default_amount_donated = donation_receipt
+        .default_amount_donated
+        .checked_sub(default_amount)
+        .unwrap();
+    donation_receipt.reward_amount = donation_receipt
+        .reward_amount
+        .checked_sub(reward_amount)
+        .unwrap();
+    donation_receipt.matched_amount = donation_receipt
+        .matched_amount
+        .checked_sub(matched_amount)
+        .unwrap();
+
+    emit!(DonationRefunded {
+        donation_protocol: ctx.accounts.donation_protocol.key(),
+        donation_data: donation_data_key,
+        contributor: ctx.accounts.donor_wallet.key(),
+        amount,
+        default_amount,
+        reward_amount,
+        matched_amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{
+    events::TreasuryRotated, rewards::transfer_reward, states::DonationProtocolData,
+    TREASURY_PREFIX,
+};
+
+#[derive(Accounts)]
+pub struct RotateTreasury<'info> {
+    #[account(mut,
+      constraint = treasury.key() == donation_protocol.treasury,
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    #[account(mut,
+      constraint = new_treasury.key() != treasury.key(),
+      constraint = new_treasury.owner == treasury_owner.key(),
+      constraint = new_treasury.mint == donation_protocol.treasury_mint,
+    )]
+    pub new_treasury: Account<'info, TokenAccount>,
+    #[account(
+      seeds = [TREASURY_PREFIX.as_bytes(), donation_protocol.key().as_ref()],
+      bump = donation_protocol.treasury_owner_bump,
+    )]
+    /// CHECK: pda account ["treasury", donation_protocol_data]
+    pub treasury_owner: AccountInfo<'info>,
+    #[account(mut,
+      constraint = donation_protocol.authority == authority.key(),
+    )]
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Moves the whole reward balance to `new_treasury` and makes it the protocol treasury
+pub fn rotate_treasury(ctx: Context<RotateTreasury>) -> Result<()> {
+    let amount = ctx.accounts.treasury.amount;
+    let donation_protocol = &ctx.accounts.donation_protocol;
+    if amount > 0 {
+        transfer_reward(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.treasury_owner.to_account_info(),
+            ctx.accounts.new_treasury.to_account_info(),
+            &donation_protocol.key(),
+            donation_protocol.treasury_owner_bump,
+            amount,
+        )?;
+    }
+
+    let donation_protocol = &mut ctx.accounts.donation_protocol;
+    donation_protocol.treasury = ctx.accounts.new_treasury.key();
+
+    emit!(TreasuryRotated {
+        donation_protocol: donation_protocol.key(),
+        old_treasury: ctx.accounts.treasury.key(),
+        new_treasury: ctx.accounts.new_treasury.key(),
+        authority: ctx.accounts.authority.key(),
+        amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::DonationError,
+    states::{DonationProtocolData, EmissionCarryOver, BPS_DENOMINATOR},
+};
+
+#[derive(Accounts)]
+pub struct SetEmissionSchedule<'info> {
+    #[account(mut,
+      constraint = donation_protocol.authority == authority.key(),
+    )]
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+    pub authority: Signer<'info>,
+}
+
+/// Starts a new schedule from the current timestamp,
+/// `epoch_duration` of zero turns the schedule off
+pub fn set_emission_schedule(
+    ctx: Context<SetEmissionSchedule>,
+    epoch_budget: u64,
+    epoch_duration: u64,
+    epoch_decay_bps: u16,
+    emission_carry_over: EmissionCarryOver,
+) -> Result<()> {
+    if epoch_decay_bps > BPS_DENOMINATOR {
+        return Err(DonationError::InvalidEmissionSchedule.into());
+    }
+
+    let now_timestamp = Clock::get().expect("Time error").unix_timestamp as u64;
+    let donation_protocol = &mut ctx.accounts.donation_protocol;
+    donation_protocol.epoch_duration = epoch_duration;
+    donation_protocol.epoch_decay_bps = epoch_decay_bps;
+    donation_protocol.emission_carry_over = emission_carry_over;
+    donation_protocol.epoch_start_timestamp = now_timestamp;
+    donation_protocol.current_epoch = 0;
+    donation_protocol.current_epoch_base_budget = epoch_budget;
+    donation_protocol.current_epoch_budget = epoch_budget;
+    donation_protocol.current_epoch_consumed = 0;
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::{
+    errors::DonationError,
+    states::{AuthorizedClmmPool, DonationProtocolData, MintFee, DISCRIMINATOR_LEN, MAX_FEE_BPS},
+};
+
+pub const MINT_FEE_PREFIX: &str = "mint_fee";
+
+#[derive(Accounts)]
+pub struct SetMintFee<'info> {
+    #[account(init_if_needed, payer = authority, space = DISCRIMINATOR_LEN + MintFee::INIT_SPACE,
+      seeds = [
+        MINT_FEE_PREFIX.as_bytes(),
+        donation_protocol.key().as_ref(),
+        mint.key().as_ref(),
+      ],
+      bump,
+    )]
+    pub mint_fee: Account<'info, MintFee>,
+    #[account(
+      constraint = donation_protocol.authority == authority.key(),
+    )]
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+    pub mint: Account<'info, Mint>,
+    /// Required unless `mint` is the protocol donation mint
+    #[account(
+      constraint = authorized_clmm_pool.mint == mint.key(),
+      constraint = authorized_clmm_pool.donation_protocol == donation_protocol.key(),
+    )]
+    pub authorized_clmm_pool: Option<Account<'info, AuthorizedClmmPool>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Overrides the protocol fee for donations created in `mint`,
+/// which must be authorized for donations
+pub fn set_mint_fee(ctx: Context<SetMintFee>, fee_bps: u16) -> Result<()> {
+    if fee_bps > MAX_FEE_BPS {
+        return Err(DonationError::InvalidFeeBps.into());
+    }
+    if ctx.accounts.mint.key() != ctx.accounts.donation_protocol.donation_mint
+        && ctx.accounts.authorized_clmm_pool.is_none()
+    {
+        return Err(DonationError::InvalidDonationMint.into());
+    }
+
+    let mint_fee = &mut ctx.accounts.mint_fee;
+    mint_fee.donation_protocol = ctx.accounts.donation_protocol.key();
+    mint_fee.mint = ctx.accounts.mint.key();
+    mint_fee.fee_bps = fee_bps;
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::DonationError,
+    states::{DonationProtocolData, MAX_FEE_BPS},
+};
+
+#[derive(Accounts)]
+pub struct SetProtocolFee<'info> {
+    #[account(mut,
+      constraint = donation_protocol.authority == authority.key(),
+    )]
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+    pub authority: Signer<'info>,
+}
+
+/// Applies only to donations created afterwards, existing ones keep their fee rate
+pub fn set_protocol_fee(ctx: Context<SetProtocolFee>, fee_bps: u16) -> Result<()> {
+    if fee_bps > MAX_FEE_BPS {
+        return Err(DonationError::InvalidFeeBps.into());
+    }
+
+    ctx.accounts.donation_protocol.fee_bps = fee_bps;
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::DonationError, states::DonationProtocolData};
+
+#[derive(Accounts)]
+pub struct SetRewardVesting<'info> {
+    #[account(mut,
+      constraint = donation_protocol.authority == authority.key(),
+    )]
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+    pub authority: Signer<'info>,
+}
+
+/// `vesting_duration` of zero pays new rewards out immediately,
+/// rewards vested before keep unlocking on the schedule they accrued with
+pub fn set_reward_vesting(
+    ctx: Context<SetRewardVesting>,
+    vesting_cliff: u64,
+    vesting_duration: u64,
+) -> Result<()> {
+    if vesting_cliff > vesting_duration {
+        return Err(DonationError::InvalidVestingSchedule.into());
+    }
+
+    let donation_protocol = &mut ctx.accounts.donation_protocol;
+    donation_protocol.vesting_cliff = vesting_cliff;
+    donation_protocol.vesting_duration = vesting_duration;
+
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::DonationError,
+    states::{DonationProtocolData, BPS_DENOMINATOR},
+};
+
+#[derive(Accounts)]
+pub struct SetSelfFundingCap<'info> {
+    #[account(mut,
+      constraint = donation_protocol.authority == authority.key(),
+    )]
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+    pub authority: Signer<'info>,
+}
+
+pub fn set_self_funding_cap(
+    ctx: Context<SetSelfFundingCap>,
+    max_self_funded_bps: u16,
+) -> Result<()> {
+    if max_self_funded_bps > BPS_DENOMINATOR {
+        return Err(DonationError::InvalidSelfFundingCap.into());
+    }
+
+    ctx.accounts.donation_protocol.max_self_funded_bps = max_self_funded_bps;
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::{errors::DonationError, states::FundingRound, MATCHING_VAULT_PREFIX};
+
+#[derive(Accounts)]
+pub struct SweepMatchingVault<'info> {
+    #[account(
+      constraint = funding_round.authority == authority.key(),
+      constraint = funding_round.matching_vault == matching_vault.key(),
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+    #[account(mut)]
+    pub matching_vault: Account<'info, TokenAccount>,
+    #[account(
+      seeds = [MATCHING_VAULT_PREFIX.as_bytes(), funding_round.key().as_ref()],
+      bump = funding_round.matching_vault_owner_bump,
+    )]
+    /// CHECK: pda account ["matching_vault", funding_round]
+    pub matching_vault_owner: AccountInfo<'info>,
+    #[account(mut,
+      constraint = destination.mint == matching_vault.mint,
+    )]
+    pub destination: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Returns what is left in the matching vault once every match is distributed:
+/// rounding dust and tokens sent to the vault after finalization
+pub fn sweep_matching_vault(ctx: Context<SweepMatchingVault>) -> Result<()> {
+    let funding_round = &ctx.accounts.funding_round;
+    if !funding_round.is_finalized {
+        return Err(DonationError::RoundNotFinalized.into());
+    }
+    if funding_round
+        .campaigns
+        .iter()
+        .any(|campaign| !campaign.is_distributed)
+    {
+        return Err(DonationError::MatchesNotDistributed.into());
+    }
+    let amount = ctx.accounts.matching_vault.amount;
+    if amount == 0 {
+        return Err(DonationError::NothingToSweep.into());
+    }
+
+    let funding_round_key = funding_round.key();
+    let seeds = &[
+        MATCHING_VAULT_PREFIX.as_bytes(),
+        funding_round_key.as_ref(),
+        &[funding_round.matching_vault_owner_bump],
+    ];
+    let signer = &[&seeds[..]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.matching_vault.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.matching_vault_owner.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+    )
+}
@@ -1,17 +1,19 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, TokenAccount, Transfer};
 
-use crate::{errors::DonationError, states::{CreatorData, DonationData, DonationProtocolData}, HOLDING_PREFIX};
-
+use crate::{
+    errors::DonationError,
+    events::FundsWithdrawn,
+    payouts::{calculate_protocol_fee, transfer_split_payouts},
+    states::{CreatorData, DonationData, DonationProtocolData, PayoutSplit},
+    FEE_VAULT_PREFIX, HOLDING_PREFIX,
+};
 
 #[derive(Accounts)]
 pub struct WithdrawFunds<'info> {
-    #[account(mut,
-      constraint = donation_data.donation_protocol == donation_protocol.key(),
-      constraint = donation_data.holding_wallet == holding_wallet.key(),
-      constraint = donation_data.creator_data == creator_data.key(),
-    )]
-    pub donation_data: Account<'info, DonationData>,
+    /// Checked against the other accounts in the handler, which loads it once
+    #[account(mut)]
+    pub donation_data: AccountLoader<'info, DonationData>,
     #[account(mut,
       constraint = creator_data.donation_protocol == donation_protocol.key(),
     )]
@@ -20,17 +22,9 @@ pub struct WithdrawFunds<'info> {
 
     #[account(mut,
       constraint = holding_wallet.mint == donation_mint.key(),
-      constraint = holding_wallet.key() == donation_data.holding_wallet,
     )]
     pub holding_wallet: Account<'info, TokenAccount>,
-    #[account(
-      seeds = [
-        HOLDING_PREFIX.as_bytes(), 
-        donation_data.to_account_info().key.as_ref(),
-      ],
-      bump = donation_data.holding_bump,
-    )]
-    /// CHECK: pda account ["holding", donation_data]
+    /// CHECK: pda account ["holding", donation_data], checked in the handler
     pub holding_wallet_owner: AccountInfo<'info>,
     #[account(mut,
       constraint = recipient_token_wallet.owner == *payer.key,
@@ -41,15 +35,40 @@ pub struct WithdrawFunds<'info> {
       constraint = donation_protocol.donation_mint.key() == donation_mint.key(),
     )]
     pub donation_mint: Account<'info, Mint>,
+    /// Required when the donation fee is not zero
+    #[account(mut,
+      seeds = [
+        FEE_VAULT_PREFIX.as_bytes(),
+        donation_protocol.key().as_ref(),
+        donation_mint.key().as_ref(),
+      ],
+      bump,
+    )]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
+    /// Required when the donation has a payout split,
+    /// its recipient token wallets are passed as remaining accounts
+    #[account(mut,
+      constraint = payout_split.donation_data == donation_data.key(),
+    )]
+    pub payout_split: Option<Account<'info, PayoutSplit>>,
     #[account(mut)]
     pub payer: Signer<'info>,
     pub token_program: Program<'info, anchor_spl::token::Token>,
 }
 
-  pub fn withdraw_funds(ctx: Context<WithdrawFunds>) -> Result<()> {
-    let donation_data = &mut ctx.accounts.donation_data;
+pub fn withdraw_funds<'info>(ctx: Context<'_, '_, '_, 'info, WithdrawFunds<'info>>) -> Result<()> {
+    let donation_data_key = ctx.accounts.donation_data.key();
+    let mut donation_data = DonationData::load_checked_mut(&ctx.accounts.donation_data)?;
+    if donation_data.donation_protocol != ctx.accounts.donation_protocol.key()
+        || donation_data.holding_wallet != ctx.accounts.holding_wallet.key()
+        || donation_data.creator_data != ctx.accounts.creator_data.key()
+    {
+        return Err(DonationError::DonationAccountMismatch.into());
+    }
+    donation_data
+        .check_holding_wallet_owner(&donation_data_key, &ctx.accounts.holding_wallet_owner.key())?;
 
-    if donation_data.is_closed {
+    if donation_data.is_closed() {
         return Err(DonationError::DonationClosed.into());
     }
 
@@ -65,38 +84,92 @@ pub struct WithdrawFunds<'info> {
         return Err(DonationError::InvalidDonationMint.into());
     }
 
+    let fee_amount =
+        calculate_protocol_fee(donation_data.total_amount_received, donation_data.fee_bps);
+    let payout_amount = donation_data
+        .total_amount_received
+        .checked_sub(fee_amount)
+        .unwrap();
+    donation_data.fee_amount = fee_amount;
+
     // Transfer amount from donation holding wallet to recipient
     let seeds = &[
         HOLDING_PREFIX.as_bytes(),
-        donation_data.to_account_info().key.as_ref(),
+        donation_data_key.as_ref(),
         &[donation_data.holding_bump],
     ];
     let signer = &[&seeds[..]];
-    token::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info().clone(),
-            Transfer {
-                from: ctx.accounts.holding_wallet.to_account_info().clone(),
-                to: ctx
-                    .accounts
-                    .recipient_token_wallet
-                    .to_account_info()
-                    .clone(),
-                authority: ctx.accounts.holding_wallet_owner.to_account_info().clone(),
-            },
+    if fee_amount > 0 {
+        let fee_vault = match ctx.accounts.fee_vault.as_ref() {
+            Some(fee_vault) => fee_vault,
+            None => return Err(DonationError::FeeVaultRequired.into()),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.holding_wallet.to_account_info(),
+                    to: fee_vault.to_account_info(),
+                    authority: ctx.accounts.holding_wallet_owner.to_account_info(),
+                },
+                signer,
+            ),
+            fee_amount,
+        )?;
+    }
+    if donation_data.payout_split == Pubkey::default() {
+        if ctx.accounts.recipient_token_wallet.key() != donation_data.recipient {
+            return Err(DonationError::DonationAccountMismatch.into());
+        }
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info().clone(),
+                Transfer {
+                    from: ctx.accounts.holding_wallet.to_account_info().clone(),
+                    to: ctx
+                        .accounts
+                        .recipient_token_wallet
+                        .to_account_info()
+                        .clone(),
+                    authority: ctx.accounts.holding_wallet_owner.to_account_info().clone(),
+                },
+                signer,
+            ),
+            payout_amount,
+        )?;
+    } else {
+        let payout_split = match ctx.accounts.payout_split.as_mut() {
+            Some(payout_split) if payout_split.key() == donation_data.payout_split => payout_split,
+            _ => return Err(DonationError::InvalidPayoutSplit.into()),
+        };
+        transfer_split_payouts(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.holding_wallet.to_account_info(),
+            ctx.accounts.holding_wallet_owner.to_account_info(),
+            payout_split,
+            ctx.remaining_accounts,
             signer,
-        ),
-        donation_data.total_amount_received,
-    )?;
+            payout_amount,
+        )?;
+    }
 
     let creator_data = &mut ctx.accounts.creator_data;
     creator_data.total_amount_received = creator_data
         .total_amount_received
-        .checked_add(donation_data.total_amount_received)
+        .checked_add(payout_amount)
         .unwrap();
     creator_data.donations_closed_count =
         creator_data.donations_closed_count.checked_add(1).unwrap();
-    donation_data.is_closed = true;
+    donation_data.close();
+
+    emit!(FundsWithdrawn {
+        donation_protocol: donation_data.donation_protocol,
+        donation_data: donation_data_key,
+        recipient: ctx.accounts.recipient_token_wallet.key(),
+        amount: payout_amount,
+        fee_amount,
+        default_amount: payout_amount,
+    });
 
     Ok(())
 }
@@ -2,17 +2,20 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, TokenAccount, Transfer};
 use raydium_amm_v3::states::PoolState;
 
-use crate::{errors::DonationError, states::{AuthorizedClmmPool, CreatorData, DonationData, DonationProtocolData}, utils::calculate_amount, AUTHORIZED_CLMM_POOL_PREFIX, HOLDING_PREFIX};
-
+use crate::{
+    errors::DonationError,
+    events::FundsWithdrawn,
+    payouts::{calculate_protocol_fee, transfer_split_payouts},
+    states::{AuthorizedClmmPool, CreatorData, DonationData, DonationProtocolData, PayoutSplit},
+    utils::{calculate_amount, is_default_token_mint_0},
+    AUTHORIZED_CLMM_POOL_PREFIX, FEE_VAULT_PREFIX, HOLDING_PREFIX,
+};
 
 #[derive(Accounts)]
 pub struct WithdrawFundsV2<'info> {
-    #[account(mut,
-      constraint = donation_data.donation_protocol == donation_protocol.key(),
-      constraint = donation_data.holding_wallet == holding_wallet.key(),
-      constraint = donation_data.creator_data == creator_data.key(),
-    )]
-    pub donation_data: Account<'info, DonationData>,
+    /// Checked against the other accounts in the handler, which loads it once
+    #[account(mut)]
+    pub donation_data: AccountLoader<'info, DonationData>,
     #[account(mut,
       constraint = creator_data.donation_protocol == donation_protocol.key(),
     )]
@@ -24,17 +27,9 @@ pub struct WithdrawFundsV2<'info> {
 
     #[account(mut,
       constraint = holding_wallet.mint == donation_mint.key(),
-      constraint = holding_wallet.key() == donation_data.holding_wallet,
     )]
     pub holding_wallet: Account<'info, TokenAccount>,
-    #[account(
-      seeds = [
-        HOLDING_PREFIX.as_bytes(), 
-        donation_data.to_account_info().key.as_ref(),
-      ],
-      bump = donation_data.holding_bump,
-    )]
-    /// CHECK: pda account ["holding", donation_data]
+    /// CHECK: pda account ["holding", donation_data], checked in the handler
     pub holding_wallet_owner: AccountInfo<'info>,
     #[account(mut,
       constraint = recipient_token_wallet.owner == *payer.key,
@@ -58,21 +53,44 @@ pub struct WithdrawFundsV2<'info> {
         constraint = authorized_clmm_pool.pool_state == pool_state.key(),
     )]
     pub authorized_clmm_pool: Account<'info, AuthorizedClmmPool>,
-    #[account(
-        constraint = (pool_state.load()?.token_mint_0 == donation_protocol.donation_mint && pool_state.load()?.token_mint_1 == donation_mint.key())
-            || (pool_state.load()?.token_mint_1 == donation_protocol.donation_mint && pool_state.load()?.token_mint_0 == donation_mint.key()),
-    )]
     pub pool_state: AccountLoader<'info, PoolState>,
 
+    /// Required when the donation fee is not zero
+    #[account(mut,
+      seeds = [
+        FEE_VAULT_PREFIX.as_bytes(),
+        donation_protocol.key().as_ref(),
+        donation_mint.key().as_ref(),
+      ],
+      bump,
+    )]
+    pub fee_vault: Option<Account<'info, TokenAccount>>,
+    /// Required when the donation has a payout split,
+    /// its recipient token wallets are passed as remaining accounts
+    #[account(mut,
+      constraint = payout_split.donation_data == donation_data.key(),
+    )]
+    pub payout_split: Option<Account<'info, PayoutSplit>>,
     #[account(mut)]
     pub payer: Signer<'info>,
     pub token_program: Program<'info, anchor_spl::token::Token>,
 }
 
-  pub fn withdraw_funds_v2(ctx: Context<WithdrawFundsV2>) -> Result<()> {
-    let donation_data = &mut ctx.accounts.donation_data;
+pub fn withdraw_funds_v2<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawFundsV2<'info>>,
+) -> Result<()> {
+    let donation_data_key = ctx.accounts.donation_data.key();
+    let mut donation_data = DonationData::load_checked_mut(&ctx.accounts.donation_data)?;
+    if donation_data.donation_protocol != ctx.accounts.donation_protocol.key()
+        || donation_data.holding_wallet != ctx.accounts.holding_wallet.key()
+        || donation_data.creator_data != ctx.accounts.creator_data.key()
+    {
+        return Err(DonationError::DonationAccountMismatch.into());
+    }
+    donation_data
+        .check_holding_wallet_owner(&donation_data_key, &ctx.accounts.holding_wallet_owner.key())?;
 
-    if donation_data.is_closed {
+    if donation_data.is_closed() {
         return Err(DonationError::DonationClosed.into());
     }
 
@@ -85,42 +103,97 @@ pub struct WithdrawFundsV2<'info> {
     if ctx.accounts.donation_protocol.donation_mint == ctx.accounts.donation_mint.key() {
         return Err(DonationError::InvalidDonationMint.into());
     }
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let is_default_token_mint_0 = match is_default_token_mint_0(
+        &pool_state,
+        &ctx.accounts.donation_protocol.donation_mint,
+        &ctx.accounts.donation_mint.key(),
+    ) {
+        Some(is_default_token_mint_0) => is_default_token_mint_0,
+        None => return Err(DonationError::InvalidPoolState.into()),
+    };
+
+    let fee_amount =
+        calculate_protocol_fee(donation_data.total_amount_received, donation_data.fee_bps);
+    let payout_amount = donation_data
+        .total_amount_received
+        .checked_sub(fee_amount)
+        .unwrap();
+    donation_data.fee_amount = fee_amount;
 
     // Transfer amount from donation holding wallet to recipient
     let seeds = &[
         HOLDING_PREFIX.as_bytes(),
-        donation_data.to_account_info().key.as_ref(),
+        donation_data_key.as_ref(),
         &[donation_data.holding_bump],
     ];
     let signer = &[&seeds[..]];
-    token::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info().clone(),
-            Transfer {
-                from: ctx.accounts.holding_wallet.to_account_info().clone(),
-                to: ctx
-                    .accounts
-                    .recipient_token_wallet
-                    .to_account_info()
-                    .clone(),
-                authority: ctx.accounts.holding_wallet_owner.to_account_info().clone(),
-            },
+    if fee_amount > 0 {
+        let fee_vault = match ctx.accounts.fee_vault.as_ref() {
+            Some(fee_vault) => fee_vault,
+            None => return Err(DonationError::FeeVaultRequired.into()),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.holding_wallet.to_account_info(),
+                    to: fee_vault.to_account_info(),
+                    authority: ctx.accounts.holding_wallet_owner.to_account_info(),
+                },
+                signer,
+            ),
+            fee_amount,
+        )?;
+    }
+    if donation_data.payout_split == Pubkey::default() {
+        if ctx.accounts.recipient_token_wallet.key() != donation_data.recipient {
+            return Err(DonationError::DonationAccountMismatch.into());
+        }
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info().clone(),
+                Transfer {
+                    from: ctx.accounts.holding_wallet.to_account_info().clone(),
+                    to: ctx
+                        .accounts
+                        .recipient_token_wallet
+                        .to_account_info()
+                        .clone(),
+                    authority: ctx.accounts.holding_wallet_owner.to_account_info().clone(),
+                },
+                signer,
+            ),
+            payout_amount,
+        )?;
+    } else {
+        let payout_split = match ctx.accounts.payout_split.as_mut() {
+            Some(payout_split) if payout_split.key() == donation_data.payout_split => payout_split,
+            _ => return Err(DonationError::InvalidPayoutSplit.into()),
+        };
+        transfer_split_payouts(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.holding_wallet.to_account_info(),
+            ctx.accounts.holding_wallet_owner.to_account_info(),
+            payout_split,
+            ctx.remaining_accounts,
             signer,
-        ),
-        donation_data.total_amount_received,
-    )?;
+            payout_amount,
+        )?;
+    }
 
     let default_donation_mint = &ctx.accounts.default_donation_mint;
     let donation_mint = &ctx.accounts.donation_mint;
-    let is_default_token_mint_0 = ctx.accounts.pool_state.load()?.token_mint_0
-        == ctx.accounts.donation_protocol.donation_mint;
-    let total_amount_received = calculate_amount(
+    let total_amount_received = match calculate_amount(
         default_donation_mint.decimals,
         donation_mint.decimals,
-        donation_data.total_amount_received,
-        ctx.accounts.pool_state.load()?.sqrt_price_x64,
+        payout_amount,
+        pool_state.sqrt_price_x64,
         is_default_token_mint_0,
-    );
+    ) {
+        Some(total_amount_received) => total_amount_received,
+        None => return Err(DonationError::AmountConversionOverflow.into()),
+    };
 
     let creator_data = &mut ctx.accounts.creator_data;
     creator_data.total_amount_received = creator_data
@@ -129,7 +202,16 @@ pub struct WithdrawFundsV2<'info> {
         .unwrap();
     creator_data.donations_closed_count =
         creator_data.donations_closed_count.checked_add(1).unwrap();
-    donation_data.is_closed = true;
+    donation_data.close();
+
+    emit!(FundsWithdrawn {
+        donation_protocol: donation_data.donation_protocol,
+        donation_data: donation_data_key,
+        recipient: ctx.accounts.recipient_token_wallet.key(),
+        amount: payout_amount,
+        fee_amount,
+        default_amount: total_amount_received,
+    });
 
     Ok(())
 }
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{
+    errors::DonationError, events::TreasuryWithdrawn, rewards::transfer_reward,
+    states::DonationProtocolData, TREASURY_PREFIX,
+};
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    #[account(mut,
+      constraint = treasury.key() == donation_protocol.treasury,
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    #[account(
+      seeds = [TREASURY_PREFIX.as_bytes(), donation_protocol.key().as_ref()],
+      bump = donation_protocol.treasury_owner_bump,
+    )]
+    /// CHECK: pda account ["treasury", donation_protocol_data]
+    pub treasury_owner: AccountInfo<'info>,
+    #[account(mut,
+      constraint = destination.mint == donation_protocol.treasury_mint,
+    )]
+    pub destination: Account<'info, TokenAccount>,
+    #[account(
+      constraint = donation_protocol.authority == authority.key(),
+    )]
+    pub donation_protocol: Account<'info, DonationProtocolData>,
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return Err(DonationError::TreasuryAmountZero.into());
+    }
+
+    let donation_protocol = &ctx.accounts.donation_protocol;
+    // vesting rewards are owed to contributors and can not be withdrawn
+    let available_amount = ctx
+        .accounts
+        .treasury
+        .amount
+        .saturating_sub(donation_protocol.total_reward_vesting);
+    if amount > available_amount {
+        return Err(DonationError::TreasuryReservedForVesting.into());
+    }
+
+    transfer_reward(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.treasury_owner.to_account_info(),
+        ctx.accounts.destination.to_account_info(),
+        &donation_protocol.key(),
+        donation_protocol.treasury_owner_bump,
+        amount,
+    )?;
+
+    emit!(TreasuryWithdrawn {
+        donation_protocol: donation_protocol.key(),
+        treasury: ctx.accounts.treasury.key(),
+        destination: ctx.accounts.destination.key(),
+        authority: ctx.accounts.authority.key(),
+        amount,
+    });
+
+    Ok(())
+}
@@ -1,8 +1,16 @@
 use anchor_lang::prelude::*;
 pub mod instructions;
 use instructions::*;
+use states::{EmissionCarryOver, HardCapMode, PerkTierArgs};
 
+pub mod donations;
 pub mod errors;
+pub mod events;
+pub mod payouts;
+pub mod pledges;
+pub mod receipts;
+pub mod rewards;
+pub mod rounds;
 pub mod states;
 pub mod utils;
 
@@ -40,15 +48,29 @@ pub mod donaproto {
         ipfs_hash: String,
         ending_timestamp: u64,
         holding_bump: u8,
+        hard_cap_mode: HardCapMode,
+        tiers: Vec<PerkTierArgs>,
+        refundable: bool,
     ) -> Result<()> {
-        instructions::create_donation(ctx, amount, ipfs_hash, ending_timestamp, holding_bump)
+        instructions::create_donation(
+            ctx,
+            amount,
+            ipfs_hash,
+            ending_timestamp,
+            holding_bump,
+            hard_cap_mode,
+            tiers,
+            refundable,
+        )
     }
 
     pub fn donate(ctx: Context<Donate>, amount: u64) -> Result<()> {
         instructions::donate(ctx, amount)
     }
 
-    pub fn withdraw_funds(ctx: Context<WithdrawFunds>) -> Result<()> {
+    pub fn withdraw_funds<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawFunds<'info>>,
+    ) -> Result<()> {
         instructions::withdraw_funds(ctx)
     }
 
@@ -66,15 +88,174 @@ pub mod donaproto {
         ipfs_hash: String,
         ending_timestamp: u64,
         holding_bump: u8,
+        hard_cap_mode: HardCapMode,
+        tiers: Vec<PerkTierArgs>,
+        refundable: bool,
     ) -> Result<()> {
-        instructions::create_donation_v2(ctx, amount, ipfs_hash, ending_timestamp, holding_bump)
+        instructions::create_donation_v2(
+            ctx,
+            amount,
+            ipfs_hash,
+            ending_timestamp,
+            holding_bump,
+            hard_cap_mode,
+            tiers,
+            refundable,
+        )
     }
 
     pub fn donate_v2(ctx: Context<DonateV2>, amount: u64) -> Result<()> {
         instructions::donate_v2(ctx, amount)
     }
 
-    pub fn withdraw_funds_v2(ctx: Context<WithdrawFundsV2>) -> Result<()> {
+    pub fn withdraw_funds_v2<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawFundsV2<'info>>,
+    ) -> Result<()> {
         instructions::withdraw_funds_v2(ctx)
     }
+
+    pub fn donate_on_behalf(ctx: Context<DonateOnBehalf>, amount: u64) -> Result<()> {
+        instructions::donate_on_behalf(ctx, amount)
+    }
+
+    pub fn create_subscription(
+        ctx: Context<CreateSubscription>,
+        amount: u64,
+        period: u64,
+        ending_timestamp: u64,
+    ) -> Result<()> {
+        instructions::create_subscription(ctx, amount, period, ending_timestamp)
+    }
+
+    pub fn execute_subscription(ctx: Context<ExecuteSubscription>) -> Result<()> {
+        instructions::execute_subscription(ctx)
+    }
+
+    pub fn cancel_subscription(ctx: Context<CancelSubscription>) -> Result<()> {
+        instructions::cancel_subscription(ctx)
+    }
+
+    pub fn create_payout_split<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePayoutSplit<'info>>,
+        shares_bps: Vec<u16>,
+    ) -> Result<()> {
+        instructions::create_payout_split(ctx, shares_bps)
+    }
+
+    pub fn set_protocol_fee(ctx: Context<SetProtocolFee>, fee_bps: u16) -> Result<()> {
+        instructions::set_protocol_fee(ctx, fee_bps)
+    }
+
+    pub fn set_mint_fee(ctx: Context<SetMintFee>, fee_bps: u16) -> Result<()> {
+        instructions::set_mint_fee(ctx, fee_bps)
+    }
+
+    pub fn initialize_fee_vault(ctx: Context<InitializeFeeVault>) -> Result<()> {
+        instructions::initialize_fee_vault(ctx)
+    }
+
+    pub fn collect_fees(ctx: Context<CollectFees>, amount: u64) -> Result<()> {
+        instructions::collect_fees(ctx, amount)
+    }
+
+    pub fn fund_treasury(ctx: Context<FundTreasury>, amount: u64) -> Result<()> {
+        instructions::fund_treasury(ctx, amount)
+    }
+
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        instructions::withdraw_treasury(ctx, amount)
+    }
+
+    pub fn rotate_treasury(ctx: Context<RotateTreasury>) -> Result<()> {
+        instructions::rotate_treasury(ctx)
+    }
+
+    pub fn set_emission_schedule(
+        ctx: Context<SetEmissionSchedule>,
+        epoch_budget: u64,
+        epoch_duration: u64,
+        epoch_decay_bps: u16,
+        emission_carry_over: EmissionCarryOver,
+    ) -> Result<()> {
+        instructions::set_emission_schedule(
+            ctx,
+            epoch_budget,
+            epoch_duration,
+            epoch_decay_bps,
+            emission_carry_over,
+        )
+    }
+
+    pub fn initialize_reward_vesting(ctx: Context<InitializeRewardVesting>) -> Result<()> {
+        instructions::initialize_reward_vesting(ctx)
+    }
+
+    pub fn set_reward_vesting(
+        ctx: Context<SetRewardVesting>,
+        vesting_cliff: u64,
+        vesting_duration: u64,
+    ) -> Result<()> {
+        instructions::set_reward_vesting(ctx, vesting_cliff, vesting_duration)
+    }
+
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        instructions::claim_rewards(ctx)
+    }
+
+    pub fn set_self_funding_cap(
+        ctx: Context<SetSelfFundingCap>,
+        max_self_funded_bps: u16,
+    ) -> Result<()> {
+        instructions::set_self_funding_cap(ctx, max_self_funded_bps)
+    }
+
+    pub fn refund_donation(ctx: Context<RefundDonation>, amount: u64) -> Result<()> {
+        instructions::refund_donation(ctx, amount)
+    }
+
+    pub fn create_funding_round<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateFundingRound<'info>>,
+        start_timestamp: u64,
+        end_timestamp: u64,
+    ) -> Result<()> {
+        instructions::create_funding_round(ctx, start_timestamp, end_timestamp)
+    }
+
+    pub fn finalize_round(ctx: Context<FinalizeRound>) -> Result<()> {
+        instructions::finalize_round(ctx)
+    }
+
+    pub fn distribute_match<'info>(
+        ctx: Context<'_, '_, '_, 'info, DistributeMatch<'info>>,
+    ) -> Result<()> {
+        instructions::distribute_match(ctx)
+    }
+
+    pub fn sweep_matching_vault(ctx: Context<SweepMatchingVault>) -> Result<()> {
+        instructions::sweep_matching_vault(ctx)
+    }
+
+    pub fn create_match_pledge(
+        ctx: Context<CreateMatchPledge>,
+        ratio_bps: u16,
+        cap: u64,
+        deadline: u64,
+    ) -> Result<()> {
+        instructions::create_match_pledge(ctx, ratio_bps, cap, deadline)
+    }
+
+    pub fn reclaim_match_pledge(ctx: Context<ReclaimMatchPledge>) -> Result<()> {
+        instructions::reclaim_match_pledge(ctx)
+    }
+
+    pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::migrate_account(ctx)
+    }
+
+    pub fn donate_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, DonateBatch<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        instructions::donate_batch(ctx, amounts)
+    }
 }
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer};
+
+use crate::{
+    errors::DonationError,
+    states::{PayoutSplit, BPS_DENOMINATOR},
+};
+
+/// Protocol share of a withdrawal, rounded down in favour of recipients
+pub fn calculate_protocol_fee(amount: u64, fee_bps: u16) -> u64 {
+    (amount as u128 * fee_bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+/// Distribute `amount` from the holding wallet between the payout split recipients,
+/// whose token wallets are passed in the same order as they are stored in the split
+pub fn transfer_split_payouts<'info>(
+    token_program: AccountInfo<'info>,
+    holding_wallet: AccountInfo<'info>,
+    holding_wallet_owner: AccountInfo<'info>,
+    payout_split: &mut PayoutSplit,
+    recipient_token_wallets: &[AccountInfo<'info>],
+    signer: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    if recipient_token_wallets.len() != payout_split.recipients.len() {
+        return Err(DonationError::InvalidPayoutSplit.into());
+    }
+
+    let amounts = payout_split.split_amounts(amount);
+    for ((recipient, recipient_token_wallet), recipient_amount) in payout_split
+        .recipients
+        .iter_mut()
+        .zip(recipient_token_wallets)
+        .zip(amounts)
+    {
+        if recipient.token_wallet != recipient_token_wallet.key() {
+            return Err(DonationError::InvalidPayoutSplit.into());
+        }
+        if recipient_amount == 0 {
+            continue;
+        }
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                Transfer {
+                    from: holding_wallet.clone(),
+                    to: recipient_token_wallet.clone(),
+                    authority: holding_wallet_owner.clone(),
+                },
+                signer,
+            ),
+            recipient_amount,
+        )?;
+        recipient.total_amount_received = recipient
+            .total_amount_received
+            .checked_add(recipient_amount)
+            .unwrap();
+    }
+
+    Ok(())
+}
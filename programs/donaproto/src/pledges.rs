@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TokenAccount, Transfer};
+
+use crate::{
+    errors::DonationError,
+    states::{DonationData, MatchPledge},
+    MATCH_PLEDGE_PREFIX,
+};
+
+/// Moves the sponsor match for a donation of `amount` from the pledge escrow
+/// into the holding wallet, returns the matched amount
+pub fn transfer_pledge_match<'info>(
+    donation_data: &mut DonationData,
+    match_pledge: Option<&mut Account<'info, MatchPledge>>,
+    match_pledge_escrow: Option<&Account<'info, TokenAccount>>,
+    token_program: AccountInfo<'info>,
+    holding_wallet: AccountInfo<'info>,
+    amount: u64,
+) -> Result<u64> {
+    if donation_data.match_pledge == Pubkey::default() {
+        return Ok(0);
+    }
+    let match_pledge = match match_pledge {
+        Some(match_pledge) if match_pledge.key() == donation_data.match_pledge => match_pledge,
+        _ => return Err(DonationError::MatchPledgeRequired.into()),
+    };
+    let now_timestamp = Clock::get()?.unix_timestamp as u64;
+    if now_timestamp > match_pledge.deadline {
+        return Ok(0);
+    }
+    let match_pledge_escrow = match match_pledge_escrow {
+        Some(escrow) if escrow.key() == match_pledge.escrow => escrow,
+        _ => return Err(DonationError::MatchPledgeRequired.into()),
+    };
+
+    let mut match_amount = match_pledge.match_amount(amount, match_pledge_escrow.amount);
+    if donation_data.is_hard_capped() {
+        match_amount = match_amount.min(donation_data.headroom());
+    }
+    if match_amount == 0 {
+        return Ok(0);
+    }
+
+    let seeds = &[
+        MATCH_PLEDGE_PREFIX.as_bytes(),
+        match_pledge.donation_data.as_ref(),
+        &[match_pledge.bump],
+    ];
+    let signer = &[&seeds[..]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program,
+            Transfer {
+                from: match_pledge_escrow.to_account_info(),
+                to: holding_wallet,
+                authority: match_pledge.to_account_info(),
+            },
+            signer,
+        ),
+        match_amount,
+    )?;
+
+    match_pledge.total_amount_matched = match_pledge
+        .total_amount_matched
+        .checked_add(match_amount)
+        .unwrap();
+    donation_data.total_amount_received = donation_data
+        .total_amount_received
+        .checked_add(match_amount)
+        .unwrap();
+    donation_data.total_amount_matched = donation_data
+        .total_amount_matched
+        .checked_add(match_amount)
+        .unwrap();
+
+    Ok(match_amount)
+}
@@ -0,0 +1,161 @@
+use anchor_lang::{
+    error::ErrorCode,
+    prelude::*,
+    system_program::{self, CreateAccount},
+};
+use anchor_spl::{
+    memo::{self, BuildMemo, Memo},
+    token::{
+        self, spl_token::instruction::AuthorityType, Mint, MintTo, SetAuthority, TokenAccount,
+    },
+};
+
+use crate::{
+    errors::DonationError,
+    states::{DonationReceipt, ReceiptMetadata, DISCRIMINATOR_LEN},
+    RECEIPT_METADATA_PREFIX, RECEIPT_MINT_AUTHORITY_PREFIX,
+};
+
+/// Optional receipt accounts of `donate`/`donate_v2`,
+/// a receipt is issued when `receipt_mint` is set and then every other account is required
+pub struct ReceiptAccounts<'a, 'info> {
+    pub receipt_mint: Option<&'a Account<'info, Mint>>,
+    pub receipt_token_account: Option<&'a Account<'info, TokenAccount>>,
+    pub receipt_metadata: Option<&'a AccountInfo<'info>>,
+    /// Authority pda with its bump
+    pub receipt_mint_authority: Option<(&'a AccountInfo<'info>, u8)>,
+    pub memo_program: Option<&'a Program<'info, Memo>>,
+    pub payer: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+/// Donation the receipt is issued for
+pub struct ReceiptDonation {
+    pub donation_protocol: Pubkey,
+    pub donation_data: Pubkey,
+    pub donation_mint: Pubkey,
+    pub donor: Pubkey,
+    /// In donation mint units, after the hard cap
+    pub amount: u64,
+}
+
+/// Mints the one-of-one receipt token to the donor and revokes the mint authority.
+/// `ReceiptMetadata` keeps the donation on chain, the same fields are logged
+/// through the memo program so wallets and explorers show them with the transaction
+pub fn issue_donation_receipt(
+    accounts: ReceiptAccounts,
+    donation: ReceiptDonation,
+    donation_receipt: &mut DonationReceipt,
+) -> Result<()> {
+    let receipt_mint = match accounts.receipt_mint {
+        Some(receipt_mint) => receipt_mint,
+        None => return Ok(()),
+    };
+    let (receipt_token_account, receipt_metadata_info, receipt_mint_authority, memo_program) =
+        match (
+            accounts.receipt_token_account,
+            accounts.receipt_metadata,
+            accounts.receipt_mint_authority,
+            accounts.memo_program,
+        ) {
+            (Some(token_account), Some(metadata), Some(authority), Some(memo_program)) => {
+                (token_account, metadata, authority, memo_program)
+            }
+            _ => return Err(DonationError::ReceiptAccountsRequired.into()),
+        };
+    let (receipt_mint_authority, receipt_mint_authority_bump) = receipt_mint_authority;
+
+    let seeds = &[
+        RECEIPT_MINT_AUTHORITY_PREFIX.as_bytes(),
+        donation.donation_protocol.as_ref(),
+        &[receipt_mint_authority_bump],
+    ];
+    let signer = &[&seeds[..]];
+    token::mint_to(
+        CpiContext::new_with_signer(
+            accounts.token_program.clone(),
+            MintTo {
+                mint: receipt_mint.to_account_info(),
+                to: receipt_token_account.to_account_info(),
+                authority: receipt_mint_authority.clone(),
+            },
+            signer,
+        ),
+        1,
+    )?;
+    // supply stays at one for good
+    token::set_authority(
+        CpiContext::new_with_signer(
+            accounts.token_program.clone(),
+            SetAuthority {
+                current_authority: receipt_mint_authority.clone(),
+                account_or_mint: receipt_mint.to_account_info(),
+            },
+            signer,
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+
+    let mut receipt_metadata =
+        create_receipt_metadata(&accounts, &receipt_mint.key(), receipt_metadata_info)?;
+    receipt_metadata.receipt_mint = receipt_mint.key();
+    receipt_metadata.donation_data = donation.donation_data;
+    receipt_metadata.donor = donation.donor;
+    receipt_metadata.donation_mint = donation.donation_mint;
+    receipt_metadata.amount = donation.amount;
+    receipt_metadata.timestamp = Clock::get()?.unix_timestamp as u64;
+    memo::build_memo(
+        CpiContext::new(memo_program.to_account_info(), BuildMemo {}),
+        receipt_metadata.memo().as_bytes(),
+    )?;
+    receipt_metadata.exit(&crate::ID)?;
+
+    donation_receipt.record_receipted(donation.amount);
+
+    Ok(())
+}
+
+/// Metadata pda of `receipt_mint`, its seeds depend on an optional account
+/// so it is created here instead of by an `init` constraint
+fn create_receipt_metadata<'info>(
+    accounts: &ReceiptAccounts<'_, 'info>,
+    receipt_mint: &Pubkey,
+    receipt_metadata_info: &AccountInfo<'info>,
+) -> Result<Account<'info, ReceiptMetadata>> {
+    let (receipt_metadata_key, bump) = Pubkey::find_program_address(
+        &[RECEIPT_METADATA_PREFIX.as_bytes(), receipt_mint.as_ref()],
+        &crate::ID,
+    );
+    if receipt_metadata_info.key() != receipt_metadata_key {
+        return Err(ErrorCode::ConstraintSeeds.into());
+    }
+
+    let space = DISCRIMINATOR_LEN + ReceiptMetadata::INIT_SPACE;
+    let seeds = &[
+        RECEIPT_METADATA_PREFIX.as_bytes(),
+        receipt_mint.as_ref(),
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            accounts.system_program.clone(),
+            CreateAccount {
+                from: accounts.payer.clone(),
+                to: receipt_metadata_info.clone(),
+            },
+            signer,
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &crate::ID,
+    )?;
+
+    let mut receipt_metadata =
+        Account::<ReceiptMetadata>::try_from_unchecked(receipt_metadata_info)?;
+    receipt_metadata.bump = bump;
+
+    Ok(receipt_metadata)
+}
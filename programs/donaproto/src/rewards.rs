@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer};
+
+use crate::{
+    errors::DonationError,
+    states::{ContributorData, DonationData, DonationProtocolData, RewardVesting},
+    TREASURY_PREFIX,
+};
+
+/// Reward for a donation worth `amount` in default donation mint units,
+/// limited by the treasury balance and by the emission budget left in the current epoch
+pub fn calculate_reward_amount(
+    donation_protocol: &mut DonationProtocolData,
+    amount: u64,
+    reward_treasury_balance: u64,
+) -> Result<u64> {
+    if amount < donation_protocol.min_amount_to_earn {
+        return Ok(0);
+    }
+
+    // vested rewards are still held by the treasury
+    let reward_treasury_balance =
+        reward_treasury_balance.saturating_sub(donation_protocol.total_reward_vesting);
+
+    // TODO: add calculation for reward amount
+    let mut reward_amount = amount;
+    if reward_amount > reward_treasury_balance {
+        reward_amount = reward_treasury_balance.checked_div(100).unwrap();
+    }
+    // END
+
+    let now_timestamp = Clock::get()?.unix_timestamp as u64;
+    donation_protocol.roll_emission_epoch(now_timestamp);
+
+    Ok(donation_protocol.consume_emission(reward_amount))
+}
+
+/// Records self-funded volume of the donation and tells whether it may earn a reward:
+/// creators and recipient owners earn nothing on their own campaigns,
+/// and nobody does once the campaign is self-funded above the protocol cap
+pub fn track_reward_eligibility(
+    donation_data: &mut DonationData,
+    donors: &[Pubkey],
+    recipient_owner: &Pubkey,
+    amount: u64,
+    max_self_funded_bps: u16,
+) -> bool {
+    if donors
+        .iter()
+        .any(|donor| donation_data.is_self_funded_by(donor, recipient_owner))
+    {
+        donation_data.self_funded_amount = donation_data
+            .self_funded_amount
+            .checked_add(amount)
+            .unwrap();
+        return false;
+    }
+
+    !donation_data.is_over_self_funding_cap(max_self_funded_bps)
+}
+
+/// Transfers the reward to `destination`, or accrues it into `reward_vesting`
+/// when the protocol vests rewards, and credits the contributor
+#[allow(clippy::too_many_arguments)]
+pub fn distribute_reward<'info>(
+    donation_protocol: &mut Account<'info, DonationProtocolData>,
+    contributor_data: &mut ContributorData,
+    reward_vesting: Option<&mut Account<'info, RewardVesting>>,
+    token_program: AccountInfo<'info>,
+    reward_treasury: AccountInfo<'info>,
+    reward_treasury_owner: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    reward_amount: u64,
+) -> Result<()> {
+    if reward_amount == 0 {
+        return Ok(());
+    }
+
+    if donation_protocol.is_reward_vesting() {
+        let reward_vesting = match reward_vesting {
+            Some(reward_vesting) => reward_vesting,
+            None => return Err(DonationError::RewardVestingRequired.into()),
+        };
+        let now_timestamp = Clock::get()?.unix_timestamp as u64;
+        reward_vesting.accrue(
+            reward_amount,
+            now_timestamp,
+            donation_protocol.vesting_cliff,
+            donation_protocol.vesting_duration,
+        );
+        donation_protocol.total_reward_vesting = donation_protocol
+            .total_reward_vesting
+            .checked_add(reward_amount)
+            .unwrap();
+        contributor_data.total_amount_vested = contributor_data
+            .total_amount_vested
+            .checked_add(reward_amount)
+            .unwrap();
+    } else {
+        transfer_reward(
+            token_program,
+            reward_treasury,
+            reward_treasury_owner,
+            destination,
+            &donation_protocol.key(),
+            donation_protocol.treasury_owner_bump,
+            reward_amount,
+        )?;
+    }
+
+    contributor_data.total_amount_earned = contributor_data
+        .total_amount_earned
+        .checked_add(reward_amount)
+        .unwrap();
+
+    Ok(())
+}
+
+/// Transfer reward tokens from the treasury, signed by the ["treasury", donation_protocol] pda
+pub fn transfer_reward<'info>(
+    token_program: AccountInfo<'info>,
+    reward_treasury: AccountInfo<'info>,
+    reward_treasury_owner: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    donation_protocol: &Pubkey,
+    treasury_owner_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[
+        TREASURY_PREFIX.as_bytes(),
+        donation_protocol.as_ref(),
+        &[treasury_owner_bump],
+    ];
+    let signer = &[&seeds[..]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program,
+            Transfer {
+                from: reward_treasury,
+                to: destination,
+                authority: reward_treasury_owner,
+            },
+            signer,
+        ),
+        amount,
+    )
+}
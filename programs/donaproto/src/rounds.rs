@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::DonationError,
+    states::{DonationReceipt, FundingRound},
+};
+
+/// Integer square root, rounded down
+pub fn isqrt(value: u64) -> u64 {
+    if value < 2 {
+        return value;
+    }
+
+    let mut x = value;
+    let mut y = (x + 1) / 2;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+
+    x
+}
+
+/// Adds `amount` donated during the round to the donor receipt
+/// and updates the campaign sqrt-sum by the change of the donor square root
+pub fn track_round_contribution<'info>(
+    funding_round: Option<&mut Account<'info, FundingRound>>,
+    donation_data: &Pubkey,
+    donation_receipt: &mut DonationReceipt,
+    amount: u64,
+) -> Result<()> {
+    let funding_round = match funding_round {
+        Some(funding_round) => funding_round,
+        None => return Ok(()),
+    };
+    let now_timestamp = Clock::get()?.unix_timestamp as u64;
+    if !funding_round.is_active(now_timestamp) {
+        return Err(DonationError::RoundNotActive.into());
+    }
+
+    if donation_receipt.funding_round != funding_round.key() {
+        donation_receipt.funding_round = funding_round.key();
+        donation_receipt.round_amount_donated = 0;
+    }
+    let previous_amount = donation_receipt.round_amount_donated;
+    let round_amount_donated = previous_amount.checked_add(amount).unwrap();
+
+    let campaign = match funding_round.campaign_mut(donation_data) {
+        Some(campaign) => campaign,
+        None => return Err(DonationError::CampaignNotInRound.into()),
+    };
+    campaign.sqrt_sum = campaign
+        .sqrt_sum
+        .checked_sub(isqrt(previous_amount))
+        .unwrap()
+        .checked_add(isqrt(round_amount_donated))
+        .unwrap();
+    campaign.total_amount_contributed = campaign
+        .total_amount_contributed
+        .checked_add(amount)
+        .unwrap();
+    donation_receipt.round_amount_donated = round_amount_donated;
+
+    Ok(())
+}
+
+/// Removes refunded `amount` from the round the donor contributed in,
+/// contributions can not be refunded once the round is finalized
+pub fn untrack_round_contribution<'info>(
+    funding_round: Option<&mut Account<'info, FundingRound>>,
+    donation_data: &Pubkey,
+    donation_receipt: &mut DonationReceipt,
+    amount: u64,
+) -> Result<()> {
+    if donation_receipt.round_amount_donated == 0 {
+        return Ok(());
+    }
+    let funding_round = match funding_round {
+        Some(funding_round) if funding_round.key() == donation_receipt.funding_round => {
+            funding_round
+        }
+        _ => return Err(DonationError::InvalidFundingRound.into()),
+    };
+    if funding_round.is_finalized {
+        return Err(DonationError::RoundContributionLocked.into());
+    }
+
+    let previous_amount = donation_receipt.round_amount_donated;
+    let removed_amount = amount.min(previous_amount);
+    let round_amount_donated = previous_amount - removed_amount;
+
+    let campaign = match funding_round.campaign_mut(donation_data) {
+        Some(campaign) => campaign,
+        None => return Err(DonationError::CampaignNotInRound.into()),
+    };
+    campaign.sqrt_sum = campaign
+        .sqrt_sum
+        .checked_sub(isqrt(previous_amount))
+        .unwrap()
+        .checked_add(isqrt(round_amount_donated))
+        .unwrap();
+    campaign.total_amount_contributed = campaign
+        .total_amount_contributed
+        .checked_sub(removed_amount)
+        .unwrap();
+    donation_receipt.round_amount_donated = round_amount_donated;
+
+    Ok(())
+}
@@ -1 +1,23 @@
+use anchor_lang::{prelude::*, Discriminator};
+
 pub const DISCRIMINATOR_LEN: usize = 8;
+
+pub const RESERVED_LEN: usize = 32;
+
+/// Accounts carrying a layout `version`, every account type counts its own versions
+pub trait Versioned {
+    /// Layout version written at creation and by `migrate_account`,
+    /// accounts written before versioning read as version 0
+    const VERSION: u8;
+
+    fn version(&self) -> u8;
+}
+
+/// Borsh accounts `migrate_account` upgrades in place. Fields are only appended or carved out of
+/// `reserved`, so an older account grown by realloc reads every newer field as zero.
+pub trait Migrate: Versioned + AccountSerialize + AccountDeserialize + Discriminator {
+    const SPACE: usize;
+
+    /// Fills fields whose default is not zero and bumps the version to `VERSION`
+    fn migrate(&mut self);
+}
@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
 use std::mem;
 
-use super::DISCRIMINATOR_LEN;
+use super::{Migrate, Versioned, DISCRIMINATOR_LEN, RESERVED_LEN};
+use crate::errors::DonationError;
 
 #[account]
 #[derive(Default)]
@@ -11,6 +12,11 @@ pub struct ContributorData {
     pub donations_count: u64,
     pub donation_protocol: Pubkey,
     pub bump: u8,
+    pub total_amount_vested: u64,
+    pub total_amount_claimed: u64,
+    pub version: u8,
+    /// Zeroed space for future fields
+    pub reserved: [u8; RESERVED_LEN],
 }
 
 impl ContributorData {
@@ -19,10 +25,61 @@ impl ContributorData {
     const DONATIONS_COUNT_LEN: usize = mem::size_of::<u64>();
     const DONATION_PROTOCOL_LEN: usize = mem::size_of::<Pubkey>();
     const BUMP_LEN: usize = mem::size_of::<u8>();
+    const TOTAL_AMOUNT_VESTED_LEN: usize = mem::size_of::<u64>();
+    const TOTAL_AMOUNT_CLAIMED_LEN: usize = mem::size_of::<u64>();
+    const VERSION_LEN: usize = mem::size_of::<u8>();
     pub const LEN: usize = DISCRIMINATOR_LEN
         + ContributorData::TOTAL_AMOUNT_DONATED_LEN
         + ContributorData::TOTAL_AMOUNT_EARNED_LEN
         + ContributorData::DONATIONS_COUNT_LEN
         + ContributorData::DONATION_PROTOCOL_LEN
-        + ContributorData::BUMP_LEN;
+        + ContributorData::BUMP_LEN
+        + ContributorData::TOTAL_AMOUNT_VESTED_LEN
+        + ContributorData::TOTAL_AMOUNT_CLAIMED_LEN
+        + ContributorData::VERSION_LEN
+        + RESERVED_LEN;
+}
+
+impl ContributorData {
+    /// Fills a contributor account created on the fly by `init_if_needed`,
+    /// or checks that an already initialized one belongs to the protocol.
+    pub fn init_if_needed(&mut self, donation_protocol: Pubkey, bump: u8) -> Result<()> {
+        if self.donation_protocol == Pubkey::default() {
+            self.total_amount_donated = 0;
+            self.total_amount_earned = 0;
+            self.donations_count = 0;
+            self.total_amount_vested = 0;
+            self.total_amount_claimed = 0;
+            self.donation_protocol = donation_protocol;
+            self.bump = bump;
+            self.version = ContributorData::VERSION;
+
+            return Ok(());
+        }
+
+        if self.donation_protocol != donation_protocol {
+            return Err(DonationError::InvalidContributorData.into());
+        }
+        if self.bump != bump {
+            return Err(DonationError::InvalidBump.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl Versioned for ContributorData {
+    const VERSION: u8 = 1;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+}
+
+impl Migrate for ContributorData {
+    const SPACE: usize = ContributorData::LEN;
+
+    fn migrate(&mut self) {
+        self.version = ContributorData::VERSION;
+    }
 }
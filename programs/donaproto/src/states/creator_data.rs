@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use std::mem;
 
-use super::DISCRIMINATOR_LEN;
+use super::{Migrate, Versioned, DISCRIMINATOR_LEN, RESERVED_LEN};
 
 #[account]
 #[derive(Default)]
@@ -11,6 +11,9 @@ pub struct CreatorData {
     pub donations_created_count: u64,
     pub donations_closed_count: u64,
     pub donation_protocol: Pubkey,
+    pub version: u8,
+    /// Zeroed space for future fields
+    pub reserved: [u8; RESERVED_LEN],
 }
 
 impl CreatorData {
@@ -19,10 +22,29 @@ impl CreatorData {
   const DONATIONS_CREATED_COUNT_LEN: usize = mem::size_of::<u64>();
   const DONATIONS_CLOSED_COUNT_LEN: usize = mem::size_of::<u64>();
   const DONATION_PROTOCOL_LEN: usize = mem::size_of::<Pubkey>();
+  const VERSION_LEN: usize = mem::size_of::<u8>();
   pub const LEN: usize = DISCRIMINATOR_LEN
     + CreatorData::TOTAL_AMOUNT_RECEIVED_LEN
     + CreatorData::TOTAL_AMOUNT_COLLECTING_LEN
     + CreatorData::DONATIONS_CREATED_COUNT_LEN
     + CreatorData::DONATIONS_CLOSED_COUNT_LEN
-    + CreatorData::DONATION_PROTOCOL_LEN;
+    + CreatorData::DONATION_PROTOCOL_LEN
+    + CreatorData::VERSION_LEN
+    + RESERVED_LEN;
+}
+
+impl Versioned for CreatorData {
+  const VERSION: u8 = 1;
+
+  fn version(&self) -> u8 {
+    self.version
+  }
+}
+
+impl Migrate for CreatorData {
+  const SPACE: usize = CreatorData::LEN;
+
+  fn migrate(&mut self) {
+    self.version = CreatorData::VERSION;
+  }
 }
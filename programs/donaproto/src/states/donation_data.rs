@@ -1,27 +1,256 @@
-use anchor_lang::prelude::*;
-use std::mem;
+use anchor_lang::{prelude::*, Discriminator};
+use bytemuck::Zeroable;
+use std::{
+    cell::{Ref, RefMut},
+    mem,
+};
 
-use super::DISCRIMINATOR_LEN;
+use super::{Versioned, BPS_DENOMINATOR, DISCRIMINATOR_LEN, RESERVED_LEN};
+use crate::{errors::DonationError, HOLDING_PREFIX};
 
-#[account]
-#[derive(Default)]
+/// How donations past `amount_collecting` are handled, chosen at creation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HardCapMode {
+    /// Goal is only a threshold for early withdrawal
+    #[default]
+    None,
+    /// Donations that would exceed the goal fail
+    Reject,
+    /// Only the headroom left before the goal is accepted
+    Trim,
+}
+
+/// Hot path state of a campaign, read without deserialization on every donation.
+/// Booleans and enums are stored as `u8` to keep the layout `Pod`.
+#[account(zero_copy)]
 pub struct DonationData {
     pub amount_collecting: u64,
     pub total_amount_received: u64,
     pub ending_timestamp: u64,
-    pub is_closed: bool,
+    pub fee_amount: u64,
+    /// Donated by the creator or the current recipient owner, never rewarded
+    pub self_funded_amount: u64,
+    /// Part of `total_amount_received` paid by matching funds
+    pub total_amount_matched: u64,
     pub recipient: Pubkey,
     pub donation_protocol: Pubkey,
     pub holding_wallet: Pubkey,
     pub creator_data: Pubkey,
     pub donation_mint: Pubkey,
+    pub payout_split: Pubkey,
+    pub creator_wallet: Pubkey,
+    pub match_pledge: Pubkey,
+    /// Set when the donation is created with perk tiers
+    pub donation_tiers: Pubkey,
+    /// UTF-8 CID padded with zeros
+    pub ipfs_hash: [u8; MAX_IPFS_HASH_LEN],
+    pub fee_bps: u16,
+    pub is_closed: u8,
     pub holding_bump: u8,
-    pub ipfs_hash: String,
+    /// `HardCapMode` variant index
+    pub hard_cap_mode: u8,
+    pub version: u8,
+    /// Donors may refund while the donation runs, chosen at creation
+    pub is_refundable: u8,
+    pub padding: [u8; 1],
+    /// Zeroed space for future fields
+    pub reserved: [u8; RESERVED_LEN],
 }
 
 pub const MAX_IPFS_HASH_LEN: usize = 64;
 
 impl DonationData {
+    pub const LEN: usize = DISCRIMINATOR_LEN + mem::size_of::<DonationData>();
+
+    /// Reads account data including its discriminator, unlike `AccountDeserialize`
+    /// it neither needs aligned data nor panics on a borsh layout awaiting migration
+    pub fn try_from_account_data(data: &[u8]) -> Result<Self> {
+        if data.get(..DISCRIMINATOR_LEN) != Some(&DonationData::DISCRIMINATOR[..]) {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        match bytemuck::try_pod_read_unaligned(&data[DISCRIMINATOR_LEN..]) {
+            Ok(donation_data) => Ok(donation_data),
+            Err(_) => Err(ErrorCode::AccountDidNotDeserialize.into()),
+        }
+    }
+
+    /// Accounts on the borsh layout are shorter until `migrate_account` grows them,
+    /// mapping one would fail with an unhelpful error
+    pub fn check_migrated(donation_data: &AccountInfo) -> Result<()> {
+        if donation_data.data_len() != DonationData::LEN {
+            return Err(DonationError::AccountNotMigrated.into());
+        }
+
+        Ok(())
+    }
+
+    /// `AccountLoader::load` behind `check_migrated`
+    pub fn load_checked<'a>(
+        donation_data: &'a AccountLoader<DonationData>,
+    ) -> Result<Ref<'a, DonationData>> {
+        Self::check_migrated(donation_data.as_ref())?;
+        donation_data.load()
+    }
+
+    /// `AccountLoader::load_mut` behind `check_migrated`
+    pub fn load_checked_mut<'a>(
+        donation_data: &'a AccountLoader<DonationData>,
+    ) -> Result<RefMut<'a, DonationData>> {
+        Self::check_migrated(donation_data.as_ref())?;
+        donation_data.load_mut()
+    }
+
+    /// Donation instructions check their accounts here instead of in constraints,
+    /// so the hot path maps the account data once
+    pub fn check_donation_accounts(
+        &self,
+        donation_protocol: &Pubkey,
+        holding_wallet: &Pubkey,
+        recipient: &Pubkey,
+    ) -> Result<()> {
+        if self.donation_protocol != *donation_protocol
+            || self.holding_wallet != *holding_wallet
+            || self.recipient != *recipient
+        {
+            return Err(DonationError::DonationAccountMismatch.into());
+        }
+
+        Ok(())
+    }
+
+    /// Pda ["holding", donation_data] signing for the holding wallet
+    pub fn check_holding_wallet_owner(
+        &self,
+        donation_data: &Pubkey,
+        holding_wallet_owner: &Pubkey,
+    ) -> Result<()> {
+        match Pubkey::create_program_address(
+            &[
+                HOLDING_PREFIX.as_bytes(),
+                donation_data.as_ref(),
+                &[self.holding_bump],
+            ],
+            &crate::ID,
+        ) {
+            Ok(expected) if expected == *holding_wallet_owner => Ok(()),
+            _ => Err(DonationError::DonationAccountMismatch.into()),
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.is_closed != 0
+    }
+
+    pub fn close(&mut self) {
+        self.is_closed = 1;
+    }
+
+    pub fn is_refundable(&self) -> bool {
+        self.is_refundable != 0
+    }
+
+    pub fn hard_cap_mode(&self) -> HardCapMode {
+        match self.hard_cap_mode {
+            1 => HardCapMode::Reject,
+            2 => HardCapMode::Trim,
+            _ => HardCapMode::None,
+        }
+    }
+
+    pub fn ipfs_hash(&self) -> String {
+        let len = self
+            .ipfs_hash
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(MAX_IPFS_HASH_LEN);
+        String::from_utf8_lossy(&self.ipfs_hash[..len]).into_owned()
+    }
+
+    pub fn set_ipfs_hash(&mut self, ipfs_hash: &str) -> Result<()> {
+        if ipfs_hash.len() > MAX_IPFS_HASH_LEN {
+            return Err(DonationError::IpfsHashTooLong.into());
+        }
+        self.ipfs_hash = [0; MAX_IPFS_HASH_LEN];
+        self.ipfs_hash[..ipfs_hash.len()].copy_from_slice(ipfs_hash.as_bytes());
+
+        Ok(())
+    }
+
+    pub fn is_hard_capped(&self) -> bool {
+        self.hard_cap_mode() != HardCapMode::None
+    }
+
+    pub fn headroom(&self) -> u64 {
+        self.amount_collecting
+            .saturating_sub(self.total_amount_received)
+    }
+
+    /// Part of a donation of `amount` the hard cap lets in,
+    /// a capped donation reaching its goal becomes withdrawable right away
+    pub fn accepted_amount(&self, amount: u64) -> Result<u64> {
+        match self.hard_cap_mode() {
+            HardCapMode::None => Ok(amount),
+            HardCapMode::Reject if amount > self.headroom() => {
+                Err(DonationError::HardCapExceeded.into())
+            }
+            HardCapMode::Reject => Ok(amount),
+            HardCapMode::Trim if self.headroom() == 0 => Err(DonationError::HardCapExceeded.into()),
+            HardCapMode::Trim => Ok(amount.min(self.headroom())),
+        }
+    }
+
+    /// `recipient_owner` is the current owner of the `recipient` token account,
+    /// which can change hands after the campaign is created
+    pub fn is_self_funded_by(&self, wallet: &Pubkey, recipient_owner: &Pubkey) -> bool {
+        *wallet == self.creator_wallet || wallet == recipient_owner
+    }
+
+    /// `max_self_funded_bps` of zero disables the cap
+    pub fn is_over_self_funding_cap(&self, max_self_funded_bps: u16) -> bool {
+        max_self_funded_bps > 0
+            && self.self_funded_amount as u128 * BPS_DENOMINATOR as u128
+                > self.total_amount_received as u128 * max_self_funded_bps as u128
+    }
+}
+
+impl Versioned for DonationData {
+    /// Versions up to 1 are the borsh layout read by `LegacyDonationData`
+    const VERSION: u8 = 2;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+}
+
+/// Borsh layout of `DonationData` up to version 1, only read by `migrate_account`.
+/// Older accounts are shorter, the fields they lack are read as zero.
+#[derive(AnchorSerialize, AnchorDeserialize, Default)]
+pub struct LegacyDonationData {
+    pub amount_collecting: u64,
+    pub total_amount_received: u64,
+    pub ending_timestamp: u64,
+    pub is_closed: bool,
+    pub recipient: Pubkey,
+    pub donation_protocol: Pubkey,
+    pub holding_wallet: Pubkey,
+    pub creator_data: Pubkey,
+    pub donation_mint: Pubkey,
+    pub holding_bump: u8,
+    pub ipfs_hash: String,
+    pub payout_split: Pubkey,
+    pub fee_bps: u16,
+    pub fee_amount: u64,
+    pub creator_wallet: Pubkey,
+    pub recipient_owner: Pubkey,
+    pub self_funded_amount: u64,
+    pub total_amount_matched: u64,
+    pub match_pledge: Pubkey,
+    pub hard_cap_mode: HardCapMode,
+    pub version: u8,
+    pub reserved: [u8; RESERVED_LEN],
+}
+
+impl LegacyDonationData {
     const AMOUNT_COLLECTING_LEN: usize = mem::size_of::<u64>();
     const TOTAL_AMOUNT_RECEIVED_LEN: usize = mem::size_of::<u64>();
     const ENDING_TIMESTAMP_LEN: usize = mem::size_of::<u64>();
@@ -32,18 +261,77 @@ impl DonationData {
     const CREATOR_DATA_LEN: usize = mem::size_of::<Pubkey>();
     const DONATION_MINT_LEN: usize = mem::size_of::<Pubkey>();
     const HOLDING_BUMP_LEN: usize = mem::size_of::<u8>();
-    const IPFS_HASH_LEN: usize = MAX_IPFS_HASH_LEN;
+    // borsh string is prefixed with its u32 length
+    const IPFS_HASH_LEN: usize = mem::size_of::<u32>() + MAX_IPFS_HASH_LEN;
+    const PAYOUT_SPLIT_LEN: usize = mem::size_of::<Pubkey>();
+    const FEE_BPS_LEN: usize = mem::size_of::<u16>();
+    const FEE_AMOUNT_LEN: usize = mem::size_of::<u64>();
+    const CREATOR_WALLET_LEN: usize = mem::size_of::<Pubkey>();
+    const RECIPIENT_OWNER_LEN: usize = mem::size_of::<Pubkey>();
+    const SELF_FUNDED_AMOUNT_LEN: usize = mem::size_of::<u64>();
+    const TOTAL_AMOUNT_MATCHED_LEN: usize = mem::size_of::<u64>();
+    const MATCH_PLEDGE_LEN: usize = mem::size_of::<Pubkey>();
+    // borsh enum is a single u8 variant index
+    const HARD_CAP_MODE_LEN: usize = mem::size_of::<u8>();
+    const VERSION_LEN: usize = mem::size_of::<u8>();
 
     pub const LEN: usize = DISCRIMINATOR_LEN
-        + DonationData::AMOUNT_COLLECTING_LEN
-        + DonationData::TOTAL_AMOUNT_RECEIVED_LEN
-        + DonationData::ENDING_TIMESTAMP_LEN
-        + DonationData::IS_CLOSED_LEN
-        + DonationData::RECIPIENT_LEN
-        + DonationData::DONATION_PROTOCOL_LEN
-        + DonationData::HOLDING_WALLET_LEN
-        + DonationData::CREATOR_DATA_LEN
-        + DonationData::DONATION_MINT_LEN
-        + DonationData::HOLDING_BUMP_LEN
-        + DonationData::IPFS_HASH_LEN;
+        + LegacyDonationData::AMOUNT_COLLECTING_LEN
+        + LegacyDonationData::TOTAL_AMOUNT_RECEIVED_LEN
+        + LegacyDonationData::ENDING_TIMESTAMP_LEN
+        + LegacyDonationData::IS_CLOSED_LEN
+        + LegacyDonationData::RECIPIENT_LEN
+        + LegacyDonationData::DONATION_PROTOCOL_LEN
+        + LegacyDonationData::HOLDING_WALLET_LEN
+        + LegacyDonationData::CREATOR_DATA_LEN
+        + LegacyDonationData::DONATION_MINT_LEN
+        + LegacyDonationData::HOLDING_BUMP_LEN
+        + LegacyDonationData::IPFS_HASH_LEN
+        + LegacyDonationData::PAYOUT_SPLIT_LEN
+        + LegacyDonationData::FEE_BPS_LEN
+        + LegacyDonationData::FEE_AMOUNT_LEN
+        + LegacyDonationData::CREATOR_WALLET_LEN
+        + LegacyDonationData::RECIPIENT_OWNER_LEN
+        + LegacyDonationData::SELF_FUNDED_AMOUNT_LEN
+        + LegacyDonationData::TOTAL_AMOUNT_MATCHED_LEN
+        + LegacyDonationData::MATCH_PLEDGE_LEN
+        + LegacyDonationData::HARD_CAP_MODE_LEN
+        + LegacyDonationData::VERSION_LEN
+        + RESERVED_LEN;
+
+    /// Reads account data including its discriminator, shorter data is zero padded
+    pub fn try_from_account_data(data: &[u8]) -> Result<Self> {
+        let mut buffer = data.get(DISCRIMINATOR_LEN..).unwrap_or_default().to_vec();
+        buffer.resize(LegacyDonationData::LEN - DISCRIMINATOR_LEN, 0);
+        match LegacyDonationData::deserialize(&mut buffer.as_slice()) {
+            Ok(legacy) => Ok(legacy),
+            Err(_) => Err(DonationError::InvalidMigrationAccount.into()),
+        }
+    }
+
+    pub fn to_donation_data(&self) -> Result<DonationData> {
+        let mut donation_data = DonationData::zeroed();
+        donation_data.amount_collecting = self.amount_collecting;
+        donation_data.total_amount_received = self.total_amount_received;
+        donation_data.ending_timestamp = self.ending_timestamp;
+        donation_data.fee_amount = self.fee_amount;
+        donation_data.self_funded_amount = self.self_funded_amount;
+        donation_data.total_amount_matched = self.total_amount_matched;
+        donation_data.recipient = self.recipient;
+        donation_data.donation_protocol = self.donation_protocol;
+        donation_data.holding_wallet = self.holding_wallet;
+        donation_data.creator_data = self.creator_data;
+        donation_data.donation_mint = self.donation_mint;
+        donation_data.payout_split = self.payout_split;
+        donation_data.creator_wallet = self.creator_wallet;
+        donation_data.match_pledge = self.match_pledge;
+        donation_data.set_ipfs_hash(&self.ipfs_hash)?;
+        donation_data.fee_bps = self.fee_bps;
+        donation_data.is_closed = self.is_closed as u8;
+        donation_data.holding_bump = self.holding_bump;
+        donation_data.hard_cap_mode = self.hard_cap_mode as u8;
+        donation_data.version = DonationData::VERSION;
+
+        Ok(donation_data)
+    }
 }
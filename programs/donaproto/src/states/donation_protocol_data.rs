@@ -1,5 +1,18 @@
 use anchor_lang::prelude::*;
 
+use super::{Migrate, Versioned, BPS_DENOMINATOR, DISCRIMINATOR_LEN, RESERVED_LEN};
+
+/// Maximum number of elapsed epochs replayed at once, older ones are skipped
+pub const MAX_EPOCH_CATCH_UP: u64 = 64;
+
+/// What happens with the emission budget left unused at the end of an epoch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(InitSpace)]
+pub enum EmissionCarryOver {
+    #[default]
+    Forfeit,
+    CarryOver,
+}
 
 #[account]
 #[derive(Default)]
@@ -12,4 +25,98 @@ pub struct DonationProtocolData {
     pub min_amount_to_earn: u64,
     pub min_amount_to_collect: u64,
     pub treasury_owner_bump: u8,
+    pub fee_bps: u16,
+    /// Zero disables the emission schedule, rewards are capped only by treasury balance
+    pub epoch_duration: u64,
+    pub epoch_decay_bps: u16,
+    pub emission_carry_over: EmissionCarryOver,
+    pub epoch_start_timestamp: u64,
+    pub current_epoch: u64,
+    pub current_epoch_base_budget: u64,
+    pub current_epoch_budget: u64,
+    pub current_epoch_consumed: u64,
+    /// Zero pays rewards out immediately, otherwise they vest in `RewardVesting`
+    pub vesting_duration: u64,
+    pub vesting_cliff: u64,
+    /// Vested but not yet claimed rewards, still held by the treasury
+    pub total_reward_vesting: u64,
+    /// Campaigns self-funded above this share of received amount stop earning rewards,
+    /// zero disables the cap
+    pub max_self_funded_bps: u16,
+    pub version: u8,
+    /// Zeroed space for future fields
+    pub reserved: [u8; RESERVED_LEN],
+}
+
+impl DonationProtocolData {
+    pub fn is_reward_vesting(&self) -> bool {
+        self.vesting_duration > 0
+    }
+
+    pub fn is_emission_scheduled(&self) -> bool {
+        self.epoch_duration > 0
+    }
+
+    /// Advances the schedule to the epoch containing `now`,
+    /// decaying the base budget and carrying over the unused part per policy
+    pub fn roll_emission_epoch(&mut self, now: u64) {
+        if !self.is_emission_scheduled() || now < self.epoch_start_timestamp {
+            return;
+        }
+
+        let elapsed_epochs = (now - self.epoch_start_timestamp) / self.epoch_duration;
+        for _ in 0..elapsed_epochs.min(MAX_EPOCH_CATCH_UP) {
+            let unused = self
+                .current_epoch_budget
+                .saturating_sub(self.current_epoch_consumed);
+            self.current_epoch_base_budget = (self.current_epoch_base_budget as u128
+                * self.epoch_decay_bps as u128
+                / BPS_DENOMINATOR as u128) as u64;
+            self.current_epoch_budget = match self.emission_carry_over {
+                EmissionCarryOver::Forfeit => self.current_epoch_base_budget,
+                EmissionCarryOver::CarryOver => {
+                    self.current_epoch_base_budget.saturating_add(unused)
+                }
+            };
+            self.current_epoch_consumed = 0;
+        }
+
+        self.current_epoch = self.current_epoch.saturating_add(elapsed_epochs);
+        self.epoch_start_timestamp = self
+            .epoch_start_timestamp
+            .saturating_add(elapsed_epochs.saturating_mul(self.epoch_duration));
+    }
+
+    /// Takes up to `amount` out of the current epoch budget and returns the granted part
+    pub fn consume_emission(&mut self, amount: u64) -> u64 {
+        if !self.is_emission_scheduled() {
+            return amount;
+        }
+
+        let remaining = self
+            .current_epoch_budget
+            .saturating_sub(self.current_epoch_consumed);
+        let granted = amount.min(remaining);
+        self.current_epoch_consumed = self.current_epoch_consumed.checked_add(granted).unwrap();
+
+        granted
+    }
+}
+
+impl Versioned for DonationProtocolData {
+    const VERSION: u8 = 1;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+}
+
+/// Every field appended since the first deployed layout defaults to zero,
+/// which leaves fees, emission schedule, vesting and self funding cap disabled
+impl Migrate for DonationProtocolData {
+    const SPACE: usize = DISCRIMINATOR_LEN + DonationProtocolData::INIT_SPACE;
+
+    fn migrate(&mut self) {
+        self.version = DonationProtocolData::VERSION;
+    }
 }
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+/// Contribution of one donor to one donation
+#[account]
+#[derive(Default)]
+#[derive(InitSpace)]
+pub struct DonationReceipt {
+    pub donation_data: Pubkey,
+    pub donor: Pubkey,
+    /// In donation mint units
+    pub amount_donated: u64,
+    /// In default donation mint units
+    pub default_amount_donated: u64,
+    /// Rewards earned on this donation, clawed back on refund
+    pub reward_amount: u64,
+    pub bump: u8,
+    /// Last funding round the donor contributed to this donation in
+    pub funding_round: Pubkey,
+    /// In default donation mint units, counted towards `funding_round` matching
+    pub round_amount_donated: u64,
+    /// Sponsor match paid for this donor, returned to the pledge escrow on refund
+    pub matched_amount: u64,
+    /// `DonationTiers` tier number the cumulative contribution reached, zero when none
+    pub perk_tier: u8,
+    /// Part of `amount_donated` covered by minted receipt tokens, can not be refunded
+    pub amount_receipted: u64,
+    /// Part of `amount_donated` the donor paid from their own tokens and can still refund,
+    /// gifts made with `donate_on_behalf` are not refundable
+    pub amount_refundable: u64,
+}
+
+impl DonationReceipt {
+    /// Receipt pda is derived from donation and donor,
+    /// so a receipt created on the fly by `init_if_needed` is simply filled in
+    pub fn record(
+        &mut self,
+        donation_data: Pubkey,
+        donor: Pubkey,
+        bump: u8,
+        amount: u64,
+        default_amount: u64,
+        reward_amount: u64,
+    ) {
+        self.donation_data = donation_data;
+        self.donor = donor;
+        self.bump = bump;
+        self.amount_donated = self.amount_donated.checked_add(amount).unwrap();
+        self.default_amount_donated = self
+            .default_amount_donated
+            .checked_add(default_amount)
+            .unwrap();
+        self.reward_amount = self.reward_amount.checked_add(reward_amount).unwrap();
+    }
+
+    pub fn record_refundable(&mut self, amount: u64) {
+        self.amount_refundable = self.amount_refundable.checked_add(amount).unwrap();
+    }
+
+    /// Receipted part of a donation is no longer refundable
+    pub fn record_receipted(&mut self, amount: u64) {
+        self.amount_receipted = self.amount_receipted.checked_add(amount).unwrap();
+        self.amount_refundable = self.amount_refundable.checked_sub(amount).unwrap();
+    }
+
+    pub fn record_match(&mut self, matched_amount: u64) {
+        self.matched_amount = self.matched_amount.checked_add(matched_amount).unwrap();
+    }
+
+    /// Share of `total` matching `amount` refunded out of `amount_donated`
+    pub fn proportional_amount(&self, total: u64, amount: u64) -> u64 {
+        (total as u128 * amount as u128 / self.amount_donated as u128) as u64
+    }
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+
+use super::{DonationReceipt, MAX_IPFS_HASH_LEN};
+use crate::errors::DonationError;
+
+pub const MAX_PERK_TIERS: usize = 5;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PerkTierArgs {
+    /// Cumulative contribution to the donation in default donation mint units
+    pub threshold: u64,
+    pub supply: u32,
+    pub ipfs_hash: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+#[derive(InitSpace)]
+pub struct PerkTier {
+    pub threshold: u64,
+    pub supply: u32,
+    pub claimed: u32,
+    #[max_len(MAX_IPFS_HASH_LEN)]
+    pub ipfs_hash: String,
+}
+
+/// Kickstarter-like perks of a donation, ordered by ascending threshold
+#[account]
+#[derive(Default)]
+#[derive(InitSpace)]
+pub struct DonationTiers {
+    pub donation_data: Pubkey,
+    #[max_len(MAX_PERK_TIERS)]
+    pub tiers: Vec<PerkTier>,
+    pub bump: u8,
+}
+
+impl DonationTiers {
+    /// Tiers are set once with the donation, thresholds must ascend and every tier needs supply
+    pub fn init(
+        &mut self,
+        donation_data: Pubkey,
+        tiers: Vec<PerkTierArgs>,
+        bump: u8,
+    ) -> Result<()> {
+        if tiers.is_empty() || tiers.len() > MAX_PERK_TIERS {
+            return Err(DonationError::InvalidPerkTiers.into());
+        }
+
+        let mut perk_tiers: Vec<PerkTier> = Vec::with_capacity(tiers.len());
+        for tier in tiers {
+            if tier.ipfs_hash.len() > MAX_IPFS_HASH_LEN {
+                return Err(DonationError::IpfsHashTooLong.into());
+            }
+            let is_ascending = perk_tiers
+                .last()
+                .map_or(tier.threshold > 0, |last| tier.threshold > last.threshold);
+            if !is_ascending || tier.supply == 0 {
+                return Err(DonationError::InvalidPerkTiers.into());
+            }
+            perk_tiers.push(PerkTier {
+                threshold: tier.threshold,
+                supply: tier.supply,
+                claimed: 0,
+                ipfs_hash: tier.ipfs_hash,
+            });
+        }
+
+        self.donation_data = donation_data;
+        self.tiers = perk_tiers;
+        self.bump = bump;
+
+        Ok(())
+    }
+
+    /// Highest tier with supply left that `amount` qualifies for
+    pub fn qualified_tier(&self, amount: u64) -> Option<usize> {
+        self.tiers
+            .iter()
+            .rposition(|tier| tier.threshold <= amount && tier.claimed < tier.supply)
+    }
+
+    /// Moves the receipt up to the highest tier its cumulative contribution reached,
+    /// releasing the lower tier it held
+    pub fn record_contribution(&mut self, donation_receipt: &mut DonationReceipt) {
+        let tier_index = match self.qualified_tier(donation_receipt.default_amount_donated) {
+            Some(tier_index) if tier_index + 1 > donation_receipt.perk_tier as usize => tier_index,
+            _ => return,
+        };
+
+        if donation_receipt.perk_tier > 0 {
+            let previous_tier = &mut self.tiers[donation_receipt.perk_tier as usize - 1];
+            previous_tier.claimed = previous_tier.claimed.checked_sub(1).unwrap();
+        }
+        let tier = &mut self.tiers[tier_index];
+        tier.claimed = tier.claimed.checked_add(1).unwrap();
+        donation_receipt.perk_tier = (tier_index + 1) as u8;
+    }
+}
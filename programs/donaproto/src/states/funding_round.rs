@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_ROUND_CAMPAIGNS: usize = 10;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+#[derive(InitSpace)]
+pub struct RoundCampaign {
+    pub donation_data: Pubkey,
+    /// Sum of square roots of each donor contribution made during the round
+    pub sqrt_sum: u64,
+    pub total_amount_contributed: u64,
+    pub match_amount: u64,
+    pub is_distributed: bool,
+}
+
+impl RoundCampaign {
+    /// Quadratic funding weight, `(Σ √c)² - Σ c`
+    pub fn match_weight(&self) -> u128 {
+        (self.sqrt_sum as u128 * self.sqrt_sum as u128)
+            .saturating_sub(self.total_amount_contributed as u128)
+    }
+}
+
+#[account]
+#[derive(Default)]
+#[derive(InitSpace)]
+pub struct FundingRound {
+    pub donation_protocol: Pubkey,
+    pub authority: Pubkey,
+    pub matching_mint: Pubkey,
+    pub matching_vault: Pubkey,
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    pub is_finalized: bool,
+    /// Matching vault balance at finalization, split between campaigns
+    pub matching_pool_amount: u64,
+    #[max_len(MAX_ROUND_CAMPAIGNS)]
+    pub campaigns: Vec<RoundCampaign>,
+    pub matching_vault_owner_bump: u8,
+}
+
+impl FundingRound {
+    pub fn is_active(&self, now: u64) -> bool {
+        !self.is_finalized && now >= self.start_timestamp && now < self.end_timestamp
+    }
+
+    pub fn campaign_mut(&mut self, donation_data: &Pubkey) -> Option<&mut RoundCampaign> {
+        self.campaigns
+            .iter_mut()
+            .find(|campaign| campaign.donation_data == *donation_data)
+    }
+
+    /// Splits the matching pool proportionally to campaign weights,
+    /// rounding down so the vault always covers every match
+    pub fn calculate_matches(&mut self, matching_pool_amount: u64) {
+        let total_weight = self.campaigns.iter().fold(0u128, |total, campaign| {
+            total.saturating_add(campaign.match_weight())
+        });
+        for campaign in self.campaigns.iter_mut() {
+            campaign.match_amount = if total_weight == 0 {
+                0
+            } else {
+                (matching_pool_amount as u128 * campaign.match_weight() / total_weight) as u64
+            };
+        }
+        self.matching_pool_amount = matching_pool_amount;
+        self.is_finalized = true;
+    }
+}
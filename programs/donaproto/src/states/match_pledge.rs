@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use super::BPS_DENOMINATOR;
+
+/// Sponsor escrow matching every donation to one donation campaign
+#[account]
+#[derive(Default)]
+#[derive(InitSpace)]
+pub struct MatchPledge {
+    pub donation_data: Pubkey,
+    pub sponsor: Pubkey,
+    pub escrow: Pubkey,
+    /// Matched amount per donated amount, 10000 is 1:1
+    pub ratio_bps: u16,
+    /// Maximum amount matched over the pledge lifetime
+    pub cap: u64,
+    pub total_amount_matched: u64,
+    /// No matching after this timestamp, the sponsor can reclaim the escrow
+    pub deadline: u64,
+    pub bump: u8,
+}
+
+impl MatchPledge {
+    pub fn match_amount(&self, amount: u64, escrow_balance: u64) -> u64 {
+        let match_amount =
+            (amount as u128 * self.ratio_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+
+        match_amount
+            .min(self.cap.saturating_sub(self.total_amount_matched))
+            .min(escrow_balance)
+    }
+}
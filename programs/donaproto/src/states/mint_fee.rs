@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+/// Highest protocol or mint fee the authority can set, 10%
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+#[account]
+#[derive(Default)]
+#[derive(InitSpace)]
+pub struct MintFee {
+    pub donation_protocol: Pubkey,
+    pub mint: Pubkey,
+    pub fee_bps: u16,
+}
+
+impl MintFee {
+    /// Fee rate for a new donation, `mint_fee` is the ["mint_fee", donation_protocol, mint] pda
+    /// which stays uninitialized until the authority overrides the protocol fee for the mint
+    pub fn resolve_fee_bps(mint_fee: &AccountInfo, protocol_fee_bps: u16) -> Result<u16> {
+        if mint_fee.data_is_empty() {
+            return Ok(protocol_fee_bps);
+        }
+
+        let mint_fee = Account::<MintFee>::try_from(mint_fee)?;
+        Ok(mint_fee.fee_bps)
+    }
+}
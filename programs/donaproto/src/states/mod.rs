@@ -18,3 +18,30 @@ pub use authorized_pool::*;
 
 pub mod authorized_clmm;
 pub use authorized_clmm::*;
+
+pub mod subscription;
+pub use subscription::*;
+
+pub mod payout_split;
+pub use payout_split::*;
+
+pub mod mint_fee;
+pub use mint_fee::*;
+
+pub mod reward_vesting;
+pub use reward_vesting::*;
+
+pub mod donation_receipt;
+pub use donation_receipt::*;
+
+pub mod funding_round;
+pub use funding_round::*;
+
+pub mod match_pledge;
+pub use match_pledge::*;
+
+pub mod donation_tiers;
+pub use donation_tiers::*;
+
+pub mod receipt_metadata;
+pub use receipt_metadata::*;
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_SPLIT_RECIPIENTS: usize = 5;
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+#[derive(InitSpace)]
+pub struct SplitRecipient {
+    pub token_wallet: Pubkey,
+    pub share_bps: u16,
+    pub total_amount_received: u64,
+}
+
+#[account]
+#[derive(Default)]
+#[derive(InitSpace)]
+pub struct PayoutSplit {
+    pub donation_data: Pubkey,
+    #[max_len(MAX_SPLIT_RECIPIENTS)]
+    pub recipients: Vec<SplitRecipient>,
+    pub bump: u8,
+}
+
+impl PayoutSplit {
+    /// Splits `total` by recipient shares, rounding dust goes to the first recipient
+    pub fn split_amounts(&self, total: u64) -> Vec<u64> {
+        let mut amounts: Vec<u64> = self
+            .recipients
+            .iter()
+            .map(|recipient| {
+                (total as u128 * recipient.share_bps as u128 / BPS_DENOMINATOR as u128) as u64
+            })
+            .collect();
+        let distributed: u64 = amounts.iter().sum();
+        if let Some(first) = amounts.first_mut() {
+            *first = first.checked_add(total - distributed).unwrap();
+        }
+
+        amounts
+    }
+}
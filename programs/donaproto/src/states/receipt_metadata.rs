@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+/// Proof of a single donation attached to its one-of-one receipt mint
+#[account]
+#[derive(Default)]
+#[derive(InitSpace)]
+pub struct ReceiptMetadata {
+    pub receipt_mint: Pubkey,
+    pub donation_data: Pubkey,
+    pub donor: Pubkey,
+    pub donation_mint: Pubkey,
+    /// Amount of the receipted donation, in donation mint units
+    pub amount: u64,
+    pub timestamp: u64,
+    pub bump: u8,
+}
+
+impl ReceiptMetadata {
+    /// Receipt fields as logged through the memo program, readable in wallets and explorers
+    pub fn memo(&self) -> String {
+        format!(
+            "{{\"receipt_mint\":\"{}\",\"campaign\":\"{}\",\"amount\":{},\"mint\":\"{}\",\"donor\":\"{}\",\"timestamp\":{}}}",
+            self.receipt_mint,
+            self.donation_data,
+            self.amount,
+            self.donation_mint,
+            self.donor,
+            self.timestamp,
+        )
+    }
+}
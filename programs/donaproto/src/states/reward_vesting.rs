@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(Default)]
+#[derive(InitSpace)]
+pub struct RewardVesting {
+    pub contributor: Pubkey,
+    pub donation_protocol: Pubkey,
+    pub total_amount_vested: u64,
+    pub total_amount_claimed: u64,
+    /// Average of accrual timestamps weighted by unclaimed amounts
+    pub start_timestamp: u64,
+    pub bump: u8,
+    /// Protocol `vesting_cliff` at accrual, weighted like `start_timestamp`,
+    /// so later schedule changes do not apply to rewards already vested
+    pub vesting_cliff: u64,
+    /// Protocol `vesting_duration` at accrual, weighted like `start_timestamp`
+    pub vesting_duration: u64,
+    /// `total_amount_claimed` at the last accrual, the schedule only vests the rest
+    pub claimed_at_start: u64,
+}
+
+impl RewardVesting {
+    /// Restarts the schedule over the unclaimed balance: later accruals push it forward
+    /// proportionally to their share of that balance and blend their cliff and duration
+    /// into the one already vesting. Claimed rewards carry no weight, so they can not
+    /// make new rewards unlock early.
+    pub fn accrue(&mut self, amount: u64, now: u64, cliff: u64, duration: u64) {
+        let unclaimed_amount = self
+            .total_amount_vested
+            .checked_sub(self.total_amount_claimed)
+            .unwrap();
+        let total_unclaimed_amount = unclaimed_amount.checked_add(amount).unwrap();
+        let weighted = |current: u64, value: u64| {
+            ((current as u128 * unclaimed_amount as u128 + value as u128 * amount as u128)
+                / total_unclaimed_amount as u128) as u64
+        };
+        self.start_timestamp = weighted(self.start_timestamp, now);
+        self.vesting_cliff = weighted(self.vesting_cliff, cliff);
+        self.vesting_duration = weighted(self.vesting_duration, duration);
+        self.total_amount_vested = self.total_amount_vested.checked_add(amount).unwrap();
+        self.claimed_at_start = self.total_amount_claimed;
+    }
+
+    /// Nothing unlocks before the cliff, then rewards unlock linearly until the duration is over
+    pub fn unlocked_amount(&self, now: u64) -> u64 {
+        let elapsed = now.saturating_sub(self.start_timestamp);
+        if elapsed < self.vesting_cliff {
+            return self.claimed_at_start;
+        }
+        if elapsed >= self.vesting_duration {
+            return self.total_amount_vested;
+        }
+
+        let vesting_amount = self
+            .total_amount_vested
+            .saturating_sub(self.claimed_at_start);
+        let unlocked_amount =
+            (vesting_amount as u128 * elapsed as u128 / self.vesting_duration as u128) as u64;
+        self.claimed_at_start.saturating_add(unlocked_amount)
+    }
+
+    /// Clawbacks on refund and a later weighted start can push the unlocked amount
+    /// below what was already claimed
+    pub fn claimable_amount(&self, now: u64) -> u64 {
+        self.unlocked_amount(now)
+            .saturating_sub(self.total_amount_claimed)
+    }
+}
@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(Default)]
+#[derive(InitSpace)]
+pub struct Subscription {
+    pub donor: Pubkey,
+    pub donor_token_wallet: Pubkey,
+    pub donation_data: Pubkey,
+    pub donation_protocol: Pubkey,
+    pub amount: u64,
+    pub period: u64,
+    pub next_due: u64,
+    pub ending_timestamp: u64,
+    pub installments_paid: u64,
+    pub bump: u8,
+}
@@ -1,12 +1,18 @@
+use anchor_lang::prelude::Pubkey;
+use raydium_amm_v3::states::PoolState;
+
 /// A library for handling Q64.64 fixed point numbers
 /// copied from `raydium-clmm` program library
-// TODO: write tests for this module
 
 pub const Q64: u128 = (u64::MAX as u128) + 1; // 2^64
 pub const RESOLUTION: u8 = 64;
 
+/// Exclusive upper bound of amounts representable as u64
+const U64_LIMIT: f64 = Q64 as f64;
+
+/// Finite for any token decimals, exact up to 22 decimals
 pub fn multipler(decimals: u8) -> f64 {
-    (10_i32).checked_pow(decimals.try_into().unwrap()).unwrap() as f64
+    10f64.powi(decimals as i32)
 }
 
 pub fn from_x64_price(price: u128) -> f64 {
@@ -29,16 +35,19 @@ pub fn identify_mint_decimals(
     (donation_mint_decimals, default_donation_mint_decimals)
 }
 
-pub fn amount_from_price(
-    amount: u64,
-    price: f64,
-    is_default_token_mint_0: bool,
-) -> u64 {
-    if is_default_token_mint_0 {
-        return (amount as f64 / price).round() as u64;
+/// None when the converted amount does not fit into u64
+pub fn amount_from_price(amount: u64, price: f64, is_default_token_mint_0: bool) -> Option<u64> {
+    let converted_amount = if is_default_token_mint_0 {
+        (amount as f64 / price).round()
+    } else {
+        (amount as f64 * price).round()
+    };
+    // `as u64` saturates, out of range and NaN results must not be credited
+    if !(0.0..U64_LIMIT).contains(&converted_amount) {
+        return None;
     }
-    
-    (amount as f64 * price).round() as u64
+
+    Some(converted_amount as u64)
 }
 
 pub fn calculate_amount(
@@ -47,7 +56,7 @@ pub fn calculate_amount(
     amount: u64,
     sqrt_price_x64: u128,
     is_default_token_mint_0: bool,
-) -> u64 {
+) -> Option<u64> {
     let (decimals_0, decimals_1) = identify_mint_decimals(
         default_donation_mint_decimals,
         donation_mint_decimals,
@@ -57,3 +66,24 @@ pub fn calculate_amount(
 
     amount_from_price(amount, price, is_default_token_mint_0)
 }
+
+/// Whether the protocol donation mint is `token_mint_0` of `pool_state`,
+/// None when the pool does not pair it with `donation_mint`
+pub fn is_default_token_mint_0(
+    pool_state: &PoolState,
+    default_donation_mint: &Pubkey,
+    donation_mint: &Pubkey,
+) -> Option<bool> {
+    if pool_state.token_mint_0 == *default_donation_mint
+        && pool_state.token_mint_1 == *donation_mint
+    {
+        return Some(true);
+    }
+    if pool_state.token_mint_1 == *default_donation_mint
+        && pool_state.token_mint_0 == *donation_mint
+    {
+        return Some(false);
+    }
+
+    None
+}
@@ -0,0 +1,250 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use common::*;
+use donaproto::states::{
+    AuthorizedClmm, AuthorizedClmmPool, ContributorData, CreatorData, DonationReceipt, HardCapMode,
+};
+use donaproto_client::{
+    pda::{
+        find_authorized_clmm, find_contributor_data, find_creator_data, find_donation_receipt,
+        find_holding_wallet_owner,
+    },
+    AuthorizeClmmBuilder, AuthorizeClmmPoolBuilder, CreateDonationBuilder, DonateBuilder,
+    ProtocolKeys, WithdrawFundsBuilder,
+};
+use solana_sdk::signature::{Keypair, Signer};
+
+const OTHER_MINT_DECIMALS: u8 = 9;
+
+#[tokio::test]
+async fn authorizes_clmm_program_and_pool() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let other_mint = env.create_mint(OTHER_MINT_DECIMALS).await;
+    let fixture = create_pool_fixture(
+        &mut env,
+        &protocol.donation_protocol,
+        (protocol.donation_mint, DONATION_MINT_DECIMALS),
+        (other_mint, OTHER_MINT_DECIMALS),
+        1.0,
+    );
+
+    let stranger = env.wallet().await;
+    let authorize = AuthorizeClmmBuilder::new(
+        protocol.donation_protocol,
+        raydium_amm_v3::ID,
+        stranger.pubkey(),
+    )
+    .instruction();
+    assert_constraint_error(env.process(&[authorize], &[&stranger]).await);
+
+    authorize_clmm(&mut env, &protocol).await;
+    let authorized_clmm: AuthorizedClmm = env
+        .fetch(&find_authorized_clmm(&protocol.donation_protocol, &raydium_amm_v3::ID).0)
+        .await;
+    assert_eq!(authorized_clmm.program_id, raydium_amm_v3::ID);
+    assert_eq!(
+        authorized_clmm.donation_protocol,
+        protocol.donation_protocol
+    );
+
+    // pool must pair the protocol mint with the authorized one
+    let unrelated_mint = env.create_mint(OTHER_MINT_DECIMALS).await;
+    let authorize = AuthorizeClmmPoolBuilder::new(
+        protocol.donation_protocol,
+        raydium_amm_v3::ID,
+        fixture.pool.pool_state,
+        fixture.amm_config,
+        unrelated_mint,
+        env.payer(),
+    )
+    .instruction();
+    assert_constraint_error(env.process(&[authorize], &[]).await);
+
+    authorize_pool(&mut env, &protocol, &fixture, &other_mint).await;
+    let authorized_pool: AuthorizedClmmPool = env.fetch(&fixture.pool.authorized_clmm_pool).await;
+    assert_eq!(authorized_pool.pool_state, fixture.pool.pool_state);
+    assert_eq!(authorized_pool.program_id, raydium_amm_v3::ID);
+    assert_eq!(authorized_pool.mint, other_mint);
+}
+
+#[tokio::test]
+async fn prices_donations_with_protocol_mint_as_token_0() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let other_mint = env.create_mint(DONATION_MINT_DECIMALS).await;
+    // 4 other tokens per protocol token
+    let fixture = create_pool_fixture(
+        &mut env,
+        &protocol.donation_protocol,
+        (protocol.donation_mint, DONATION_MINT_DECIMALS),
+        (other_mint, DONATION_MINT_DECIMALS),
+        4.0,
+    );
+    authorize_clmm(&mut env, &protocol).await;
+    authorize_pool(&mut env, &protocol, &fixture, &other_mint).await;
+
+    let campaign = create_campaign_v2(
+        &mut env,
+        &protocol,
+        &fixture,
+        &other_mint,
+        8_000_000,
+        HardCapMode::None,
+    )
+    .await;
+    let creator_data: CreatorData = env.fetch(&campaign.keys.creator_data).await;
+    assert_eq!(creator_data.total_amount_collecting, 2_000_000);
+
+    let donor = create_donor(&mut env, &protocol, &other_mint, 8_000_000).await;
+    // campaigns in other mints are priced through donate_v2
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 4_000_000)
+        .instruction();
+    assert_constraint_error(env.process(&[donate], &[&donor.wallet]).await);
+
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 4_000_000)
+        .v2(fixture.pool)
+        .instruction();
+    env.process(&[donate], &[&donor.wallet]).await.unwrap();
+    assert_eq!(
+        env.token_balance(&campaign.keys.holding_wallet).await,
+        4_000_000
+    );
+    assert_eq!(env.token_balance(&donor.reward_wallet).await, 1_000_000);
+    let contributor_data: ContributorData = env
+        .fetch(&find_contributor_data(&protocol.donation_protocol, &donor.wallet.pubkey()).0)
+        .await;
+    assert_eq!(contributor_data.total_amount_donated, 1_000_000);
+    let receipt: DonationReceipt = env
+        .fetch(&find_donation_receipt(&campaign.keys.donation_data, &donor.wallet.pubkey()).0)
+        .await;
+    assert_eq!(receipt.amount_donated, 4_000_000);
+    assert_eq!(receipt.default_amount_donated, 1_000_000);
+
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 4_000_000)
+        .v2(fixture.pool)
+        .instruction();
+    env.process(&[donate], &[&donor.wallet]).await.unwrap();
+
+    withdraw_v2(&mut env, &protocol, &campaign, &fixture).await;
+    assert_eq!(env.token_balance(&campaign.recipient).await, 8_000_000);
+    let creator_data: CreatorData = env.fetch(&campaign.keys.creator_data).await;
+    assert_eq!(creator_data.total_amount_received, 2_000_000);
+    assert_eq!(creator_data.donations_closed_count, 1);
+}
+
+#[tokio::test]
+async fn prices_donations_with_protocol_mint_as_token_1() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let other_mint = env.create_mint(OTHER_MINT_DECIMALS).await;
+    // 1 other token is worth 2 protocol tokens
+    let fixture = create_pool_fixture(
+        &mut env,
+        &protocol.donation_protocol,
+        (other_mint, OTHER_MINT_DECIMALS),
+        (protocol.donation_mint, DONATION_MINT_DECIMALS),
+        0.002,
+    );
+    authorize_clmm(&mut env, &protocol).await;
+    authorize_pool(&mut env, &protocol, &fixture, &other_mint).await;
+
+    let campaign = create_campaign_v2(
+        &mut env,
+        &protocol,
+        &fixture,
+        &other_mint,
+        1_000_000_000,
+        HardCapMode::None,
+    )
+    .await;
+    let creator_data: CreatorData = env
+        .fetch(&find_creator_data(&protocol.donation_protocol, &campaign.creator.pubkey()).0)
+        .await;
+    assert_eq!(creator_data.total_amount_collecting, 2_000_000);
+
+    let donor = create_donor(&mut env, &protocol, &other_mint, 1_000_000_000).await;
+    let donate = DonateBuilder::new(
+        &protocol,
+        &campaign.keys,
+        donor.wallet.pubkey(),
+        1_000_000_000,
+    )
+    .v2(fixture.pool)
+    .instruction();
+    env.process(&[donate], &[&donor.wallet]).await.unwrap();
+    assert_eq!(env.token_balance(&donor.reward_wallet).await, 2_000_000);
+
+    // goal is reached, withdrawal does not wait for the ending timestamp
+    withdraw_v2(&mut env, &protocol, &campaign, &fixture).await;
+    assert_eq!(env.token_balance(&campaign.recipient).await, 1_000_000_000);
+    let creator_data: CreatorData = env.fetch(&campaign.keys.creator_data).await;
+    assert_eq!(creator_data.total_amount_received, 2_000_000);
+}
+
+#[tokio::test]
+async fn rejects_unauthorized_pool() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let other_mint = env.create_mint(DONATION_MINT_DECIMALS).await;
+    let fixture = create_pool_fixture(
+        &mut env,
+        &protocol.donation_protocol,
+        (protocol.donation_mint, DONATION_MINT_DECIMALS),
+        (other_mint, DONATION_MINT_DECIMALS),
+        1.0,
+    );
+    authorize_clmm(&mut env, &protocol).await;
+
+    let creator = initialize_creator(&mut env, &protocol.donation_protocol).await;
+    let recipient = env.token_wallet(&creator.pubkey(), &other_mint).await;
+    let donation_data = Keypair::new();
+    let holding_wallet_owner = find_holding_wallet_owner(&donation_data.pubkey()).0;
+    env.token_wallet(&holding_wallet_owner, &other_mint).await;
+    let ending_timestamp = env.now().await + CAMPAIGN_DURATION;
+    let create = CreateDonationBuilder::new(
+        protocol.donation_protocol,
+        donation_data.pubkey(),
+        other_mint,
+        recipient,
+        creator.pubkey(),
+        MIN_AMOUNT_TO_COLLECT,
+        "some_ipfs_hash",
+        ending_timestamp,
+    )
+    .v2(protocol.donation_mint, fixture.pool)
+    .instruction();
+    assert_anchor_error(
+        env.process(&[create], &[&creator, &donation_data]).await,
+        ErrorCode::AccountNotInitialized,
+    );
+}
+
+async fn withdraw_v2(
+    env: &mut TestEnv,
+    protocol: &ProtocolKeys,
+    campaign: &Campaign,
+    fixture: &PoolFixture,
+) {
+    // campaigns in other mints are priced through withdraw_funds_v2
+    let withdraw = WithdrawFundsBuilder::new(
+        &campaign.keys,
+        campaign.recipient,
+        campaign.creator.pubkey(),
+    )
+    .instruction();
+    assert_constraint_error(env.process(&[withdraw], &[&campaign.creator]).await);
+
+    let withdraw = WithdrawFundsBuilder::new(
+        &campaign.keys,
+        campaign.recipient,
+        campaign.creator.pubkey(),
+    )
+    .v2(protocol.donation_mint, fixture.pool)
+    .instruction();
+    env.process(&[withdraw], &[&campaign.creator])
+        .await
+        .unwrap();
+    assert!(campaign.data(env).await.is_closed());
+}
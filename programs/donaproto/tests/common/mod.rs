@@ -0,0 +1,609 @@
+//! In-process bank running donaproto next to the Raydium CLMM program,
+//! with token helpers and fixtures shared by the integration tests
+#![allow(dead_code)]
+
+use anchor_lang::{
+    error::ErrorCode,
+    prelude::{AccountInfo, Clock, Pubkey},
+    solana_program::{entrypoint::ProgramResult, instruction::Instruction, program_pack::Pack},
+    AccountDeserialize, AccountSerialize, Discriminator,
+};
+use anchor_spl::{
+    associated_token::{
+        get_associated_token_address,
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    },
+    token::spl_token,
+};
+use donaproto::{
+    errors::DonationError,
+    states::{DonationData, DonationProtocolData, HardCapMode},
+};
+use donaproto_client::{
+    pda::{find_creator_data, find_holding_wallet_owner, find_treasury, find_treasury_owner},
+    AuthorizeClmmBuilder, AuthorizeClmmPoolBuilder, CampaignKeys, CreateDonationBuilder,
+    InitializeCreatorBuilder, InitializeDonationProtocolBuilder, PoolKeys, ProtocolKeys,
+};
+use raydium_amm_v3::states::{AmmConfig, PoolState};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    bpf_loader,
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+
+pub const DONATION_MINT_DECIMALS: u8 = 6;
+pub const REWARD_MINT_DECIMALS: u8 = 9;
+pub const TREASURY_SUPPLY: u64 = 1_000_000_000_000_000;
+pub const MIN_AMOUNT_TO_EARN: u64 = 1_000;
+pub const MIN_AMOUNT_TO_COLLECT: u64 = 1_000_000;
+pub const CAMPAIGN_DURATION: u64 = 100_000;
+
+const RAYDIUM_AMM_V3_SO: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../raydium_amm_v3.so");
+const DEPLOY_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../target/deploy");
+
+// Anchor ties the lifetime of the account slice to the lifetime of its accounts,
+// the program test processor hands them out with unrelated lifetimes.
+// The copies share lamports and data with the originals, leaking them keeps the slice
+// alive for 'c without unsafe code
+fn process_instruction<'a, 'b, 'c, 'd>(
+    program_id: &'a Pubkey,
+    accounts: &'b [AccountInfo<'c>],
+    data: &'d [u8],
+) -> ProgramResult {
+    let accounts: &'c [AccountInfo<'c>] = Box::leak(accounts.to_vec().into_boxed_slice());
+    donaproto::entry(program_id, accounts, data)
+}
+
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+}
+
+impl TestEnv {
+    pub async fn start() -> Self {
+        Self::start_with(ProgramTest::new(
+            "donaproto",
+            donaproto::ID,
+            processor!(process_instruction),
+        ))
+        .await
+    }
+
+    /// Runs the `anchor build` output instead of the native processor,
+    /// compute units are only metered for BPF programs
+    pub async fn start_bpf() -> Self {
+        if std::env::var_os("SBF_OUT_DIR").is_none() {
+            std::env::set_var("SBF_OUT_DIR", DEPLOY_DIR);
+        }
+        let mut program_test = ProgramTest::new("donaproto", donaproto::ID, None);
+        program_test.prefer_bpf(true);
+        Self::start_with(program_test).await
+    }
+
+    async fn start_with(mut program_test: ProgramTest) -> Self {
+        program_test.add_account(
+            raydium_amm_v3::ID,
+            Account {
+                lamports: 1_000_000_000,
+                data: std::fs::read(RAYDIUM_AMM_V3_SO).expect("raydium_amm_v3.so"),
+                owner: bpf_loader::ID,
+                executable: true,
+                rent_epoch: 0,
+            },
+        );
+
+        Self {
+            context: program_test.start_with_context().await,
+        }
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    /// Sends `instructions` paid by the bank payer, on a fresh blockhash
+    /// so that repeating an identical transaction is not deduplicated
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let blockhash = self.context.get_new_latest_blockhash().await?;
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    /// Compute units `instructions` consume, simulated without committing them
+    pub async fn units_consumed(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> u64 {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+
+        let simulation = self
+            .context
+            .banks_client
+            .simulate_transaction(transaction)
+            .await
+            .unwrap();
+        simulation.result.expect("simulation").unwrap();
+        simulation
+            .simulation_details
+            .expect("details")
+            .units_consumed
+    }
+
+    pub async fn now(&mut self) -> u64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp as u64
+    }
+
+    pub async fn warp_to_timestamp(&mut self, unix_timestamp: u64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = unix_timestamp as i64;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn warp_forward(&mut self, seconds: u64) {
+        let now = self.now().await;
+        self.warp_to_timestamp(now + seconds).await;
+    }
+
+    /// New system wallet holding enough lamports to pay for its accounts
+    pub async fn wallet(&mut self) -> Keypair {
+        let wallet = Keypair::new();
+        let transfer =
+            system_instruction::transfer(&self.payer(), &wallet.pubkey(), 10_000_000_000);
+        self.process(&[transfer], &[]).await.unwrap();
+        wallet
+    }
+
+    pub async fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let instructions = [
+            system_instruction::create_account(
+                &self.payer(),
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::ID,
+                &mint.pubkey(),
+                &self.payer(),
+                None,
+                decimals,
+            )
+            .unwrap(),
+        ];
+        self.process(&instructions, &[&mint]).await.unwrap();
+        mint.pubkey()
+    }
+
+    /// Associated token account of `owner`, created when missing
+    pub async fn token_wallet(&mut self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        let create =
+            create_associated_token_account_idempotent(&self.payer(), owner, mint, &spl_token::ID);
+        self.process(&[create], &[]).await.unwrap();
+        get_associated_token_address(owner, mint)
+    }
+
+    /// Token account of `owner` at a fresh address
+    pub async fn token_account(&mut self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        let account = Keypair::new();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let instructions = [
+            system_instruction::create_account(
+                &self.payer(),
+                &account.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_account3(
+                &spl_token::ID,
+                &account.pubkey(),
+                mint,
+                owner,
+            )
+            .unwrap(),
+        ];
+        self.process(&instructions, &[&account]).await.unwrap();
+        account.pubkey()
+    }
+
+    /// Mints are created with the bank payer as mint authority
+    pub async fn mint_to(&mut self, mint: &Pubkey, destination: &Pubkey, amount: u64) {
+        let mint_to = spl_token::instruction::mint_to(
+            &spl_token::ID,
+            mint,
+            destination,
+            &self.payer(),
+            &[],
+            amount,
+        )
+        .unwrap();
+        self.process(&[mint_to], &[]).await.unwrap();
+    }
+
+    pub async fn token_balance(&mut self, token_wallet: &Pubkey) -> u64 {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*token_wallet)
+            .await
+            .unwrap()
+            .expect("token account");
+        spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .amount
+    }
+
+    pub async fn account_exists(&mut self, address: &Pubkey) -> bool {
+        self.context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    pub async fn fetch<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .expect("account");
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    /// `DonationData` is zero copy, `fetch` would need the account data aligned
+    pub async fn fetch_donation_data(&mut self, address: &Pubkey) -> DonationData {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .expect("account");
+        DonationData::try_from_account_data(&account.data).unwrap()
+    }
+
+    /// Writes a Raydium owned account straight into the bank
+    pub fn set_raydium_account(&mut self, address: &Pubkey, data: Vec<u8>) {
+        let mut account = AccountSharedData::new(1_000_000_000, data.len(), &raydium_amm_v3::ID);
+        account.set_data(data);
+        self.context.set_account(address, &account);
+    }
+
+    pub fn set_amm_config(&mut self, address: &Pubkey, amm_config: &AmmConfig) {
+        let mut data = Vec::new();
+        amm_config.try_serialize(&mut data).unwrap();
+        self.set_raydium_account(address, data);
+    }
+
+    pub fn set_pool_state(&mut self, address: &Pubkey, pool_state: &PoolState) {
+        let mut data = PoolState::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(pool_state));
+        self.set_raydium_account(address, data);
+    }
+}
+
+/// Asserts the transaction failed with `error` raised by donaproto
+pub fn assert_donation_error(result: Result<(), BanksClientError>, error: DonationError) {
+    match result.expect_err("transaction should fail").unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, u32::from(error), "expected {:?}", error)
+        }
+        other => panic!("expected {:?}, got {:?}", error, other),
+    }
+}
+
+/// Asserts the transaction failed with `error` raised by anchor account validation
+pub fn assert_anchor_error(result: Result<(), BanksClientError>, error: ErrorCode) {
+    match result.expect_err("transaction should fail").unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, u32::from(error), "expected {:?}", error)
+        }
+        other => panic!("expected {:?}, got {:?}", error, other),
+    }
+}
+
+/// Asserts the transaction failed on a `constraint = ...` account check
+pub fn assert_constraint_error(result: Result<(), BanksClientError>) {
+    assert_anchor_error(result, ErrorCode::ConstraintRaw);
+}
+
+/// Protocol with fresh donation and reward mints, authority is the bank payer
+pub async fn setup_protocol(env: &mut TestEnv) -> ProtocolKeys {
+    let donation_mint = env.create_mint(DONATION_MINT_DECIMALS).await;
+    let treasury_mint = env.create_mint(REWARD_MINT_DECIMALS).await;
+    let donation_protocol = Keypair::new();
+    let treasury_owner = find_treasury_owner(&donation_protocol.pubkey()).0;
+    let treasury = env.token_wallet(&treasury_owner, &treasury_mint).await;
+    assert_eq!(
+        treasury,
+        find_treasury(&donation_protocol.pubkey(), &treasury_mint)
+    );
+
+    let initialize = InitializeDonationProtocolBuilder::new(
+        donation_protocol.pubkey(),
+        treasury,
+        treasury_mint,
+        donation_mint,
+        env.payer(),
+    )
+    .min_amount_to_earn(MIN_AMOUNT_TO_EARN)
+    .min_amount_to_collect(MIN_AMOUNT_TO_COLLECT)
+    .instruction();
+    env.process(&[initialize], &[&donation_protocol])
+        .await
+        .unwrap();
+    env.mint_to(&treasury_mint, &treasury, TREASURY_SUPPLY)
+        .await;
+
+    protocol_keys(env, &donation_protocol.pubkey()).await
+}
+
+pub async fn protocol_keys(env: &mut TestEnv, donation_protocol: &Pubkey) -> ProtocolKeys {
+    let data: DonationProtocolData = env.fetch(donation_protocol).await;
+    ProtocolKeys::new(*donation_protocol, &data)
+}
+
+pub async fn campaign_keys(env: &mut TestEnv, donation_data: &Pubkey) -> CampaignKeys {
+    let data = env.fetch_donation_data(donation_data).await;
+    CampaignKeys::new(*donation_data, &data)
+}
+
+pub struct Campaign {
+    pub keys: CampaignKeys,
+    pub creator: Keypair,
+    /// Donation mint wallet of the creator receiving the payout
+    pub recipient: Pubkey,
+}
+
+impl Campaign {
+    pub async fn refresh(&mut self, env: &mut TestEnv) {
+        self.keys = campaign_keys(env, &self.keys.donation_data).await;
+    }
+
+    pub async fn data(&self, env: &mut TestEnv) -> DonationData {
+        env.fetch_donation_data(&self.keys.donation_data).await
+    }
+}
+
+pub async fn initialize_creator(env: &mut TestEnv, donation_protocol: &Pubkey) -> Keypair {
+    let creator = env.wallet().await;
+    if !env
+        .account_exists(&find_creator_data(donation_protocol, &creator.pubkey()).0)
+        .await
+    {
+        let initialize =
+            InitializeCreatorBuilder::new(*donation_protocol, creator.pubkey()).instruction();
+        env.process(&[initialize], &[&creator]).await.unwrap();
+    }
+    creator
+}
+
+/// Campaign in the protocol donation mint ending `CAMPAIGN_DURATION` seconds from now
+pub async fn create_campaign(
+    env: &mut TestEnv,
+    protocol: &ProtocolKeys,
+    amount: u64,
+    hard_cap_mode: HardCapMode,
+) -> Campaign {
+    create_campaign_with(env, protocol, amount, |builder| {
+        builder.hard_cap_mode(hard_cap_mode)
+    })
+    .await
+}
+
+/// Campaign whose creation instruction is adjusted by `configure`
+pub async fn create_campaign_with(
+    env: &mut TestEnv,
+    protocol: &ProtocolKeys,
+    amount: u64,
+    configure: impl FnOnce(CreateDonationBuilder) -> CreateDonationBuilder,
+) -> Campaign {
+    let creator = initialize_creator(env, &protocol.donation_protocol).await;
+    let recipient = env
+        .token_wallet(&creator.pubkey(), &protocol.donation_mint)
+        .await;
+    let donation_data = Keypair::new();
+    let holding_wallet_owner = find_holding_wallet_owner(&donation_data.pubkey()).0;
+    env.token_wallet(&holding_wallet_owner, &protocol.donation_mint)
+        .await;
+    let ending_timestamp = env.now().await + CAMPAIGN_DURATION;
+
+    let create = CreateDonationBuilder::new(
+        protocol.donation_protocol,
+        donation_data.pubkey(),
+        protocol.donation_mint,
+        recipient,
+        creator.pubkey(),
+        amount,
+        "some_ipfs_hash",
+        ending_timestamp,
+    );
+    let create = configure(create).instruction();
+    env.process(&[create], &[&creator, &donation_data])
+        .await
+        .unwrap();
+
+    Campaign {
+        keys: campaign_keys(env, &donation_data.pubkey()).await,
+        creator,
+        recipient,
+    }
+}
+
+pub struct Donor {
+    pub wallet: Keypair,
+    pub token_wallet: Pubkey,
+    pub reward_wallet: Pubkey,
+}
+
+/// Wallet holding `amount` of `donation_mint` and an empty reward wallet
+pub async fn create_donor(
+    env: &mut TestEnv,
+    protocol: &ProtocolKeys,
+    donation_mint: &Pubkey,
+    amount: u64,
+) -> Donor {
+    let wallet = env.wallet().await;
+    let token_wallet = env.token_wallet(&wallet.pubkey(), donation_mint).await;
+    env.mint_to(donation_mint, &token_wallet, amount).await;
+    let reward_wallet = env
+        .token_wallet(&wallet.pubkey(), &protocol.treasury_mint)
+        .await;
+
+    Donor {
+        wallet,
+        token_wallet,
+        reward_wallet,
+    }
+}
+
+/// `sqrt_price_x64` at which a pool quotes `price` as used by `utils::sqrt_price_x64_to_price`
+pub fn sqrt_price_x64(price: f64, decimals_0: u8, decimals_1: u8) -> u128 {
+    let sqrt_price = (price * 10f64.powi(decimals_1 as i32) / 10f64.powi(decimals_0 as i32)).sqrt();
+    (sqrt_price * donaproto::utils::Q64 as f64) as u128
+}
+
+pub struct PoolFixture {
+    pub amm_config: Pubkey,
+    pub pool: PoolKeys,
+}
+
+/// Writes an `AmmConfig` and a `PoolState` pairing `token_mint_0` and `token_mint_1` at `price`
+pub fn create_pool_fixture(
+    env: &mut TestEnv,
+    donation_protocol: &Pubkey,
+    token_mint_0: (Pubkey, u8),
+    token_mint_1: (Pubkey, u8),
+    price: f64,
+) -> PoolFixture {
+    let amm_config = Pubkey::new_unique();
+    env.set_amm_config(
+        &amm_config,
+        &AmmConfig {
+            index: 2,
+            protocol_fee_rate: 120_000,
+            trade_fee_rate: 500,
+            tick_spacing: 10,
+            fund_fee_rate: 40_000,
+            ..Default::default()
+        },
+    );
+
+    let mut pool_state: PoolState = bytemuck::Zeroable::zeroed();
+    pool_state.amm_config = amm_config;
+    pool_state.token_mint_0 = token_mint_0.0;
+    pool_state.token_mint_1 = token_mint_1.0;
+    pool_state.mint_decimals_0 = token_mint_0.1;
+    pool_state.mint_decimals_1 = token_mint_1.1;
+    pool_state.tick_spacing = 10;
+    pool_state.liquidity = 1_000_000_000_000;
+    pool_state.sqrt_price_x64 = sqrt_price_x64(price, token_mint_0.1, token_mint_1.1);
+    let pool_state_key = Pubkey::new_unique();
+    env.set_pool_state(&pool_state_key, &pool_state);
+
+    PoolFixture {
+        amm_config,
+        pool: PoolKeys::new(donation_protocol, pool_state_key),
+    }
+}
+
+/// Authorizes the Raydium CLMM program for the protocol
+pub async fn authorize_clmm(env: &mut TestEnv, protocol: &ProtocolKeys) {
+    let authorize =
+        AuthorizeClmmBuilder::new(protocol.donation_protocol, raydium_amm_v3::ID, env.payer())
+            .instruction();
+    env.process(&[authorize], &[]).await.unwrap();
+}
+
+/// Authorizes the fixture pool to price donations in `mint`
+pub async fn authorize_pool(
+    env: &mut TestEnv,
+    protocol: &ProtocolKeys,
+    fixture: &PoolFixture,
+    mint: &Pubkey,
+) {
+    let authorize = AuthorizeClmmPoolBuilder::new(
+        protocol.donation_protocol,
+        raydium_amm_v3::ID,
+        fixture.pool.pool_state,
+        fixture.amm_config,
+        *mint,
+        env.payer(),
+    )
+    .instruction();
+    env.process(&[authorize], &[]).await.unwrap();
+}
+
+/// Campaign collecting `mint` priced against the protocol mint by `fixture`
+pub async fn create_campaign_v2(
+    env: &mut TestEnv,
+    protocol: &ProtocolKeys,
+    fixture: &PoolFixture,
+    mint: &Pubkey,
+    amount: u64,
+    hard_cap_mode: HardCapMode,
+) -> Campaign {
+    let creator = initialize_creator(env, &protocol.donation_protocol).await;
+    let recipient = env.token_wallet(&creator.pubkey(), mint).await;
+    let donation_data = Keypair::new();
+    let holding_wallet_owner = find_holding_wallet_owner(&donation_data.pubkey()).0;
+    env.token_wallet(&holding_wallet_owner, mint).await;
+    let ending_timestamp = env.now().await + CAMPAIGN_DURATION;
+
+    let create = CreateDonationBuilder::new(
+        protocol.donation_protocol,
+        donation_data.pubkey(),
+        *mint,
+        recipient,
+        creator.pubkey(),
+        amount,
+        "some_ipfs_hash",
+        ending_timestamp,
+    )
+    .hard_cap_mode(hard_cap_mode)
+    .v2(protocol.donation_mint, fixture.pool)
+    .instruction();
+    env.process(&[create], &[&creator, &donation_data])
+        .await
+        .unwrap();
+
+    Campaign {
+        keys: campaign_keys(env, &donation_data.pubkey()).await,
+        creator,
+        recipient,
+    }
+}
@@ -0,0 +1,75 @@
+//! Compute units of the hot path instructions, measured against the BPF build.
+//! Run `anchor build` first, then
+//! `cargo test -p donaproto --test compute_units -- --ignored --nocapture`
+mod common;
+
+use common::*;
+use donaproto::states::HardCapMode;
+use donaproto_client::DonateBuilder;
+use solana_sdk::signature::Signer;
+
+const OTHER_MINT_DECIMALS: u8 = 9;
+
+#[tokio::test]
+#[ignore = "needs the anchor build output in target/deploy"]
+async fn reports_donate_compute_units() {
+    let mut env = TestEnv::start_bpf().await;
+    let protocol = setup_protocol(&mut env).await;
+    let campaign = create_campaign(&mut env, &protocol, 1_000_000_000, HardCapMode::None).await;
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, 1_000_000_000).await;
+
+    // the first donation also creates the contributor and receipt accounts
+    for label in ["donate (first)", "donate"] {
+        let donate =
+            DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 1_000_000)
+                .instruction();
+        let units = env
+            .units_consumed(std::slice::from_ref(&donate), &[&donor.wallet])
+            .await;
+        println!("{}: {} CU", label, units);
+        env.process(&[donate], &[&donor.wallet]).await.unwrap();
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs the anchor build output in target/deploy"]
+async fn reports_donate_v2_compute_units() {
+    let mut env = TestEnv::start_bpf().await;
+    let protocol = setup_protocol(&mut env).await;
+    let other_mint = env.create_mint(OTHER_MINT_DECIMALS).await;
+    let fixture = create_pool_fixture(
+        &mut env,
+        &protocol.donation_protocol,
+        (protocol.donation_mint, DONATION_MINT_DECIMALS),
+        (other_mint, OTHER_MINT_DECIMALS),
+        4.0,
+    );
+    authorize_clmm(&mut env, &protocol).await;
+    authorize_pool(&mut env, &protocol, &fixture, &other_mint).await;
+    let campaign = create_campaign_v2(
+        &mut env,
+        &protocol,
+        &fixture,
+        &other_mint,
+        1_000_000_000_000,
+        HardCapMode::None,
+    )
+    .await;
+    let donor = create_donor(&mut env, &protocol, &other_mint, 1_000_000_000_000).await;
+
+    for label in ["donate_v2 (first)", "donate_v2"] {
+        let donate = DonateBuilder::new(
+            &protocol,
+            &campaign.keys,
+            donor.wallet.pubkey(),
+            1_000_000_000,
+        )
+        .v2(fixture.pool)
+        .instruction();
+        let units = env
+            .units_consumed(std::slice::from_ref(&donate), &[&donor.wallet])
+            .await;
+        println!("{}: {} CU", label, units);
+        env.process(&[donate], &[&donor.wallet]).await.unwrap();
+    }
+}
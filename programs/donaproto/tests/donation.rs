@@ -0,0 +1,762 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_spl::{
+    associated_token::get_associated_token_address,
+    token::{
+        spl_token::{self, instruction::AuthorityType},
+        Mint,
+    },
+};
+use common::*;
+use donaproto::{
+    errors::DonationError,
+    states::{
+        ContributorData, CreatorData, DonationReceipt, DonationTiers, HardCapMode, PayoutSplit,
+        ReceiptMetadata,
+    },
+};
+use donaproto_client::{
+    pda::{
+        find_contributor_data, find_donation_receipt, find_donation_tiers,
+        find_holding_wallet_owner, find_receipt_metadata,
+    },
+    CampaignKeys, CreateDonationBuilder, CreatePayoutSplitBuilder, DonateBatchBuilder,
+    DonateBuilder, DonateOnBehalfBuilder, RefundDonationBuilder, WithdrawFundsBuilder,
+};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn rejects_invalid_campaigns() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let creator = initialize_creator(&mut env, &protocol.donation_protocol).await;
+    let recipient = env
+        .token_wallet(&creator.pubkey(), &protocol.donation_mint)
+        .await;
+    let now = env.now().await;
+
+    let donation_data = Keypair::new();
+    let create = |amount: u64, ipfs_hash: &str, ending_timestamp: u64| {
+        CreateDonationBuilder::new(
+            protocol.donation_protocol,
+            donation_data.pubkey(),
+            protocol.donation_mint,
+            recipient,
+            creator.pubkey(),
+            amount,
+            ipfs_hash,
+            ending_timestamp,
+        )
+        .instruction()
+    };
+
+    assert_donation_error(
+        env.process(
+            &[create(MIN_AMOUNT_TO_COLLECT, "hash", now)],
+            &[&creator, &donation_data],
+        )
+        .await,
+        DonationError::InvalidEndingTimestamp,
+    );
+    assert_donation_error(
+        env.process(
+            &[create(MIN_AMOUNT_TO_COLLECT - 1, "hash", now + 100)],
+            &[&creator, &donation_data],
+        )
+        .await,
+        DonationError::DonationAmountTooLow,
+    );
+    assert_donation_error(
+        env.process(
+            &[create(MIN_AMOUNT_TO_COLLECT, &"h".repeat(65), now + 100)],
+            &[&creator, &donation_data],
+        )
+        .await,
+        DonationError::IpfsHashTooLong,
+    );
+}
+
+#[tokio::test]
+async fn donates_and_withdraws() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let amount = 1_000_000_000;
+    let campaign = create_campaign(&mut env, &protocol, amount, HardCapMode::None).await;
+    let creator_data: CreatorData = env.fetch(&campaign.keys.creator_data).await;
+    assert_eq!(creator_data.total_amount_collecting, amount);
+    assert_eq!(creator_data.donations_created_count, 1);
+
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, amount).await;
+    let donate =
+        DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 0).instruction();
+    assert_donation_error(
+        env.process(&[donate], &[&donor.wallet]).await,
+        DonationError::DonationAmountZero,
+    );
+
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), amount / 2)
+        .instruction();
+    env.process(&[donate], &[&donor.wallet]).await.unwrap();
+    assert_eq!(
+        campaign.data(&mut env).await.total_amount_received,
+        amount / 2
+    );
+    assert_eq!(
+        env.token_balance(&campaign.keys.holding_wallet).await,
+        amount / 2
+    );
+    // reward equals the donated amount while the treasury can cover it
+    assert_eq!(env.token_balance(&donor.reward_wallet).await, amount / 2);
+    let contributor_data: ContributorData = env
+        .fetch(&find_contributor_data(&protocol.donation_protocol, &donor.wallet.pubkey()).0)
+        .await;
+    assert_eq!(contributor_data.total_amount_donated, amount / 2);
+    assert_eq!(contributor_data.total_amount_earned, amount / 2);
+    assert_eq!(contributor_data.donations_count, 1);
+
+    // recipients are fixed once donations arrived
+    let create_split = CreatePayoutSplitBuilder::new(
+        protocol.donation_protocol,
+        campaign.keys.donation_data,
+        campaign.creator.pubkey(),
+    )
+    .recipient(campaign.recipient, 10_000)
+    .instruction();
+    assert_donation_error(
+        env.process(&[create_split], &[&campaign.creator]).await,
+        DonationError::PayoutSplitLocked,
+    );
+
+    // donations below `min_amount_to_earn` earn nothing
+    let donate = DonateBuilder::new(
+        &protocol,
+        &campaign.keys,
+        donor.wallet.pubkey(),
+        MIN_AMOUNT_TO_EARN - 1,
+    )
+    .instruction();
+    env.process(&[donate], &[&donor.wallet]).await.unwrap();
+    assert_eq!(env.token_balance(&donor.reward_wallet).await, amount / 2);
+
+    let withdraw = WithdrawFundsBuilder::new(
+        &campaign.keys,
+        campaign.recipient,
+        campaign.creator.pubkey(),
+    )
+    .instruction();
+    assert_donation_error(
+        env.process(&[withdraw.clone()], &[&campaign.creator]).await,
+        DonationError::DonationEndingReqiuirementsNotMet,
+    );
+
+    env.warp_forward(CAMPAIGN_DURATION + 1).await;
+    // payouts only go to the campaign recipient
+    let creator_wallet = env
+        .token_account(&campaign.creator.pubkey(), &protocol.donation_mint)
+        .await;
+    let foreign_withdraw =
+        WithdrawFundsBuilder::new(&campaign.keys, creator_wallet, campaign.creator.pubkey())
+            .instruction();
+    assert_donation_error(
+        env.process(&[foreign_withdraw], &[&campaign.creator]).await,
+        DonationError::DonationAccountMismatch,
+    );
+    env.process(&[withdraw], &[&campaign.creator])
+        .await
+        .unwrap();
+    let total_amount_received = amount / 2 + MIN_AMOUNT_TO_EARN - 1;
+    assert_eq!(
+        env.token_balance(&campaign.recipient).await,
+        total_amount_received
+    );
+    assert_eq!(env.token_balance(&campaign.keys.holding_wallet).await, 0);
+    let data = campaign.data(&mut env).await;
+    assert!(data.is_closed());
+    let creator_data: CreatorData = env.fetch(&campaign.keys.creator_data).await;
+    assert_eq!(creator_data.total_amount_received, total_amount_received);
+    assert_eq!(creator_data.donations_closed_count, 1);
+
+    // closed campaigns take no donations and pay out only once
+    let donate =
+        DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 1_000).instruction();
+    assert_donation_error(
+        env.process(&[donate], &[&donor.wallet]).await,
+        DonationError::DonationClosed,
+    );
+    let withdraw = WithdrawFundsBuilder::new(
+        &campaign.keys,
+        campaign.recipient,
+        campaign.creator.pubkey(),
+    )
+    .instruction();
+    assert_donation_error(
+        env.process(&[withdraw], &[&campaign.creator]).await,
+        DonationError::DonationClosed,
+    );
+}
+
+#[tokio::test]
+async fn hard_cap_rejects_or_trims_donations() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let amount = 2_000_000;
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, 10 * amount).await;
+
+    let rejecting = create_campaign(&mut env, &protocol, amount, HardCapMode::Reject).await;
+    let donate = DonateBuilder::new(
+        &protocol,
+        &rejecting.keys,
+        donor.wallet.pubkey(),
+        amount + 1,
+    )
+    .instruction();
+    assert_donation_error(
+        env.process(&[donate], &[&donor.wallet]).await,
+        DonationError::HardCapExceeded,
+    );
+
+    let trimming = create_campaign(&mut env, &protocol, amount, HardCapMode::Trim).await;
+    let donate = DonateBuilder::new(&protocol, &trimming.keys, donor.wallet.pubkey(), amount + 1)
+        .instruction();
+    env.process(&[donate], &[&donor.wallet]).await.unwrap();
+    assert_eq!(trimming.data(&mut env).await.total_amount_received, amount);
+    assert_eq!(
+        env.token_balance(&trimming.keys.holding_wallet).await,
+        amount
+    );
+
+    let donate =
+        DonateBuilder::new(&protocol, &trimming.keys, donor.wallet.pubkey(), 1).instruction();
+    assert_donation_error(
+        env.process(&[donate], &[&donor.wallet]).await,
+        DonationError::HardCapExceeded,
+    );
+
+    // reaching the goal allows withdrawal before the ending timestamp
+    let withdraw = WithdrawFundsBuilder::new(
+        &trimming.keys,
+        trimming.recipient,
+        trimming.creator.pubkey(),
+    )
+    .instruction();
+    env.process(&[withdraw], &[&trimming.creator])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&trimming.recipient).await, amount);
+}
+
+#[tokio::test]
+async fn donates_on_behalf_of_beneficiary() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let campaign = create_campaign_with(
+        &mut env,
+        &protocol,
+        2_000_000,
+        CreateDonationBuilder::refundable,
+    )
+    .await;
+    let payer = create_donor(&mut env, &protocol, &protocol.donation_mint, 1_000_000).await;
+    let beneficiary = create_donor(&mut env, &protocol, &protocol.donation_mint, 0).await;
+
+    let donate = DonateOnBehalfBuilder::new(
+        &protocol,
+        &campaign.keys,
+        beneficiary.wallet.pubkey(),
+        payer.wallet.pubkey(),
+        1_000_000,
+    )
+    .instruction();
+    env.process(&[donate], &[&payer.wallet]).await.unwrap();
+
+    assert_eq!(env.token_balance(&payer.token_wallet).await, 0);
+    assert_eq!(env.token_balance(&payer.reward_wallet).await, 0);
+    assert_eq!(
+        env.token_balance(&beneficiary.reward_wallet).await,
+        1_000_000
+    );
+    let receipt: DonationReceipt = env
+        .fetch(&find_donation_receipt(&campaign.keys.donation_data, &beneficiary.wallet.pubkey()).0)
+        .await;
+    assert_eq!(receipt.donor, beneficiary.wallet.pubkey());
+    assert_eq!(receipt.amount_donated, 1_000_000);
+    assert_eq!(receipt.amount_refundable, 0);
+
+    // gifts can not be refunded, the sponsor's tokens would go to the beneficiary
+    let refund = RefundDonationBuilder::new(
+        &protocol,
+        &campaign.keys,
+        beneficiary.wallet.pubkey(),
+        1_000_000,
+    )
+    .instruction();
+    assert_donation_error(
+        env.process(&[refund], &[&beneficiary.wallet]).await,
+        DonationError::InvalidRefundAmount,
+    );
+
+    // creators earn nothing when donating to their own campaigns
+    let creator_wallet = env
+        .token_wallet(&campaign.creator.pubkey(), &protocol.donation_mint)
+        .await;
+    env.mint_to(&protocol.donation_mint, &creator_wallet, 1_000_000)
+        .await;
+    let creator_reward_wallet = env
+        .token_wallet(&campaign.creator.pubkey(), &protocol.treasury_mint)
+        .await;
+    let donate = DonateBuilder::new(
+        &protocol,
+        &campaign.keys,
+        campaign.creator.pubkey(),
+        1_000_000,
+    )
+    .instruction();
+    env.process(&[donate], &[&campaign.creator]).await.unwrap();
+    assert_eq!(env.token_balance(&creator_reward_wallet).await, 0);
+    assert_eq!(campaign.data(&mut env).await.self_funded_amount, 1_000_000);
+}
+
+#[tokio::test]
+async fn reads_recipient_owner_at_donation_time() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let campaign = create_campaign(&mut env, &protocol, 2_000_000, HardCapMode::None).await;
+    let new_owner = create_donor(&mut env, &protocol, &protocol.donation_mint, 1_000_000).await;
+
+    // recipient token account changes hands after the campaign is created
+    let set_owner = spl_token::instruction::set_authority(
+        &spl_token::ID,
+        &campaign.recipient,
+        Some(&new_owner.wallet.pubkey()),
+        AuthorityType::AccountOwner,
+        &campaign.creator.pubkey(),
+        &[],
+    )
+    .unwrap();
+    env.process(&[set_owner], &[&campaign.creator])
+        .await
+        .unwrap();
+
+    let donate = DonateBuilder::new(
+        &protocol,
+        &campaign.keys,
+        new_owner.wallet.pubkey(),
+        1_000_000,
+    )
+    .instruction();
+    env.process(&[donate], &[&new_owner.wallet]).await.unwrap();
+    assert_eq!(env.token_balance(&new_owner.reward_wallet).await, 0);
+    assert_eq!(campaign.data(&mut env).await.self_funded_amount, 1_000_000);
+}
+
+#[tokio::test]
+async fn rejects_donation_to_foreign_recipient() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let campaign = create_campaign(&mut env, &protocol, 2_000_000, HardCapMode::None).await;
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, 1_000_000).await;
+
+    let keys = CampaignKeys {
+        recipient: donor.reward_wallet,
+        ..campaign.keys
+    };
+    let donate =
+        DonateBuilder::new(&protocol, &keys, donor.wallet.pubkey(), 1_000_000).instruction();
+    assert_donation_error(
+        env.process(&[donate], &[&donor.wallet]).await,
+        DonationError::DonationAccountMismatch,
+    );
+}
+
+#[tokio::test]
+async fn withdraws_through_payout_split() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let mut campaign = create_campaign(&mut env, &protocol, 1_000_000, HardCapMode::None).await;
+    let partner = env.wallet().await;
+    let partner_wallet = env
+        .token_wallet(&partner.pubkey(), &protocol.donation_mint)
+        .await;
+
+    let create_split = CreatePayoutSplitBuilder::new(
+        protocol.donation_protocol,
+        campaign.keys.donation_data,
+        campaign.creator.pubkey(),
+    )
+    .recipient(campaign.recipient, 7_000)
+    .recipient(partner_wallet, 2_000)
+    .instruction();
+    assert_donation_error(
+        env.process(&[create_split], &[&campaign.creator]).await,
+        DonationError::InvalidPayoutSplit,
+    );
+
+    let create_split = CreatePayoutSplitBuilder::new(
+        protocol.donation_protocol,
+        campaign.keys.donation_data,
+        campaign.creator.pubkey(),
+    )
+    .recipient(campaign.recipient, 7_000)
+    .recipient(partner_wallet, 3_000)
+    .instruction();
+    env.process(&[create_split], &[&campaign.creator])
+        .await
+        .unwrap();
+    campaign.refresh(&mut env).await;
+    let payout_split: PayoutSplit = env.fetch(&campaign.keys.payout_split.unwrap()).await;
+    assert_eq!(payout_split.recipients.len(), 2);
+
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, 1_000_000).await;
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 1_000_000)
+        .instruction();
+    env.process(&[donate], &[&donor.wallet]).await.unwrap();
+
+    let withdraw = WithdrawFundsBuilder::new(
+        &campaign.keys,
+        campaign.recipient,
+        campaign.creator.pubkey(),
+    )
+    .payout_recipients(vec![campaign.recipient, partner_wallet])
+    .instruction();
+    env.process(&[withdraw], &[&campaign.creator])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&campaign.recipient).await, 700_000);
+    assert_eq!(env.token_balance(&partner_wallet).await, 300_000);
+}
+
+#[tokio::test]
+async fn refunds_donation_before_ending() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let campaign = create_campaign_with(
+        &mut env,
+        &protocol,
+        10_000_000,
+        CreateDonationBuilder::refundable,
+    )
+    .await;
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, 2_000_000).await;
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 2_000_000)
+        .instruction();
+    env.process(&[donate], &[&donor.wallet]).await.unwrap();
+
+    // refunds are opt-in per campaign
+    let final_campaign = create_campaign(&mut env, &protocol, 10_000_000, HardCapMode::None).await;
+    let final_donor = create_donor(&mut env, &protocol, &protocol.donation_mint, 1_000_000).await;
+    let donate = DonateBuilder::new(
+        &protocol,
+        &final_campaign.keys,
+        final_donor.wallet.pubkey(),
+        1_000_000,
+    )
+    .instruction();
+    env.process(&[donate], &[&final_donor.wallet])
+        .await
+        .unwrap();
+    let refund = RefundDonationBuilder::new(
+        &protocol,
+        &final_campaign.keys,
+        final_donor.wallet.pubkey(),
+        1_000_000,
+    )
+    .instruction();
+    assert_donation_error(
+        env.process(&[refund], &[&final_donor.wallet]).await,
+        DonationError::RefundsDisabled,
+    );
+
+    let refund =
+        RefundDonationBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 2_000_001)
+            .instruction();
+    assert_donation_error(
+        env.process(&[refund], &[&donor.wallet]).await,
+        DonationError::InvalidRefundAmount,
+    );
+
+    let refund =
+        RefundDonationBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 500_000)
+            .instruction();
+    env.process(&[refund], &[&donor.wallet]).await.unwrap();
+    assert_eq!(env.token_balance(&donor.token_wallet).await, 500_000);
+    // proportional reward goes back to the treasury
+    assert_eq!(env.token_balance(&donor.reward_wallet).await, 1_500_000);
+    assert_eq!(
+        campaign.data(&mut env).await.total_amount_received,
+        1_500_000
+    );
+    let receipt: DonationReceipt = env
+        .fetch(&find_donation_receipt(&campaign.keys.donation_data, &donor.wallet.pubkey()).0)
+        .await;
+    assert_eq!(receipt.amount_donated, 1_500_000);
+    assert_eq!(receipt.amount_refundable, 1_500_000);
+    assert_eq!(receipt.reward_amount, 1_500_000);
+
+    env.warp_forward(CAMPAIGN_DURATION).await;
+    let refund =
+        RefundDonationBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 500_000)
+            .instruction();
+    assert_donation_error(
+        env.process(&[refund], &[&donor.wallet]).await,
+        DonationError::RefundPeriodOver,
+    );
+}
+
+#[tokio::test]
+async fn records_perk_tiers_from_cumulative_contribution() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let creator = initialize_creator(&mut env, &protocol.donation_protocol).await;
+    let recipient = env
+        .token_wallet(&creator.pubkey(), &protocol.donation_mint)
+        .await;
+    let donation_data = Keypair::new();
+    let holding_wallet_owner = find_holding_wallet_owner(&donation_data.pubkey()).0;
+    env.token_wallet(&holding_wallet_owner, &protocol.donation_mint)
+        .await;
+    let ending_timestamp = env.now().await + CAMPAIGN_DURATION;
+    let create = || {
+        CreateDonationBuilder::new(
+            protocol.donation_protocol,
+            donation_data.pubkey(),
+            protocol.donation_mint,
+            recipient,
+            creator.pubkey(),
+            10_000_000,
+            "some_ipfs_hash",
+            ending_timestamp,
+        )
+        .refundable()
+    };
+
+    let unordered = create()
+        .tier(5_000_000, 1, "early_access")
+        .tier(1_000_000, 2, "thank_you_nft")
+        .instruction();
+    assert_donation_error(
+        env.process(&[unordered], &[&creator, &donation_data]).await,
+        DonationError::InvalidPerkTiers,
+    );
+    let create = create()
+        .tier(1_000_000, 2, "thank_you_nft")
+        .tier(5_000_000, 1, "early_access")
+        .instruction();
+    env.process(&[create], &[&creator, &donation_data])
+        .await
+        .unwrap();
+    let keys = campaign_keys(&mut env, &donation_data.pubkey()).await;
+    let donation_tiers_key = find_donation_tiers(&donation_data.pubkey()).0;
+    assert_eq!(keys.donation_tiers, Some(donation_tiers_key));
+
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, 5_000_000).await;
+    let receipt_key = find_donation_receipt(&donation_data.pubkey(), &donor.wallet.pubkey()).0;
+    let without_tiers = CampaignKeys {
+        donation_tiers: None,
+        ..keys
+    };
+    let donate =
+        DonateBuilder::new(&protocol, &without_tiers, donor.wallet.pubkey(), 500_000).instruction();
+    assert_donation_error(
+        env.process(&[donate], &[&donor.wallet]).await,
+        DonationError::DonationTiersRequired,
+    );
+
+    let donate = |amount: u64| {
+        DonateBuilder::new(&protocol, &keys, donor.wallet.pubkey(), amount).instruction()
+    };
+    env.process(&[donate(500_000)], &[&donor.wallet])
+        .await
+        .unwrap();
+    let receipt: DonationReceipt = env.fetch(&receipt_key).await;
+    assert_eq!(receipt.perk_tier, 0);
+
+    env.process(&[donate(500_000)], &[&donor.wallet])
+        .await
+        .unwrap();
+    let receipt: DonationReceipt = env.fetch(&receipt_key).await;
+    assert_eq!(receipt.perk_tier, 1);
+
+    // cumulative contribution moves the donor up a tier
+    env.process(&[donate(4_000_000)], &[&donor.wallet])
+        .await
+        .unwrap();
+    let receipt: DonationReceipt = env.fetch(&receipt_key).await;
+    assert_eq!(receipt.perk_tier, 2);
+    let donation_tiers: DonationTiers = env.fetch(&donation_tiers_key).await;
+    assert_eq!(donation_tiers.tiers[0].claimed, 0);
+    assert_eq!(donation_tiers.tiers[1].claimed, 1);
+
+    // fulfilled perks are final
+    let refund = RefundDonationBuilder::new(&protocol, &keys, donor.wallet.pubkey(), 1_000_000)
+        .instruction();
+    assert_donation_error(
+        env.process(&[refund], &[&donor.wallet]).await,
+        DonationError::PerkTierClaimed,
+    );
+}
+
+#[tokio::test]
+async fn issues_receipt_token_with_donation() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let campaign = create_campaign_with(
+        &mut env,
+        &protocol,
+        10_000_000,
+        CreateDonationBuilder::refundable,
+    )
+    .await;
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, 3_000_000).await;
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 1_000_000)
+        .instruction();
+    env.process(&[donate], &[&donor.wallet]).await.unwrap();
+
+    let receipt_mint = Keypair::new();
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 2_000_000)
+        .receipt(receipt_mint.pubkey())
+        .instruction();
+    env.process(&[donate], &[&donor.wallet, &receipt_mint])
+        .await
+        .unwrap();
+    let receipt_token_account =
+        get_associated_token_address(&donor.wallet.pubkey(), &receipt_mint.pubkey());
+    assert_eq!(env.token_balance(&receipt_token_account).await, 1);
+    let mint: Mint = env.fetch(&receipt_mint.pubkey()).await;
+    assert_eq!(mint.supply, 1);
+    assert_eq!(mint.decimals, 0);
+    assert!(mint.mint_authority.is_none());
+    let metadata: ReceiptMetadata = env
+        .fetch(&find_receipt_metadata(&receipt_mint.pubkey()).0)
+        .await;
+    assert_eq!(metadata.receipt_mint, receipt_mint.pubkey());
+    assert_eq!(metadata.donation_data, campaign.keys.donation_data);
+    assert_eq!(metadata.donor, donor.wallet.pubkey());
+    assert_eq!(metadata.donation_mint, protocol.donation_mint);
+    assert_eq!(metadata.amount, 2_000_000);
+    assert_eq!(metadata.timestamp, env.now().await);
+
+    // only the receipted donation is locked
+    let receipt: DonationReceipt = env
+        .fetch(&find_donation_receipt(&campaign.keys.donation_data, &donor.wallet.pubkey()).0)
+        .await;
+    assert_eq!(receipt.amount_donated, 3_000_000);
+    assert_eq!(receipt.amount_receipted, 2_000_000);
+    assert_eq!(receipt.amount_refundable, 1_000_000);
+    let refund =
+        RefundDonationBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 1_000_001)
+            .instruction();
+    assert_donation_error(
+        env.process(&[refund], &[&donor.wallet]).await,
+        DonationError::InvalidRefundAmount,
+    );
+}
+
+#[tokio::test]
+async fn donates_to_several_campaigns_in_one_batch() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let amount = 1_000_000_000;
+    let mut campaigns = Vec::new();
+    for _ in 0..3 {
+        campaigns.push(create_campaign(&mut env, &protocol, amount, HardCapMode::None).await);
+    }
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, amount).await;
+
+    // every part is below `min_amount_to_earn`, the reward is computed on the whole gift
+    let part = MIN_AMOUNT_TO_EARN / 2;
+    let batch = campaigns
+        .iter()
+        .fold(
+            DonateBatchBuilder::new(&protocol, donor.wallet.pubkey()),
+            |builder, campaign| builder.donation(&campaign.keys, part),
+        )
+        .instruction();
+    env.process(&[batch], &[&donor.wallet]).await.unwrap();
+
+    for campaign in &campaigns {
+        assert_eq!(campaign.data(&mut env).await.total_amount_received, part);
+        assert_eq!(env.token_balance(&campaign.keys.holding_wallet).await, part);
+        let receipt: DonationReceipt = env
+            .fetch(&find_donation_receipt(&campaign.keys.donation_data, &donor.wallet.pubkey()).0)
+            .await;
+        assert_eq!(receipt.amount_donated, part);
+        assert_eq!(receipt.reward_amount, part);
+    }
+    assert_eq!(env.token_balance(&donor.reward_wallet).await, 3 * part);
+    let contributor_data: ContributorData = env
+        .fetch(&find_contributor_data(&protocol.donation_protocol, &donor.wallet.pubkey()).0)
+        .await;
+    assert_eq!(contributor_data.total_amount_donated, 3 * part);
+    assert_eq!(contributor_data.total_amount_earned, 3 * part);
+    assert_eq!(contributor_data.donations_count, 3);
+}
+
+#[tokio::test]
+async fn rejects_invalid_donation_batches() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let amount = 1_000_000_000;
+    let first = create_campaign(&mut env, &protocol, amount, HardCapMode::None).await;
+    let second = create_campaign(&mut env, &protocol, amount, HardCapMode::None).await;
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, amount).await;
+    let batch = || DonateBatchBuilder::new(&protocol, donor.wallet.pubkey());
+
+    let duplicated = batch()
+        .donation(&first.keys, amount / 4)
+        .donation(&first.keys, amount / 4)
+        .instruction();
+    assert_donation_error(
+        env.process(&[duplicated], &[&donor.wallet]).await,
+        DonationError::InvalidDonationBatch,
+    );
+
+    let mut foreign_holding_wallet = first.keys;
+    foreign_holding_wallet.holding_wallet = second.keys.holding_wallet;
+    let mixed = batch()
+        .donation(&foreign_holding_wallet, amount / 4)
+        .instruction();
+    assert_donation_error(
+        env.process(&[mixed], &[&donor.wallet]).await,
+        DonationError::InvalidDonationBatch,
+    );
+
+    let mut missing_accounts = batch()
+        .donation(&first.keys, amount / 4)
+        .donation(&second.keys, amount / 4)
+        .instruction();
+    let accounts_len = missing_accounts.accounts.len();
+    missing_accounts.accounts.truncate(accounts_len - 2);
+    assert_donation_error(
+        env.process(&[missing_accounts], &[&donor.wallet]).await,
+        DonationError::InvalidDonationBatch,
+    );
+
+    let mut readonly = batch().donation(&first.keys, amount / 4).instruction();
+    readonly.accounts.last_mut().unwrap().is_writable = false;
+    assert_donation_error(
+        env.process(&[readonly], &[&donor.wallet]).await,
+        DonationError::InvalidDonationBatch,
+    );
+
+    let mut not_a_campaign = first.keys;
+    not_a_campaign.donation_data = protocol.donation_protocol;
+    let spoofed = batch().donation(&not_a_campaign, amount / 4).instruction();
+    assert_anchor_error(
+        env.process(&[spoofed], &[&donor.wallet]).await,
+        ErrorCode::AccountDiscriminatorMismatch,
+    );
+
+    let zero = batch()
+        .donation(&first.keys, amount / 4)
+        .donation(&second.keys, 0)
+        .instruction();
+    assert_donation_error(
+        env.process(&[zero], &[&donor.wallet]).await,
+        DonationError::DonationAmountZero,
+    );
+
+    // a failed batch transfers nothing
+    assert_eq!(env.token_balance(&first.keys.holding_wallet).await, 0);
+    assert_eq!(env.token_balance(&donor.token_wallet).await, amount);
+}
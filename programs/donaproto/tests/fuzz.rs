@@ -0,0 +1,316 @@
+//! Stateful fuzzing of campaign lifecycles: random sequences of `create_donation(_v2)`,
+//! `donate(_v2)` and `withdraw_funds(_v2)` run in the in-process bank and core accounting
+//! invariants are checked after every step. `FUZZ_CASES` sets the number of sequences.
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use donaproto::states::{ContributorData, HardCapMode};
+use donaproto_client::{
+    pda::find_contributor_data, DonateBuilder, ProtocolKeys, WithdrawFundsBuilder,
+};
+use proptest::{prelude::*, sample::Index};
+use solana_sdk::signature::Signer;
+
+const OTHER_MINT_DECIMALS: u8 = 9;
+/// 1 other token is worth 2 protocol tokens
+const OTHER_MINT_PRICE: f64 = 0.002;
+const DONOR_COUNT: usize = 3;
+const DONOR_BALANCE: u64 = 1_000_000_000_000_000;
+const MAX_ACTIONS: usize = 24;
+const HARD_CAP_MODES: [HardCapMode; 3] =
+    [HardCapMode::None, HardCapMode::Reject, HardCapMode::Trim];
+
+#[derive(Clone, Debug)]
+enum Action {
+    CreateDonation {
+        is_v2: bool,
+        hard_cap_mode: usize,
+        amount: u64,
+    },
+    Donate {
+        campaign: Index,
+        donor: Index,
+        amount: u64,
+    },
+    DonateV2 {
+        campaign: Index,
+        donor: Index,
+        amount: u64,
+    },
+    WithdrawFunds {
+        campaign: Index,
+    },
+    WithdrawFundsV2 {
+        campaign: Index,
+    },
+    Warp {
+        seconds: u64,
+    },
+}
+
+fn action_strategy() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        2 => (
+            any::<bool>(),
+            0..HARD_CAP_MODES.len(),
+            MIN_AMOUNT_TO_COLLECT..=100 * MIN_AMOUNT_TO_COLLECT,
+        )
+            .prop_map(|(is_v2, hard_cap_mode, amount)| Action::CreateDonation {
+                is_v2,
+                hard_cap_mode,
+                amount,
+            }),
+        4 => (any::<Index>(), any::<Index>(), 0..=50 * MIN_AMOUNT_TO_COLLECT)
+            .prop_map(|(campaign, donor, amount)| Action::Donate { campaign, donor, amount }),
+        4 => (any::<Index>(), any::<Index>(), 0..=50 * MIN_AMOUNT_TO_COLLECT)
+            .prop_map(|(campaign, donor, amount)| Action::DonateV2 { campaign, donor, amount }),
+        1 => any::<Index>().prop_map(|campaign| Action::WithdrawFunds { campaign }),
+        1 => any::<Index>().prop_map(|campaign| Action::WithdrawFundsV2 { campaign }),
+        1 => (1..=CAMPAIGN_DURATION / 2).prop_map(|seconds| Action::Warp { seconds }),
+    ]
+}
+
+struct FuzzCampaign {
+    campaign: Campaign,
+    is_v2: bool,
+    /// Holding balance and `total_amount_received` when the campaign got closed
+    closed_state: Option<(u64, u64)>,
+}
+
+struct Harness {
+    env: TestEnv,
+    protocol: ProtocolKeys,
+    other_mint: Pubkey,
+    fixture: PoolFixture,
+    /// Hold both the protocol mint and the other mint
+    donors: Vec<Donor>,
+    campaigns: Vec<FuzzCampaign>,
+}
+
+impl Harness {
+    async fn start() -> Self {
+        let mut env = TestEnv::start().await;
+        let protocol = setup_protocol(&mut env).await;
+        let other_mint = env.create_mint(OTHER_MINT_DECIMALS).await;
+        let fixture = create_pool_fixture(
+            &mut env,
+            &protocol.donation_protocol,
+            (other_mint, OTHER_MINT_DECIMALS),
+            (protocol.donation_mint, DONATION_MINT_DECIMALS),
+            OTHER_MINT_PRICE,
+        );
+        authorize_clmm(&mut env, &protocol).await;
+        authorize_pool(&mut env, &protocol, &fixture, &other_mint).await;
+
+        let mut donors = Vec::with_capacity(DONOR_COUNT);
+        for _ in 0..DONOR_COUNT {
+            let donor =
+                create_donor(&mut env, &protocol, &protocol.donation_mint, DONOR_BALANCE).await;
+            let other_token_wallet = env.token_wallet(&donor.wallet.pubkey(), &other_mint).await;
+            env.mint_to(&other_mint, &other_token_wallet, DONOR_BALANCE)
+                .await;
+            donors.push(donor);
+        }
+
+        Self {
+            env,
+            protocol,
+            other_mint,
+            fixture,
+            donors,
+            campaigns: Vec::new(),
+        }
+    }
+
+    async fn apply(&mut self, action: &Action) {
+        match action {
+            Action::CreateDonation {
+                is_v2,
+                hard_cap_mode,
+                amount,
+            } => {
+                let hard_cap_mode = HARD_CAP_MODES[*hard_cap_mode];
+                let campaign = if *is_v2 {
+                    create_campaign_v2(
+                        &mut self.env,
+                        &self.protocol,
+                        &self.fixture,
+                        &self.other_mint,
+                        *amount,
+                        hard_cap_mode,
+                    )
+                    .await
+                } else {
+                    create_campaign(&mut self.env, &self.protocol, *amount, hard_cap_mode).await
+                };
+                self.campaigns.push(FuzzCampaign {
+                    campaign,
+                    is_v2: *is_v2,
+                    closed_state: None,
+                });
+            }
+            Action::Donate {
+                campaign,
+                donor,
+                amount,
+            } => self.donate(campaign, donor, *amount, false).await,
+            Action::DonateV2 {
+                campaign,
+                donor,
+                amount,
+            } => self.donate(campaign, donor, *amount, true).await,
+            Action::WithdrawFunds { campaign } => self.withdraw(campaign, false).await,
+            Action::WithdrawFundsV2 { campaign } => self.withdraw(campaign, true).await,
+            Action::Warp { seconds } => self.env.warp_forward(*seconds).await,
+        }
+    }
+
+    /// Instructions not matching the campaign kind are sent too and must fail on their own
+    async fn donate(&mut self, campaign: &Index, donor: &Index, amount: u64, is_v2: bool) {
+        if self.campaigns.is_empty() {
+            return;
+        }
+        let campaign = &self.campaigns[campaign.index(self.campaigns.len())];
+        let donor = &self.donors[donor.index(self.donors.len())];
+        let mut donate = DonateBuilder::new(
+            &self.protocol,
+            &campaign.campaign.keys,
+            donor.wallet.pubkey(),
+            amount,
+        );
+        if is_v2 {
+            donate = donate.v2(self.fixture.pool);
+        }
+
+        let result = self
+            .env
+            .process(&[donate.instruction()], &[&donor.wallet])
+            .await;
+        if campaign.closed_state.is_some() {
+            assert!(result.is_err(), "closed campaign accepted a donation");
+        }
+    }
+
+    async fn withdraw(&mut self, campaign: &Index, is_v2: bool) {
+        if self.campaigns.is_empty() {
+            return;
+        }
+        let campaign = &mut self.campaigns[campaign.index(self.campaigns.len())];
+        let keys = &campaign.campaign.keys;
+        let mut withdraw = WithdrawFundsBuilder::new(
+            keys,
+            campaign.campaign.recipient,
+            campaign.campaign.creator.pubkey(),
+        );
+        if is_v2 {
+            withdraw = withdraw.v2(self.protocol.donation_mint, self.fixture.pool);
+        }
+        let holding_balance = self.env.token_balance(&keys.holding_wallet).await;
+
+        let result = self
+            .env
+            .process(&[withdraw.instruction()], &[&campaign.campaign.creator])
+            .await;
+        if campaign.closed_state.is_some() {
+            assert!(result.is_err(), "closed campaign was withdrawn again");
+            return;
+        }
+        if result.is_ok() {
+            assert_eq!(
+                campaign.is_v2, is_v2,
+                "withdrawn through the wrong instruction"
+            );
+            let data = campaign.campaign.data(&mut self.env).await;
+            assert!(data.is_closed());
+            let closed_balance = self.env.token_balance(&keys.holding_wallet).await;
+            assert_eq!(
+                closed_balance,
+                holding_balance - data.total_amount_received,
+                "withdrawal moved more or less than received"
+            );
+            campaign.closed_state = Some((closed_balance, data.total_amount_received));
+        }
+    }
+
+    async fn check_invariants(&mut self) {
+        for campaign in &self.campaigns {
+            let keys = &campaign.campaign.keys;
+            let holding_balance = self.env.token_balance(&keys.holding_wallet).await;
+            let data = self.env.fetch_donation_data(&keys.donation_data).await;
+            match campaign.closed_state {
+                Some((closed_balance, total_amount_received)) => {
+                    assert!(data.is_closed());
+                    assert_eq!(
+                        holding_balance, closed_balance,
+                        "closed campaign moved funds"
+                    );
+                    assert_eq!(data.total_amount_received, total_amount_received);
+                }
+                None => {
+                    assert!(!data.is_closed());
+                    assert!(
+                        holding_balance >= data.total_amount_received,
+                        "holding wallet {} below received {}",
+                        holding_balance,
+                        data.total_amount_received
+                    );
+                }
+            }
+        }
+
+        let mut total_amount_earned = 0;
+        let mut total_reward_balance = 0;
+        for donor in &self.donors {
+            let wallet = donor.wallet.pubkey();
+            let contributor_data =
+                find_contributor_data(&self.protocol.donation_protocol, &wallet).0;
+            if self.env.account_exists(&contributor_data).await {
+                let data: ContributorData = self.env.fetch(&contributor_data).await;
+                total_amount_earned += data.total_amount_earned;
+            }
+            total_reward_balance += self.env.token_balance(&donor.reward_wallet).await;
+        }
+        let treasury_outflow =
+            TREASURY_SUPPLY - self.env.token_balance(&self.protocol.treasury).await;
+        assert_eq!(
+            treasury_outflow, total_amount_earned,
+            "treasury outflow differs from earned rewards"
+        );
+        assert_eq!(treasury_outflow, total_reward_balance);
+    }
+}
+
+async fn run(actions: Vec<Action>) {
+    let mut harness = Harness::start().await;
+    for action in &actions {
+        harness.apply(action).await;
+        harness.check_invariants().await;
+    }
+}
+
+fn fuzz_cases() -> u32 {
+    std::env::var("FUZZ_CASES")
+        .ok()
+        .and_then(|cases| cases.parse().ok())
+        .unwrap_or(16)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: fuzz_cases(),
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn campaign_lifecycles_keep_invariants(
+        actions in prop::collection::vec(action_strategy(), 1..MAX_ACTIONS),
+    ) {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(run(actions));
+    }
+}
@@ -0,0 +1,393 @@
+mod common;
+
+use common::*;
+use donaproto::{
+    errors::DonationError,
+    states::{CreatorData, FundingRound, HardCapMode, MatchPledge},
+};
+use donaproto_client::{
+    pda::{find_match_pledge, find_match_pledge_escrow, find_matching_vault},
+    CreateFundingRoundBuilder, CreateMatchPledgeBuilder, CreatePayoutSplitBuilder,
+    DistributeMatchBuilder, DonateBatchBuilder, DonateBuilder, FinalizeRoundBuilder,
+    ReclaimMatchPledgeBuilder, SweepMatchingVaultBuilder, WithdrawFundsBuilder,
+};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn distributes_quadratic_matches_after_round() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let popular = create_campaign(&mut env, &protocol, 100_000_000, HardCapMode::None).await;
+    let whale_backed = create_campaign(&mut env, &protocol, 100_000_000, HardCapMode::None).await;
+    let round = Keypair::new();
+    let start_timestamp = env.now().await;
+    let end_timestamp = start_timestamp + 1_000;
+
+    let create_round = CreateFundingRoundBuilder::new(
+        round.pubkey(),
+        protocol.donation_protocol,
+        protocol.donation_mint,
+        env.payer(),
+        start_timestamp,
+        end_timestamp,
+    )
+    .campaign(popular.keys.donation_data)
+    .campaign(whale_backed.keys.donation_data)
+    .instruction();
+    env.process(&[create_round], &[&round]).await.unwrap();
+    let matching_vault = find_matching_vault(&round.pubkey(), &protocol.donation_mint).0;
+    env.mint_to(&protocol.donation_mint, &matching_vault, 3_000_000)
+        .await;
+
+    // two small donors outweigh a single large one
+    for _ in 0..2 {
+        let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, 1_000_000).await;
+        let donate = DonateBuilder::new(&protocol, &popular.keys, donor.wallet.pubkey(), 1_000_000)
+            .funding_round(round.pubkey())
+            .instruction();
+        env.process(&[donate], &[&donor.wallet]).await.unwrap();
+    }
+    let whale = create_donor(&mut env, &protocol, &protocol.donation_mint, 4_000_000).await;
+    let donate = DonateBuilder::new(
+        &protocol,
+        &whale_backed.keys,
+        whale.wallet.pubkey(),
+        4_000_000,
+    )
+    .funding_round(round.pubkey())
+    .instruction();
+    env.process(&[donate], &[&whale.wallet]).await.unwrap();
+
+    let finalize = FinalizeRoundBuilder::new(round.pubkey(), protocol.donation_mint).instruction();
+    assert_donation_error(
+        env.process(&[finalize.clone()], &[]).await,
+        DonationError::RoundNotEnded,
+    );
+    env.warp_to_timestamp(end_timestamp).await;
+    env.process(&[finalize], &[]).await.unwrap();
+    let funding_round: FundingRound = env.fetch(&round.pubkey()).await;
+    assert!(funding_round.is_finalized);
+    assert_eq!(funding_round.matching_pool_amount, 3_000_000);
+
+    let distribute = DistributeMatchBuilder::new(round.pubkey(), &popular.keys).instruction();
+    env.process(&[distribute.clone()], &[]).await.unwrap();
+    assert_donation_error(
+        env.process(&[distribute], &[]).await,
+        DonationError::MatchAlreadyDistributed,
+    );
+    let distribute = DistributeMatchBuilder::new(round.pubkey(), &whale_backed.keys).instruction();
+    env.process(&[distribute], &[]).await.unwrap();
+
+    assert_eq!(
+        env.token_balance(&popular.keys.holding_wallet).await,
+        5_000_000
+    );
+    assert_eq!(popular.data(&mut env).await.total_amount_matched, 3_000_000);
+    assert_eq!(
+        env.token_balance(&whale_backed.keys.holding_wallet).await,
+        4_000_000
+    );
+    assert_eq!(whale_backed.data(&mut env).await.total_amount_matched, 0);
+    assert_eq!(env.token_balance(&matching_vault).await, 0);
+}
+
+#[tokio::test]
+async fn pays_closed_campaigns_and_sweeps_rounding_dust() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let mut funded = create_campaign(&mut env, &protocol, 2_000_000, HardCapMode::None).await;
+    let running = create_campaign(&mut env, &protocol, 100_000_000, HardCapMode::None).await;
+    let partner = env.wallet().await;
+    let partner_wallet = env
+        .token_wallet(&partner.pubkey(), &protocol.donation_mint)
+        .await;
+    let create_split = CreatePayoutSplitBuilder::new(
+        protocol.donation_protocol,
+        funded.keys.donation_data,
+        funded.creator.pubkey(),
+    )
+    .recipient(funded.recipient, 7_000)
+    .recipient(partner_wallet, 3_000)
+    .instruction();
+    env.process(&[create_split], &[&funded.creator])
+        .await
+        .unwrap();
+    funded.refresh(&mut env).await;
+    let round = Keypair::new();
+    let start_timestamp = env.now().await;
+    let end_timestamp = start_timestamp + 1_000;
+
+    let create_round = CreateFundingRoundBuilder::new(
+        round.pubkey(),
+        protocol.donation_protocol,
+        protocol.donation_mint,
+        env.payer(),
+        start_timestamp,
+        end_timestamp,
+    )
+    .campaign(funded.keys.donation_data)
+    .campaign(running.keys.donation_data)
+    .instruction();
+    env.process(&[create_round], &[&round]).await.unwrap();
+    let matching_vault = find_matching_vault(&round.pubkey(), &protocol.donation_mint).0;
+    env.mint_to(&protocol.donation_mint, &matching_vault, 1_000_000)
+        .await;
+
+    // weights of 2_000_000 and 1_000_000 leave one token of rounding dust
+    for (campaign, amount) in [
+        (&funded, 1_000_000),
+        (&funded, 1_000_000),
+        (&running, 1_000_000),
+        (&running, 250_000),
+    ] {
+        let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, amount).await;
+        let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), amount)
+            .funding_round(round.pubkey())
+            .instruction();
+        env.process(&[donate], &[&donor.wallet]).await.unwrap();
+    }
+
+    // the funded campaign reached its goal and withdraws before the round ends
+    let withdraw =
+        WithdrawFundsBuilder::new(&funded.keys, funded.recipient, funded.creator.pubkey())
+            .payout_recipients(vec![funded.recipient, partner_wallet])
+            .instruction();
+    env.process(&[withdraw], &[&funded.creator]).await.unwrap();
+    assert_eq!(env.token_balance(&funded.recipient).await, 1_400_000);
+    assert_eq!(env.token_balance(&partner_wallet).await, 600_000);
+
+    let authority_wallet = env
+        .token_wallet(&env.payer(), &protocol.donation_mint)
+        .await;
+    let sweep = SweepMatchingVaultBuilder::new(
+        round.pubkey(),
+        protocol.donation_mint,
+        authority_wallet,
+        env.payer(),
+    )
+    .instruction();
+    assert_donation_error(
+        env.process(&[sweep.clone()], &[]).await,
+        DonationError::RoundNotFinalized,
+    );
+
+    env.warp_to_timestamp(end_timestamp).await;
+    let finalize = FinalizeRoundBuilder::new(round.pubkey(), protocol.donation_mint).instruction();
+    env.process(&[finalize], &[]).await.unwrap();
+
+    // closed campaigns pay their match out through the payout split right away
+    let distribute = DistributeMatchBuilder::new(round.pubkey(), &funded.keys).instruction();
+    assert_donation_error(
+        env.process(&[distribute], &[]).await,
+        DonationError::InvalidPayoutSplit,
+    );
+    let distribute = DistributeMatchBuilder::new(round.pubkey(), &funded.keys)
+        .payout_recipients(vec![funded.recipient, partner_wallet])
+        .instruction();
+    env.process(&[distribute], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&funded.recipient).await, 1_866_667);
+    assert_eq!(env.token_balance(&partner_wallet).await, 799_999);
+    assert_eq!(env.token_balance(&funded.keys.holding_wallet).await, 0);
+    let creator_data: CreatorData = env.fetch(&funded.keys.creator_data).await;
+    assert_eq!(creator_data.total_amount_received, 2_666_666);
+    assert_donation_error(
+        env.process(&[sweep.clone()], &[]).await,
+        DonationError::MatchesNotDistributed,
+    );
+
+    let distribute = DistributeMatchBuilder::new(round.pubkey(), &running.keys).instruction();
+    env.process(&[distribute], &[]).await.unwrap();
+    assert_eq!(
+        env.token_balance(&running.keys.holding_wallet).await,
+        1_583_333
+    );
+
+    let stranger = env.wallet().await;
+    let stranger_sweep = SweepMatchingVaultBuilder::new(
+        round.pubkey(),
+        protocol.donation_mint,
+        authority_wallet,
+        stranger.pubkey(),
+    )
+    .instruction();
+    assert_constraint_error(env.process(&[stranger_sweep], &[&stranger]).await);
+
+    env.process(&[sweep.clone()], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&authority_wallet).await, 1);
+    assert_eq!(env.token_balance(&matching_vault).await, 0);
+    assert_donation_error(
+        env.process(&[sweep], &[]).await,
+        DonationError::NothingToSweep,
+    );
+}
+
+#[tokio::test]
+async fn matches_donations_from_sponsor_pledge() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let mut campaign = create_campaign(&mut env, &protocol, 100_000_000, HardCapMode::None).await;
+    let sponsor = env.wallet().await;
+    let sponsor_token_wallet = env
+        .token_wallet(&sponsor.pubkey(), &protocol.donation_mint)
+        .await;
+    env.mint_to(&protocol.donation_mint, &sponsor_token_wallet, 1_500_000)
+        .await;
+    let deadline = env.now().await + 1_000;
+
+    // a sponsor can not take the single pledge slot of a campaign without its creator
+    let create_pledge = CreateMatchPledgeBuilder::new(
+        &campaign.keys,
+        sponsor.pubkey(),
+        sponsor.pubkey(),
+        sponsor_token_wallet,
+        5_000,
+        1_500_000,
+        deadline,
+    )
+    .instruction();
+    assert_donation_error(
+        env.process(&[create_pledge], &[&sponsor]).await,
+        DonationError::DonationAccountMismatch,
+    );
+
+    let create_pledge = CreateMatchPledgeBuilder::new(
+        &campaign.keys,
+        campaign.creator.pubkey(),
+        sponsor.pubkey(),
+        sponsor_token_wallet,
+        5_000,
+        1_500_000,
+        deadline,
+    )
+    .instruction();
+    env.process(&[create_pledge], &[&sponsor, &campaign.creator])
+        .await
+        .unwrap();
+    let match_pledge = find_match_pledge(&campaign.keys.donation_data).0;
+    let escrow = find_match_pledge_escrow(&match_pledge).0;
+    assert_eq!(env.token_balance(&escrow).await, 1_500_000);
+
+    // donations now have to pass the pledge
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, 4_000_000).await;
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 2_000_000)
+        .instruction();
+    assert_donation_error(
+        env.process(&[donate], &[&donor.wallet]).await,
+        DonationError::MatchPledgeRequired,
+    );
+    campaign.refresh(&mut env).await;
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 2_000_000)
+        .instruction();
+    env.process(&[donate.clone()], &[&donor.wallet])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.token_balance(&campaign.keys.holding_wallet).await,
+        3_000_000
+    );
+
+    let reclaim = ReclaimMatchPledgeBuilder::new(
+        campaign.keys.donation_data,
+        sponsor.pubkey(),
+        sponsor_token_wallet,
+    )
+    .instruction();
+    assert_donation_error(
+        env.process(&[reclaim.clone()], &[&sponsor]).await,
+        DonationError::MatchPledgeActive,
+    );
+
+    // matches stop at the deadline, leftovers return to the sponsor
+    env.warp_to_timestamp(deadline + 1).await;
+    env.process(&[donate], &[&donor.wallet]).await.unwrap();
+    assert_eq!(
+        env.token_balance(&campaign.keys.holding_wallet).await,
+        5_000_000
+    );
+    env.process(&[reclaim], &[&sponsor]).await.unwrap();
+    assert_eq!(env.token_balance(&sponsor_token_wallet).await, 500_000);
+    let pledge: MatchPledge = env.fetch(&match_pledge).await;
+    assert_eq!(pledge.total_amount_matched, 1_000_000);
+    assert_eq!(
+        campaign.data(&mut env).await.total_amount_matched,
+        1_000_000
+    );
+}
+
+#[tokio::test]
+async fn batch_donations_are_matched_and_count_towards_rounds() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let mut pledged = create_campaign(&mut env, &protocol, 100_000_000, HardCapMode::None).await;
+    let other = create_campaign(&mut env, &protocol, 100_000_000, HardCapMode::None).await;
+    let sponsor = env.wallet().await;
+    let sponsor_token_wallet = env
+        .token_wallet(&sponsor.pubkey(), &protocol.donation_mint)
+        .await;
+    env.mint_to(&protocol.donation_mint, &sponsor_token_wallet, 1_000_000)
+        .await;
+    let deadline = env.now().await + 1_000;
+    let create_pledge = CreateMatchPledgeBuilder::new(
+        &pledged.keys,
+        pledged.creator.pubkey(),
+        sponsor.pubkey(),
+        sponsor_token_wallet,
+        5_000,
+        1_000_000,
+        deadline,
+    )
+    .instruction();
+    env.process(&[create_pledge], &[&sponsor, &pledged.creator])
+        .await
+        .unwrap();
+
+    let round = Keypair::new();
+    let start_timestamp = env.now().await;
+    let create_round = CreateFundingRoundBuilder::new(
+        round.pubkey(),
+        protocol.donation_protocol,
+        protocol.donation_mint,
+        env.payer(),
+        start_timestamp,
+        start_timestamp + 1_000,
+    )
+    .campaign(pledged.keys.donation_data)
+    .campaign(other.keys.donation_data)
+    .instruction();
+    env.process(&[create_round], &[&round]).await.unwrap();
+
+    // a batch can not skip the pledge of a campaign
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, 2_000_000).await;
+    let batch = DonateBatchBuilder::new(&protocol, donor.wallet.pubkey())
+        .donation(&pledged.keys, 1_000_000)
+        .donation(&other.keys, 1_000_000)
+        .funding_round(round.pubkey())
+        .instruction();
+    assert_donation_error(
+        env.process(&[batch], &[&donor.wallet]).await,
+        DonationError::MatchPledgeRequired,
+    );
+
+    pledged.refresh(&mut env).await;
+    let batch = DonateBatchBuilder::new(&protocol, donor.wallet.pubkey())
+        .donation(&pledged.keys, 1_000_000)
+        .donation(&other.keys, 1_000_000)
+        .funding_round(round.pubkey())
+        .instruction();
+    env.process(&[batch], &[&donor.wallet]).await.unwrap();
+
+    assert_eq!(
+        env.token_balance(&pledged.keys.holding_wallet).await,
+        1_500_000
+    );
+    assert_eq!(pledged.data(&mut env).await.total_amount_matched, 500_000);
+    assert_eq!(
+        env.token_balance(&other.keys.holding_wallet).await,
+        1_000_000
+    );
+    let funding_round: FundingRound = env.fetch(&round.pubkey()).await;
+    for campaign in funding_round.campaigns.iter() {
+        assert_eq!(campaign.total_amount_contributed, 1_000_000);
+        assert_eq!(campaign.sqrt_sum, 1_000);
+    }
+}
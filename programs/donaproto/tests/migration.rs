@@ -0,0 +1,297 @@
+mod common;
+
+use anchor_lang::{prelude::Pubkey, AccountSerialize, AnchorSerialize, Discriminator};
+use common::*;
+use donaproto::{
+    errors::DonationError,
+    states::{
+        ContributorData, CreatorData, DonationData, DonationProtocolData, HardCapMode,
+        LegacyDonationData, Migrate, Versioned, DISCRIMINATOR_LEN, RESERVED_LEN,
+    },
+};
+use donaproto_client::{
+    pda::{find_contributor_data, find_donation_receipt},
+    DonateBuilder, MigrateAccountBuilder, WithdrawFundsBuilder,
+};
+use solana_sdk::{account::AccountSharedData, signature::Signer};
+
+/// `version` and `reserved`, the fields appended by the first versioned layout
+const VERSIONED_FIELDS_LEN: usize = 1 + RESERVED_LEN;
+
+/// `DonationProtocolData` as first deployed: four keys, two amounts and the treasury bump
+const LEGACY_PROTOCOL_LEN: usize = DISCRIMINATOR_LEN + 4 * 32 + 2 * 8 + 1;
+
+fn serialize<T: AccountSerialize>(data: &T) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    data.try_serialize(&mut buffer).unwrap();
+    buffer
+}
+
+/// `DonationData` as the program stored it with borsh, before it became zero copy
+fn serialize_legacy_donation_data(data: &DonationData, version: u8) -> Vec<u8> {
+    let legacy = LegacyDonationData {
+        amount_collecting: data.amount_collecting,
+        total_amount_received: data.total_amount_received,
+        ending_timestamp: data.ending_timestamp,
+        is_closed: data.is_closed(),
+        recipient: data.recipient,
+        donation_protocol: data.donation_protocol,
+        holding_wallet: data.holding_wallet,
+        creator_data: data.creator_data,
+        donation_mint: data.donation_mint,
+        holding_bump: data.holding_bump,
+        ipfs_hash: data.ipfs_hash(),
+        payout_split: data.payout_split,
+        fee_bps: data.fee_bps,
+        fee_amount: data.fee_amount,
+        creator_wallet: data.creator_wallet,
+        recipient_owner: Pubkey::default(),
+        self_funded_amount: data.self_funded_amount,
+        total_amount_matched: data.total_amount_matched,
+        match_pledge: data.match_pledge,
+        hard_cap_mode: data.hard_cap_mode(),
+        version,
+        reserved: [0; RESERVED_LEN],
+    };
+    let mut buffer = DonationData::DISCRIMINATOR.to_vec();
+    legacy.serialize(&mut buffer).unwrap();
+    buffer
+}
+
+/// Rewrites `address` with the serialized `legacy_data` padded or cut to `legacy_len`,
+/// rent exempt for that size only
+async fn set_account_data(
+    env: &mut TestEnv,
+    address: &Pubkey,
+    mut legacy_data: Vec<u8>,
+    legacy_len: usize,
+) {
+    // the versioned fields are zero and serialized last, so only zeros are cut off,
+    // shorter data is zero padded like an account allocated with `space`
+    assert!(legacy_data[legacy_len.min(legacy_data.len())..]
+        .iter()
+        .all(|byte| *byte == 0));
+    legacy_data.resize(legacy_len, 0);
+
+    let rent = env.context.banks_client.get_rent().await.unwrap();
+    let mut account =
+        AccountSharedData::new(rent.minimum_balance(legacy_len), legacy_len, &donaproto::ID);
+    account.set_data(legacy_data);
+    env.context.set_account(address, &account);
+}
+
+/// Rewrites `address` the way the program stored `data` before layouts were versioned:
+/// without the versioned fields and rent exempt for the smaller size only
+async fn set_legacy_account(env: &mut TestEnv, address: &Pubkey, data: Vec<u8>, len: usize) {
+    set_account_data(env, address, data, len - VERSIONED_FIELDS_LEN).await;
+}
+
+/// Size and rent exemption of a migrated account
+async fn assert_migrated_account(env: &mut TestEnv, address: &Pubkey, len: usize) {
+    let rent = env.context.banks_client.get_rent().await.unwrap();
+    let account = env
+        .context
+        .banks_client
+        .get_account(*address)
+        .await
+        .unwrap()
+        .expect("account");
+    assert_eq!(account.data.len(), len);
+    assert!(account.lamports >= rent.minimum_balance(len));
+}
+
+#[tokio::test]
+async fn migrates_legacy_accounts() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let amount = 1_000_000_000;
+    let campaign = create_campaign(&mut env, &protocol, amount, HardCapMode::None).await;
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, amount).await;
+    let contributor_data_address =
+        find_contributor_data(&protocol.donation_protocol, &donor.wallet.pubkey()).0;
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), amount / 4)
+        .instruction();
+    env.process(&[donate], &[&donor.wallet]).await.unwrap();
+
+    let mut protocol_data: DonationProtocolData = env.fetch(&protocol.donation_protocol).await;
+    let donation_data = campaign.data(&mut env).await;
+    let mut creator_data: CreatorData = env.fetch(&campaign.keys.creator_data).await;
+    let mut contributor_data: ContributorData = env.fetch(&contributor_data_address).await;
+    assert_eq!(protocol_data.version, DonationProtocolData::VERSION);
+    assert_eq!(donation_data.version, DonationData::VERSION);
+    assert_eq!(creator_data.version, CreatorData::VERSION);
+    assert_eq!(contributor_data.version, ContributorData::VERSION);
+
+    protocol_data.version = 0;
+    creator_data.version = 0;
+    contributor_data.version = 0;
+    // fees, emission schedule, vesting and self funding cap were appended later
+    set_account_data(
+        &mut env,
+        &protocol.donation_protocol,
+        serialize(&protocol_data),
+        LEGACY_PROTOCOL_LEN,
+    )
+    .await;
+    set_legacy_account(
+        &mut env,
+        &campaign.keys.donation_data,
+        serialize_legacy_donation_data(&donation_data, 0),
+        LegacyDonationData::LEN,
+    )
+    .await;
+    set_legacy_account(
+        &mut env,
+        &campaign.keys.creator_data,
+        serialize(&creator_data),
+        CreatorData::LEN,
+    )
+    .await;
+    set_legacy_account(
+        &mut env,
+        &contributor_data_address,
+        serialize(&contributor_data),
+        ContributorData::LEN,
+    )
+    .await;
+
+    // the contributor account no longer has the space `init_if_needed` expects
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), amount / 4)
+        .instruction();
+    assert!(env.process(&[donate], &[&donor.wallet]).await.is_err());
+
+    let payer = env.wallet().await;
+    let migrate: Vec<_> = [
+        protocol.donation_protocol,
+        campaign.keys.donation_data,
+        campaign.keys.creator_data,
+        contributor_data_address,
+    ]
+    .iter()
+    .map(|account| MigrateAccountBuilder::new(*account, payer.pubkey()).instruction())
+    .collect();
+    env.process(&migrate, &[&payer]).await.unwrap();
+
+    assert_migrated_account(
+        &mut env,
+        &protocol.donation_protocol,
+        DonationProtocolData::SPACE,
+    )
+    .await;
+    assert_migrated_account(&mut env, &campaign.keys.donation_data, DonationData::LEN).await;
+    assert_migrated_account(&mut env, &campaign.keys.creator_data, CreatorData::LEN).await;
+    assert_migrated_account(&mut env, &contributor_data_address, ContributorData::LEN).await;
+    let migrated_protocol_data: DonationProtocolData = env.fetch(&protocol.donation_protocol).await;
+    assert_eq!(
+        migrated_protocol_data.version,
+        DonationProtocolData::VERSION
+    );
+    assert_eq!(
+        migrated_protocol_data.min_amount_to_earn,
+        MIN_AMOUNT_TO_EARN
+    );
+    assert_eq!(migrated_protocol_data.fee_bps, 0);
+    assert!(!migrated_protocol_data.is_emission_scheduled());
+    let migrated_donation_data = campaign.data(&mut env).await;
+    assert_eq!(migrated_donation_data.version, DonationData::VERSION);
+    assert_eq!(
+        migrated_donation_data.ipfs_hash(),
+        donation_data.ipfs_hash()
+    );
+    assert_eq!(
+        migrated_donation_data.holding_bump,
+        donation_data.holding_bump
+    );
+    assert_eq!(migrated_donation_data.total_amount_received, amount / 4);
+    let migrated_creator_data: CreatorData = env.fetch(&campaign.keys.creator_data).await;
+    assert_eq!(migrated_creator_data.version, CreatorData::VERSION);
+    assert_eq!(migrated_creator_data.total_amount_collecting, amount);
+    let migrated_contributor_data: ContributorData = env.fetch(&contributor_data_address).await;
+    assert_eq!(migrated_contributor_data.version, ContributorData::VERSION);
+    assert_eq!(migrated_contributor_data.total_amount_donated, amount / 4);
+
+    assert_donation_error(
+        env.process(&migrate[..1], &[&payer]).await,
+        DonationError::AccountAlreadyMigrated,
+    );
+
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), amount / 4)
+        .instruction();
+    env.process(&[donate], &[&donor.wallet]).await.unwrap();
+    assert_eq!(
+        campaign.data(&mut env).await.total_amount_received,
+        amount / 2
+    );
+    let contributor_data: ContributorData = env.fetch(&contributor_data_address).await;
+    assert_eq!(contributor_data.donations_count, 2);
+}
+
+#[tokio::test]
+async fn migrates_versioned_borsh_donation_data() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let amount = 1_000_000_000;
+    let campaign = create_campaign(&mut env, &protocol, amount, HardCapMode::Trim).await;
+    let donation_data = campaign.data(&mut env).await;
+    set_account_data(
+        &mut env,
+        &campaign.keys.donation_data,
+        serialize_legacy_donation_data(&donation_data, 1),
+        LegacyDonationData::LEN,
+    )
+    .await;
+
+    // handlers check the size before mapping the account
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, amount).await;
+    let donate =
+        DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), amount).instruction();
+    assert_donation_error(
+        env.process(&[donate], &[&donor.wallet]).await,
+        DonationError::AccountNotMigrated,
+    );
+    let withdraw = WithdrawFundsBuilder::new(
+        &campaign.keys,
+        campaign.recipient,
+        campaign.creator.pubkey(),
+    )
+    .instruction();
+    assert_donation_error(
+        env.process(&[withdraw], &[&campaign.creator]).await,
+        DonationError::AccountNotMigrated,
+    );
+
+    // the versioned borsh layout was larger, the account shrinks to the zero copy size
+    let payer = env.wallet().await;
+    let migrate =
+        MigrateAccountBuilder::new(campaign.keys.donation_data, payer.pubkey()).instruction();
+    env.process(&[migrate], &[&payer]).await.unwrap();
+
+    assert_migrated_account(&mut env, &campaign.keys.donation_data, DonationData::LEN).await;
+    let migrated_donation_data = campaign.data(&mut env).await;
+    assert_eq!(migrated_donation_data.version, DonationData::VERSION);
+    assert!(migrated_donation_data.hard_cap_mode() == HardCapMode::Trim);
+    assert_eq!(migrated_donation_data.amount_collecting, amount);
+    assert_eq!(
+        migrated_donation_data.ipfs_hash(),
+        donation_data.ipfs_hash()
+    );
+}
+
+#[tokio::test]
+async fn rejects_unversioned_account_types() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let campaign = create_campaign(&mut env, &protocol, 1_000_000, HardCapMode::None).await;
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, 1_000_000).await;
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 1_000_000)
+        .instruction();
+    env.process(&[donate], &[&donor.wallet]).await.unwrap();
+
+    let donation_receipt =
+        find_donation_receipt(&campaign.keys.donation_data, &donor.wallet.pubkey()).0;
+    let migrate = MigrateAccountBuilder::new(donation_receipt, env.payer()).instruction();
+    assert_donation_error(
+        env.process(&[migrate], &[]).await,
+        DonationError::InvalidMigrationAccount,
+    );
+}
@@ -0,0 +1,201 @@
+mod common;
+
+use common::*;
+use donaproto::{
+    errors::DonationError,
+    states::{ContributorData, CreatorData, DonationProtocolData, HardCapMode, MintFee},
+};
+use donaproto_client::{
+    pda::{find_contributor_data, find_creator_data, find_fee_vault, find_mint_fee},
+    CollectFeesBuilder, DonateBuilder, InitializeContributorBuilder, InitializeFeeVaultBuilder,
+    SetMintFeeBuilder, SetProtocolFeeBuilder, SetSelfFundingCapBuilder, WithdrawFundsBuilder,
+};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn initializes_donation_protocol() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+
+    let data: DonationProtocolData = env.fetch(&protocol.donation_protocol).await;
+    assert_eq!(data.authority, env.payer());
+    assert_eq!(data.treasury, protocol.treasury);
+    assert_eq!(data.min_amount_to_earn, MIN_AMOUNT_TO_EARN);
+    assert_eq!(data.min_amount_to_collect, MIN_AMOUNT_TO_COLLECT);
+    assert_eq!(data.fee_bps, 0);
+    assert!(!data.is_emission_scheduled());
+    assert!(!data.is_reward_vesting());
+    assert_eq!(env.token_balance(&protocol.treasury).await, TREASURY_SUPPLY);
+}
+
+#[tokio::test]
+async fn initializes_creator_and_contributor() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+
+    let creator = initialize_creator(&mut env, &protocol.donation_protocol).await;
+    let creator_data: CreatorData = env
+        .fetch(&find_creator_data(&protocol.donation_protocol, &creator.pubkey()).0)
+        .await;
+    assert_eq!(creator_data.donation_protocol, protocol.donation_protocol);
+    assert_eq!(creator_data.donations_created_count, 0);
+    assert_eq!(creator_data.total_amount_collecting, 0);
+
+    let contributor = env.wallet().await;
+    let initialize =
+        InitializeContributorBuilder::new(protocol.donation_protocol, contributor.pubkey())
+            .instruction();
+    env.process(&[initialize], &[&contributor]).await.unwrap();
+    let contributor_data: ContributorData = env
+        .fetch(&find_contributor_data(&protocol.donation_protocol, &contributor.pubkey()).0)
+        .await;
+    assert_eq!(
+        contributor_data.donation_protocol,
+        protocol.donation_protocol
+    );
+    assert_eq!(contributor_data.total_amount_donated, 0);
+    assert_eq!(contributor_data.donations_count, 0);
+}
+
+#[tokio::test]
+async fn only_authority_sets_fees() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let stranger = env.wallet().await;
+
+    let set_fee = SetProtocolFeeBuilder::new(protocol.donation_protocol, stranger.pubkey(), 100)
+        .instruction();
+    assert_constraint_error(env.process(&[set_fee], &[&stranger]).await);
+
+    let set_fee =
+        SetProtocolFeeBuilder::new(protocol.donation_protocol, env.payer(), 1_001).instruction();
+    assert_donation_error(
+        env.process(&[set_fee], &[]).await,
+        DonationError::InvalidFeeBps,
+    );
+
+    let set_fee =
+        SetProtocolFeeBuilder::new(protocol.donation_protocol, env.payer(), 250).instruction();
+    env.process(&[set_fee], &[]).await.unwrap();
+    let data: DonationProtocolData = env.fetch(&protocol.donation_protocol).await;
+    assert_eq!(data.fee_bps, 250);
+
+    let set_self_funding_cap =
+        SetSelfFundingCapBuilder::new(protocol.donation_protocol, env.payer(), 10_001)
+            .instruction();
+    assert_donation_error(
+        env.process(&[set_self_funding_cap], &[]).await,
+        DonationError::InvalidSelfFundingCap,
+    );
+    let set_self_funding_cap =
+        SetSelfFundingCapBuilder::new(protocol.donation_protocol, env.payer(), 2_000).instruction();
+    env.process(&[set_self_funding_cap], &[]).await.unwrap();
+    let data: DonationProtocolData = env.fetch(&protocol.donation_protocol).await;
+    assert_eq!(data.max_self_funded_bps, 2_000);
+}
+
+#[tokio::test]
+async fn mint_fee_overrides_protocol_fee_and_is_collected() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+
+    let set_fee =
+        SetProtocolFeeBuilder::new(protocol.donation_protocol, env.payer(), 250).instruction();
+    let set_mint_fee = SetMintFeeBuilder::new(
+        protocol.donation_protocol,
+        protocol.donation_mint,
+        env.payer(),
+        1_001,
+    )
+    .instruction();
+    assert_donation_error(
+        env.process(&[set_mint_fee], &[]).await,
+        DonationError::InvalidFeeBps,
+    );
+
+    // fees are only set for mints authorized for donations
+    let unauthorized_mint = env.create_mint(6).await;
+    let set_mint_fee = SetMintFeeBuilder::new(
+        protocol.donation_protocol,
+        unauthorized_mint,
+        env.payer(),
+        500,
+    )
+    .instruction();
+    assert_donation_error(
+        env.process(&[set_mint_fee], &[]).await,
+        DonationError::InvalidDonationMint,
+    );
+
+    let set_mint_fee = SetMintFeeBuilder::new(
+        protocol.donation_protocol,
+        protocol.donation_mint,
+        env.payer(),
+        500,
+    )
+    .instruction();
+    let initialize_fee_vault = InitializeFeeVaultBuilder::new(
+        protocol.donation_protocol,
+        protocol.donation_mint,
+        env.payer(),
+    )
+    .instruction();
+    env.process(&[set_fee, set_mint_fee, initialize_fee_vault], &[])
+        .await
+        .unwrap();
+    let mint_fee: MintFee = env
+        .fetch(&find_mint_fee(&protocol.donation_protocol, &protocol.donation_mint).0)
+        .await;
+    assert_eq!(mint_fee.fee_bps, 500);
+
+    // fee rate is locked at creation
+    let campaign = create_campaign(&mut env, &protocol, 2_000_000, HardCapMode::None).await;
+    assert_eq!(campaign.data(&mut env).await.fee_bps, 500);
+
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, 2_000_000).await;
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 2_000_000)
+        .instruction();
+    env.process(&[donate], &[&donor.wallet]).await.unwrap();
+
+    // fee vault is required once a fee is charged
+    let withdraw = WithdrawFundsBuilder::new(
+        &campaign.keys,
+        campaign.recipient,
+        campaign.creator.pubkey(),
+    )
+    .instruction();
+    assert_donation_error(
+        env.process(&[withdraw], &[&campaign.creator]).await,
+        DonationError::FeeVaultRequired,
+    );
+
+    let withdraw = WithdrawFundsBuilder::new(
+        &campaign.keys,
+        campaign.recipient,
+        campaign.creator.pubkey(),
+    )
+    .with_fee_vault()
+    .instruction();
+    env.process(&[withdraw], &[&campaign.creator])
+        .await
+        .unwrap();
+    let fee_vault = find_fee_vault(&protocol.donation_protocol, &protocol.donation_mint).0;
+    assert_eq!(env.token_balance(&fee_vault).await, 100_000);
+    assert_eq!(env.token_balance(&campaign.recipient).await, 1_900_000);
+    assert_eq!(campaign.data(&mut env).await.fee_amount, 100_000);
+
+    let destination = env
+        .token_wallet(&env.payer(), &protocol.donation_mint)
+        .await;
+    let collect = CollectFeesBuilder::new(
+        protocol.donation_protocol,
+        protocol.donation_mint,
+        destination,
+        env.payer(),
+        100_000,
+    )
+    .instruction();
+    env.process(&[collect], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&fee_vault).await, 0);
+    assert_eq!(env.token_balance(&destination).await, 100_000);
+}
@@ -0,0 +1,204 @@
+mod common;
+
+use common::*;
+use donaproto::{
+    errors::DonationError,
+    states::{
+        ContributorData, DonationProtocolData, EmissionCarryOver, HardCapMode, RewardVesting,
+    },
+};
+use donaproto_client::{
+    pda::{find_contributor_data, find_reward_vesting},
+    ClaimRewardsBuilder, DonateBuilder, InitializeRewardVestingBuilder, SetEmissionScheduleBuilder,
+    SetRewardVestingBuilder, WithdrawTreasuryBuilder,
+};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn emission_schedule_limits_rewards_per_epoch() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let epoch_duration = 1_000;
+
+    let set_schedule =
+        SetEmissionScheduleBuilder::new(protocol.donation_protocol, env.payer(), 1_500_000, 1_000)
+            .epoch_decay_bps(10_001)
+            .instruction();
+    assert_donation_error(
+        env.process(&[set_schedule], &[]).await,
+        DonationError::InvalidEmissionSchedule,
+    );
+    let set_schedule = SetEmissionScheduleBuilder::new(
+        protocol.donation_protocol,
+        env.payer(),
+        1_500_000,
+        epoch_duration,
+    )
+    .epoch_decay_bps(5_000)
+    .emission_carry_over(EmissionCarryOver::Forfeit)
+    .instruction();
+    env.process(&[set_schedule], &[]).await.unwrap();
+
+    let campaign = create_campaign(&mut env, &protocol, 100_000_000, HardCapMode::None).await;
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, 10_000_000).await;
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 1_000_000)
+        .instruction();
+
+    env.process(&[donate.clone()], &[&donor.wallet])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&donor.reward_wallet).await, 1_000_000);
+    // the rest of the epoch budget
+    env.process(&[donate.clone()], &[&donor.wallet])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&donor.reward_wallet).await, 1_500_000);
+    env.process(&[donate.clone()], &[&donor.wallet])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&donor.reward_wallet).await, 1_500_000);
+
+    // next epoch budget decays by half, the unused part is forfeited
+    env.warp_forward(epoch_duration).await;
+    env.process(&[donate], &[&donor.wallet]).await.unwrap();
+    assert_eq!(env.token_balance(&donor.reward_wallet).await, 2_250_000);
+    let data: DonationProtocolData = env.fetch(&protocol.donation_protocol).await;
+    assert_eq!(data.current_epoch, 1);
+    assert_eq!(data.current_epoch_budget, 750_000);
+    assert_eq!(data.current_epoch_consumed, 750_000);
+}
+
+#[tokio::test]
+async fn vests_rewards_and_claims_unlocked_part() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+
+    let set_vesting =
+        SetRewardVestingBuilder::new(protocol.donation_protocol, env.payer(), 1_001, 1_000)
+            .instruction();
+    assert_donation_error(
+        env.process(&[set_vesting], &[]).await,
+        DonationError::InvalidVestingSchedule,
+    );
+    let set_vesting =
+        SetRewardVestingBuilder::new(protocol.donation_protocol, env.payer(), 100, 1_000)
+            .instruction();
+    env.process(&[set_vesting], &[]).await.unwrap();
+    let protocol = protocol_keys(&mut env, &protocol.donation_protocol).await;
+    assert!(protocol.is_reward_vesting);
+
+    let campaign = create_campaign(&mut env, &protocol, 100_000_000, HardCapMode::None).await;
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, 1_000_000).await;
+    let initialize =
+        InitializeRewardVestingBuilder::new(protocol.donation_protocol, donor.wallet.pubkey())
+            .instruction();
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 1_000_000)
+        .instruction();
+    env.process(&[initialize, donate], &[&donor.wallet])
+        .await
+        .unwrap();
+
+    // rewards stay in the treasury until claimed
+    assert_eq!(env.token_balance(&donor.reward_wallet).await, 0);
+    let reward_vesting: RewardVesting = env
+        .fetch(&find_reward_vesting(&protocol.donation_protocol, &donor.wallet.pubkey()).0)
+        .await;
+    assert_eq!(reward_vesting.total_amount_vested, 1_000_000);
+    let data: DonationProtocolData = env.fetch(&protocol.donation_protocol).await;
+    assert_eq!(data.total_reward_vesting, 1_000_000);
+
+    let withdraw_treasury = WithdrawTreasuryBuilder::new(
+        &protocol,
+        env.token_wallet(&env.payer(), &protocol.treasury_mint)
+            .await,
+        env.payer(),
+        TREASURY_SUPPLY,
+    )
+    .instruction();
+    assert_donation_error(
+        env.process(&[withdraw_treasury], &[]).await,
+        DonationError::TreasuryReservedForVesting,
+    );
+
+    // schedule changes only apply to rewards accrued afterwards,
+    // turning vesting off does not unlock what is already vesting
+    let set_vesting =
+        SetRewardVestingBuilder::new(protocol.donation_protocol, env.payer(), 0, 0).instruction();
+    env.process(&[set_vesting], &[]).await.unwrap();
+    let reward_vesting: RewardVesting = env
+        .fetch(&find_reward_vesting(&protocol.donation_protocol, &donor.wallet.pubkey()).0)
+        .await;
+    assert_eq!(reward_vesting.vesting_cliff, 100);
+    assert_eq!(reward_vesting.vesting_duration, 1_000);
+
+    let claim = ClaimRewardsBuilder::new(&protocol, donor.wallet.pubkey()).instruction();
+    assert_donation_error(
+        env.process(&[claim.clone()], &[&donor.wallet]).await,
+        DonationError::NothingToClaim,
+    );
+
+    env.warp_forward(500).await;
+    env.process(&[claim.clone()], &[&donor.wallet])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&donor.reward_wallet).await, 500_000);
+
+    env.warp_forward(500).await;
+    env.process(&[claim], &[&donor.wallet]).await.unwrap();
+    assert_eq!(env.token_balance(&donor.reward_wallet).await, 1_000_000);
+    let contributor_data: ContributorData = env
+        .fetch(&find_contributor_data(&protocol.donation_protocol, &donor.wallet.pubkey()).0)
+        .await;
+    assert_eq!(contributor_data.total_amount_vested, 1_000_000);
+    assert_eq!(contributor_data.total_amount_claimed, 1_000_000);
+    let data: DonationProtocolData = env.fetch(&protocol.donation_protocol).await;
+    assert_eq!(data.total_reward_vesting, 0);
+}
+
+#[tokio::test]
+async fn vests_repeat_donation_from_unclaimed_balance() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let set_vesting =
+        SetRewardVestingBuilder::new(protocol.donation_protocol, env.payer(), 100, 1_000)
+            .instruction();
+    env.process(&[set_vesting], &[]).await.unwrap();
+    let protocol = protocol_keys(&mut env, &protocol.donation_protocol).await;
+
+    let campaign = create_campaign(&mut env, &protocol, 100_000_000, HardCapMode::None).await;
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, 1_500_000).await;
+    let initialize =
+        InitializeRewardVestingBuilder::new(protocol.donation_protocol, donor.wallet.pubkey())
+            .instruction();
+    let donate = DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 1_000_000)
+        .instruction();
+    env.process(&[initialize, donate], &[&donor.wallet])
+        .await
+        .unwrap();
+    env.warp_forward(1_000).await;
+    let claim = ClaimRewardsBuilder::new(&protocol, donor.wallet.pubkey()).instruction();
+    env.process(&[claim.clone()], &[&donor.wallet])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&donor.reward_wallet).await, 1_000_000);
+
+    // the claimed reward does not count as time already vested for the new one
+    let donate =
+        DonateBuilder::new(&protocol, &campaign.keys, donor.wallet.pubkey(), 500_000).instruction();
+    env.process(&[donate], &[&donor.wallet]).await.unwrap();
+    let reward_vesting: RewardVesting = env
+        .fetch(&find_reward_vesting(&protocol.donation_protocol, &donor.wallet.pubkey()).0)
+        .await;
+    let now = env.now().await;
+    assert_eq!(reward_vesting.start_timestamp, now);
+    assert_eq!(reward_vesting.claimable_amount(now), 0);
+    assert_eq!(reward_vesting.claimable_amount(now + 500), 250_000);
+    assert_donation_error(
+        env.process(&[claim.clone()], &[&donor.wallet]).await,
+        DonationError::NothingToClaim,
+    );
+
+    env.warp_forward(500).await;
+    env.process(&[claim], &[&donor.wallet]).await.unwrap();
+    assert_eq!(env.token_balance(&donor.reward_wallet).await, 1_250_000);
+}
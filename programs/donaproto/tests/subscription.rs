@@ -0,0 +1,109 @@
+mod common;
+
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token::TokenAccount;
+use common::*;
+use donaproto::{
+    errors::DonationError,
+    states::{HardCapMode, Subscription},
+};
+use donaproto_client::{
+    pda::find_subscription, CancelSubscriptionBuilder, CreateSubscriptionBuilder,
+    ExecuteSubscriptionBuilder,
+};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn executes_due_installments_and_cancels() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let campaign = create_campaign(&mut env, &protocol, 100_000_000, HardCapMode::None).await;
+    let donor = create_donor(&mut env, &protocol, &protocol.donation_mint, 10_000_000).await;
+    let period = 1_000;
+    let ending_timestamp = env.now().await + 10 * period;
+
+    let create = CreateSubscriptionBuilder::new(
+        &campaign.keys,
+        donor.wallet.pubkey(),
+        1_000_000,
+        0,
+        ending_timestamp,
+    )
+    .instruction();
+    assert_donation_error(
+        env.process(&[create], &[&donor.wallet]).await,
+        DonationError::InvalidSubscriptionPeriod,
+    );
+    let create = CreateSubscriptionBuilder::new(
+        &campaign.keys,
+        donor.wallet.pubkey(),
+        1_000_000,
+        period,
+        ending_timestamp,
+    )
+    .instruction();
+    env.process(&[create], &[&donor.wallet]).await.unwrap();
+    let subscription = find_subscription(&campaign.keys.donation_data, &donor.wallet.pubkey()).0;
+    let token_wallet: TokenAccount = env.fetch(&donor.token_wallet).await;
+    assert_eq!(token_wallet.delegate, COption::Some(subscription));
+    assert_eq!(token_wallet.delegated_amount, 11_000_000);
+
+    // the only delegate of the token wallet is taken, a second subscription needs its own wallet
+    let other_campaign = create_campaign(&mut env, &protocol, 100_000_000, HardCapMode::None).await;
+    let create = CreateSubscriptionBuilder::new(
+        &other_campaign.keys,
+        donor.wallet.pubkey(),
+        1_000_000,
+        period,
+        ending_timestamp,
+    )
+    .instruction();
+    assert_donation_error(
+        env.process(&[create], &[&donor.wallet]).await,
+        DonationError::TokenWalletAlreadyDelegated,
+    );
+
+    // anyone cranks, the first installment is due right away
+    let execute = ExecuteSubscriptionBuilder::new(
+        &protocol,
+        &campaign.keys,
+        donor.wallet.pubkey(),
+        env.payer(),
+    )
+    .instruction();
+    env.process(&[execute.clone()], &[]).await.unwrap();
+    assert_eq!(
+        env.token_balance(&campaign.keys.holding_wallet).await,
+        1_000_000
+    );
+    assert_eq!(env.token_balance(&donor.reward_wallet).await, 1_000_000);
+    assert_donation_error(
+        env.process(&[execute.clone()], &[]).await,
+        DonationError::SubscriptionNotDue,
+    );
+
+    env.warp_forward(period).await;
+    env.process(&[execute], &[]).await.unwrap();
+    assert_eq!(
+        env.token_balance(&campaign.keys.holding_wallet).await,
+        2_000_000
+    );
+    assert_eq!(env.token_balance(&donor.token_wallet).await, 8_000_000);
+    let data: Subscription = env.fetch(&subscription).await;
+    assert_eq!(data.installments_paid, 2);
+    assert_eq!(
+        campaign.data(&mut env).await.total_amount_received,
+        2_000_000
+    );
+
+    let cancel = CancelSubscriptionBuilder::new(
+        campaign.keys.donation_data,
+        donor.wallet.pubkey(),
+        donor.token_wallet,
+    )
+    .instruction();
+    env.process(&[cancel], &[&donor.wallet]).await.unwrap();
+    assert!(!env.account_exists(&subscription).await);
+    let token_wallet: TokenAccount = env.fetch(&donor.token_wallet).await;
+    assert_eq!(token_wallet.delegate, COption::None);
+}
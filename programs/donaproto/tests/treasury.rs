@@ -0,0 +1,63 @@
+mod common;
+
+use common::*;
+use donaproto::{errors::DonationError, states::DonationProtocolData};
+use donaproto_client::{FundTreasuryBuilder, RotateTreasuryBuilder, WithdrawTreasuryBuilder};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn funds_and_withdraws_treasury() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let funder = env.wallet().await;
+    let funder_wallet = env
+        .token_wallet(&funder.pubkey(), &protocol.treasury_mint)
+        .await;
+    env.mint_to(&protocol.treasury_mint, &funder_wallet, 1_000)
+        .await;
+
+    let fund = FundTreasuryBuilder::new(&protocol, funder_wallet, funder.pubkey(), 0).instruction();
+    assert_donation_error(
+        env.process(&[fund], &[&funder]).await,
+        DonationError::TreasuryAmountZero,
+    );
+    let fund =
+        FundTreasuryBuilder::new(&protocol, funder_wallet, funder.pubkey(), 1_000).instruction();
+    env.process(&[fund], &[&funder]).await.unwrap();
+    assert_eq!(
+        env.token_balance(&protocol.treasury).await,
+        TREASURY_SUPPLY + 1_000
+    );
+
+    // only the authority withdraws
+    let withdraw = WithdrawTreasuryBuilder::new(&protocol, funder_wallet, funder.pubkey(), 1_000)
+        .instruction();
+    assert_constraint_error(env.process(&[withdraw], &[&funder]).await);
+
+    let withdraw =
+        WithdrawTreasuryBuilder::new(&protocol, funder_wallet, env.payer(), 1_000).instruction();
+    env.process(&[withdraw], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&funder_wallet).await, 1_000);
+    assert_eq!(env.token_balance(&protocol.treasury).await, TREASURY_SUPPLY);
+}
+
+#[tokio::test]
+async fn rotates_treasury_with_its_balance() {
+    let mut env = TestEnv::start().await;
+    let protocol = setup_protocol(&mut env).await;
+    let new_treasury = env
+        .token_account(&protocol.treasury_owner, &protocol.treasury_mint)
+        .await;
+
+    let rotate = RotateTreasuryBuilder::new(&protocol, new_treasury, env.payer()).instruction();
+    env.process(&[rotate], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&protocol.treasury).await, 0);
+    assert_eq!(env.token_balance(&new_treasury).await, TREASURY_SUPPLY);
+    let data: DonationProtocolData = env.fetch(&protocol.donation_protocol).await;
+    assert_eq!(data.treasury, new_treasury);
+
+    // the old treasury is no longer accepted
+    let rotate =
+        RotateTreasuryBuilder::new(&protocol, protocol.treasury, env.payer()).instruction();
+    assert_constraint_error(env.process(&[rotate], &[]).await);
+}
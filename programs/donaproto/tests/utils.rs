@@ -0,0 +1,293 @@
+use donaproto::utils::{calculate_amount, multipler, Q64};
+use proptest::prelude::*;
+use raydium_amm_v3::libraries::sqrt_price_math::{
+    get_next_sqrt_price_from_amount_0_rounding_up, get_next_sqrt_price_from_amount_1_rounding_down,
+};
+
+/// Reference swaps move the pool price by at most 2^-PRICE_IMPACT_SHIFT
+const PRICE_IMPACT_SHIFT: u32 = 20;
+/// Relative tolerance against Raydium swap math, an order of magnitude above the price impact
+const REFERENCE_TOLERANCE: f64 = 1e-5;
+/// log10(2^64), converted amounts above it do not fit into u64
+const LOG10_U64_LIMIT: f64 = 19.265;
+
+/// Log-uniform sqrt prices quoting raw prices from ~1e-12 to ~1e12
+fn sqrt_price_strategy() -> impl Strategy<Value = u128> {
+    (44u32..84).prop_flat_map(|shift| (1u128 << shift)..(1u128 << (shift + 1)))
+}
+
+/// Log-uniform amounts up to ~1e12
+fn amount_strategy() -> impl Strategy<Value = u64> {
+    (0u32..40).prop_flat_map(|shift| (1u64 << shift)..(1u64 << (shift + 1)))
+}
+
+/// Raw token 1 amount a deep pool pays out for `amount` of token 0
+fn raydium_amount_1_for_0(sqrt_price_x64: u128, amount: u64) -> f64 {
+    let liquidity = (amount as u128 * sqrt_price_x64 / Q64 + 1) << PRICE_IMPACT_SHIFT;
+    let next_sqrt_price_x64 =
+        get_next_sqrt_price_from_amount_0_rounding_up(sqrt_price_x64, liquidity, amount, true);
+
+    liquidity as f64 * (sqrt_price_x64 - next_sqrt_price_x64) as f64 / Q64 as f64
+}
+
+/// Raw token 0 amount a deep pool pays out for `amount` of token 1
+fn raydium_amount_0_for_1(sqrt_price_x64: u128, amount: u64) -> f64 {
+    let liquidity = (((amount as u128) << 64) / sqrt_price_x64 + 1) << PRICE_IMPACT_SHIFT;
+    let next_sqrt_price_x64 =
+        get_next_sqrt_price_from_amount_1_rounding_down(sqrt_price_x64, liquidity, amount, true);
+
+    liquidity as f64 * (next_sqrt_price_x64 - sqrt_price_x64) as f64 * Q64 as f64
+        / (sqrt_price_x64 as f64 * next_sqrt_price_x64 as f64)
+}
+
+/// Pool listing the same mints in the opposite order
+fn inverse_sqrt_price_x64(sqrt_price_x64: u128) -> u128 {
+    u128::MAX / sqrt_price_x64
+}
+
+/// log10 of the exact converted amount, NaN when undefined
+fn log10_converted_amount(
+    default_decimals: u8,
+    donation_decimals: u8,
+    amount: u64,
+    sqrt_price_x64: u128,
+    is_default_token_mint_0: bool,
+) -> f64 {
+    let log10_price = 2.0 * ((sqrt_price_x64 as f64).log10() - (Q64 as f64).log10());
+    let log10_amount = (amount as f64).log10();
+    let decimals = donation_decimals as f64 - default_decimals as f64;
+    if is_default_token_mint_0 {
+        log10_amount - log10_price + decimals
+    } else {
+        log10_amount + log10_price + decimals
+    }
+}
+
+#[test]
+fn multipler_is_exact_for_representable_powers() {
+    for decimals in 0..=22u8 {
+        assert_eq!(multipler(decimals), 10u128.pow(decimals as u32) as f64);
+    }
+}
+
+#[test]
+fn converts_at_human_price_keeping_donation_decimals() {
+    // USDC(6, token 0) per BONK(5, token 1) = 36626.12336, as in donateV2.test.js
+    let raw_price = 36626.12336 * multipler(5) / multipler(6);
+    let sqrt_price_x64 = (raw_price.sqrt() * Q64 as f64) as u128;
+
+    assert_eq!(
+        calculate_amount(6, 5, 3_500_000_000, sqrt_price_x64, true),
+        Some(95_560)
+    );
+}
+
+#[test]
+fn never_panics_for_any_decimals() {
+    let sqrt_prices = [1, Q64, u128::MAX];
+    let amounts = [1, u64::MAX];
+    for default_decimals in 0..=u8::MAX {
+        for donation_decimals in 0..=u8::MAX {
+            for sqrt_price_x64 in sqrt_prices {
+                for amount in amounts {
+                    for is_default_token_mint_0 in [true, false] {
+                        let converted_amount = calculate_amount(
+                            default_decimals,
+                            donation_decimals,
+                            amount,
+                            sqrt_price_x64,
+                            is_default_token_mint_0,
+                        );
+                        assert_ne!(converted_amount, Some(u64::MAX));
+                    }
+                }
+            }
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn agrees_with_raydium_swap_math(
+        sqrt_price_x64 in sqrt_price_strategy(),
+        amount in amount_strategy(),
+        default_decimals in 0u8..=12,
+        donation_decimals in 0u8..=12,
+        is_default_token_mint_0: bool,
+    ) {
+        let raw_default_amount = if is_default_token_mint_0 {
+            raydium_amount_0_for_1(sqrt_price_x64, amount)
+        } else {
+            raydium_amount_1_for_0(sqrt_price_x64, amount)
+        };
+        // converted amounts keep the decimals of the donation mint
+        let expected_amount =
+            raw_default_amount * multipler(donation_decimals) / multipler(default_decimals);
+        prop_assume!(expected_amount < u64::MAX as f64 / 2.0);
+
+        let converted_amount = calculate_amount(
+            default_decimals,
+            donation_decimals,
+            amount,
+            sqrt_price_x64,
+            is_default_token_mint_0,
+        )
+        .unwrap() as f64;
+        prop_assert!(
+            (converted_amount - expected_amount).abs()
+                <= expected_amount * REFERENCE_TOLERANCE + 1.0,
+            "converted {} expected {}",
+            converted_amount,
+            expected_amount
+        );
+    }
+
+    #[test]
+    fn agrees_between_mint_orderings(
+        sqrt_price_x64 in sqrt_price_strategy(),
+        amount in amount_strategy(),
+        default_decimals in 0u8..=12,
+        donation_decimals in 0u8..=12,
+        is_default_token_mint_0: bool,
+    ) {
+        let converted_amount = calculate_amount(
+            default_decimals,
+            donation_decimals,
+            amount,
+            sqrt_price_x64,
+            is_default_token_mint_0,
+        );
+        let reordered_amount = calculate_amount(
+            default_decimals,
+            donation_decimals,
+            amount,
+            inverse_sqrt_price_x64(sqrt_price_x64),
+            !is_default_token_mint_0,
+        );
+        prop_assume!(converted_amount.is_some() && reordered_amount.is_some());
+
+        let converted_amount = converted_amount.unwrap() as f64;
+        let reordered_amount = reordered_amount.unwrap() as f64;
+        prop_assert!((converted_amount - reordered_amount).abs() <= converted_amount * 1e-9 + 1.0);
+    }
+
+    #[test]
+    fn round_trips_between_mints(
+        sqrt_price_x64 in sqrt_price_strategy(),
+        amount in (1u64 << 20)..(1u64 << 40),
+        default_decimals in 0u8..=9,
+        donation_decimals in 0u8..=9,
+        is_default_token_mint_0: bool,
+    ) {
+        let default_amount = calculate_amount(
+            default_decimals,
+            donation_decimals,
+            amount,
+            sqrt_price_x64,
+            is_default_token_mint_0,
+        );
+        prop_assume!(matches!(default_amount, Some(default_amount) if default_amount >= 1_000));
+        let default_amount = default_amount.unwrap();
+
+        // the default mint becomes the donation mint of the same pool
+        let round_trip_amount = calculate_amount(
+            donation_decimals,
+            default_decimals,
+            default_amount,
+            sqrt_price_x64,
+            !is_default_token_mint_0,
+        )
+        .unwrap();
+        // rounding `default_amount` loses up to half of its unit
+        let tolerance = 1.0 + amount as f64 * 1e-9 + 0.5 * amount as f64 / default_amount as f64;
+        prop_assert!((round_trip_amount as f64 - amount as f64).abs() <= tolerance);
+    }
+
+    #[test]
+    fn is_monotonic_in_amount(
+        sqrt_price_x64 in sqrt_price_strategy(),
+        amounts in (amount_strategy(), amount_strategy()),
+        default_decimals in 0u8..=12,
+        donation_decimals in 0u8..=12,
+        is_default_token_mint_0: bool,
+    ) {
+        let (smaller, larger) = (amounts.0.min(amounts.1), amounts.0.max(amounts.1));
+        let convert = |amount| {
+            calculate_amount(
+                default_decimals,
+                donation_decimals,
+                amount,
+                sqrt_price_x64,
+                is_default_token_mint_0,
+            )
+        };
+
+        match (convert(smaller), convert(larger)) {
+            (Some(smaller), Some(larger)) => prop_assert!(smaller <= larger),
+            // only larger amounts may exceed u64
+            (smaller, larger) => prop_assert!(smaller.is_some() || larger.is_none()),
+        }
+    }
+
+    #[test]
+    fn is_monotonic_in_price(
+        sqrt_prices in (sqrt_price_strategy(), sqrt_price_strategy()),
+        amount in amount_strategy(),
+        default_decimals in 0u8..=12,
+        donation_decimals in 0u8..=12,
+        is_default_token_mint_0: bool,
+    ) {
+        let (lower, higher) = (sqrt_prices.0.min(sqrt_prices.1), sqrt_prices.0.max(sqrt_prices.1));
+        let convert = |sqrt_price_x64| {
+            calculate_amount(
+                default_decimals,
+                donation_decimals,
+                amount,
+                sqrt_price_x64,
+                is_default_token_mint_0,
+            )
+        };
+        let (at_lower, at_higher) = (convert(lower), convert(higher));
+        prop_assume!(at_lower.is_some() && at_higher.is_some());
+
+        // pool price is token 1 per token 0, a default token 0 gets cheaper as it rises
+        if is_default_token_mint_0 {
+            prop_assert!(at_lower >= at_higher);
+        } else {
+            prop_assert!(at_lower <= at_higher);
+        }
+    }
+
+    #[test]
+    fn never_overflows_silently(
+        sqrt_price_x64: u128,
+        amount: u64,
+        default_decimals: u8,
+        donation_decimals: u8,
+        is_default_token_mint_0: bool,
+    ) {
+        let converted_amount = calculate_amount(
+            default_decimals,
+            donation_decimals,
+            amount,
+            sqrt_price_x64,
+            is_default_token_mint_0,
+        );
+        let log10_amount = log10_converted_amount(
+            default_decimals,
+            donation_decimals,
+            amount,
+            sqrt_price_x64,
+            is_default_token_mint_0,
+        );
+
+        // f64 rounding blurs only a thin band around the limit
+        if log10_amount < LOG10_U64_LIMIT - 0.01 {
+            prop_assert!(converted_amount.is_some());
+        }
+        if log10_amount > LOG10_U64_LIMIT + 0.01 {
+            prop_assert!(converted_amount.is_none());
+        }
+        prop_assert_ne!(converted_amount, Some(u64::MAX));
+    }
+}
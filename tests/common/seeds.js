@@ -3,6 +3,18 @@ const TREASURY_PREFIX = 'treasury';
 const CREATOR_PREFIX = 'creator';
 const HOLDING_PREFIX = 'holding';
 const CONTRIBUTOR_PREFIX = 'contributor';
+const SUBSCRIPTION_PREFIX = 'subscription';
+const PAYOUT_SPLIT_PREFIX = 'payout_split';
+const MINT_FEE_PREFIX = 'mint_fee';
+const FEE_VAULT_PREFIX = 'fee_vault';
+const REWARD_VESTING_PREFIX = 'reward_vesting';
+const DONATION_RECEIPT_PREFIX = 'donation_receipt';
+const MATCHING_VAULT_PREFIX = 'matching_vault';
+const MATCH_PLEDGE_PREFIX = 'match_pledge';
+const MATCH_PLEDGE_ESCROW_PREFIX = 'match_pledge_escrow';
+const DONATION_TIERS_PREFIX = 'donation_tiers';
+const RECEIPT_METADATA_PREFIX = 'receipt_metadata';
+const RECEIPT_MINT_AUTHORITY_PREFIX = 'receipt_mint_authority';
 
 const AUTHORIZED_CLMM_PREFIX = 'authorized_clmm';
 const AUTHORIZED_CLMM_POOL_PREFIX = 'authorized_clmm_pool';
@@ -12,6 +24,18 @@ module.exports = {
   CREATOR_PREFIX,
   HOLDING_PREFIX,
   CONTRIBUTOR_PREFIX,
+  SUBSCRIPTION_PREFIX,
+  PAYOUT_SPLIT_PREFIX,
+  MINT_FEE_PREFIX,
+  FEE_VAULT_PREFIX,
+  REWARD_VESTING_PREFIX,
+  DONATION_RECEIPT_PREFIX,
+  MATCHING_VAULT_PREFIX,
+  MATCH_PLEDGE_PREFIX,
+  MATCH_PLEDGE_ESCROW_PREFIX,
+  DONATION_TIERS_PREFIX,
+  RECEIPT_METADATA_PREFIX,
+  RECEIPT_MINT_AUTHORITY_PREFIX,
   AUTHORIZED_CLMM_PREFIX,
   AUTHORIZED_CLMM_POOL_PREFIX,
 };
\ No newline at end of file
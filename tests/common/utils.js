@@ -1,6 +1,7 @@
 const borsh = require('borsh');
 const anchor = require("@coral-xyz/anchor");
 const fs = require('fs');
+const { MINT_FEE_PREFIX, DONATION_RECEIPT_PREFIX } = require('./seeds');
 
 async function getNowTs(provider) {
   const accountInfo = await provider.connection.getAccountInfo(anchor.web3.SYSVAR_CLOCK_PUBKEY);
@@ -20,6 +21,39 @@ async function rechargeWallet(connection, wallet, lamports) {
   return wallet;
 }
 
+function findMintFeeAddress(programId, donationProtocol, mint) {
+  const [mintFeePubkey] = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(MINT_FEE_PREFIX),
+      donationProtocol.toBuffer(),
+      mint.toBuffer(),
+    ],
+    programId,
+  );
+
+  return mintFeePubkey;
+}
+
+function findDonationReceiptAddress(programId, donationData, donor) {
+  const [donationReceiptPubkey] = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(DONATION_RECEIPT_PREFIX),
+      donationData.toBuffer(),
+      donor.toBuffer(),
+    ],
+    programId,
+  );
+
+  return donationReceiptPubkey;
+}
+
+// DonationData stores the ipfs hash as zero padded bytes
+function decodeIpfsHash(ipfsHash) {
+  const bytes = Buffer.from(ipfsHash);
+  const len = bytes.indexOf(0);
+  return bytes.subarray(0, len === -1 ? bytes.length : len).toString('utf8');
+}
+
 const loadObjectFromJsonFile = (filePath) => {
   try {
     const jsonString = fs.readFileSync(filePath, 'utf8');
@@ -34,5 +68,8 @@ const loadObjectFromJsonFile = (filePath) => {
 module.exports = {
   getNowTs,
   rechargeWallet,
+  findMintFeeAddress,
+  findDonationReceiptAddress,
+  decodeIpfsHash,
   loadObjectFromJsonFile,
 };
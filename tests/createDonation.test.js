@@ -6,7 +6,7 @@ const {
 } = require('@solana/spl-token');
 const os = require('os');
 const assert = require('assert');
-const { getNowTs, rechargeWallet } = require('./common/utils');
+const { getNowTs, rechargeWallet, findMintFeeAddress, decodeIpfsHash } = require('./common/utils');
 const {
   TREASURY_PREFIX,
   CREATOR_PREFIX,
@@ -167,9 +167,13 @@ describe("donaproto", () => {
       ipfsHash,
       new anchor.BN(endingTimestamp),
       holdingWalletOwnerBump,
+      { none: {} },
+      [],
+      false,
       {
         accounts: {
           donationData: donationData.publicKey,
+          donationTiers: null,
           donationProtocol: donationProtocolData.publicKey,
           holdingWallet: holdingWallet.address,
           holdingWalletOwner: holdingWalletOwnerPubkey,
@@ -178,6 +182,7 @@ describe("donaproto", () => {
           donationMint: donationMintPubKey,
           creatorWalletAddress: creatorWallet.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
+          mintFee: findMintFeeAddress(program.programId, donationProtocolData.publicKey, donationMintPubKey),
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         },
         signers: [donationData, creatorWallet],
@@ -195,7 +200,7 @@ describe("donaproto", () => {
     assert.deepEqual(onchainDonationData.holdingWallet, holdingWallet.address);
     assert.deepEqual(onchainDonationData.creatorData, creatorDataPubkey);
     assert.equal(onchainDonationData.holdingBump, holdingWalletOwnerBump);
-    assert.equal(onchainDonationData.ipfsHash, ipfsHash);
+    assert.equal(decodeIpfsHash(onchainDonationData.ipfsHash), ipfsHash);
     assert.deepEqual(onchainDonationData.donationMint, donationMintPubKey);
   });
 
@@ -226,9 +231,13 @@ describe("donaproto", () => {
         ipfsHash,
         new anchor.BN(endingTimestamp),
         holdingWalletOwnerBump,
+        { none: {} },
+        [],
+        false,
         {
           accounts: {
             donationData: donationData.publicKey,
+            donationTiers: null,
             donationProtocol: donationProtocolData.publicKey,
             holdingWallet: holdingWallet.address,
             holdingWalletOwner: holdingWalletOwnerPubkey,
@@ -237,6 +246,7 @@ describe("donaproto", () => {
             donationMint: donationMintPubKey,
             creatorWalletAddress: creatorWallet.publicKey,
             systemProgram: anchor.web3.SystemProgram.programId,
+            mintFee: findMintFeeAddress(program.programId, donationProtocolData.publicKey, donationMintPubKey),
             rent: anchor.web3.SYSVAR_RENT_PUBKEY,
           },
           signers: [donationData, creatorWallet],
@@ -7,7 +7,7 @@ const {
 } = require('@solana/spl-token');
 const os = require('os');
 const assert = require('assert');
-const { rechargeWallet, loadObjectFromJsonFile, getNowTs } = require("./common/utils");
+const { rechargeWallet, loadObjectFromJsonFile, getNowTs, findMintFeeAddress, decodeIpfsHash } = require("./common/utils");
 const { createPoolState } = require("./common/amm_helper");
 const {
   TREASURY_PREFIX,
@@ -281,9 +281,13 @@ describe("Create donation V2", () => {
       ipfsHash,
       new anchor.BN(endingTimestamp),
       holdingWalletOwnerBump,
+      { none: {} },
+      [],
+      false,
       {
         accounts: {
           donationData: donationData.publicKey,
+          donationTiers: null,
           donationProtocol: donationProtocolData.publicKey,
           holdingWallet: holdingWallet.address,
           holdingWalletOwner: holdingWalletOwnerPubkey,
@@ -295,6 +299,7 @@ describe("Create donation V2", () => {
           poolState: poolStatePubkey,
           creatorWalletAddress: creatorWallet.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
+          mintFee: findMintFeeAddress(program.programId, donationProtocolData.publicKey, newDonationMintPubKey),
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         },
         signers: [donationData, creatorWallet],
@@ -312,7 +317,7 @@ describe("Create donation V2", () => {
     assert.deepEqual(onchainDonationData.holdingWallet, holdingWallet.address);
     assert.deepEqual(onchainDonationData.creatorData, creatorDataPubkey);
     assert.equal(onchainDonationData.holdingBump, holdingWalletOwnerBump);
-    assert.equal(onchainDonationData.ipfsHash, ipfsHash);
+    assert.equal(decodeIpfsHash(onchainDonationData.ipfsHash), ipfsHash);
     assert.deepEqual(onchainDonationData.donationMint, newDonationMintPubKey);
   });
 });
@@ -7,7 +7,7 @@ const {
 } = require('@solana/spl-token');
 const os = require('os');
 const assert = require('assert');
-const { getNowTs, rechargeWallet } = require('./common/utils');
+const { getNowTs, rechargeWallet, findMintFeeAddress, findDonationReceiptAddress, decodeIpfsHash } = require('./common/utils');
 const {
   TREASURY_PREFIX,
   CREATOR_PREFIX,
@@ -171,9 +171,13 @@ describe("donaproto", () => {
       ipfsHash,
       new anchor.BN(endingTimestamp),
       holdingWalletOwnerBump,
+      { none: {} },
+      [],
+      false,
       {
         accounts: {
           donationData: donationData.publicKey,
+          donationTiers: null,
           donationProtocol: donationProtocolData.publicKey,
           holdingWallet: donationHoldingWallet.address,
           holdingWalletOwner: holdingWalletOwnerPubkey,
@@ -182,6 +186,7 @@ describe("donaproto", () => {
           donationMint: donationMintPubKey,
           creatorWalletAddress: creatorWallet.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
+          mintFee: findMintFeeAddress(program.programId, donationProtocolData.publicKey, donationMintPubKey),
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         },
         signers: [donationData, creatorWallet],
@@ -199,7 +204,7 @@ describe("donaproto", () => {
     assert.deepEqual(onchainDonationData.holdingWallet, donationHoldingWallet.address);
     assert.deepEqual(onchainDonationData.creatorData, creatorDataPubkey);
     assert.equal(onchainDonationData.holdingBump, holdingWalletOwnerBump);
-    assert.equal(onchainDonationData.ipfsHash, ipfsHash);
+    assert.equal(decodeIpfsHash(onchainDonationData.ipfsHash), ipfsHash);
   });
 
   it("donates and earns reward", async () => {
@@ -257,16 +262,30 @@ describe("donaproto", () => {
         accounts: {
           donationData: donationData.publicKey,
           contributorData: contributorDataPubkey,
+          donationReceipt: findDonationReceiptAddress(program.programId, donationData.publicKey, contributorWallet.publicKey),
+          fundingRound: null,
+          matchPledge: null,
+          matchPledgeEscrow: null,
+          donationTiers: null,
           donationProtocol: donationProtocolData.publicKey,
           userTokenWallet: contributorDonationTokenAccount.address,
           userRewardTokenWallet: contributorRewardTokenAccount.address,
           rewardTreasury: treasuryTokenAccount.address,
           rewardTreasuryOwner: treasuryOwnerPubkey,
+          rewardVesting: null,
+          recipient: creatorDonationTokenAccount.address,
           holdingWallet: donationHoldingWallet.address,
           donationMint: donationMintPubKey,
           rewardMint: rewardsMintPubKey,
+          receiptMint: null,
+          receiptTokenAccount: null,
+          receiptMetadata: null,
+          receiptMintAuthority: null,
           userWallet: contributorWallet.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          associatedTokenProgram: null,
+          memoProgram: null,
         },
         signers: [contributorWallet],
       }
@@ -302,16 +321,30 @@ describe("donaproto", () => {
         accounts: {
           donationData: donationData.publicKey,
           contributorData: contributorDataPubkey,
+          donationReceipt: findDonationReceiptAddress(program.programId, donationData.publicKey, contributorWallet.publicKey),
+          fundingRound: null,
+          matchPledge: null,
+          matchPledgeEscrow: null,
+          donationTiers: null,
           donationProtocol: donationProtocolData.publicKey,
           userTokenWallet: contributorDonationTokenAccount.address,
           userRewardTokenWallet: contributorRewardTokenAccount.address,
           rewardTreasury: treasuryTokenAccount.address,
           rewardTreasuryOwner: treasuryOwnerPubkey,
+          rewardVesting: null,
+          recipient: creatorDonationTokenAccount.address,
           holdingWallet: donationHoldingWallet.address,
           donationMint: donationMintPubKey,
           rewardMint: rewardsMintPubKey,
+          receiptMint: null,
+          receiptTokenAccount: null,
+          receiptMetadata: null,
+          receiptMintAuthority: null,
           userWallet: contributorWallet.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          associatedTokenProgram: null,
+          memoProgram: null,
         },
         signers: [contributorWallet],
       }
@@ -331,4 +364,147 @@ describe("donaproto", () => {
     assert.equal(onchainContributorData.totalAmountEarned.toString(), "1000000");
     assert.equal(onchainContributorData.donationsCount.toString(), 2);
   });
+
+  it("initializes contributor data on first donation", async () => {
+    const newContributorWallet = anchor.web3.Keypair.generate();
+    await rechargeWallet(connection, newContributorWallet.publicKey, 1_000_000_000);
+    const [contributorDataPubkey, contributorDataBump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(CONTRIBUTOR_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+        newContributorWallet.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+
+    const contributorDonationTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      newContributorWallet.publicKey
+    )
+    await mintTo(
+      connection,
+      payer,
+      donationMintPubKey,
+      contributorDonationTokenAccount.address,
+      donationMintAuthority,
+      10_000_000,
+    )
+    const contributorRewardTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      newContributorWallet.publicKey
+    )
+
+    const amount = new anchor.BN(1_000_000);
+    for (let i = 0; i < 2; i++) {
+      await program.rpc.donate(
+        amount,
+        {
+          accounts: {
+            donationData: donationData.publicKey,
+            contributorData: contributorDataPubkey,
+            donationReceipt: findDonationReceiptAddress(program.programId, donationData.publicKey, newContributorWallet.publicKey),
+            fundingRound: null,
+            matchPledge: null,
+            matchPledgeEscrow: null,
+            donationTiers: null,
+            donationProtocol: donationProtocolData.publicKey,
+            userTokenWallet: contributorDonationTokenAccount.address,
+            userRewardTokenWallet: contributorRewardTokenAccount.address,
+            rewardTreasury: treasuryTokenAccount.address,
+            rewardTreasuryOwner: treasuryOwnerPubkey,
+            rewardVesting: null,
+            recipient: creatorDonationTokenAccount.address,
+            holdingWallet: donationHoldingWallet.address,
+            donationMint: donationMintPubKey,
+            rewardMint: rewardsMintPubKey,
+            receiptMint: null,
+            receiptTokenAccount: null,
+            receiptMetadata: null,
+            receiptMintAuthority: null,
+            userWallet: newContributorWallet.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            associatedTokenProgram: null,
+            memoProgram: null,
+          },
+          signers: [newContributorWallet],
+        }
+      );
+    }
+
+    const onchainContributorData = await program.account.contributorData.fetch(contributorDataPubkey);
+    assert.ok(onchainContributorData.donationProtocol.equals(donationProtocolData.publicKey));
+    assert.equal(onchainContributorData.bump, contributorDataBump);
+    assert.equal(onchainContributorData.totalAmountDonated.toString(), amount.muln(2).toString());
+    assert.equal(onchainContributorData.donationsCount.toString(), 2);
+  });
+
+  it("pays no reward when creator donates to own donation", async () => {
+    const [creatorContributorDataPubkey] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(CONTRIBUTOR_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+        creatorWallet.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    const creatorRewardTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      creatorWallet.publicKey
+    )
+    const onchainDonationDataBefore = await program.account.donationData.fetch(donationData.publicKey);
+
+    const amount = new anchor.BN(1_000_000);
+    await program.rpc.donate(
+      amount,
+      {
+        accounts: {
+          donationData: donationData.publicKey,
+          contributorData: creatorContributorDataPubkey,
+          donationReceipt: findDonationReceiptAddress(program.programId, donationData.publicKey, creatorWallet.publicKey),
+          fundingRound: null,
+          matchPledge: null,
+          matchPledgeEscrow: null,
+          donationTiers: null,
+          donationProtocol: donationProtocolData.publicKey,
+          userTokenWallet: creatorDonationTokenAccount.address,
+          userRewardTokenWallet: creatorRewardTokenAccount.address,
+          rewardTreasury: treasuryTokenAccount.address,
+          rewardTreasuryOwner: treasuryOwnerPubkey,
+          rewardVesting: null,
+          recipient: creatorDonationTokenAccount.address,
+          holdingWallet: donationHoldingWallet.address,
+          donationMint: donationMintPubKey,
+          rewardMint: rewardsMintPubKey,
+          receiptMint: null,
+          receiptTokenAccount: null,
+          receiptMetadata: null,
+          receiptMintAuthority: null,
+          userWallet: creatorWallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          associatedTokenProgram: null,
+          memoProgram: null,
+        },
+        signers: [creatorWallet],
+      }
+    );
+
+    const balanceCreatorRewardTokenAccount = await connection.getTokenAccountBalance(creatorRewardTokenAccount.address);
+    assert.equal(balanceCreatorRewardTokenAccount.value.amount, "0");
+    const onchainDonationData = await program.account.donationData.fetch(donationData.publicKey);
+    assert.ok(onchainDonationData.creatorWallet.equals(creatorWallet.publicKey));
+    assert.equal(
+      onchainDonationData.selfFundedAmount.toString(),
+      onchainDonationDataBefore.selfFundedAmount.add(amount).toString(),
+    );
+    const onchainContributorData = await program.account.contributorData.fetch(creatorContributorDataPubkey);
+    assert.equal(onchainContributorData.totalAmountEarned.toString(), "0");
+  });
 });
@@ -0,0 +1,318 @@
+const anchor = require("@coral-xyz/anchor");
+const {
+  createMint,
+  mintTo,
+  getOrCreateAssociatedTokenAccount,
+  approve,
+  TOKEN_PROGRAM_ID,
+} = require('@solana/spl-token');
+const os = require('os');
+const assert = require('assert');
+const { getNowTs, rechargeWallet, findMintFeeAddress, findDonationReceiptAddress, decodeIpfsHash } = require('./common/utils');
+const {
+  TREASURY_PREFIX,
+  CREATOR_PREFIX,
+  HOLDING_PREFIX,
+  CONTRIBUTOR_PREFIX
+} = require("./common/seeds");
+
+
+describe("donaproto", () => {
+  const homedir = os.homedir();
+  process.env.ANCHOR_WALLET = `${homedir}/.config/solana/id.json`;
+  const program = anchor.workspace.Donaproto;
+  const provider = anchor.AnchorProvider.local();
+  anchor.setProvider(provider);
+  const connection = provider.connection;
+  const { payer } = program.provider.wallet
+  const donationMintAuthority = payer;
+  const donationMintDecimals = 6;
+  let donationMintPubKey, rewardsMintPubKey
+  const rewardMintAuthority = payer;
+  const rewardMintDecimals = 9;
+  const donationProtocolData = anchor.web3.Keypair.generate();
+  const minAmountToEarn = new anchor.BN(1000);
+  const creatorWallet = anchor.web3.Keypair.generate();
+  let creatorDataPubkey, creatorDataBump;
+  let creatorDonationTokenAccount;
+  const donationData = anchor.web3.Keypair.generate();
+  const tokenOwnerWallet = anchor.web3.Keypair.generate();
+  const relayerWallet = anchor.web3.Keypair.generate();
+  const beneficiaryWallet = anchor.web3.Keypair.generate();
+  let treasuryTokenAccount, treasuryOwnerPubkey, treasuryOwnerBump;
+  let donationHoldingWallet;
+  const minAmountToCollect = new anchor.BN(1_000_000);
+
+  before(async () => {
+    donationMintPubKey = await createMint(
+      provider.connection,
+      payer,
+      donationMintAuthority.publicKey,
+      null,
+      donationMintDecimals
+    );
+
+    rewardsMintPubKey = await createMint(
+      connection,
+      payer,
+      rewardMintAuthority.publicKey,
+      null,
+      rewardMintDecimals
+    )
+
+    const [treasuryOwnerPubkeyFound, treasuryOwnerBumpFound] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(TREASURY_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    treasuryOwnerPubkey = treasuryOwnerPubkeyFound;
+    treasuryOwnerBump = treasuryOwnerBumpFound;
+
+    treasuryTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      treasuryOwnerPubkey,
+      allowOwnerOffCurve = true
+    )
+
+    await program.rpc.initializeDonationProtocol(
+      minAmountToEarn,
+      minAmountToCollect,
+      treasuryOwnerBump,
+      {
+        accounts: {
+          donationProtocolData: donationProtocolData.publicKey,
+          treasury: treasuryTokenAccount.address,
+          treasuryOwner: treasuryOwnerPubkey,
+          treasuryMint: rewardsMintPubKey,
+          donationMint: donationMintPubKey,
+          payer: payer.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        signers: [donationProtocolData],
+      }
+    );
+
+    creatorDonationTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      creatorWallet.publicKey
+    )
+
+    await mintTo(
+      connection,
+      payer,
+      donationMintPubKey,
+      creatorDonationTokenAccount.address,
+      donationMintAuthority,
+      10_000_000_000
+    )
+
+    const [creatorDataPubkeyFound, creatorDataBumpFound] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(CREATOR_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+        creatorWallet.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    creatorDataPubkey = creatorDataPubkeyFound;
+    creatorDataBump = creatorDataBumpFound;
+
+    await program.rpc.initializeCreator(
+      {
+        accounts: {
+          creatorData: creatorDataPubkey,
+          donationProtocol: donationProtocolData.publicKey,
+          creatorWalletAddress: creatorWallet.publicKey,
+          payer: payer.publicKey,
+          donationMint: donationMintPubKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      },
+    );
+
+    // check data
+    const onchainCreatorData = await program.account.creatorData.fetch(creatorDataPubkey);
+    assert.ok(onchainCreatorData.donationProtocol.equals(donationProtocolData.publicKey));
+    assert.equal(onchainCreatorData.donationsCreatedCount, 0);
+    assert.equal(onchainCreatorData.donationsClosedCount, 0);
+    assert.equal(onchainCreatorData.totalAmountReceived, 0);
+    assert.equal(onchainCreatorData.totalAmountCollecting, 0);
+
+    // top up creator wallet to be able to pay for createDonation tx
+    await rechargeWallet(connection, creatorWallet.publicKey, 10_00_000_000);
+    const creatorWalletBalance = await connection.getBalance(creatorWallet.publicKey);
+
+    const amount = new anchor.BN(1_000_000_000); // 1000$
+    const ipfsHash = "some_ipfs_hash";
+    const endingTimestamp = await getNowTs(provider) + 100_000;
+    const [holdingWalletOwnerPubkey, holdingWalletOwnerBump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(HOLDING_PREFIX),
+        donationData.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+
+    donationHoldingWallet = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      holdingWalletOwnerPubkey,
+      true,
+    );
+
+    const tx = await program.rpc.createDonation(
+      amount,
+      ipfsHash,
+      new anchor.BN(endingTimestamp),
+      holdingWalletOwnerBump,
+      { none: {} },
+      [],
+      false,
+      {
+        accounts: {
+          donationData: donationData.publicKey,
+          donationTiers: null,
+          donationProtocol: donationProtocolData.publicKey,
+          holdingWallet: donationHoldingWallet.address,
+          holdingWalletOwner: holdingWalletOwnerPubkey,
+          recipient: creatorDonationTokenAccount.address,
+          creatorData: creatorDataPubkey,
+          donationMint: donationMintPubKey,
+          creatorWalletAddress: creatorWallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          mintFee: findMintFeeAddress(program.programId, donationProtocolData.publicKey, donationMintPubKey),
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        signers: [donationData, creatorWallet],
+      }
+    );
+    console.log("Your transaction signature", tx);
+
+    const onchainDonationData = await program.account.donationData.fetch(donationData.publicKey);
+    assert.equal(onchainDonationData.amountCollecting.toString(), amount.toString());
+    assert.equal(onchainDonationData.totalAmountReceived, 0);
+    assert.equal(onchainDonationData.endingTimestamp, endingTimestamp);
+    assert.equal(onchainDonationData.isClosed, false);
+    assert.deepEqual(onchainDonationData.recipient.toString(), creatorDonationTokenAccount.address.toString());
+    assert.deepEqual(onchainDonationData.donationProtocol, donationProtocolData.publicKey);
+    assert.deepEqual(onchainDonationData.holdingWallet, donationHoldingWallet.address);
+    assert.deepEqual(onchainDonationData.creatorData, creatorDataPubkey);
+    assert.equal(onchainDonationData.holdingBump, holdingWalletOwnerBump);
+    assert.equal(decodeIpfsHash(onchainDonationData.ipfsHash), ipfsHash);
+  });
+
+  it("donates on behalf of beneficiary with delegated allowance", async () => {
+    await rechargeWallet(connection, relayerWallet.publicKey, 1_000_000_000);
+    const [contributorDataPubkey] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(CONTRIBUTOR_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+        beneficiaryWallet.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+
+    const sourceTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      tokenOwnerWallet.publicKey
+    )
+    await mintTo(
+      connection,
+      payer,
+      donationMintPubKey,
+      sourceTokenAccount.address,
+      donationMintAuthority,
+      10_000_000,
+    )
+    const beneficiaryRewardTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      beneficiaryWallet.publicKey
+    )
+    await mintTo(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      treasuryTokenAccount.address,
+      rewardMintAuthority,
+      10_000_000_000,
+    )
+
+    // token owner approves relayer to spend part of the balance
+    const allowance = 2_000_000;
+    await approve(
+      connection,
+      payer,
+      sourceTokenAccount.address,
+      relayerWallet.publicKey,
+      tokenOwnerWallet,
+      allowance,
+    );
+
+    const donateOnBehalf = (amount) => program.rpc.donateOnBehalf(
+      amount,
+      {
+        accounts: {
+          donationData: donationData.publicKey,
+          contributorData: contributorDataPubkey,
+          donationReceipt: findDonationReceiptAddress(program.programId, donationData.publicKey, beneficiaryWallet.publicKey),
+          fundingRound: null,
+          matchPledge: null,
+          matchPledgeEscrow: null,
+          donationTiers: null,
+          donationProtocol: donationProtocolData.publicKey,
+          sourceTokenWallet: sourceTokenAccount.address,
+          beneficiaryRewardTokenWallet: beneficiaryRewardTokenAccount.address,
+          rewardTreasury: treasuryTokenAccount.address,
+          rewardTreasuryOwner: treasuryOwnerPubkey,
+          rewardVesting: null,
+          recipient: creatorDonationTokenAccount.address,
+          holdingWallet: donationHoldingWallet.address,
+          donationMint: donationMintPubKey,
+          rewardMint: rewardsMintPubKey,
+          beneficiary: beneficiaryWallet.publicKey,
+          tokenAuthority: relayerWallet.publicKey,
+          payer: relayerWallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        },
+        signers: [relayerWallet],
+      }
+    );
+
+    const amount = new anchor.BN(1_000_000);
+    const tx = await donateOnBehalf(amount);
+    console.log("Your transaction signature", tx);
+
+    const balanceDonationHoldingWallet = await connection.getTokenAccountBalance(donationHoldingWallet.address);
+    assert.equal(balanceDonationHoldingWallet.value.amount, amount.toString());
+    const balanceBeneficiaryRewardTokenAccount = await connection.getTokenAccountBalance(beneficiaryRewardTokenAccount.address);
+    assert.equal(balanceBeneficiaryRewardTokenAccount.value.amount, amount.toString());
+
+    const onchainContributorData = await program.account.contributorData.fetch(contributorDataPubkey);
+    assert.ok(onchainContributorData.donationProtocol.equals(donationProtocolData.publicKey));
+    assert.equal(onchainContributorData.totalAmountDonated.toString(), amount.toString());
+    assert.equal(onchainContributorData.totalAmountEarned.toString(), amount.toString());
+    assert.equal(onchainContributorData.donationsCount.toString(), 1);
+
+    // relayer can not spend more than approved
+    try {
+      await donateOnBehalf(new anchor.BN(allowance));
+      assert.fail("donation above delegated amount should fail");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "InsufficientDelegatedAmount");
+    }
+  });
+});
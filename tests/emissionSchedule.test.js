@@ -0,0 +1,323 @@
+const anchor = require("@coral-xyz/anchor");
+const {
+  createMint,
+  mintTo,
+  getOrCreateAssociatedTokenAccount,
+  TOKEN_PROGRAM_ID,
+} = require('@solana/spl-token');
+const os = require('os');
+const assert = require('assert');
+const { getNowTs, rechargeWallet, findMintFeeAddress, findDonationReceiptAddress, decodeIpfsHash } = require('./common/utils');
+const {
+  TREASURY_PREFIX,
+  CREATOR_PREFIX,
+  HOLDING_PREFIX,
+  CONTRIBUTOR_PREFIX
+} = require("./common/seeds");
+
+
+describe("donaproto", () => {
+  const homedir = os.homedir();
+  process.env.ANCHOR_WALLET = `${homedir}/.config/solana/id.json`;
+  const program = anchor.workspace.Donaproto;
+  const provider = anchor.AnchorProvider.local();
+  anchor.setProvider(provider);
+  const connection = provider.connection;
+  const { payer } = program.provider.wallet
+  const donationMintAuthority = payer;
+  const donationMintDecimals = 6;
+  let donationMintPubKey, rewardsMintPubKey
+  const rewardMintAuthority = payer;
+  const rewardMintDecimals = 9;
+  const donationProtocolData = anchor.web3.Keypair.generate();
+  const minAmountToEarn = new anchor.BN(1000);
+  const creatorWallet = anchor.web3.Keypair.generate();
+  let creatorDataPubkey, creatorDataBump;
+  let creatorDonationTokenAccount;
+  const donationData = anchor.web3.Keypair.generate();
+  const contributorWallet = anchor.web3.Keypair.generate();
+  let treasuryTokenAccount, treasuryOwnerPubkey, treasuryOwnerBump;
+  let donationHoldingWallet;
+  const minAmountToCollect = new anchor.BN(1_000_000);
+
+  before(async () => {
+    donationMintPubKey = await createMint(
+      provider.connection,
+      payer,
+      donationMintAuthority.publicKey,
+      null,
+      donationMintDecimals
+    );
+
+    rewardsMintPubKey = await createMint(
+      connection,
+      payer,
+      rewardMintAuthority.publicKey,
+      null,
+      rewardMintDecimals
+    )
+
+    const [treasuryOwnerPubkeyFound, treasuryOwnerBumpFound] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(TREASURY_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    treasuryOwnerPubkey = treasuryOwnerPubkeyFound;
+    treasuryOwnerBump = treasuryOwnerBumpFound;
+
+    treasuryTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      treasuryOwnerPubkey,
+      allowOwnerOffCurve = true
+    )
+
+    await program.rpc.initializeDonationProtocol(
+      minAmountToEarn,
+      minAmountToCollect,
+      treasuryOwnerBump,
+      {
+        accounts: {
+          donationProtocolData: donationProtocolData.publicKey,
+          treasury: treasuryTokenAccount.address,
+          treasuryOwner: treasuryOwnerPubkey,
+          treasuryMint: rewardsMintPubKey,
+          donationMint: donationMintPubKey,
+          payer: payer.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        signers: [donationProtocolData],
+      }
+    );
+
+    creatorDonationTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      creatorWallet.publicKey
+    )
+
+    await mintTo(
+      connection,
+      payer,
+      donationMintPubKey,
+      creatorDonationTokenAccount.address,
+      donationMintAuthority,
+      10_000_000_000
+    )
+
+    const [creatorDataPubkeyFound, creatorDataBumpFound] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(CREATOR_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+        creatorWallet.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    creatorDataPubkey = creatorDataPubkeyFound;
+    creatorDataBump = creatorDataBumpFound;
+
+    await program.rpc.initializeCreator(
+      {
+        accounts: {
+          creatorData: creatorDataPubkey,
+          donationProtocol: donationProtocolData.publicKey,
+          creatorWalletAddress: creatorWallet.publicKey,
+          payer: payer.publicKey,
+          donationMint: donationMintPubKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      },
+    );
+
+    // check data
+    const onchainCreatorData = await program.account.creatorData.fetch(creatorDataPubkey);
+    assert.ok(onchainCreatorData.donationProtocol.equals(donationProtocolData.publicKey));
+    assert.equal(onchainCreatorData.donationsCreatedCount, 0);
+    assert.equal(onchainCreatorData.donationsClosedCount, 0);
+    assert.equal(onchainCreatorData.totalAmountReceived, 0);
+    assert.equal(onchainCreatorData.totalAmountCollecting, 0);
+
+    // top up creator wallet to be able to pay for createDonation tx
+    await rechargeWallet(connection, creatorWallet.publicKey, 10_00_000_000);
+    const creatorWalletBalance = await connection.getBalance(creatorWallet.publicKey);
+
+    const amount = new anchor.BN(1_000_000_000); // 1000$
+    const ipfsHash = "some_ipfs_hash";
+    const endingTimestamp = await getNowTs(provider) + 100_000;
+    const [holdingWalletOwnerPubkey, holdingWalletOwnerBump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(HOLDING_PREFIX),
+        donationData.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+
+    donationHoldingWallet = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      holdingWalletOwnerPubkey,
+      true,
+    );
+
+    const tx = await program.rpc.createDonation(
+      amount,
+      ipfsHash,
+      new anchor.BN(endingTimestamp),
+      holdingWalletOwnerBump,
+      { none: {} },
+      [],
+      false,
+      {
+        accounts: {
+          donationData: donationData.publicKey,
+          donationTiers: null,
+          donationProtocol: donationProtocolData.publicKey,
+          holdingWallet: donationHoldingWallet.address,
+          holdingWalletOwner: holdingWalletOwnerPubkey,
+          recipient: creatorDonationTokenAccount.address,
+          creatorData: creatorDataPubkey,
+          donationMint: donationMintPubKey,
+          creatorWalletAddress: creatorWallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          mintFee: findMintFeeAddress(program.programId, donationProtocolData.publicKey, donationMintPubKey),
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        signers: [donationData, creatorWallet],
+      }
+    );
+    console.log("Your transaction signature", tx);
+
+    const onchainDonationData = await program.account.donationData.fetch(donationData.publicKey);
+    assert.equal(onchainDonationData.amountCollecting.toString(), amount.toString());
+    assert.equal(onchainDonationData.totalAmountReceived, 0);
+    assert.equal(onchainDonationData.endingTimestamp, endingTimestamp);
+    assert.equal(onchainDonationData.isClosed, false);
+    assert.deepEqual(onchainDonationData.recipient.toString(), creatorDonationTokenAccount.address.toString());
+    assert.deepEqual(onchainDonationData.donationProtocol, donationProtocolData.publicKey);
+    assert.deepEqual(onchainDonationData.holdingWallet, donationHoldingWallet.address);
+    assert.deepEqual(onchainDonationData.creatorData, creatorDataPubkey);
+    assert.equal(onchainDonationData.holdingBump, holdingWalletOwnerBump);
+    assert.equal(decodeIpfsHash(onchainDonationData.ipfsHash), ipfsHash);
+  });
+
+  it("pays rewards only out of the current epoch emission budget", async () => {
+    await rechargeWallet(connection, contributorWallet.publicKey, 1_000_000_000);
+    const [contributorDataPubkey] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(CONTRIBUTOR_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+        contributorWallet.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    const contributorDonationTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      contributorWallet.publicKey
+    )
+    await mintTo(
+      connection,
+      payer,
+      donationMintPubKey,
+      contributorDonationTokenAccount.address,
+      donationMintAuthority,
+      10_000_000_000,
+    )
+    const contributorRewardTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      contributorWallet.publicKey
+    )
+    await mintTo(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      treasuryTokenAccount.address,
+      rewardMintAuthority,
+      10_000_000_000,
+    )
+
+    const setEmissionSchedule = (epochBudget, epochDuration) => program.rpc.setEmissionSchedule(
+      new anchor.BN(epochBudget),
+      new anchor.BN(epochDuration),
+      5000, // budget halves every epoch
+      { forfeit: {} },
+      {
+        accounts: {
+          donationProtocol: donationProtocolData.publicKey,
+          authority: payer.publicKey,
+        },
+      }
+    );
+    const donateAndGetReward = async (amount) => {
+      const balanceBefore = await connection.getTokenAccountBalance(contributorRewardTokenAccount.address);
+      await program.rpc.donate(
+        new anchor.BN(amount),
+        {
+          accounts: {
+            donationData: donationData.publicKey,
+            contributorData: contributorDataPubkey,
+            donationReceipt: findDonationReceiptAddress(program.programId, donationData.publicKey, contributorWallet.publicKey),
+            fundingRound: null,
+            matchPledge: null,
+            matchPledgeEscrow: null,
+            donationTiers: null,
+            donationProtocol: donationProtocolData.publicKey,
+            userTokenWallet: contributorDonationTokenAccount.address,
+            userRewardTokenWallet: contributorRewardTokenAccount.address,
+            rewardTreasury: treasuryTokenAccount.address,
+            rewardTreasuryOwner: treasuryOwnerPubkey,
+            rewardVesting: null,
+            recipient: creatorDonationTokenAccount.address,
+            holdingWallet: donationHoldingWallet.address,
+            donationMint: donationMintPubKey,
+            rewardMint: rewardsMintPubKey,
+            receiptMint: null,
+            receiptTokenAccount: null,
+            receiptMetadata: null,
+            receiptMintAuthority: null,
+            userWallet: contributorWallet.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            associatedTokenProgram: null,
+            memoProgram: null,
+          },
+          signers: [contributorWallet],
+        }
+      );
+      const balanceAfter = await connection.getTokenAccountBalance(contributorRewardTokenAccount.address);
+      return new anchor.BN(balanceAfter.value.amount).sub(new anchor.BN(balanceBefore.value.amount)).toNumber();
+    };
+
+    await setEmissionSchedule(1_500_000, 100_000);
+    assert.equal(await donateAndGetReward(1_000_000), 1_000_000);
+    // only the rest of the epoch budget is paid
+    assert.equal(await donateAndGetReward(1_000_000), 500_000);
+    assert.equal(await donateAndGetReward(1_000_000), 0);
+
+    let onchainDonationProtocolData = await program.account.donationProtocolData.fetch(donationProtocolData.publicKey);
+    assert.equal(onchainDonationProtocolData.currentEpoch.toString(), "0");
+    assert.equal(onchainDonationProtocolData.currentEpochConsumed.toString(), "1500000");
+
+    // budget decays once the epoch is over
+    await setEmissionSchedule(1_000_000, 2);
+    console.log("waiting epoch end for 3 seconds");
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+    const reward = await donateAndGetReward(1_000_000);
+
+    onchainDonationProtocolData = await program.account.donationProtocolData.fetch(donationProtocolData.publicKey);
+    const currentEpoch = onchainDonationProtocolData.currentEpoch.toNumber();
+    assert.ok(currentEpoch >= 1);
+    assert.equal(reward, 1_000_000 / 2 ** currentEpoch);
+    assert.equal(onchainDonationProtocolData.currentEpochBudget.toNumber(), reward);
+  });
+});
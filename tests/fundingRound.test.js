@@ -0,0 +1,386 @@
+const anchor = require("@coral-xyz/anchor");
+const {
+  createMint,
+  mintTo,
+  getOrCreateAssociatedTokenAccount,
+  TOKEN_PROGRAM_ID,
+} = require('@solana/spl-token');
+const os = require('os');
+const assert = require('assert');
+const { getNowTs, rechargeWallet, findMintFeeAddress, findDonationReceiptAddress } = require('./common/utils');
+const {
+  TREASURY_PREFIX,
+  CREATOR_PREFIX,
+  HOLDING_PREFIX,
+  CONTRIBUTOR_PREFIX,
+  MATCHING_VAULT_PREFIX
+} = require("./common/seeds");
+
+
+describe("donaproto", () => {
+  const homedir = os.homedir();
+  process.env.ANCHOR_WALLET = `${homedir}/.config/solana/id.json`;
+  const program = anchor.workspace.Donaproto;
+  const provider = anchor.AnchorProvider.local();
+  anchor.setProvider(provider);
+  const connection = provider.connection;
+  const { payer } = program.provider.wallet
+  const donationMintAuthority = payer;
+  const donationMintDecimals = 6;
+  let donationMintPubKey, rewardsMintPubKey
+  const rewardMintAuthority = payer;
+  const rewardMintDecimals = 9;
+  const donationProtocolData = anchor.web3.Keypair.generate();
+  const minAmountToEarn = new anchor.BN(1000);
+  const creatorWallet = anchor.web3.Keypair.generate();
+  let creatorDataPubkey, creatorDataBump;
+  let creatorDonationTokenAccount;
+  const donationData = anchor.web3.Keypair.generate();
+  const contributorWallet = anchor.web3.Keypair.generate();
+  let treasuryTokenAccount, treasuryOwnerPubkey, treasuryOwnerBump;
+  let donationHoldingWallet;
+  const minAmountToCollect = new anchor.BN(1_000_000);
+
+  before(async () => {
+    donationMintPubKey = await createMint(
+      provider.connection,
+      payer,
+      donationMintAuthority.publicKey,
+      null,
+      donationMintDecimals
+    );
+
+    rewardsMintPubKey = await createMint(
+      connection,
+      payer,
+      rewardMintAuthority.publicKey,
+      null,
+      rewardMintDecimals
+    )
+
+    const [treasuryOwnerPubkeyFound, treasuryOwnerBumpFound] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(TREASURY_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    treasuryOwnerPubkey = treasuryOwnerPubkeyFound;
+    treasuryOwnerBump = treasuryOwnerBumpFound;
+
+    treasuryTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      treasuryOwnerPubkey,
+      allowOwnerOffCurve = true
+    )
+
+    await program.rpc.initializeDonationProtocol(
+      minAmountToEarn,
+      minAmountToCollect,
+      treasuryOwnerBump,
+      {
+        accounts: {
+          donationProtocolData: donationProtocolData.publicKey,
+          treasury: treasuryTokenAccount.address,
+          treasuryOwner: treasuryOwnerPubkey,
+          treasuryMint: rewardsMintPubKey,
+          donationMint: donationMintPubKey,
+          payer: payer.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        signers: [donationProtocolData],
+      }
+    );
+
+    creatorDonationTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      creatorWallet.publicKey
+    )
+
+    await mintTo(
+      connection,
+      payer,
+      donationMintPubKey,
+      creatorDonationTokenAccount.address,
+      donationMintAuthority,
+      10_000_000_000
+    )
+
+    const [creatorDataPubkeyFound, creatorDataBumpFound] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(CREATOR_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+        creatorWallet.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    creatorDataPubkey = creatorDataPubkeyFound;
+    creatorDataBump = creatorDataBumpFound;
+
+    await program.rpc.initializeCreator(
+      {
+        accounts: {
+          creatorData: creatorDataPubkey,
+          donationProtocol: donationProtocolData.publicKey,
+          creatorWalletAddress: creatorWallet.publicKey,
+          payer: payer.publicKey,
+          donationMint: donationMintPubKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      },
+    );
+
+    // check data
+    const onchainCreatorData = await program.account.creatorData.fetch(creatorDataPubkey);
+    assert.ok(onchainCreatorData.donationProtocol.equals(donationProtocolData.publicKey));
+    assert.equal(onchainCreatorData.donationsCreatedCount, 0);
+    assert.equal(onchainCreatorData.donationsClosedCount, 0);
+    assert.equal(onchainCreatorData.totalAmountReceived, 0);
+    assert.equal(onchainCreatorData.totalAmountCollecting, 0);
+
+    // top up creator wallet to be able to pay for createDonation tx
+    await rechargeWallet(connection, creatorWallet.publicKey, 10_00_000_000);
+    const creatorWalletBalance = await connection.getBalance(creatorWallet.publicKey);
+
+    const amount = new anchor.BN(1_000_000_000); // 1000$
+    const ipfsHash = "some_ipfs_hash";
+    const endingTimestamp = await getNowTs(provider) + 100_000;
+    const [holdingWalletOwnerPubkey, holdingWalletOwnerBump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(HOLDING_PREFIX),
+        donationData.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+
+    donationHoldingWallet = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      holdingWalletOwnerPubkey,
+      true,
+    );
+
+    const tx = await program.rpc.createDonation(
+      amount,
+      ipfsHash,
+      new anchor.BN(endingTimestamp),
+      holdingWalletOwnerBump,
+      { none: {} },
+      [],
+      false,
+      {
+        accounts: {
+          donationData: donationData.publicKey,
+          donationTiers: null,
+          donationProtocol: donationProtocolData.publicKey,
+          holdingWallet: donationHoldingWallet.address,
+          holdingWalletOwner: holdingWalletOwnerPubkey,
+          recipient: creatorDonationTokenAccount.address,
+          creatorData: creatorDataPubkey,
+          donationMint: donationMintPubKey,
+          creatorWalletAddress: creatorWallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          mintFee: findMintFeeAddress(program.programId, donationProtocolData.publicKey, donationMintPubKey),
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        signers: [donationData, creatorWallet],
+      }
+    );
+    console.log("Your transaction signature", tx);
+
+    const onchainDonationData = await program.account.donationData.fetch(donationData.publicKey);
+    assert.equal(onchainDonationData.amountCollecting.toString(), amount.toString());
+    assert.equal(onchainDonationData.totalAmountReceived, 0);
+    assert.equal(onchainDonationData.endingTimestamp, endingTimestamp);
+    assert.equal(onchainDonationData.isClosed, false);
+    assert.deepEqual(onchainDonationData.recipient.toString(), creatorDonationTokenAccount.address.toString());
+  });
+
+  it("matches donations made during the round quadratically", async () => {
+    const fundingRound = anchor.web3.Keypair.generate();
+    const [matchingVaultOwnerPubkey] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(MATCHING_VAULT_PREFIX),
+        fundingRound.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    const [matchingVaultPubkey] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(MATCHING_VAULT_PREFIX),
+        fundingRound.publicKey.toBuffer(),
+        donationMintPubKey.toBuffer(),
+      ],
+      program.programId,
+    );
+
+    const nowTs = await getNowTs(provider);
+    await program.rpc.createFundingRound(
+      new anchor.BN(nowTs - 1),
+      new anchor.BN(nowTs + 8),
+      {
+        accounts: {
+          fundingRound: fundingRound.publicKey,
+          matchingVault: matchingVaultPubkey,
+          matchingVaultOwner: matchingVaultOwnerPubkey,
+          donationProtocol: donationProtocolData.publicKey,
+          matchingMint: donationMintPubKey,
+          authority: payer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        },
+        remainingAccounts: [
+          { pubkey: donationData.publicKey, isWritable: false, isSigner: false },
+        ],
+        signers: [fundingRound],
+      }
+    );
+    const matchingPoolAmount = 5_000_000;
+    await mintTo(
+      connection,
+      payer,
+      donationMintPubKey,
+      matchingVaultPubkey,
+      donationMintAuthority,
+      matchingPoolAmount,
+    )
+
+    const amount = new anchor.BN(1_000_000);
+    const donors = [contributorWallet, anchor.web3.Keypair.generate()];
+    for (const donor of donors) {
+      await rechargeWallet(connection, donor.publicKey, 1_000_000_000);
+      const [contributorDataPubkey] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from(CONTRIBUTOR_PREFIX),
+          donationProtocolData.publicKey.toBuffer(),
+          donor.publicKey.toBuffer(),
+        ],
+        program.programId,
+      );
+      const donorDonationTokenAccount = await getOrCreateAssociatedTokenAccount(
+        connection,
+        payer,
+        donationMintPubKey,
+        donor.publicKey
+      )
+      await mintTo(
+        connection,
+        payer,
+        donationMintPubKey,
+        donorDonationTokenAccount.address,
+        donationMintAuthority,
+        amount.toNumber(),
+      )
+      const donorRewardTokenAccount = await getOrCreateAssociatedTokenAccount(
+        connection,
+        payer,
+        rewardsMintPubKey,
+        donor.publicKey
+      )
+      await program.rpc.donate(
+        amount,
+        {
+          accounts: {
+            donationData: donationData.publicKey,
+            contributorData: contributorDataPubkey,
+            donationReceipt: findDonationReceiptAddress(program.programId, donationData.publicKey, donor.publicKey),
+            fundingRound: fundingRound.publicKey,
+            matchPledge: null,
+            matchPledgeEscrow: null,
+            donationTiers: null,
+            donationProtocol: donationProtocolData.publicKey,
+            userTokenWallet: donorDonationTokenAccount.address,
+            userRewardTokenWallet: donorRewardTokenAccount.address,
+            rewardTreasury: treasuryTokenAccount.address,
+            rewardTreasuryOwner: treasuryOwnerPubkey,
+            rewardVesting: null,
+            recipient: creatorDonationTokenAccount.address,
+            holdingWallet: donationHoldingWallet.address,
+            donationMint: donationMintPubKey,
+            rewardMint: rewardsMintPubKey,
+            receiptMint: null,
+            receiptTokenAccount: null,
+            receiptMetadata: null,
+            receiptMintAuthority: null,
+            userWallet: donor.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            associatedTokenProgram: null,
+            memoProgram: null,
+          },
+          signers: [donor],
+        }
+      );
+    }
+
+    let onchainFundingRound = await program.account.fundingRound.fetch(fundingRound.publicKey);
+    assert.equal(onchainFundingRound.campaigns[0].sqrtSum.toString(), "2000");
+    assert.equal(onchainFundingRound.campaigns[0].totalAmountContributed.toString(), amount.muln(2).toString());
+
+    const finalizeRound = () => program.rpc.finalizeRound(
+      {
+        accounts: {
+          fundingRound: fundingRound.publicKey,
+          matchingVault: matchingVaultPubkey,
+        },
+      }
+    );
+    try {
+      await finalizeRound();
+      assert.fail("finalize before round end should fail");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "RoundNotEnded");
+    }
+
+    console.log("waiting round end for 9 seconds");
+    await new Promise((resolve) => setTimeout(resolve, 9000));
+    await finalizeRound();
+
+    onchainFundingRound = await program.account.fundingRound.fetch(fundingRound.publicKey);
+    assert.ok(onchainFundingRound.isFinalized);
+    assert.equal(onchainFundingRound.matchingPoolAmount.toNumber(), matchingPoolAmount);
+    // the only campaign in the round takes the whole pool
+    assert.equal(onchainFundingRound.campaigns[0].matchAmount.toNumber(), matchingPoolAmount);
+
+    const [holdingWalletOwnerPubkey] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(HOLDING_PREFIX),
+        donationData.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    const balanceBefore = await connection.getTokenAccountBalance(donationHoldingWallet.address);
+    await program.rpc.distributeMatch(
+      {
+        accounts: {
+          fundingRound: fundingRound.publicKey,
+          donationData: donationData.publicKey,
+          donationProtocol: donationProtocolData.publicKey,
+          creatorData: creatorDataPubkey,
+          holdingWallet: donationHoldingWallet.address,
+          holdingWalletOwner: holdingWalletOwnerPubkey,
+          recipient: creatorDonationTokenAccount.address,
+          matchingVault: matchingVaultPubkey,
+          matchingVaultOwner: matchingVaultOwnerPubkey,
+          feeVault: null,
+          payoutSplit: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+      }
+    );
+    const balanceAfter = await connection.getTokenAccountBalance(donationHoldingWallet.address);
+    assert.equal(
+      new anchor.BN(balanceAfter.value.amount).sub(new anchor.BN(balanceBefore.value.amount)).toNumber(),
+      matchingPoolAmount,
+    );
+    const onchainDonationData = await program.account.donationData.fetch(donationData.publicKey);
+    assert.equal(onchainDonationData.totalAmountMatched.toNumber(), matchingPoolAmount);
+  });
+});
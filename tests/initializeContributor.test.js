@@ -112,5 +112,6 @@ describe("donaproto", () => {
     assert.equal(onchainContributorData.totalAmountEarned, 0);
     assert.equal(onchainContributorData.donationsCount, 0);
     assert.equal(onchainContributorData.bump, contributorDataBump);
+    assert.equal(onchainContributorData.version, 1);
   });
 });
@@ -128,5 +128,6 @@ describe("donaproto", () => {
     assert.equal(onchainCreatorData.donationsClosedCount, 0);
     assert.equal(onchainCreatorData.totalAmountReceived, 0);
     assert.equal(onchainCreatorData.totalAmountCollecting, 0);
+    assert.equal(onchainCreatorData.version, 1);
   });
 });
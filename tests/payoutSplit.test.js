@@ -0,0 +1,424 @@
+const anchor = require("@coral-xyz/anchor");
+const {
+  createMint,
+  mintTo,
+  getOrCreateAssociatedTokenAccount,
+  TOKEN_PROGRAM_ID,
+} = require('@solana/spl-token');
+const os = require('os');
+const assert = require('assert');
+const { getNowTs, rechargeWallet, findMintFeeAddress, findDonationReceiptAddress, decodeIpfsHash } = require('./common/utils');
+const {
+  CONTRIBUTOR_PREFIX,
+  TREASURY_PREFIX,
+  CREATOR_PREFIX,
+  HOLDING_PREFIX,
+  PAYOUT_SPLIT_PREFIX,
+} = require("./common/seeds");
+
+
+describe("donaproto", () => {
+  const homedir = os.homedir();
+  process.env.ANCHOR_WALLET = `${homedir}/.config/solana/id.json`;
+  const program = anchor.workspace.Donaproto;
+  const provider = anchor.AnchorProvider.local();
+  anchor.setProvider(provider);
+  const connection = provider.connection;
+  const { payer } = program.provider.wallet
+  const donationMintAuthority = payer;
+  const donationMintDecimals = 6;
+  let donationMintPubKey, rewardsMintPubKey
+  const rewardMintAuthority = payer;
+  const rewardMintDecimals = 9;
+  const donationProtocolData = anchor.web3.Keypair.generate();
+  const minAmountToEarn = new anchor.BN(1000);
+  const creatorWallet = anchor.web3.Keypair.generate();
+  let creatorDataPubkey, creatorDataBump;
+  let creatorDonationTokenAccount;
+  const donationData = anchor.web3.Keypair.generate();
+  const contributorWallet = anchor.web3.Keypair.generate();
+  let treasuryTokenAccount, treasuryOwnerPubkey, treasuryOwnerBump;
+  let donationHoldingWallet;
+  let holdingWalletOwnerPubkey, holdingWalletOwnerBump;
+  const donationAmount = new anchor.BN(1000000000); // 1000$
+  const [contributorDataPubkey, contributorDataBump] = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(CONTRIBUTOR_PREFIX),
+      donationProtocolData.publicKey.toBuffer(),
+      contributorWallet.publicKey.toBuffer(),
+    ],
+    program.programId,
+  );
+  let contributorDonationTokenAccount, contributorRewardTokenAccount;
+  const minAmountToCollect = new anchor.BN(1_000_000);
+
+  before(async () => {
+    donationMintPubKey = await createMint(
+      provider.connection,
+      payer,
+      donationMintAuthority.publicKey,
+      null,
+      donationMintDecimals
+    );
+
+    rewardsMintPubKey = await createMint(
+      connection,
+      payer,
+      rewardMintAuthority.publicKey,
+      null,
+      rewardMintDecimals
+    )
+
+    const [treasuryOwnerPubkeyFound, treasuryOwnerBumpFound] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(TREASURY_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    treasuryOwnerPubkey = treasuryOwnerPubkeyFound;
+    treasuryOwnerBump = treasuryOwnerBumpFound;
+
+    treasuryTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      treasuryOwnerPubkey,
+      allowOwnerOffCurve = true
+    )
+
+    await program.rpc.initializeDonationProtocol(
+      minAmountToEarn,
+      minAmountToCollect,
+      treasuryOwnerBump,
+      {
+        accounts: {
+          donationProtocolData: donationProtocolData.publicKey,
+          treasury: treasuryTokenAccount.address,
+          treasuryOwner: treasuryOwnerPubkey,
+          treasuryMint: rewardsMintPubKey,
+          donationMint: donationMintPubKey,
+          payer: payer.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        signers: [donationProtocolData],
+      }
+    );
+
+    creatorDonationTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      creatorWallet.publicKey
+    )
+
+    await mintTo(
+      connection,
+      payer,
+      donationMintPubKey,
+      creatorDonationTokenAccount.address,
+      donationMintAuthority,
+      10000000000
+    )
+
+    const [creatorDataPubkeyFound, creatorDataBumpFound] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(CREATOR_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+        creatorWallet.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    creatorDataPubkey = creatorDataPubkeyFound;
+    creatorDataBump = creatorDataBumpFound;
+
+    await program.rpc.initializeCreator(
+      {
+        accounts: {
+          creatorData: creatorDataPubkey,
+          donationProtocol: donationProtocolData.publicKey,
+          creatorWalletAddress: creatorWallet.publicKey,
+          payer: payer.publicKey,
+          donationMint: donationMintPubKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      },
+    );
+
+    // check data
+    const onchainCreatorData = await program.account.creatorData.fetch(creatorDataPubkey);
+    assert.ok(onchainCreatorData.donationProtocol.equals(donationProtocolData.publicKey));
+    assert.equal(onchainCreatorData.donationsCreatedCount, 0);
+    assert.equal(onchainCreatorData.donationsClosedCount, 0);
+    assert.equal(onchainCreatorData.totalAmountReceived, 0);
+    assert.equal(onchainCreatorData.totalAmountCollecting, 0);
+
+    // top up creator wallet to be able to pay for createDonation tx
+    await rechargeWallet(connection, creatorWallet.publicKey, 1000000000);
+
+    const ipfsHash = "some_ipfs_hash";
+    const endingTimestamp = await getNowTs(provider) + 100000;
+    const [holdingWalletOwnerPubkeyFound, holdingWalletOwnerBumpFound] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(HOLDING_PREFIX),
+        donationData.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    holdingWalletOwnerPubkey = holdingWalletOwnerPubkeyFound;
+    holdingWalletOwnerBump = holdingWalletOwnerBumpFound;
+
+    donationHoldingWallet = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      holdingWalletOwnerPubkey,
+      true,
+    );
+
+    await program.rpc.createDonation(
+      donationAmount,
+      ipfsHash,
+      new anchor.BN(endingTimestamp),
+      holdingWalletOwnerBump,
+      { none: {} },
+      [],
+      false,
+      {
+        accounts: {
+          donationData: donationData.publicKey,
+          donationTiers: null,
+          donationProtocol: donationProtocolData.publicKey,
+          holdingWallet: donationHoldingWallet.address,
+          holdingWalletOwner: holdingWalletOwnerPubkey,
+          recipient: creatorDonationTokenAccount.address,
+          creatorData: creatorDataPubkey,
+          donationMint: donationMintPubKey,
+          creatorWalletAddress: creatorWallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          mintFee: findMintFeeAddress(program.programId, donationProtocolData.publicKey, donationMintPubKey),
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        signers: [donationData, creatorWallet],
+      }
+    );
+
+    const onchainDonationData = await program.account.donationData.fetch(donationData.publicKey);
+    assert.equal(onchainDonationData.amountCollecting.toString(), donationAmount.toString());
+    assert.equal(onchainDonationData.totalAmountReceived, 0);
+    assert.equal(onchainDonationData.endingTimestamp, endingTimestamp);
+    assert.equal(onchainDonationData.isClosed, false);
+    assert.deepEqual(onchainDonationData.recipient.toString(), creatorDonationTokenAccount.address.toString());
+    assert.deepEqual(onchainDonationData.donationProtocol, donationProtocolData.publicKey);
+    assert.deepEqual(onchainDonationData.holdingWallet, donationHoldingWallet.address);
+    assert.deepEqual(onchainDonationData.creatorData, creatorDataPubkey);
+    assert.equal(onchainDonationData.holdingBump, holdingWalletOwnerBump);
+    assert.equal(decodeIpfsHash(onchainDonationData.ipfsHash), ipfsHash);
+
+    await rechargeWallet(connection, contributorWallet.publicKey, 1000000000);
+    await program.rpc.initializeContributor(
+      contributorDataBump,
+      {
+        accounts: {
+          contributorData: contributorDataPubkey,
+          donationProtocol: donationProtocolData.publicKey,
+          contributorWalletAddress: contributorWallet.publicKey,
+          payer: payer.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      },
+    );
+
+    contributorDonationTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      contributorWallet.publicKey
+    )
+    await mintTo(
+      connection,
+      payer,
+      donationMintPubKey,
+      contributorDonationTokenAccount.address,
+      donationMintAuthority,
+      10000000000, // 10000$
+    )
+
+    contributorRewardTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      contributorWallet.publicKey
+    )
+  });
+
+  it("withdraws funds split between several recipients", async () => {
+    const [payoutSplitPubkey, payoutSplitBump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(PAYOUT_SPLIT_PREFIX),
+        donationData.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    const recipientWallets = [
+      anchor.web3.Keypair.generate(),
+      anchor.web3.Keypair.generate(),
+      anchor.web3.Keypair.generate(),
+    ];
+    const recipientTokenAccounts = [];
+    for (const recipientWallet of recipientWallets) {
+      const recipientTokenAccount = await getOrCreateAssociatedTokenAccount(
+        connection,
+        payer,
+        donationMintPubKey,
+        recipientWallet.publicKey
+      )
+      recipientTokenAccounts.push(recipientTokenAccount.address);
+    }
+    const remainingAccounts = recipientTokenAccounts.map((pubkey) => ({
+      pubkey,
+      isWritable: true,
+      isSigner: false,
+    }));
+
+    // shares have to sum up to 10000 bps
+    try {
+      await program.rpc.createPayoutSplit(
+        [5000, 3000, 1000],
+        {
+          accounts: {
+            payoutSplit: payoutSplitPubkey,
+            donationData: donationData.publicKey,
+            creatorData: creatorDataPubkey,
+            creatorWalletAddress: creatorWallet.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          },
+          remainingAccounts,
+          signers: [creatorWallet],
+        }
+      );
+      assert.fail("shares not summing up to 10000 bps should fail");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "InvalidPayoutSplit");
+    }
+
+    const tx = await program.rpc.createPayoutSplit(
+      [5000, 3000, 2000],
+      {
+        accounts: {
+          payoutSplit: payoutSplitPubkey,
+          donationData: donationData.publicKey,
+          creatorData: creatorDataPubkey,
+          creatorWalletAddress: creatorWallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        remainingAccounts,
+        signers: [creatorWallet],
+      }
+    );
+    console.log("Your transaction signature", tx);
+
+    let onchainPayoutSplit = await program.account.payoutSplit.fetch(payoutSplitPubkey);
+    assert.ok(onchainPayoutSplit.donationData.equals(donationData.publicKey));
+    assert.equal(onchainPayoutSplit.recipients.length, 3);
+    assert.equal(onchainPayoutSplit.bump, payoutSplitBump);
+    let onchainDonationData = await program.account.donationData.fetch(donationData.publicKey);
+    assert.ok(onchainDonationData.payoutSplit.equals(payoutSplitPubkey));
+
+    // one token above the goal leaves rounding dust for the first recipient
+    const amount = donationAmount.addn(1);
+    await program.rpc.donate(
+      amount,
+      {
+        accounts: {
+          donationData: donationData.publicKey,
+          contributorData: contributorDataPubkey,
+          donationReceipt: findDonationReceiptAddress(program.programId, donationData.publicKey, contributorWallet.publicKey),
+          fundingRound: null,
+          matchPledge: null,
+          matchPledgeEscrow: null,
+          donationTiers: null,
+          donationProtocol: donationProtocolData.publicKey,
+          userTokenWallet: contributorDonationTokenAccount.address,
+          userRewardTokenWallet: contributorRewardTokenAccount.address,
+          rewardTreasury: treasuryTokenAccount.address,
+          rewardTreasuryOwner: treasuryOwnerPubkey,
+          rewardVesting: null,
+          recipient: creatorDonationTokenAccount.address,
+          holdingWallet: donationHoldingWallet.address,
+          donationMint: donationMintPubKey,
+          rewardMint: rewardsMintPubKey,
+          receiptMint: null,
+          receiptTokenAccount: null,
+          receiptMetadata: null,
+          receiptMintAuthority: null,
+          userWallet: contributorWallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          associatedTokenProgram: null,
+          memoProgram: null,
+        },
+        signers: [contributorWallet],
+      }
+    );
+
+    // payout split is required once it is set
+    try {
+      await program.rpc.withdrawFunds({
+        accounts: {
+          donationData: donationData.publicKey,
+          creatorData: creatorDataPubkey,
+          donationProtocol: donationProtocolData.publicKey,
+          holdingWallet: donationHoldingWallet.address,
+          holdingWalletOwner: holdingWalletOwnerPubkey,
+          recipientTokenWallet: creatorDonationTokenAccount.address,
+          donationMint: donationMintPubKey,
+          payer: creatorWallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          payoutSplit: null,
+          feeVault: null,
+        },
+        signers: [creatorWallet],
+      });
+      assert.fail("withdrawal without payout split should fail");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "InvalidPayoutSplit");
+    }
+
+    await program.rpc.withdrawFunds({
+      accounts: {
+        donationData: donationData.publicKey,
+        creatorData: creatorDataPubkey,
+        donationProtocol: donationProtocolData.publicKey,
+        holdingWallet: donationHoldingWallet.address,
+        holdingWalletOwner: holdingWalletOwnerPubkey,
+        recipientTokenWallet: creatorDonationTokenAccount.address,
+        donationMint: donationMintPubKey,
+        payer: creatorWallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        payoutSplit: payoutSplitPubkey,
+        feeVault: null,
+      },
+      remainingAccounts,
+      signers: [creatorWallet],
+    });
+
+    const expectedAmounts = ["500000001", "300000000", "200000000"];
+    for (let i = 0; i < recipientTokenAccounts.length; i++) {
+      const balance = await connection.getTokenAccountBalance(recipientTokenAccounts[i]);
+      assert.equal(balance.value.amount, expectedAmounts[i]);
+    }
+    onchainPayoutSplit = await program.account.payoutSplit.fetch(payoutSplitPubkey);
+    assert.deepEqual(
+      onchainPayoutSplit.recipients.map((recipient) => recipient.totalAmountReceived.toString()),
+      expectedAmounts,
+    );
+    onchainDonationData = await program.account.donationData.fetch(donationData.publicKey);
+    assert.equal(onchainDonationData.isClosed, true);
+  });
+});
@@ -0,0 +1,466 @@
+const anchor = require("@coral-xyz/anchor");
+const {
+  createMint,
+  mintTo,
+  getOrCreateAssociatedTokenAccount,
+  TOKEN_PROGRAM_ID,
+} = require('@solana/spl-token');
+const os = require('os');
+const assert = require('assert');
+const { getNowTs, rechargeWallet, findMintFeeAddress, findDonationReceiptAddress, decodeIpfsHash } = require('./common/utils');
+const {
+  CONTRIBUTOR_PREFIX,
+  TREASURY_PREFIX,
+  CREATOR_PREFIX,
+  HOLDING_PREFIX,
+  FEE_VAULT_PREFIX,
+} = require("./common/seeds");
+
+
+describe("donaproto", () => {
+  const homedir = os.homedir();
+  process.env.ANCHOR_WALLET = `${homedir}/.config/solana/id.json`;
+  const program = anchor.workspace.Donaproto;
+  const provider = anchor.AnchorProvider.local();
+  anchor.setProvider(provider);
+  const connection = provider.connection;
+  const { payer } = program.provider.wallet
+  const donationMintAuthority = payer;
+  const donationMintDecimals = 6;
+  let donationMintPubKey, rewardsMintPubKey
+  const rewardMintAuthority = payer;
+  const rewardMintDecimals = 9;
+  const donationProtocolData = anchor.web3.Keypair.generate();
+  const minAmountToEarn = new anchor.BN(1000);
+  const creatorWallet = anchor.web3.Keypair.generate();
+  let creatorDataPubkey, creatorDataBump;
+  let creatorDonationTokenAccount;
+  const donationData = anchor.web3.Keypair.generate();
+  const contributorWallet = anchor.web3.Keypair.generate();
+  let treasuryTokenAccount, treasuryOwnerPubkey, treasuryOwnerBump;
+  let donationHoldingWallet;
+  let holdingWalletOwnerPubkey, holdingWalletOwnerBump;
+  const donationAmount = new anchor.BN(1000000000); // 1000$
+  const [contributorDataPubkey, contributorDataBump] = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(CONTRIBUTOR_PREFIX),
+      donationProtocolData.publicKey.toBuffer(),
+      contributorWallet.publicKey.toBuffer(),
+    ],
+    program.programId,
+  );
+  let contributorDonationTokenAccount, contributorRewardTokenAccount;
+  const minAmountToCollect = new anchor.BN(1_000_000);
+
+  before(async () => {
+    donationMintPubKey = await createMint(
+      provider.connection,
+      payer,
+      donationMintAuthority.publicKey,
+      null,
+      donationMintDecimals
+    );
+
+    rewardsMintPubKey = await createMint(
+      connection,
+      payer,
+      rewardMintAuthority.publicKey,
+      null,
+      rewardMintDecimals
+    )
+
+    const [treasuryOwnerPubkeyFound, treasuryOwnerBumpFound] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(TREASURY_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    treasuryOwnerPubkey = treasuryOwnerPubkeyFound;
+    treasuryOwnerBump = treasuryOwnerBumpFound;
+
+    treasuryTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      treasuryOwnerPubkey,
+      allowOwnerOffCurve = true
+    )
+
+    await program.rpc.initializeDonationProtocol(
+      minAmountToEarn,
+      minAmountToCollect,
+      treasuryOwnerBump,
+      {
+        accounts: {
+          donationProtocolData: donationProtocolData.publicKey,
+          treasury: treasuryTokenAccount.address,
+          treasuryOwner: treasuryOwnerPubkey,
+          treasuryMint: rewardsMintPubKey,
+          donationMint: donationMintPubKey,
+          payer: payer.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        signers: [donationProtocolData],
+      }
+    );
+
+    creatorDonationTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      creatorWallet.publicKey
+    )
+
+    await mintTo(
+      connection,
+      payer,
+      donationMintPubKey,
+      creatorDonationTokenAccount.address,
+      donationMintAuthority,
+      10000000000
+    )
+
+    const [creatorDataPubkeyFound, creatorDataBumpFound] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(CREATOR_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+        creatorWallet.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    creatorDataPubkey = creatorDataPubkeyFound;
+    creatorDataBump = creatorDataBumpFound;
+
+    await program.rpc.initializeCreator(
+      {
+        accounts: {
+          creatorData: creatorDataPubkey,
+          donationProtocol: donationProtocolData.publicKey,
+          creatorWalletAddress: creatorWallet.publicKey,
+          payer: payer.publicKey,
+          donationMint: donationMintPubKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      },
+    );
+
+    // check data
+    const onchainCreatorData = await program.account.creatorData.fetch(creatorDataPubkey);
+    assert.ok(onchainCreatorData.donationProtocol.equals(donationProtocolData.publicKey));
+    assert.equal(onchainCreatorData.donationsCreatedCount, 0);
+    assert.equal(onchainCreatorData.donationsClosedCount, 0);
+    assert.equal(onchainCreatorData.totalAmountReceived, 0);
+    assert.equal(onchainCreatorData.totalAmountCollecting, 0);
+
+    // top up creator wallet to be able to pay for createDonation tx
+    await rechargeWallet(connection, creatorWallet.publicKey, 1000000000);
+
+    const ipfsHash = "some_ipfs_hash";
+    const endingTimestamp = await getNowTs(provider) + 100000;
+    const [holdingWalletOwnerPubkeyFound, holdingWalletOwnerBumpFound] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(HOLDING_PREFIX),
+        donationData.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    holdingWalletOwnerPubkey = holdingWalletOwnerPubkeyFound;
+    holdingWalletOwnerBump = holdingWalletOwnerBumpFound;
+
+    donationHoldingWallet = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      holdingWalletOwnerPubkey,
+      true,
+    );
+
+    await program.rpc.createDonation(
+      donationAmount,
+      ipfsHash,
+      new anchor.BN(endingTimestamp),
+      holdingWalletOwnerBump,
+      { none: {} },
+      [],
+      false,
+      {
+        accounts: {
+          donationData: donationData.publicKey,
+          donationTiers: null,
+          donationProtocol: donationProtocolData.publicKey,
+          holdingWallet: donationHoldingWallet.address,
+          holdingWalletOwner: holdingWalletOwnerPubkey,
+          recipient: creatorDonationTokenAccount.address,
+          creatorData: creatorDataPubkey,
+          donationMint: donationMintPubKey,
+          creatorWalletAddress: creatorWallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          mintFee: findMintFeeAddress(program.programId, donationProtocolData.publicKey, donationMintPubKey),
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        signers: [donationData, creatorWallet],
+      }
+    );
+
+    const onchainDonationData = await program.account.donationData.fetch(donationData.publicKey);
+    assert.equal(onchainDonationData.amountCollecting.toString(), donationAmount.toString());
+    assert.equal(onchainDonationData.totalAmountReceived, 0);
+    assert.equal(onchainDonationData.endingTimestamp, endingTimestamp);
+    assert.equal(onchainDonationData.isClosed, false);
+    assert.deepEqual(onchainDonationData.recipient.toString(), creatorDonationTokenAccount.address.toString());
+    assert.deepEqual(onchainDonationData.donationProtocol, donationProtocolData.publicKey);
+    assert.deepEqual(onchainDonationData.holdingWallet, donationHoldingWallet.address);
+    assert.deepEqual(onchainDonationData.creatorData, creatorDataPubkey);
+    assert.equal(onchainDonationData.holdingBump, holdingWalletOwnerBump);
+    assert.equal(decodeIpfsHash(onchainDonationData.ipfsHash), ipfsHash);
+
+    await rechargeWallet(connection, contributorWallet.publicKey, 1000000000);
+    await program.rpc.initializeContributor(
+      contributorDataBump,
+      {
+        accounts: {
+          contributorData: contributorDataPubkey,
+          donationProtocol: donationProtocolData.publicKey,
+          contributorWalletAddress: contributorWallet.publicKey,
+          payer: payer.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      },
+    );
+
+    contributorDonationTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      contributorWallet.publicKey
+    )
+    await mintTo(
+      connection,
+      payer,
+      donationMintPubKey,
+      contributorDonationTokenAccount.address,
+      donationMintAuthority,
+      10000000000, // 10000$
+    )
+
+    contributorRewardTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      contributorWallet.publicKey
+    )
+  });
+
+  it("locks fee rate at donation creation and skims it into fee vault on withdrawal", async () => {
+    const createDonation = async (donationData) => {
+      const [holdingWalletOwnerPubkey, holdingWalletOwnerBump] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from(HOLDING_PREFIX),
+          donationData.publicKey.toBuffer(),
+        ],
+        program.programId,
+      );
+      const holdingWallet = await getOrCreateAssociatedTokenAccount(
+        connection,
+        payer,
+        donationMintPubKey,
+        holdingWalletOwnerPubkey,
+        true,
+      );
+      const endingTimestamp = await getNowTs(provider) + 100_000;
+      await program.rpc.createDonation(
+        donationAmount,
+        "some_ipfs_hash",
+        new anchor.BN(endingTimestamp),
+        holdingWalletOwnerBump,
+        { none: {} },
+        [],
+        false,
+        {
+          accounts: {
+            donationData: donationData.publicKey,
+            donationTiers: null,
+            donationProtocol: donationProtocolData.publicKey,
+            holdingWallet: holdingWallet.address,
+            holdingWalletOwner: holdingWalletOwnerPubkey,
+            recipient: creatorDonationTokenAccount.address,
+            creatorData: creatorDataPubkey,
+            donationMint: donationMintPubKey,
+            creatorWalletAddress: creatorWallet.publicKey,
+            mintFee: findMintFeeAddress(program.programId, donationProtocolData.publicKey, donationMintPubKey),
+            systemProgram: anchor.web3.SystemProgram.programId,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          },
+          signers: [donationData, creatorWallet],
+        }
+      );
+
+      return { holdingWallet: holdingWallet.address, holdingWalletOwnerPubkey };
+    };
+    const setProtocolFee = (feeBps) => program.rpc.setProtocolFee(
+      feeBps,
+      {
+        accounts: {
+          donationProtocol: donationProtocolData.publicKey,
+          authority: payer.publicKey,
+        },
+      }
+    );
+
+    await setProtocolFee(500);
+    const feeDonationData = anchor.web3.Keypair.generate();
+    const { holdingWallet, holdingWalletOwnerPubkey } = await createDonation(feeDonationData);
+    let onchainDonationData = await program.account.donationData.fetch(feeDonationData.publicKey);
+    assert.equal(onchainDonationData.feeBps, 500);
+
+    // later changes don't affect existing donations
+    await setProtocolFee(1000);
+    onchainDonationData = await program.account.donationData.fetch(feeDonationData.publicKey);
+    assert.equal(onchainDonationData.feeBps, 500);
+
+    // mint override takes precedence over protocol fee
+    const mintFeePubkey = findMintFeeAddress(program.programId, donationProtocolData.publicKey, donationMintPubKey);
+    await program.rpc.setMintFee(
+      100,
+      {
+        accounts: {
+          mintFee: mintFeePubkey,
+          donationProtocol: donationProtocolData.publicKey,
+          mint: donationMintPubKey,
+          authorizedClmmPool: null,
+          authority: payer.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      }
+    );
+    const overriddenDonationData = anchor.web3.Keypair.generate();
+    await createDonation(overriddenDonationData);
+    onchainDonationData = await program.account.donationData.fetch(overriddenDonationData.publicKey);
+    assert.equal(onchainDonationData.feeBps, 100);
+
+    const [feeVaultPubkey] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(FEE_VAULT_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+        donationMintPubKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    const [feeVaultOwnerPubkey] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(FEE_VAULT_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    await program.rpc.initializeFeeVault(
+      {
+        accounts: {
+          feeVault: feeVaultPubkey,
+          feeVaultOwner: feeVaultOwnerPubkey,
+          donationProtocol: donationProtocolData.publicKey,
+          mint: donationMintPubKey,
+          payer: payer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      }
+    );
+
+    await program.rpc.donate(
+      donationAmount,
+      {
+        accounts: {
+          donationData: feeDonationData.publicKey,
+          contributorData: contributorDataPubkey,
+          donationReceipt: findDonationReceiptAddress(program.programId, feeDonationData.publicKey, contributorWallet.publicKey),
+          fundingRound: null,
+          matchPledge: null,
+          matchPledgeEscrow: null,
+          donationTiers: null,
+          donationProtocol: donationProtocolData.publicKey,
+          userTokenWallet: contributorDonationTokenAccount.address,
+          userRewardTokenWallet: contributorRewardTokenAccount.address,
+          rewardTreasury: treasuryTokenAccount.address,
+          rewardTreasuryOwner: treasuryOwnerPubkey,
+          rewardVesting: null,
+          recipient: creatorDonationTokenAccount.address,
+          holdingWallet,
+          donationMint: donationMintPubKey,
+          rewardMint: rewardsMintPubKey,
+          receiptMint: null,
+          receiptTokenAccount: null,
+          receiptMetadata: null,
+          receiptMintAuthority: null,
+          userWallet: contributorWallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          associatedTokenProgram: null,
+          memoProgram: null,
+        },
+        signers: [contributorWallet],
+      }
+    );
+
+    const recipientBalanceBefore = await connection.getTokenAccountBalance(creatorDonationTokenAccount.address);
+    await program.rpc.withdrawFunds({
+      accounts: {
+        donationData: feeDonationData.publicKey,
+        creatorData: creatorDataPubkey,
+        donationProtocol: donationProtocolData.publicKey,
+        holdingWallet,
+        holdingWalletOwner: holdingWalletOwnerPubkey,
+        recipientTokenWallet: creatorDonationTokenAccount.address,
+        donationMint: donationMintPubKey,
+        feeVault: feeVaultPubkey,
+        payoutSplit: null,
+        payer: creatorWallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      },
+      signers: [creatorWallet],
+    });
+
+    const feeAmount = donationAmount.muln(500).divn(10_000);
+    const feeVaultBalance = await connection.getTokenAccountBalance(feeVaultPubkey);
+    assert.equal(feeVaultBalance.value.amount, feeAmount.toString());
+    const recipientBalanceAfter = await connection.getTokenAccountBalance(creatorDonationTokenAccount.address);
+    assert.equal(
+      new anchor.BN(recipientBalanceAfter.value.amount).sub(new anchor.BN(recipientBalanceBefore.value.amount)).toString(),
+      donationAmount.sub(feeAmount).toString(),
+    );
+    onchainDonationData = await program.account.donationData.fetch(feeDonationData.publicKey);
+    assert.equal(onchainDonationData.feeAmount.toString(), feeAmount.toString());
+
+    const authorityTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      payer.publicKey
+    )
+    const authorityBalanceBefore = await connection.getTokenAccountBalance(authorityTokenAccount.address);
+    await program.rpc.collectFees(
+      feeAmount,
+      {
+        accounts: {
+          feeVault: feeVaultPubkey,
+          feeVaultOwner: feeVaultOwnerPubkey,
+          destination: authorityTokenAccount.address,
+          donationProtocol: donationProtocolData.publicKey,
+          authority: payer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+      }
+    );
+    const authorityBalanceAfter = await connection.getTokenAccountBalance(authorityTokenAccount.address);
+    assert.equal(
+      new anchor.BN(authorityBalanceAfter.value.amount).sub(new anchor.BN(authorityBalanceBefore.value.amount)).toString(),
+      feeAmount.toString(),
+    );
+  });
+});
@@ -0,0 +1,354 @@
+const anchor = require("@coral-xyz/anchor");
+const {
+  createMint,
+  mintTo,
+  getOrCreateAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  getMint,
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+} = require('@solana/spl-token');
+const os = require('os');
+const assert = require('assert');
+const { getNowTs, rechargeWallet, findMintFeeAddress, findDonationReceiptAddress } = require('./common/utils');
+const {
+  TREASURY_PREFIX,
+  CREATOR_PREFIX,
+  HOLDING_PREFIX,
+  CONTRIBUTOR_PREFIX,
+  RECEIPT_METADATA_PREFIX,
+  RECEIPT_MINT_AUTHORITY_PREFIX,
+} = require("./common/seeds");
+
+
+describe("donaproto", () => {
+  const homedir = os.homedir();
+  process.env.ANCHOR_WALLET = `${homedir}/.config/solana/id.json`;
+  const program = anchor.workspace.Donaproto;
+  const memoProgramId = new anchor.web3.PublicKey("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+  const provider = anchor.AnchorProvider.local();
+  anchor.setProvider(provider);
+  const connection = provider.connection;
+  const { payer } = program.provider.wallet
+  const donationMintAuthority = payer;
+  const donationMintDecimals = 6;
+  let donationMintPubKey, rewardsMintPubKey
+  const rewardMintAuthority = payer;
+  const rewardMintDecimals = 9;
+  const donationProtocolData = anchor.web3.Keypair.generate();
+  const minAmountToEarn = new anchor.BN(1000);
+  const creatorWallet = anchor.web3.Keypair.generate();
+  let creatorDataPubkey, creatorDataBump;
+  let creatorDonationTokenAccount;
+  const donationData = anchor.web3.Keypair.generate();
+  const contributorWallet = anchor.web3.Keypair.generate();
+  let treasuryTokenAccount, treasuryOwnerPubkey, treasuryOwnerBump;
+  let donationHoldingWallet;
+  const minAmountToCollect = new anchor.BN(1_000_000);
+
+  before(async () => {
+    donationMintPubKey = await createMint(
+      provider.connection,
+      payer,
+      donationMintAuthority.publicKey,
+      null,
+      donationMintDecimals
+    );
+
+    rewardsMintPubKey = await createMint(
+      connection,
+      payer,
+      rewardMintAuthority.publicKey,
+      null,
+      rewardMintDecimals
+    )
+
+    const [treasuryOwnerPubkeyFound, treasuryOwnerBumpFound] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(TREASURY_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    treasuryOwnerPubkey = treasuryOwnerPubkeyFound;
+    treasuryOwnerBump = treasuryOwnerBumpFound;
+
+    treasuryTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      treasuryOwnerPubkey,
+      allowOwnerOffCurve = true
+    )
+
+    await program.rpc.initializeDonationProtocol(
+      minAmountToEarn,
+      minAmountToCollect,
+      treasuryOwnerBump,
+      {
+        accounts: {
+          donationProtocolData: donationProtocolData.publicKey,
+          treasury: treasuryTokenAccount.address,
+          treasuryOwner: treasuryOwnerPubkey,
+          treasuryMint: rewardsMintPubKey,
+          donationMint: donationMintPubKey,
+          payer: payer.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        signers: [donationProtocolData],
+      }
+    );
+
+    creatorDonationTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      creatorWallet.publicKey
+    )
+
+    await mintTo(
+      connection,
+      payer,
+      donationMintPubKey,
+      creatorDonationTokenAccount.address,
+      donationMintAuthority,
+      10_000_000_000
+    )
+
+    const [creatorDataPubkeyFound, creatorDataBumpFound] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(CREATOR_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+        creatorWallet.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    creatorDataPubkey = creatorDataPubkeyFound;
+    creatorDataBump = creatorDataBumpFound;
+
+    await program.rpc.initializeCreator(
+      {
+        accounts: {
+          creatorData: creatorDataPubkey,
+          donationProtocol: donationProtocolData.publicKey,
+          creatorWalletAddress: creatorWallet.publicKey,
+          payer: payer.publicKey,
+          donationMint: donationMintPubKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+      },
+    );
+
+    // check data
+    const onchainCreatorData = await program.account.creatorData.fetch(creatorDataPubkey);
+    assert.ok(onchainCreatorData.donationProtocol.equals(donationProtocolData.publicKey));
+    assert.equal(onchainCreatorData.donationsCreatedCount, 0);
+    assert.equal(onchainCreatorData.donationsClosedCount, 0);
+    assert.equal(onchainCreatorData.totalAmountReceived, 0);
+    assert.equal(onchainCreatorData.totalAmountCollecting, 0);
+
+    // top up creator wallet to be able to pay for createDonation tx
+    await rechargeWallet(connection, creatorWallet.publicKey, 10_00_000_000);
+    const creatorWalletBalance = await connection.getBalance(creatorWallet.publicKey);
+
+    const amount = new anchor.BN(1_000_000_000); // 1000$
+    const ipfsHash = "some_ipfs_hash";
+    const endingTimestamp = await getNowTs(provider) + 100_000;
+    const [holdingWalletOwnerPubkey, holdingWalletOwnerBump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(HOLDING_PREFIX),
+        donationData.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+
+    donationHoldingWallet = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      holdingWalletOwnerPubkey,
+      true,
+    );
+
+    const tx = await program.rpc.createDonation(
+      amount,
+      ipfsHash,
+      new anchor.BN(endingTimestamp),
+      holdingWalletOwnerBump,
+      { none: {} },
+      [],
+      false,
+      {
+        accounts: {
+          donationData: donationData.publicKey,
+          donationTiers: null,
+          donationProtocol: donationProtocolData.publicKey,
+          holdingWallet: donationHoldingWallet.address,
+          holdingWalletOwner: holdingWalletOwnerPubkey,
+          recipient: creatorDonationTokenAccount.address,
+          creatorData: creatorDataPubkey,
+          donationMint: donationMintPubKey,
+          creatorWalletAddress: creatorWallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          mintFee: findMintFeeAddress(program.programId, donationProtocolData.publicKey, donationMintPubKey),
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        signers: [donationData, creatorWallet],
+      }
+    );
+    console.log("Your transaction signature", tx);
+
+    const onchainDonationData = await program.account.donationData.fetch(donationData.publicKey);
+    assert.equal(onchainDonationData.amountCollecting.toString(), amount.toString());
+    assert.equal(onchainDonationData.totalAmountReceived, 0);
+    assert.equal(onchainDonationData.endingTimestamp, endingTimestamp);
+    assert.equal(onchainDonationData.isClosed, false);
+  });
+
+  it("issues a receipt token with the donation it covers", async () => {
+    await rechargeWallet(connection, contributorWallet.publicKey, 1_000_000_000);
+    const [contributorDataPubkey] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(CONTRIBUTOR_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+        contributorWallet.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    const [receiptMintAuthorityPubkey] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(RECEIPT_MINT_AUTHORITY_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    const donationReceiptPubkey = findDonationReceiptAddress(program.programId, donationData.publicKey, contributorWallet.publicKey);
+    const contributorDonationTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      donationMintPubKey,
+      contributorWallet.publicKey
+    )
+    await mintTo(
+      connection,
+      payer,
+      donationMintPubKey,
+      contributorDonationTokenAccount.address,
+      donationMintAuthority,
+      10_000_000,
+    )
+    const contributorRewardTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      contributorWallet.publicKey
+    )
+
+    // a receipt mint is a new keypair co-signing the donation
+    const donate = async (amount, receiptMint) => {
+      const receiptAccounts = {
+        receiptMint: null,
+        receiptTokenAccount: null,
+        receiptMetadata: null,
+        receiptMintAuthority: null,
+        associatedTokenProgram: null,
+        memoProgram: null,
+      };
+      if (receiptMint) {
+        const [receiptMetadataPubkey] = anchor.web3.PublicKey.findProgramAddressSync(
+          [
+            Buffer.from(RECEIPT_METADATA_PREFIX),
+            receiptMint.publicKey.toBuffer(),
+          ],
+          program.programId,
+        );
+        Object.assign(receiptAccounts, {
+          receiptMint: receiptMint.publicKey,
+          receiptTokenAccount: getAssociatedTokenAddressSync(receiptMint.publicKey, contributorWallet.publicKey),
+          receiptMetadata: receiptMetadataPubkey,
+          receiptMintAuthority: receiptMintAuthorityPubkey,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          memoProgram: memoProgramId,
+        });
+      }
+      return program.rpc.donate(
+        new anchor.BN(amount),
+        {
+          accounts: {
+            donationData: donationData.publicKey,
+            contributorData: contributorDataPubkey,
+            donationReceipt: donationReceiptPubkey,
+            fundingRound: null,
+            matchPledge: null,
+            matchPledgeEscrow: null,
+            donationTiers: null,
+            donationProtocol: donationProtocolData.publicKey,
+            userTokenWallet: contributorDonationTokenAccount.address,
+            userRewardTokenWallet: contributorRewardTokenAccount.address,
+            rewardTreasury: treasuryTokenAccount.address,
+            rewardTreasuryOwner: treasuryOwnerPubkey,
+            rewardVesting: null,
+            recipient: creatorDonationTokenAccount.address,
+            holdingWallet: donationHoldingWallet.address,
+            donationMint: donationMintPubKey,
+            rewardMint: rewardsMintPubKey,
+            userWallet: contributorWallet.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            ...receiptAccounts,
+          },
+          signers: receiptMint ? [contributorWallet, receiptMint] : [contributorWallet],
+        }
+      );
+    };
+
+    await donate(1_000_000);
+    const firstReceiptMint = anchor.web3.Keypair.generate();
+    const tx = await donate(2_000_000, firstReceiptMint);
+
+    const onchainReceiptMint = await getMint(connection, firstReceiptMint.publicKey);
+    assert.equal(onchainReceiptMint.supply.toString(), "1");
+    assert.equal(onchainReceiptMint.decimals, 0);
+    assert.equal(onchainReceiptMint.mintAuthority, null);
+    const receiptTokenAccountPubkey = getAssociatedTokenAddressSync(firstReceiptMint.publicKey, contributorWallet.publicKey);
+    const onchainReceiptTokenAccount = await getAccount(connection, receiptTokenAccountPubkey);
+    assert.equal(onchainReceiptTokenAccount.amount.toString(), "1");
+    const [firstReceiptMetadataPubkey] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(RECEIPT_METADATA_PREFIX),
+        firstReceiptMint.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    let onchainReceiptMetadata = await program.account.receiptMetadata.fetch(firstReceiptMetadataPubkey);
+    assert.equal(onchainReceiptMetadata.donationData.toString(), donationData.publicKey.toString());
+    assert.equal(onchainReceiptMetadata.donor.toString(), contributorWallet.publicKey.toString());
+    assert.equal(onchainReceiptMetadata.donationMint.toString(), donationMintPubKey.toString());
+    assert.equal(onchainReceiptMetadata.amount.toString(), "2000000");
+    assert.ok(onchainReceiptMetadata.timestamp.toNumber() > 0);
+    // wallets and explorers show the receipt fields from the memo of the donation
+    await connection.confirmTransaction(tx, "confirmed");
+    const confirmedTx = await connection.getTransaction(tx, { commitment: "confirmed" });
+    const memoLog = confirmedTx.meta.logMessages.find((log) => log.includes('"campaign"'));
+    assert.ok(memoLog.includes(donationData.publicKey.toString()));
+    assert.ok(memoLog.includes('"amount":2000000'));
+
+    const secondReceiptMint = anchor.web3.Keypair.generate();
+    await donate(500_000, secondReceiptMint);
+    const [secondReceiptMetadataPubkey] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(RECEIPT_METADATA_PREFIX),
+        secondReceiptMint.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    onchainReceiptMetadata = await program.account.receiptMetadata.fetch(secondReceiptMetadataPubkey);
+    assert.equal(onchainReceiptMetadata.amount.toString(), "500000");
+    const onchainDonationReceipt = await program.account.donationReceipt.fetch(donationReceiptPubkey);
+    assert.equal(onchainDonationReceipt.amountReceipted.toString(), "2500000");
+    assert.equal(onchainDonationReceipt.amountRefundable.toString(), "1000000");
+  });
+});
@@ -0,0 +1,202 @@
+const anchor = require("@coral-xyz/anchor");
+const {
+  createMint,
+  mintTo,
+  getOrCreateAssociatedTokenAccount,
+  createAccount,
+  TOKEN_PROGRAM_ID,
+} = require('@solana/spl-token');
+const os = require('os');
+const assert = require('assert');
+const { TREASURY_PREFIX } = require("./common/seeds");
+
+
+describe("donaproto", () => {
+  const homedir = os.homedir();
+  process.env.ANCHOR_WALLET = `${homedir}/.config/solana/id.json`;
+  const program = anchor.workspace.Donaproto;
+  const provider = anchor.AnchorProvider.local();
+  anchor.setProvider(provider);
+  const connection = provider.connection;
+  const { payer } = program.provider.wallet
+  const donationMintAuthority = payer;
+  const donationMintDecimals = 6;
+  let donationMintPubKey, rewardsMintPubKey
+  const rewardMintAuthority = payer;
+  const rewardMintDecimals = 9;
+  const donationProtocolData = anchor.web3.Keypair.generate();
+  const minAmountToEarn = new anchor.BN(1000);
+  let treasuryTokenAccount, treasuryOwnerPubkey;
+  const minAmountToCollect = new anchor.BN(1_000_000);
+
+  before(async () => {
+    donationMintPubKey = await createMint(
+      provider.connection,
+      payer,
+      donationMintAuthority.publicKey,
+      null,
+      donationMintDecimals
+    );
+
+    rewardsMintPubKey = await createMint(
+      connection,
+      payer,
+      rewardMintAuthority.publicKey,
+      null,
+      rewardMintDecimals
+    )
+
+    const [treasuryOwnerPubkeyFound, treasuryOwnerBump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(TREASURY_PREFIX),
+        donationProtocolData.publicKey.toBuffer(),
+      ],
+      program.programId,
+    );
+    treasuryOwnerPubkey = treasuryOwnerPubkeyFound;
+
+    treasuryTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      treasuryOwnerPubkey,
+      allowOwnerOffCurve = true
+    )
+
+    await program.rpc.initializeDonationProtocol(
+      minAmountToEarn,
+      minAmountToCollect,
+      treasuryOwnerBump,
+      {
+        accounts: {
+          donationProtocolData: donationProtocolData.publicKey,
+          treasury: treasuryTokenAccount.address,
+          treasuryOwner: treasuryOwnerPubkey,
+          treasuryMint: rewardsMintPubKey,
+          donationMint: donationMintPubKey,
+          payer: payer.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        signers: [donationProtocolData],
+      }
+    );
+  })
+
+  it("funds, withdraws and rotates reward treasury", async () => {
+    const waitForEvent = (eventName) => {
+      let listener;
+      const event = new Promise((resolve) => {
+        listener = program.addEventListener(eventName, resolve);
+      });
+      return event.then(async (data) => {
+        await program.removeEventListener(listener);
+        return data;
+      });
+    };
+
+    const authorityRewardTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      payer.publicKey
+    )
+    await mintTo(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      authorityRewardTokenAccount.address,
+      rewardMintAuthority,
+      10_000_000_000,
+    )
+
+    const fundedEvent = waitForEvent("TreasuryFunded");
+    await program.rpc.fundTreasury(
+      new anchor.BN(10_000_000_000),
+      {
+        accounts: {
+          treasury: treasuryTokenAccount.address,
+          sourceTokenWallet: authorityRewardTokenAccount.address,
+          donationProtocol: donationProtocolData.publicKey,
+          funder: payer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+      }
+    );
+    assert.equal((await fundedEvent).amount.toString(), "10000000000");
+    let treasuryBalance = await connection.getTokenAccountBalance(treasuryTokenAccount.address);
+    assert.equal(treasuryBalance.value.amount, "10000000000");
+
+    const withdrawnEvent = waitForEvent("TreasuryWithdrawn");
+    await program.rpc.withdrawTreasury(
+      new anchor.BN(4_000_000_000),
+      {
+        accounts: {
+          treasury: treasuryTokenAccount.address,
+          treasuryOwner: treasuryOwnerPubkey,
+          destination: authorityRewardTokenAccount.address,
+          donationProtocol: donationProtocolData.publicKey,
+          authority: payer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+      }
+    );
+    const withdrawn = await withdrawnEvent;
+    assert.ok(withdrawn.destination.equals(authorityRewardTokenAccount.address));
+    assert.equal(withdrawn.amount.toString(), "4000000000");
+    treasuryBalance = await connection.getTokenAccountBalance(treasuryTokenAccount.address);
+    assert.equal(treasuryBalance.value.amount, "6000000000");
+
+    // only protocol authority can withdraw
+    const stranger = anchor.web3.Keypair.generate();
+    try {
+      await program.rpc.withdrawTreasury(
+        new anchor.BN(1),
+        {
+          accounts: {
+            treasury: treasuryTokenAccount.address,
+            treasuryOwner: treasuryOwnerPubkey,
+            destination: authorityRewardTokenAccount.address,
+            donationProtocol: donationProtocolData.publicKey,
+            authority: stranger.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          },
+          signers: [stranger],
+        }
+      );
+      assert.fail("withdrawal by non authority should fail");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "ConstraintRaw");
+    }
+
+    const newTreasury = await createAccount(
+      connection,
+      payer,
+      rewardsMintPubKey,
+      treasuryOwnerPubkey,
+      anchor.web3.Keypair.generate(),
+    );
+    const rotatedEvent = waitForEvent("TreasuryRotated");
+    await program.rpc.rotateTreasury(
+      {
+        accounts: {
+          treasury: treasuryTokenAccount.address,
+          newTreasury,
+          treasuryOwner: treasuryOwnerPubkey,
+          donationProtocol: donationProtocolData.publicKey,
+          authority: payer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+      }
+    );
+    const rotated = await rotatedEvent;
+    assert.ok(rotated.oldTreasury.equals(treasuryTokenAccount.address));
+    assert.ok(rotated.newTreasury.equals(newTreasury));
+    assert.equal(rotated.amount.toString(), "6000000000");
+
+    const newTreasuryBalance = await connection.getTokenAccountBalance(newTreasury);
+    assert.equal(newTreasuryBalance.value.amount, "6000000000");
+    const onchainDonationProtocolData = await program.account.donationProtocolData.fetch(donationProtocolData.publicKey);
+    assert.ok(onchainDonationProtocolData.treasury.equals(newTreasury));
+  });
+});
@@ -7,7 +7,7 @@ const {
 } = require('@solana/spl-token');
 const os = require('os');
 const assert = require('assert');
-const { getNowTs, rechargeWallet } = require('./common/utils');
+const { getNowTs, rechargeWallet, findMintFeeAddress, findDonationReceiptAddress, decodeIpfsHash } = require('./common/utils');
 const {
   CONTRIBUTOR_PREFIX,
   TREASURY_PREFIX,
@@ -182,9 +182,13 @@ describe("donaproto", () => {
       ipfsHash,
       new anchor.BN(endingTimestamp),
       holdingWalletOwnerBump,
+      { none: {} },
+      [],
+      false,
       {
         accounts: {
           donationData: donationData.publicKey,
+          donationTiers: null,
           donationProtocol: donationProtocolData.publicKey,
           holdingWallet: donationHoldingWallet.address,
           holdingWalletOwner: holdingWalletOwnerPubkey,
@@ -193,6 +197,7 @@ describe("donaproto", () => {
           donationMint: donationMintPubKey,
           creatorWalletAddress: creatorWallet.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
+          mintFee: findMintFeeAddress(program.programId, donationProtocolData.publicKey, donationMintPubKey),
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         },
         signers: [donationData, creatorWallet],
@@ -209,7 +214,7 @@ describe("donaproto", () => {
     assert.deepEqual(onchainDonationData.holdingWallet, donationHoldingWallet.address);
     assert.deepEqual(onchainDonationData.creatorData, creatorDataPubkey);
     assert.equal(onchainDonationData.holdingBump, holdingWalletOwnerBump);
-    assert.equal(onchainDonationData.ipfsHash, ipfsHash);
+    assert.equal(decodeIpfsHash(onchainDonationData.ipfsHash), ipfsHash);
 
     await rechargeWallet(connection, contributorWallet.publicKey, 1000000000);
     await program.rpc.initializeContributor(
@@ -267,16 +272,30 @@ describe("donaproto", () => {
         accounts: {
           donationData: donationData.publicKey,
           contributorData: contributorDataPubkey,
+          donationReceipt: findDonationReceiptAddress(program.programId, donationData.publicKey, contributorWallet.publicKey),
+          fundingRound: null,
+          matchPledge: null,
+          matchPledgeEscrow: null,
+          donationTiers: null,
           donationProtocol: donationProtocolData.publicKey,
           userTokenWallet: contributorDonationTokenAccount.address,
           userRewardTokenWallet: contributorRewardTokenAccount.address,
           rewardTreasury: treasuryTokenAccount.address,
           rewardTreasuryOwner: treasuryOwnerPubkey,
+          rewardVesting: null,
+          recipient: creatorDonationTokenAccount.address,
           holdingWallet: donationHoldingWallet.address,
           donationMint: donationMintPubKey,
           rewardMint: rewardsMintPubKey,
+          receiptMint: null,
+          receiptTokenAccount: null,
+          receiptMetadata: null,
+          receiptMintAuthority: null,
           userWallet: contributorWallet.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          associatedTokenProgram: null,
+          memoProgram: null,
         },
         signers: [contributorWallet],
       }
@@ -294,6 +313,8 @@ describe("donaproto", () => {
           donationMint: donationMintPubKey,
           payer: creatorWallet.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
+          payoutSplit: null,
+          feeVault: null,
         },
         signers: [creatorWallet],
       });
@@ -308,16 +329,30 @@ describe("donaproto", () => {
         accounts: {
           donationData: donationData.publicKey,
           contributorData: contributorDataPubkey,
+          donationReceipt: findDonationReceiptAddress(program.programId, donationData.publicKey, contributorWallet.publicKey),
+          fundingRound: null,
+          matchPledge: null,
+          matchPledgeEscrow: null,
+          donationTiers: null,
           donationProtocol: donationProtocolData.publicKey,
           userTokenWallet: contributorDonationTokenAccount.address,
           userRewardTokenWallet: contributorRewardTokenAccount.address,
           rewardTreasury: treasuryTokenAccount.address,
           rewardTreasuryOwner: treasuryOwnerPubkey,
+          rewardVesting: null,
+          recipient: creatorDonationTokenAccount.address,
           holdingWallet: donationHoldingWallet.address,
           donationMint: donationMintPubKey,
           rewardMint: rewardsMintPubKey,
+          receiptMint: null,
+          receiptTokenAccount: null,
+          receiptMetadata: null,
+          receiptMintAuthority: null,
           userWallet: contributorWallet.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          associatedTokenProgram: null,
+          memoProgram: null,
         },
         signers: [contributorWallet],
       }
@@ -334,6 +369,8 @@ describe("donaproto", () => {
         donationMint: donationMintPubKey,
         payer: creatorWallet.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
+        payoutSplit: null,
+        feeVault: null,
       },
       signers: [creatorWallet],
     });
@@ -371,9 +408,13 @@ describe("donaproto", () => {
       ipfsHash,
       new anchor.BN(endingTimestamp),
       holdingWalletOwnerBump,
+      { none: {} },
+      [],
+      false,
       {
         accounts: {
           donationData: donationData.publicKey,
+          donationTiers: null,
           donationProtocol: donationProtocolData.publicKey,
           holdingWallet: donationHoldingWallet.address,
           holdingWalletOwner: holdingWalletOwnerPubkey,
@@ -382,6 +423,7 @@ describe("donaproto", () => {
           donationMint: donationMintPubKey,
           creatorWalletAddress: creatorWallet.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
+          mintFee: findMintFeeAddress(program.programId, donationProtocolData.publicKey, donationMintPubKey),
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         },
         signers: [donationData, creatorWallet],
@@ -398,16 +440,30 @@ describe("donaproto", () => {
         accounts: {
           donationData: donationData.publicKey,
           contributorData: contributorDataPubkey,
+          donationReceipt: findDonationReceiptAddress(program.programId, donationData.publicKey, contributorWallet.publicKey),
+          fundingRound: null,
+          matchPledge: null,
+          matchPledgeEscrow: null,
+          donationTiers: null,
           donationProtocol: donationProtocolData.publicKey,
           userTokenWallet: contributorDonationTokenAccount.address,
           userRewardTokenWallet: contributorRewardTokenAccount.address,
           rewardTreasury: treasuryTokenAccount.address,
           rewardTreasuryOwner: treasuryOwnerPubkey,
+          rewardVesting: null,
+          recipient: creatorDonationTokenAccount.address,
           holdingWallet: donationHoldingWallet.address,
           donationMint: donationMintPubKey,
           rewardMint: rewardsMintPubKey,
+          receiptMint: null,
+          receiptTokenAccount: null,
+          receiptMetadata: null,
+          receiptMintAuthority: null,
           userWallet: contributorWallet.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          associatedTokenProgram: null,
+          memoProgram: null,
         },
         signers: [contributorWallet],
       }
@@ -427,6 +483,8 @@ describe("donaproto", () => {
         donationMint: donationMintPubKey,
         payer: creatorWallet.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
+        payoutSplit: null,
+        feeVault: null,
       },
       signers: [creatorWallet],
     });
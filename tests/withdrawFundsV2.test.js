@@ -8,7 +8,7 @@ const {
 } = require('@solana/spl-token');
 const os = require('os');
 const assert = require('assert');
-const { rechargeWallet, loadObjectFromJsonFile, getNowTs } = require("./common/utils");
+const { rechargeWallet, loadObjectFromJsonFile, getNowTs, findMintFeeAddress, findDonationReceiptAddress, decodeIpfsHash } = require("./common/utils");
 const { createPoolState } = require("./common/amm_helper");
 const {
   TREASURY_PREFIX,
@@ -277,9 +277,13 @@ describe("Withdraw funds V2", () => {
       ipfsHash,
       new anchor.BN(endingTimestamp),
       holdingWalletOwnerBump,
+      { none: {} },
+      [],
+      false,
       {
         accounts: {
           donationData: donationData.publicKey,
+          donationTiers: null,
           donationProtocol: donationProtocolData.publicKey,
           holdingWallet: donationHoldingWallet.address,
           holdingWalletOwner: holdingWalletOwnerPubkey,
@@ -291,6 +295,7 @@ describe("Withdraw funds V2", () => {
           poolState: poolStatePubkey,
           creatorWalletAddress: creatorWallet.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
+          mintFee: findMintFeeAddress(program.programId, donationProtocolData.publicKey, newDonationMintPubKey),
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         },
         signers: [donationData, creatorWallet],
@@ -308,7 +313,7 @@ describe("Withdraw funds V2", () => {
     assert.deepEqual(onchainDonationData.holdingWallet, donationHoldingWallet.address);
     assert.deepEqual(onchainDonationData.creatorData, creatorDataPubkey);
     assert.equal(onchainDonationData.holdingBump, holdingWalletOwnerBump);
-    assert.equal(onchainDonationData.ipfsHash, ipfsHash);
+    assert.equal(decodeIpfsHash(onchainDonationData.ipfsHash), ipfsHash);
     assert.deepEqual(onchainDonationData.donationMint, newDonationMintPubKey);
   });
 
@@ -370,19 +375,33 @@ describe("Withdraw funds V2", () => {
         accounts: {
           donationData: donationData.publicKey,
           contributorData: contributorDataPubkey,
+          donationReceipt: findDonationReceiptAddress(program.programId, donationData.publicKey, contributorWallet.publicKey),
+          fundingRound: null,
+          matchPledge: null,
+          matchPledgeEscrow: null,
+          donationTiers: null,
           donationProtocol: donationProtocolData.publicKey,
           userTokenWallet: contributorDonationTokenAccount.address,
           userRewardTokenWallet: contributorRewardTokenAccount.address,
           rewardTreasury: treasuryTokenAccount.address,
           rewardTreasuryOwner: treasuryOwnerPubkey,
+          rewardVesting: null,
+          recipient: creatorDonationTokenAccount.address,
           holdingWallet: donationHoldingWallet.address,
           donationMint: newDonationMintPubKey,
           rewardMint: rewardsMintPubKey,
           defaultDonationMint: donationMintPubKey,
           authorizedClmmPool: authorizedClmmPoolPubkey,
           poolState: poolStatePubkey,
+          receiptMint: null,
+          receiptTokenAccount: null,
+          receiptMetadata: null,
+          receiptMintAuthority: null,
           userWallet: contributorWallet.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          associatedTokenProgram: null,
+          memoProgram: null,
         },
         signers: [contributorWallet],
       }
@@ -420,19 +439,33 @@ describe("Withdraw funds V2", () => {
         accounts: {
           donationData: donationData.publicKey,
           contributorData: contributorDataPubkey,
+          donationReceipt: findDonationReceiptAddress(program.programId, donationData.publicKey, contributorWallet.publicKey),
+          fundingRound: null,
+          matchPledge: null,
+          matchPledgeEscrow: null,
+          donationTiers: null,
           donationProtocol: donationProtocolData.publicKey,
           userTokenWallet: contributorDonationTokenAccount.address,
           userRewardTokenWallet: contributorRewardTokenAccount.address,
           rewardTreasury: treasuryTokenAccount.address,
           rewardTreasuryOwner: treasuryOwnerPubkey,
+          rewardVesting: null,
+          recipient: creatorDonationTokenAccount.address,
           holdingWallet: donationHoldingWallet.address,
           donationMint: newDonationMintPubKey,
           rewardMint: rewardsMintPubKey,
           defaultDonationMint: donationMintPubKey,
           authorizedClmmPool: authorizedClmmPoolPubkey,
           poolState: poolStatePubkey,
+          receiptMint: null,
+          receiptTokenAccount: null,
+          receiptMetadata: null,
+          receiptMintAuthority: null,
           userWallet: contributorWallet.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          associatedTokenProgram: null,
+          memoProgram: null,
         },
         signers: [contributorWallet],
       }
@@ -472,6 +505,8 @@ describe("Withdraw funds V2", () => {
         poolState: poolStatePubkey,
         payer: creatorWallet.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
+        payoutSplit: null,
+        feeVault: null,
       },
       signers: [creatorWallet],
     });